@@ -0,0 +1,147 @@
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::http::request;
+
+pub const DISCORD_API_BASE: &str = "https://discord.com/api/v10";
+// Discord snowflakes count milliseconds from the first second of 2015
+const DISCORD_EPOCH_MS: u64 = 1_420_070_400_000;
+
+/// The bits of `/users/@me` we care about.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordUser {
+    pub id: String,
+    pub username: String,
+    #[serde(default)]
+    pub global_name: Option<String>,
+    /// The avatar's hash; None for the default avatar.
+    #[serde(default)]
+    pub avatar: Option<String>,
+    /// Whether the account's email is verified.
+    #[serde(default)]
+    pub verified: bool,
+    #[serde(default)]
+    pub mfa_enabled: bool,
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// Private flag bits (spammer, quarantined, ...), only visible to the account itself.
+    #[serde(default)]
+    pub flags: u64,
+    /// 0 none, 1 Nitro Classic, 2 Nitro, 3 Nitro Basic; only sent for the account itself.
+    #[serde(default)]
+    pub premium_type: u8,
+}
+
+/// Relationship type for an accepted friend.
+pub const RELATIONSHIP_FRIEND: u8 = 1;
+
+/// An entry from `/users/@me/relationships`: friends, blocks and pending requests.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordRelationship {
+    #[serde(rename = "type")]
+    pub kind: u8,
+    pub user: DiscordUser,
+}
+
+/// An entry from `/users/@me/guilds`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordGuild {
+    pub id: String,
+    pub name: String,
+}
+
+/// What Discord said about a token.
+pub enum TokenCheck {
+    Valid(DiscordUser),
+    Invalid,
+    /// Network or API trouble; says nothing about the token itself.
+    Unreachable(String),
+}
+
+pub fn check_discord_token(token: &str) -> TokenCheck {
+    let request = match request("GET", &format!("{DISCORD_API_BASE}/users/@me")) {
+        Ok(request) => request,
+        Err(e) => return TokenCheck::Unreachable(e),
+    };
+    let response = match request.set("Authorization", token).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(401, _)) => return TokenCheck::Invalid,
+        Err(ureq::Error::Status(code, _)) => {
+            return TokenCheck::Unreachable(format!("Discord API returned HTTP {code}."))
+        }
+        Err(other) => return TokenCheck::Unreachable(format!("Could not reach Discord: {other}")),
+    };
+    match response.into_json() {
+        Ok(user) => TokenCheck::Valid(user),
+        Err(e) => TokenCheck::Unreachable(format!("Could not parse Discord user: {e}")),
+    }
+}
+
+pub fn fetch_discord_user(token: &str) -> Result<DiscordUser, String> {
+    match check_discord_token(token) {
+        TokenCheck::Valid(user) => Ok(user),
+        TokenCheck::Invalid => Err("Discord rejected the token.".to_string()),
+        TokenCheck::Unreachable(e) => Err(e),
+    }
+}
+
+/// The account's accepted friends.
+pub fn fetch_friends(token: &str) -> Result<Vec<DiscordUser>, String> {
+    let relationships: Vec<DiscordRelationship> = get_json(token, "/users/@me/relationships")?;
+    Ok(relationships
+        .into_iter()
+        .filter(|r| r.kind == RELATIONSHIP_FRIEND)
+        .map(|r| r.user)
+        .collect())
+}
+
+/// Servers the account is a member of.
+pub fn fetch_guilds(token: &str) -> Result<Vec<DiscordGuild>, String> {
+    get_json(token, "/users/@me/guilds")
+}
+
+/// Apply the same notification settings to several servers in one request.
+pub fn patch_guild_notification_settings(
+    token: &str,
+    guild_ids: &[String],
+    settings: &serde_json::Value,
+) -> Result<(), String> {
+    let guilds: serde_json::Map<String, serde_json::Value> = guild_ids
+        .iter()
+        .map(|id| (id.clone(), settings.clone()))
+        .collect();
+    let result = request(
+        "PATCH",
+        &format!("{DISCORD_API_BASE}/users/@me/guilds/settings"),
+    )?
+    .set("Authorization", token)
+    .send_json(serde_json::json!({ "guilds": guilds }));
+    match result {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(401, _)) => Err("Discord rejected the token.".to_string()),
+        Err(ureq::Error::Status(code, _)) => Err(format!("Discord API returned HTTP {code}.")),
+        Err(other) => Err(format!("Could not reach Discord: {other}")),
+    }
+}
+
+fn get_json<T: DeserializeOwned>(token: &str, path: &str) -> Result<T, String> {
+    let response = match request("GET", &format!("{DISCORD_API_BASE}{path}"))?
+        .set("Authorization", token)
+        .call()
+    {
+        Ok(response) => response,
+        Err(ureq::Error::Status(401, _)) => return Err("Discord rejected the token.".to_string()),
+        Err(ureq::Error::Status(code, _)) => {
+            return Err(format!("Discord API returned HTTP {code}."))
+        }
+        Err(other) => return Err(format!("Could not reach Discord: {other}")),
+    };
+    response
+        .into_json()
+        .map_err(|e| format!("Could not parse Discord response: {e}"))
+}
+
+/// When a Discord id (user, guild, ...) was created, in unix milliseconds.
+pub fn snowflake_created_at_ms(id: &str) -> Option<u128> {
+    let raw: u64 = id.parse().ok()?;
+    Some(((raw >> 22) + DISCORD_EPOCH_MS) as u128)
+}
@@ -0,0 +1,132 @@
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use serde::Serialize;
+use std::{
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::{
+    crypto::random_bytes,
+    settings::{load_launcher_settings, save_launcher_settings_to_file},
+    DataDir,
+};
+
+/// Returned by gated operations until [`verify_app_password`] succeeds.
+pub const APP_LOCKED: &str = "The app is locked. Enter the app password first.";
+
+// data folders whose app password was verified in this process
+static VERIFIED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+}
+
+pub fn app_lock_status(dir: &DataDir) -> Result<AppLockStatus, String> {
+    let enabled = password_hash(dir)?.is_some();
+    Ok(AppLockStatus {
+        enabled,
+        unlocked: !enabled || is_verified(dir),
+    })
+}
+
+/// Fail with [`APP_LOCKED`] if an app password is set and hasn't been entered
+/// this session. Token-touching operations call this themselves, so a lock
+/// can't be skipped by invoking commands directly.
+pub fn ensure_app_unlocked(dir: &DataDir) -> Result<(), String> {
+    if app_lock_status(dir)?.unlocked {
+        Ok(())
+    } else {
+        Err(APP_LOCKED.to_string())
+    }
+}
+
+/// Check the app password and unlock gated operations until [`lock_app`].
+pub fn verify_app_password(dir: &DataDir, password: &str) -> Result<AppLockStatus, String> {
+    let Some(hash) = password_hash(dir)? else {
+        return app_lock_status(dir);
+    };
+    if !password_matches(&hash, password)? {
+        return Err("Wrong app password.".to_string());
+    }
+
+    let mut verified = verified_dirs();
+    if !verified.iter().any(|root| root == dir.root()) {
+        verified.push(dir.root().to_path_buf());
+    }
+    drop(verified);
+    app_lock_status(dir)
+}
+
+pub fn lock_app(dir: &DataDir) -> Result<AppLockStatus, String> {
+    verified_dirs().retain(|root| root != dir.root());
+    app_lock_status(dir)
+}
+
+/// Set, change or (with `new_password: None`) remove the app password.
+/// Changing or removing one needs the current password.
+pub fn set_app_password(
+    dir: &DataDir,
+    current_password: Option<&str>,
+    new_password: Option<&str>,
+) -> Result<AppLockStatus, String> {
+    let file_path = dir.launcher_settings_file();
+    let mut settings = load_launcher_settings(&file_path)?;
+
+    if let Some(hash) = &settings.app_password_hash {
+        let current = current_password.unwrap_or_default();
+        if !password_matches(hash, current)? {
+            return Err("Wrong app password.".to_string());
+        }
+    }
+
+    settings.app_password_hash = match new_password {
+        Some(password) => Some(hash_password(password)?),
+        None => None,
+    };
+    save_launcher_settings_to_file(&file_path, &settings)?;
+
+    // whoever just set the password obviously knows it
+    let mut verified = verified_dirs();
+    verified.retain(|root| root != dir.root());
+    verified.push(dir.root().to_path_buf());
+    drop(verified);
+    app_lock_status(dir)
+}
+
+fn hash_password(password: &str) -> Result<String, String> {
+    if password.chars().count() < 4 {
+        return Err("App password must be at least 4 characters.".to_string());
+    }
+    let salt = SaltString::encode_b64(&random_bytes::<16>())
+        .map_err(|e| format!("Could not hash app password: {e}"))?;
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Could not hash app password: {e}"))
+}
+
+fn password_matches(hash: &str, password: &str) -> Result<bool, String> {
+    let parsed =
+        PasswordHash::new(hash).map_err(|e| format!("Could not read app password hash: {e}"))?;
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed)
+        .is_ok())
+}
+
+fn password_hash(dir: &DataDir) -> Result<Option<String>, String> {
+    Ok(load_launcher_settings(&dir.launcher_settings_file())?.app_password_hash)
+}
+
+fn verified_dirs() -> MutexGuard<'static, Vec<PathBuf>> {
+    VERIFIED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn is_verified(dir: &DataDir) -> bool {
+    verified_dirs().iter().any(|root| root == dir.root())
+}
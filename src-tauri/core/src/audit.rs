@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::Write,
+};
+
+use crate::{clock::now_ms, DataDir};
+
+pub const DEFAULT_AUDIT_PAGE_SIZE: usize = 100;
+pub const MAX_AUDIT_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditAction {
+    Capture,
+    Switch,
+    Export,
+    Reveal,
+    Wipe,
+    Restore,
+}
+
+/// One line of `audit.jsonl`. Never holds a token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub at_ms: u128,
+    pub action: AuditAction,
+    pub profile_id: Option<String>,
+    pub succeeded: bool,
+    /// Extra context, e.g. "queued" or "with tokens".
+    #[serde(default)]
+    pub detail: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Filters for [`get_audit_log`]; every field is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuditQuery {
+    pub action: Option<AuditAction>,
+    pub profile_id: Option<String>,
+    pub failures_only: bool,
+    pub since_ms: Option<u128>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditPage {
+    /// Newest first.
+    pub entries: Vec<AuditEntry>,
+    /// How many entries match the filters across all pages.
+    pub total: usize,
+    pub offset: usize,
+}
+
+/// Append the outcome of a sensitive operation to the audit log. Failing to
+/// write the log never fails the operation itself.
+pub fn record_audit<T>(
+    dir: &DataDir,
+    action: AuditAction,
+    profile_id: Option<&str>,
+    detail: Option<&str>,
+    result: &Result<T, String>,
+) {
+    let entry = AuditEntry {
+        at_ms: now_ms(),
+        action,
+        profile_id: profile_id.map(str::to_string),
+        succeeded: result.is_ok(),
+        detail: detail.map(str::to_string),
+        error: result.as_ref().err().cloned(),
+    };
+    if let Err(e) = append_entry(dir, &entry) {
+        eprintln!("Warning: could not write audit log: {e}");
+    }
+}
+
+pub fn get_audit_log(dir: &DataDir, query: &AuditQuery) -> Result<AuditPage, String> {
+    let file_path = dir.audit_log_file();
+    let content = if file_path.exists() {
+        fs::read_to_string(&file_path).map_err(|e| format!("Could not read audit log: {e}"))?
+    } else {
+        String::new()
+    };
+
+    // a torn last line from a crash shouldn't hide everything before it
+    let matching: Vec<AuditEntry> = content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|entry| matches_query(entry, query))
+        .collect();
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_AUDIT_PAGE_SIZE)
+        .clamp(1, MAX_AUDIT_PAGE_SIZE);
+    Ok(AuditPage {
+        total: matching.len(),
+        entries: matching
+            .into_iter()
+            .skip(query.offset)
+            .take(limit)
+            .collect(),
+        offset: query.offset,
+    })
+}
+
+/// When each profile last had a successful `action`, e.g. its last switch.
+pub fn last_successful(dir: &DataDir, action: AuditAction) -> HashMap<String, u128> {
+    let content = fs::read_to_string(dir.audit_log_file()).unwrap_or_default();
+    let mut last = HashMap::new();
+    for entry in content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|entry| entry.action == action && entry.succeeded)
+    {
+        if let Some(profile_id) = entry.profile_id {
+            let at = last.entry(profile_id).or_insert(entry.at_ms);
+            *at = (*at).max(entry.at_ms);
+        }
+    }
+    last
+}
+
+fn matches_query(entry: &AuditEntry, query: &AuditQuery) -> bool {
+    query.action.is_none_or(|action| entry.action == action)
+        && query
+            .profile_id
+            .as_deref()
+            .is_none_or(|id| entry.profile_id.as_deref() == Some(id))
+        && (!query.failures_only || !entry.succeeded)
+        && query.since_ms.is_none_or(|since| entry.at_ms >= since)
+}
+
+fn append_entry(dir: &DataDir, entry: &AuditEntry) -> Result<(), String> {
+    let line =
+        serde_json::to_string(entry).map_err(|e| format!("Could not encode audit entry: {e}"))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.audit_log_file())
+        .map_err(|e| format!("Could not open audit log: {e}"))?;
+    writeln!(file, "{line}").map_err(|e| format!("Could not write audit log: {e}"))
+}
@@ -0,0 +1,473 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{
+    app_lock::ensure_app_unlocked,
+    audit::{record_audit, AuditAction},
+    biometric::{require_biometric, BiometricAction},
+    clock::now_ms,
+    markers::{current_modification_marker, ModificationMarker},
+    notifications::NotificationPreset,
+    profiles::{
+        ensure_valid_profile_id, load_profiles, normalize_avatar_color, normalize_nickname,
+        normalize_notes, normalize_profile_icon, normalize_switch_reminder, save_profiles, Profile,
+        StoredProfile,
+    },
+    secret::SecretToken,
+    tokens::{profile_has_token, token_store, TokenStore},
+    upgrades::{parse_profiles_document, upgrade_profiles, PROFILES_SCHEMA_VERSION},
+    vault::ensure_unlocked,
+    DataDir,
+};
+
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Portable copy of accounts (and optionally tokens) for backups and syncing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileBackup {
+    pub version: u32,
+    pub exported_at_ms: u128,
+    pub profiles: Vec<StoredProfile>,
+    /// Profile id -> token; empty when exported without tokens.
+    #[serde(default)]
+    pub tokens: HashMap<String, String>,
+    #[serde(default)]
+    pub last_modified_by: Option<ModificationMarker>,
+    /// Schema of `profiles`; backups from before it existed are schema 1.
+    #[serde(default = "legacy_profiles_schema")]
+    pub profiles_schema_version: u32,
+}
+
+fn legacy_profiles_schema() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictKind {
+    SameId,
+    SameNickname,
+    SameUserId,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportConflict {
+    pub incoming: Profile,
+    pub local: Profile,
+    pub kinds: Vec<ConflictKind>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub new_profiles: Vec<Profile>,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictAction {
+    KeepLocal,
+    KeepRemote,
+    Merge,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictResolution {
+    pub incoming_id: String,
+    pub action: ConflictAction,
+}
+
+pub fn export_profile_backup(
+    dir: &DataDir,
+    path: &Path,
+    include_tokens: bool,
+) -> Result<(), String> {
+    let result = write_profile_backup(dir, path, include_tokens);
+    let detail = if include_tokens {
+        "with tokens"
+    } else {
+        "profiles only"
+    };
+    record_audit(dir, AuditAction::Export, None, Some(detail), &result);
+    result
+}
+
+fn write_profile_backup(dir: &DataDir, path: &Path, include_tokens: bool) -> Result<(), String> {
+    let profiles: Vec<StoredProfile> = load_profiles(&dir.profiles_file())?
+        .into_iter()
+        .filter(|p| !p.never_export)
+        .collect();
+    let mut tokens = HashMap::new();
+    if include_tokens {
+        ensure_app_unlocked(dir)?;
+        require_biometric(dir, BiometricAction::RevealToken)?;
+        let store = token_store(dir)?;
+        for profile in profiles.iter().filter(|p| !p.never_sync) {
+            if let Some(token) = store.load(&profile.id)? {
+                tokens.insert(profile.id.clone(), token.expose().to_string());
+            }
+        }
+    }
+
+    let backup = ProfileBackup {
+        version: BACKUP_FORMAT_VERSION,
+        exported_at_ms: now_ms(),
+        profiles,
+        tokens,
+        last_modified_by: Some(current_modification_marker()),
+        profiles_schema_version: PROFILES_SCHEMA_VERSION,
+    };
+    let payload = serde_json::to_string_pretty(&backup)
+        .map_err(|e| format!("Could not encode backup: {e}"))?;
+    fs::write(path, payload).map_err(|e| format!("Could not write backup: {e}"))
+}
+
+/// Show what an import would do before anything is written.
+pub fn preview_profile_import(dir: &DataDir, path: &Path) -> Result<ImportPreview, String> {
+    preview_backup(dir, load_profile_backup(path)?)
+}
+
+/// Like [`preview_profile_import`], for an export handed over as text, e.g.
+/// a file picked on a phone that has no path the app can open.
+pub fn preview_profile_import_contents(
+    dir: &DataDir,
+    contents: &str,
+) -> Result<ImportPreview, String> {
+    preview_backup(dir, parse_profile_backup(contents)?)
+}
+
+fn preview_backup(dir: &DataDir, backup: ProfileBackup) -> Result<ImportPreview, String> {
+    let local = load_profiles(&dir.profiles_file())?;
+
+    let mut preview = ImportPreview {
+        new_profiles: Vec::new(),
+        conflicts: Vec::new(),
+    };
+    for incoming in backup.profiles {
+        let has_incoming_token = backup.tokens.contains_key(&incoming.id);
+        match find_import_conflict(&local, &incoming) {
+            Some((existing, kinds)) => {
+                let has_local_token = profile_has_token(dir, &existing.id);
+                preview.conflicts.push(ImportConflict {
+                    incoming: incoming.into_profile(has_incoming_token),
+                    local: existing.clone().into_profile(has_local_token),
+                    kinds,
+                });
+            }
+            None => preview
+                .new_profiles
+                .push(incoming.into_profile(has_incoming_token)),
+        }
+    }
+    Ok(preview)
+}
+
+/// Apply an import; every conflict needs an explicit resolution.
+pub fn apply_profile_import(
+    dir: &DataDir,
+    path: &Path,
+    resolutions: &[ConflictResolution],
+) -> Result<Vec<Profile>, String> {
+    apply_backup(dir, load_profile_backup(path)?, resolutions)
+}
+
+/// Like [`apply_profile_import`], for an export handed over as text.
+pub fn apply_profile_import_contents(
+    dir: &DataDir,
+    contents: &str,
+    resolutions: &[ConflictResolution],
+) -> Result<Vec<Profile>, String> {
+    apply_backup(dir, parse_profile_backup(contents)?, resolutions)
+}
+
+fn apply_backup(
+    dir: &DataDir,
+    backup: ProfileBackup,
+    resolutions: &[ConflictResolution],
+) -> Result<Vec<Profile>, String> {
+    // a locked store would fail halfway, after the profiles are merged
+    ensure_app_unlocked(dir)?;
+    ensure_unlocked(dir)?;
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let store = token_store(dir)?;
+
+    let mut unresolved = Vec::new();
+    let mut pending_tokens: Vec<(String, String)> = Vec::new();
+
+    for incoming in backup.profiles {
+        let incoming_token = backup.tokens.get(&incoming.id).cloned();
+        let Some((existing, _)) = find_import_conflict(&profiles, &incoming) else {
+            if let Some(token) = incoming_token {
+                pending_tokens.push((incoming.id.clone(), token));
+            }
+            // a Windows user only means something with the password saved on the exporting
+            // machine, and a sandbox with the tool installed there
+            profiles.push(StoredProfile {
+                windows_account: None,
+                sandbox: None,
+                ..incoming
+            });
+            continue;
+        };
+        let local_id = existing.id.clone();
+
+        let Some(resolution) = resolutions.iter().find(|r| r.incoming_id == incoming.id) else {
+            unresolved.push(incoming.nickname.clone());
+            continue;
+        };
+
+        let local = profiles
+            .iter_mut()
+            .find(|p| p.id == local_id)
+            .ok_or_else(|| "Account not found.".to_string())?;
+        let local_has_token = store.has(&local_id);
+        // a never-sync token stays whatever it is on this machine
+        let incoming_token = incoming_token.filter(|_| !local.never_sync);
+        match resolution.action {
+            ConflictAction::KeepLocal => {}
+            ConflictAction::KeepRemote => {
+                // keep the local id so tokens and references stay attached,
+                // and the local export policy, which is about this machine
+                *local = StoredProfile {
+                    id: local_id.clone(),
+                    never_export: local.never_export || incoming.never_export,
+                    never_sync: local.never_sync || incoming.never_sync,
+                    windows_account: local.windows_account.clone(),
+                    sandbox: local.sandbox,
+                    ..incoming
+                };
+                if let Some(token) = incoming_token {
+                    pending_tokens.push((local_id, token));
+                }
+            }
+            ConflictAction::Merge => {
+                merge_imported_profile(local, incoming);
+                if let (Some(token), false) = (incoming_token, local_has_token) {
+                    pending_tokens.push((local_id, token));
+                }
+            }
+        }
+    }
+
+    if !unresolved.is_empty() {
+        return Err(format!(
+            "Choose how to resolve conflicts for: {}.",
+            unresolved.join(", ")
+        ));
+    }
+    ensure_unique_nicknames(&profiles)?;
+
+    // tokens first, so no imported account is left without its token
+    let replaced = save_imported_tokens(store.as_ref(), &pending_tokens)?;
+    if let Err(e) = save_profiles(&file_path, &profiles) {
+        restore_tokens(store.as_ref(), replaced);
+        return Err(e);
+    }
+
+    Ok(profiles
+        .into_iter()
+        .map(|p| {
+            let has = store.has(&p.id);
+            p.into_profile(has)
+        })
+        .collect())
+}
+
+// save every token, or put back what was there before when one fails; returns
+// the tokens they replaced, to put back if the rest of the import fails
+fn save_imported_tokens(
+    store: &dyn TokenStore,
+    tokens: &[(String, String)],
+) -> Result<Vec<(String, Option<SecretToken>)>, String> {
+    let mut replaced = Vec::new();
+    for (profile_id, token) in tokens {
+        let saved = store.load(profile_id).and_then(|previous| {
+            replaced.push((profile_id.clone(), previous));
+            store.save(profile_id, token)
+        });
+        if let Err(e) = saved {
+            restore_tokens(store, replaced);
+            return Err(format!("Could not save an imported token: {e}"));
+        }
+    }
+    Ok(replaced)
+}
+
+// best effort: the import already failed
+fn restore_tokens(store: &dyn TokenStore, replaced: Vec<(String, Option<SecretToken>)>) {
+    for (profile_id, previous) in replaced.into_iter().rev() {
+        let restored = match previous {
+            Some(token) => store.save(&profile_id, token.expose()),
+            None => store.delete(&profile_id),
+        };
+        if let Err(e) = restored {
+            eprintln!("Warning: could not undo an imported token for {profile_id}: {e}");
+        }
+    }
+}
+
+// the local profile an incoming one collides with (id, then user id, then nickname)
+fn find_import_conflict<'a>(
+    local: &'a [StoredProfile],
+    incoming: &StoredProfile,
+) -> Option<(&'a StoredProfile, Vec<ConflictKind>)> {
+    let kinds_for = |p: &StoredProfile| {
+        let mut kinds = Vec::new();
+        if p.id == incoming.id {
+            kinds.push(ConflictKind::SameId);
+        }
+        if p.nickname.eq_ignore_ascii_case(&incoming.nickname) {
+            kinds.push(ConflictKind::SameNickname);
+        }
+        if p.discord_user_id.is_some() && p.discord_user_id == incoming.discord_user_id {
+            kinds.push(ConflictKind::SameUserId);
+        }
+        kinds
+    };
+
+    [
+        ConflictKind::SameId,
+        ConflictKind::SameUserId,
+        ConflictKind::SameNickname,
+    ]
+    .iter()
+    .find_map(|wanted| {
+        local.iter().find_map(|p| {
+            let kinds = kinds_for(p);
+            kinds.contains(wanted).then_some((p, kinds))
+        })
+    })
+}
+
+// fill gaps in the local profile from the incoming one; local values win
+fn merge_imported_profile(local: &mut StoredProfile, incoming: StoredProfile) {
+    if local.icon.is_none() {
+        local.icon = incoming.icon;
+    }
+    if local.discord_user_id.is_none() {
+        local.discord_user_id = incoming.discord_user_id;
+    }
+    if local.switch_reminder.is_none() {
+        local.switch_reminder = incoming.switch_reminder;
+    }
+    if local.notification_preset == NotificationPreset::Unchanged {
+        local.notification_preset = incoming.notification_preset;
+    }
+    local.clear_cache_on_switch |= incoming.clear_cache_on_switch;
+    local.protected |= incoming.protected;
+    local.never_export |= incoming.never_export;
+    local.never_sync |= incoming.never_sync;
+    if local.rich_presence.is_none() {
+        local.rich_presence = incoming.rich_presence;
+    }
+    let incoming_notes = incoming.notes.trim();
+    if !incoming_notes.is_empty() && !local.notes.contains(incoming_notes) {
+        local.notes = if local.notes.trim().is_empty() {
+            incoming.notes
+        } else {
+            format!("{}\n\n{}", local.notes.trim_end(), incoming_notes)
+        };
+    }
+    if incoming.token_checked_at_ms > local.token_checked_at_ms {
+        local.token_status = incoming.token_status;
+        local.token_checked_at_ms = incoming.token_checked_at_ms;
+    }
+}
+
+fn ensure_unique_nicknames(profiles: &[StoredProfile]) -> Result<(), String> {
+    for (i, profile) in profiles.iter().enumerate() {
+        if profiles[i + 1..]
+            .iter()
+            .any(|other| other.nickname.eq_ignore_ascii_case(&profile.nickname))
+        {
+            return Err(format!(
+                "Import would leave two accounts named '{}'.",
+                profile.nickname
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Read our backup format or a bare `accounts.json`.
+pub fn load_profile_backup(file_path: &Path) -> Result<ProfileBackup, String> {
+    let content =
+        fs::read_to_string(file_path).map_err(|e| format!("Could not read backup: {e}"))?;
+    parse_profile_backup(&content)
+}
+
+// both the preview and the import go through this, so a backup that can't be
+// imported as it is fails before anything is shown
+fn parse_profile_backup(content: &str) -> Result<ProfileBackup, String> {
+    let mut backup = read_profile_backup(content)?;
+    backup.profiles = backup
+        .profiles
+        .into_iter()
+        .map(validate_imported_profile)
+        .collect::<Result<_, _>>()?;
+    Ok(backup)
+}
+
+// the same limits as profiles created or edited here
+fn validate_imported_profile(incoming: StoredProfile) -> Result<StoredProfile, String> {
+    let named = |e: String| {
+        format!(
+            "The backup's account '{}' can't be imported: {e}",
+            incoming.nickname
+        )
+    };
+    ensure_valid_profile_id(&incoming.id).map_err(named)?;
+    let nickname = normalize_nickname(&incoming.nickname).map_err(named)?;
+    let avatar_color = normalize_avatar_color(Some(&incoming.avatar_color)).map_err(named)?;
+    let icon = normalize_profile_icon(incoming.icon.as_deref()).map_err(named)?;
+    let notes = normalize_notes(&incoming.notes).map_err(named)?;
+    let switch_reminder =
+        normalize_switch_reminder(incoming.switch_reminder.as_deref().unwrap_or_default())
+            .map_err(named)?;
+    Ok(StoredProfile {
+        nickname,
+        avatar_color,
+        icon,
+        notes,
+        switch_reminder,
+        ..incoming
+    })
+}
+
+fn read_profile_backup(content: &str) -> Result<ProfileBackup, String> {
+    let mut value: Value =
+        serde_json::from_str(content).map_err(|e| format!("Could not parse backup: {e}"))?;
+    if let Some(version) = value.get("version").and_then(Value::as_u64) {
+        if version > u64::from(BACKUP_FORMAT_VERSION) {
+            return Err("This backup was made by a newer version of the app.".to_string());
+        }
+        let from = value
+            .get("profilesSchemaVersion")
+            .and_then(Value::as_u64)
+            .map_or(1, |v| u32::try_from(v).unwrap_or(u32::MAX));
+        if let Some(profiles) = value.get_mut("profiles") {
+            upgrade_profiles(profiles, from)?;
+        }
+        value["profilesSchemaVersion"] = Value::from(PROFILES_SCHEMA_VERSION);
+        return serde_json::from_value(value).map_err(|e| format!("Could not parse backup: {e}"));
+    }
+
+    let (from, mut profiles) = parse_profiles_document(content)?;
+    upgrade_profiles(&mut profiles, from)?;
+    let profiles: Vec<StoredProfile> =
+        serde_json::from_value(profiles).map_err(|e| format!("Could not parse backup: {e}"))?;
+    Ok(ProfileBackup {
+        version: BACKUP_FORMAT_VERSION,
+        exported_at_ms: 0,
+        profiles,
+        tokens: HashMap::new(),
+        last_modified_by: None,
+        profiles_schema_version: PROFILES_SCHEMA_VERSION,
+    })
+}
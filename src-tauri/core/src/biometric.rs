@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{settings::load_launcher_settings, DataDir};
+
+/// Which commands ask for Windows Hello / Touch ID before running.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricGate {
+    #[serde(default)]
+    pub switch_profile: bool,
+    /// Anything that hands a saved token back in the clear, e.g. a backup with tokens.
+    #[serde(default)]
+    pub reveal_token: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BiometricAction {
+    SwitchProfile,
+    RevealToken,
+}
+
+impl BiometricAction {
+    fn reason(self) -> &'static str {
+        match self {
+            Self::SwitchProfile => "switch Discord accounts",
+            Self::RevealToken => "reveal a saved Discord token",
+        }
+    }
+
+    fn is_gated(self, gate: &BiometricGate) -> bool {
+        match self {
+            Self::SwitchProfile => gate.switch_profile,
+            Self::RevealToken => gate.reveal_token,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricStatus {
+    /// Whether this machine can show a Windows Hello / Touch ID prompt.
+    pub available: bool,
+    pub gate: BiometricGate,
+}
+
+/// Whether this machine can show a Windows Hello / Touch ID prompt right now.
+pub fn is_available() -> bool {
+    platform::available()
+}
+
+pub fn biometric_status(dir: &DataDir) -> Result<BiometricStatus, String> {
+    Ok(BiometricStatus {
+        available: platform::available(),
+        gate: load_launcher_settings(&dir.launcher_settings_file())?.biometric,
+    })
+}
+
+/// Show the platform prompt if `action` is gated in the settings.
+/// Fails closed: a gated action on a machine without biometrics is refused.
+pub fn require_biometric(dir: &DataDir, action: BiometricAction) -> Result<(), String> {
+    let gate = load_launcher_settings(&dir.launcher_settings_file())?.biometric;
+    if !action.is_gated(&gate) {
+        return Ok(());
+    }
+    confirm(action.reason())
+}
+
+/// Turning a gate off needs the same confirmation the gate asks for,
+/// otherwise saving settings would be a way around it.
+pub fn confirm_gate_change(previous: &BiometricGate, next: &BiometricGate) -> Result<(), String> {
+    let loosened = (previous.switch_profile && !next.switch_profile)
+        || (previous.reveal_token && !next.reveal_token);
+    if loosened {
+        confirm("turn off biometric confirmation")?;
+    }
+    Ok(())
+}
+
+fn confirm(reason: &str) -> Result<(), String> {
+    if !platform::available() {
+        return Err(
+            "Biometric confirmation is required but not available on this device.".to_string(),
+        );
+    }
+    if platform::prompt(reason)? {
+        Ok(())
+    } else {
+        Err("Biometric confirmation was cancelled.".to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::{
+        core::HSTRING,
+        Security::Credentials::UI::{
+            UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
+        },
+    };
+
+    pub fn available() -> bool {
+        UserConsentVerifier::CheckAvailabilityAsync()
+            .and_then(|op| op.get())
+            .map(|availability| availability == UserConsentVerifierAvailability::Available)
+            .unwrap_or(false)
+    }
+
+    // the Windows Hello dialog is system-modal, so no window handle is needed
+    pub fn prompt(reason: &str) -> Result<bool, String> {
+        let message = HSTRING::from(format!("Discord Alt Manager wants to {reason}."));
+        let result = UserConsentVerifier::RequestVerificationAsync(&message)
+            .and_then(|op| op.get())
+            .map_err(|e| format!("Could not show Windows Hello prompt: {e}"))?;
+        Ok(result == UserConsentVerificationResult::Verified)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use block2::RcBlock;
+    use objc2::{
+        class, msg_send,
+        rc::Retained,
+        runtime::{AnyObject, Bool},
+    };
+    use std::{ffi::CString, ptr, sync::mpsc, time::Duration};
+
+    #[link(name = "LocalAuthentication", kind = "framework")]
+    extern "C" {}
+    #[link(name = "Foundation", kind = "framework")]
+    extern "C" {}
+
+    // LAPolicyDeviceOwnerAuthenticationWithBiometrics
+    const POLICY_BIOMETRICS: isize = 1;
+    const PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+    fn new_context() -> Retained<AnyObject> {
+        unsafe { msg_send![class!(LAContext), new] }
+    }
+
+    pub fn available() -> bool {
+        let context = new_context();
+        unsafe {
+            msg_send![
+                &*context,
+                canEvaluatePolicy: POLICY_BIOMETRICS,
+                error: ptr::null_mut::<*mut AnyObject>()
+            ]
+        }
+    }
+
+    // macOS puts "Discord Alt Manager is trying to" in front of the reason itself
+    pub fn prompt(reason: &str) -> Result<bool, String> {
+        let reason = CString::new(reason).map_err(|_| "Invalid prompt text.".to_string())?;
+        let context = new_context();
+        let (tx, rx) = mpsc::channel();
+        let reply = RcBlock::new(move |success: Bool, _error: *mut AnyObject| {
+            let _ = tx.send(success.as_bool());
+        });
+        unsafe {
+            let reason: Retained<AnyObject> =
+                msg_send![class!(NSString), stringWithUTF8String: reason.as_ptr()];
+            let _: () = msg_send![
+                &*context,
+                evaluatePolicy: POLICY_BIOMETRICS,
+                localizedReason: &*reason,
+                reply: &*reply
+            ];
+        }
+        rx.recv_timeout(PROMPT_TIMEOUT)
+            .map_err(|_| "Touch ID prompt timed out.".to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    pub fn available() -> bool {
+        false
+    }
+
+    pub fn prompt(_reason: &str) -> Result<bool, String> {
+        Ok(false)
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::{client_mods::detect_client_mods, launch::DiscordInstallation};
+
+// enough for every header below; PE's offset to its header is usually under 0x200
+const HEADER_BYTES: u64 = 4096;
+
+/// Fill in the version, CPU architecture and client mods of the build an
+/// installation points to, where the files say. Unreadable or unfamiliar
+/// builds are left without them.
+pub(crate) fn describe_build(installation: &mut DiscordInstallation) {
+    if installation.launch_uri.is_some() || installation.app_user_model_id.is_some() {
+        return;
+    }
+    let path = Path::new(&installation.executable_path);
+    installation.version = read_version(path);
+    installation.arch = binary_path(path).and_then(|binary| read_arch(&binary));
+    installation.mods = detect_client_mods(installation.channel, path);
+}
+
+// `<root>\app-1.0.9163\Discord.exe` on Windows, `CFBundleShortVersionString`
+// on macOS, and the `build_info.json` Discord ships next to its binary on Linux
+fn read_version(path: &Path) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    return path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix("app-"))
+        .map(str::to_string);
+
+    #[cfg(target_os = "macos")]
+    return plist_value(path, "CFBundleShortVersionString");
+
+    #[cfg(target_os = "linux")]
+    {
+        let resolved = path.canonicalize().ok()?;
+        let build_info =
+            std::fs::read_to_string(resolved.parent()?.join("resources/build_info.json")).ok()?;
+        let build_info: serde_json::Value = serde_json::from_str(&build_info).ok()?;
+        return build_info
+            .get("version")
+            .and_then(|version| version.as_str())
+            .map(str::to_string);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = path;
+        None
+    }
+}
+
+// a macOS bundle's binary is named by its Info.plist
+fn binary_path(path: &Path) -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    if path.extension().is_some_and(|ext| ext == "app") {
+        let name = plist_value(path, "CFBundleExecutable")
+            .or_else(|| path.file_stem()?.to_str().map(str::to_string))?;
+        return Some(path.join("Contents").join("MacOS").join(name));
+    }
+    Some(path.to_path_buf())
+}
+
+// plutil reads both the XML and the binary plist formats
+#[cfg(target_os = "macos")]
+fn plist_value(bundle: &Path, key: &str) -> Option<String> {
+    let output = std::process::Command::new("plutil")
+        .args(["-extract", key, "raw", "-o", "-"])
+        .arg(bundle.join("Contents").join("Info.plist"))
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+// the machine field of a PE, Mach-O or ELF header; a universal macOS binary
+// carrying more than one architecture is reported as such
+fn read_arch(binary: &Path) -> Option<String> {
+    let mut header = Vec::new();
+    File::open(binary)
+        .ok()?
+        .take(HEADER_BYTES)
+        .read_to_end(&mut header)
+        .ok()?;
+    let u16_le = |at: usize| Some(u16::from_le_bytes(header.get(at..at + 2)?.try_into().ok()?));
+    let u32_le = |at: usize| Some(u32::from_le_bytes(header.get(at..at + 4)?.try_into().ok()?));
+    let u32_be = |at: usize| Some(u32::from_be_bytes(header.get(at..at + 4)?.try_into().ok()?));
+
+    let arch = match header.get(..4)? {
+        [b'M', b'Z', ..] => {
+            let pe = u32_le(0x3c)? as usize;
+            if header.get(pe..pe + 4)? != b"PE\0\0" {
+                return None;
+            }
+            match u16_le(pe + 4)? {
+                0x8664 => "x64",
+                0x014c => "x86",
+                0xaa64 => "arm64",
+                _ => return None,
+            }
+        }
+        [0xcf, 0xfa, 0xed, 0xfe] => macho_arch(u32_le(4)?)?,
+        [0xca, 0xfe, 0xba, 0xbe] => match u32_be(4)? {
+            0 => return None,
+            1 => macho_arch(u32_be(8)?)?,
+            _ => "universal",
+        },
+        [0x7f, b'E', b'L', b'F'] => match u16_le(18)? {
+            0x3e => "x64",
+            0x03 => "x86",
+            0xb7 => "arm64",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(arch.to_string())
+}
+
+fn macho_arch(cpu_type: u32) -> Option<&'static str> {
+    match cpu_type {
+        0x0100_0007 => Some("x64"),
+        0x0100_000c => Some("arm64"),
+        _ => None,
+    }
+}
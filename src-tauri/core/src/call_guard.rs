@@ -0,0 +1,100 @@
+use crate::launch::is_discord_running;
+
+/// Returned instead of closing Discord while it looks like a call or screen
+/// share is running; the caller retries with `ignore_call` once the user confirms.
+pub const CALL_IN_PROGRESS: &str =
+    "Discord looks like it's in a call or sharing the screen. Closing it would drop you.";
+
+/// Fail with [`CALL_IN_PROGRESS`] if Discord seems busy with a call, unless
+/// `ignore_call`. Discord still gets closed and its storage checked as usual.
+pub fn ensure_not_in_call(ignore_call: bool) -> Result<(), String> {
+    if ignore_call || detect_call_activity().is_empty() {
+        Ok(())
+    } else {
+        Err(CALL_IN_PROGRESS.to_string())
+    }
+}
+
+/// Best-effort reasons to think Discord is in a call right now; empty when
+/// nothing points that way or the OS gives us no way to tell.
+pub fn detect_call_activity() -> Vec<String> {
+    if !is_discord_running() {
+        return Vec::new();
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return [("microphone", "Microphone"), ("webcam", "Camera")]
+            .into_iter()
+            .filter(|(capability, _)| capability_in_use_by_discord(capability))
+            .map(|(_, label)| format!("{label} is in use by Discord"))
+            .collect();
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        return if discord_holds_idle_assertion() {
+            vec![
+                "Discord is keeping the Mac awake, as it does during calls and screen shares"
+                    .to_string(),
+            ]
+        } else {
+            Vec::new()
+        };
+    }
+
+    #[allow(unreachable_code)]
+    Vec::new()
+}
+
+// Windows' privacy settings track which apps use the mic/camera; an entry with
+// LastUsedTimeStop = 0 is one that's using it right now
+#[cfg(target_os = "windows")]
+fn capability_in_use_by_discord(capability: &str) -> bool {
+    use std::process::{Command, Stdio};
+
+    let key = format!(
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\{capability}\NonPackaged"
+    );
+    let Ok(output) = Command::new("reg")
+        .args(["query", &key, "/s"])
+        .stderr(Stdio::null())
+        .output()
+    else {
+        return false;
+    };
+
+    // subkeys are executable paths with '#' for '\', e.g. C:#...#Discord#app-1.0.9#Discord.exe
+    let mut in_discord_key = false;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if line.starts_with("HKEY_") {
+            in_discord_key = line
+                .rsplit('\\')
+                .next()
+                .is_some_and(|app| app.to_ascii_lowercase().contains("discord"));
+        } else if in_discord_key
+            && line.trim_start().starts_with("LastUsedTimeStop")
+            && line.trim_end().ends_with(" 0x0")
+        {
+            return true;
+        }
+    }
+    false
+}
+
+// Discord's helpers take a "prevent idle sleep" assertion while voice or video runs
+#[cfg(target_os = "macos")]
+fn discord_holds_idle_assertion() -> bool {
+    use std::process::{Command, Stdio};
+
+    let Ok(output) = Command::new("pmset")
+        .args(["-g", "assertions"])
+        .stderr(Stdio::null())
+        .output()
+    else {
+        return false;
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|line| line.contains("(Discord") && line.contains("PreventUserIdle"))
+}
@@ -0,0 +1,92 @@
+use serde::Serialize;
+use std::env;
+
+use crate::{biometric, sandbox::available_sandbox_tools, settings::TokenBackend};
+
+/// Which features work on this OS and build, so the frontend can hide what
+/// doesn't instead of letting it fail at runtime.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformCapabilities {
+    pub os: String,
+    /// The phone build: saved accounts can be viewed, copied and synced from a
+    /// desktop export, but there's no Discord to switch.
+    pub companion: bool,
+    /// Token backends that keep working across restarts here.
+    pub token_backends: Vec<TokenBackend>,
+    /// The system keychain (Windows Credential Manager, macOS Keychain).
+    pub keychain: bool,
+    /// Whether the plain file backend binds tokens to this user and machine;
+    /// without it they're stored as they are.
+    pub machine_bound_files: bool,
+    /// Reading and writing tokens Discord sealed with os_crypt (DPAPI on
+    /// Windows, the Safe Storage keychain item on macOS).
+    pub discord_token_decryption: bool,
+    /// Windows Hello or Touch ID, checked right now.
+    pub biometric: bool,
+    /// Noticing a running call before Discord is closed.
+    pub call_detection: bool,
+    /// Prefixing Discord's window title with the active profile.
+    pub window_tagging: bool,
+    /// Launching through `Update.exe` and updating Discord before a switch.
+    pub squirrel_updater: bool,
+    /// The Microsoft Store build of Discord.
+    pub store_build: bool,
+    /// Finding Discord through Spotlight outside `/Applications`.
+    pub spotlight_detection: bool,
+    /// Installs in `/usr/share`, `/opt` and `~/.local/share`; Flatpak and
+    /// Snap builds of Discord aren't supported.
+    pub linux_install_paths: bool,
+    /// No tray icon or global hotkeys in this build yet.
+    pub tray: bool,
+    pub global_hotkeys: bool,
+    /// Launching a profile's Discord inside Sandboxie-Plus, firejail or
+    /// bubblewrap, when one of them is installed.
+    pub sandboxed_launch: bool,
+}
+
+pub fn get_platform_capabilities() -> PlatformCapabilities {
+    let windows = cfg!(target_os = "windows");
+    let macos = cfg!(target_os = "macos");
+    // the keyring crate is built with only the Windows and Apple stores; anywhere
+    // else it falls back to an in-memory mock that forgets everything on exit
+    let keychain = windows || macos;
+
+    let mut token_backends = vec![TokenBackend::File];
+    if keychain {
+        token_backends.extend([TokenBackend::EncryptedFile, TokenBackend::Keychain]);
+    }
+    token_backends.push(TokenBackend::Sqlite);
+
+    PlatformCapabilities {
+        os: env::consts::OS.to_string(),
+        companion: cfg!(any(target_os = "android", target_os = "ios")),
+        token_backends,
+        keychain,
+        machine_bound_files: windows || macos,
+        discord_token_decryption: windows || macos,
+        biometric: biometric::is_available(),
+        call_detection: windows || macos,
+        window_tagging: windows,
+        squirrel_updater: windows,
+        store_build: windows,
+        spotlight_detection: macos,
+        linux_install_paths: cfg!(target_os = "linux"),
+        tray: false,
+        global_hotkeys: false,
+        sandboxed_launch: !available_sandbox_tools().is_empty(),
+    }
+}
+
+/// Refuse a token backend that wouldn't keep tokens across a restart here,
+/// e.g. the keychain on Linux, where this build has no store to back it.
+pub fn ensure_token_backend_supported(backend: TokenBackend) -> Result<(), String> {
+    if get_platform_capabilities()
+        .token_backends
+        .contains(&backend)
+    {
+        Ok(())
+    } else {
+        Err("That token storage isn't available on this system.".to_string())
+    }
+}
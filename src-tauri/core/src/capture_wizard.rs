@@ -0,0 +1,112 @@
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    clock::now_ms,
+    secret::SecretToken,
+    settings::launch_channel,
+    storage::{read_discord_token_from_snapshot, token_keys},
+    DataDir,
+};
+
+/// Where the login-and-capture flow stands, so a multi-step UI can follow
+/// the backend instead of guessing from which buttons were clicked.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase", tag = "state")]
+pub enum CaptureWizardState {
+    #[default]
+    Idle,
+    /// Discord was signed out and relaunched; nobody has logged in yet.
+    /// `profile_id` is set when the next login gets captured automatically.
+    #[serde(rename_all = "camelCase")]
+    AwaitingLogin {
+        profile_id: Option<String>,
+        since_ms: u128,
+    },
+    /// Discord's storage holds a token again; it can be captured now.
+    #[serde(rename_all = "camelCase")]
+    TokenDetected {
+        profile_id: Option<String>,
+        detected_at_ms: u128,
+    },
+    #[serde(rename_all = "camelCase")]
+    Captured {
+        profile_id: String,
+        captured_at_ms: u128,
+    },
+}
+
+type Listener = Arc<dyn Fn(CaptureWizardState) + Send + Sync>;
+
+static STATE: Mutex<CaptureWizardState> = Mutex::new(CaptureWizardState::Idle);
+static LISTENER: Mutex<Option<Listener>> = Mutex::new(None);
+
+/// Receive every state change; replaces any earlier listener.
+pub fn set_capture_wizard_listener(listener: impl Fn(CaptureWizardState) + Send + Sync + 'static) {
+    if let Ok(mut current) = LISTENER.lock() {
+        *current = Some(Arc::new(listener));
+    }
+}
+
+pub fn capture_wizard_state() -> CaptureWizardState {
+    STATE.lock().map(|state| state.clone()).unwrap_or_default()
+}
+
+/// Back to idle, e.g. when the user closes the wizard or cancels an auto-capture.
+pub fn reset_capture_wizard() -> CaptureWizardState {
+    enter(CaptureWizardState::Idle);
+    CaptureWizardState::Idle
+}
+
+/// Mark the pending login as the one to capture into `profile_id`.
+pub fn expect_capture_for(profile_id: &str) {
+    if let CaptureWizardState::AwaitingLogin { since_ms, .. } = capture_wizard_state() {
+        enter(CaptureWizardState::AwaitingLogin {
+            profile_id: Some(profile_id.to_string()),
+            since_ms,
+        });
+    }
+}
+
+/// Look for a fresh login in a copy of Discord's storage (the live one stays
+/// locked while Discord runs) and move on to `TokenDetected` when one is there.
+pub fn detect_login(dir: &DataDir) -> Result<SecretToken, String> {
+    let keys = token_keys(dir)?;
+    let token = read_discord_token_from_snapshot(dir, &keys, None, launch_channel(dir)?)?;
+    if let CaptureWizardState::AwaitingLogin { profile_id, .. } = capture_wizard_state() {
+        enter(CaptureWizardState::TokenDetected {
+            profile_id,
+            detected_at_ms: now_ms(),
+        });
+    }
+    Ok(token)
+}
+
+pub(crate) fn awaiting_login() {
+    enter(CaptureWizardState::AwaitingLogin {
+        profile_id: None,
+        since_ms: now_ms(),
+    });
+}
+
+pub(crate) fn captured(profile_id: &str) {
+    enter(CaptureWizardState::Captured {
+        profile_id: profile_id.to_string(),
+        captured_at_ms: now_ms(),
+    });
+}
+
+fn enter(next: CaptureWizardState) {
+    let Ok(mut state) = STATE.lock() else {
+        return;
+    };
+    if *state == next {
+        return;
+    }
+    *state = next.clone();
+    drop(state);
+
+    if let Some(listener) = LISTENER.lock().ok().and_then(|current| current.clone()) {
+        listener(next);
+    }
+}
@@ -0,0 +1,147 @@
+use serde::Serialize;
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::{settings::DiscordChannel, storage::client_data_dir};
+
+/// A client mod injected into an official Discord install. Mods move some
+/// settings into their own files and can rewrite localStorage at startup, so
+/// a switch into a modded client is less predictable.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ClientMod {
+    BetterDiscord,
+    Vencord,
+    OpenAsar,
+}
+
+// the stock app.asar is tens of megabytes; a replaced or patched one is tiny
+const PATCHED_ASAR_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Which mods are patched into the install at `executable_path`. Forks such
+/// as Vesktop are clients of their own and are never reported as modded.
+pub(crate) fn detect_client_mods(
+    channel: DiscordChannel,
+    executable_path: &Path,
+) -> Vec<ClientMod> {
+    if matches!(
+        channel,
+        DiscordChannel::Vesktop
+            | DiscordChannel::WebCord
+            | DiscordChannel::ArmCord
+            | DiscordChannel::Custom
+    ) {
+        return Vec::new();
+    }
+    let Some(resources) = resources_dir(executable_path) else {
+        return Vec::new();
+    };
+    let app_asar = resources.join("app.asar");
+    let small_asar = fs::metadata(&app_asar).is_ok_and(|meta| meta.len() < PATCHED_ASAR_MAX_BYTES);
+
+    let mut mods = Vec::new();
+    if desktop_core_entries(channel, executable_path)
+        .iter()
+        .any(|index| file_mentions(index, "betterdiscord"))
+    {
+        mods.push(ClientMod::BetterDiscord);
+    }
+    // the installer keeps the original as _app.asar and loads its own patcher first
+    if resources.join("_app.asar").exists()
+        || (small_asar && file_mentions(&app_asar, "vencord"))
+        || file_mentions(&resources.join("app").join("index.js"), "vencord")
+    {
+        mods.push(ClientMod::Vencord);
+    }
+    if small_asar && file_mentions(&app_asar, "openasar") {
+        mods.push(ClientMod::OpenAsar);
+    }
+    mods
+}
+
+fn resources_dir(executable_path: &Path) -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    return Some(executable_path.join("Contents").join("Resources"));
+
+    // packages put a symlink on the PATH
+    #[cfg(target_os = "linux")]
+    return Some(
+        executable_path
+            .canonicalize()
+            .ok()?
+            .parent()?
+            .join("resources"),
+    );
+
+    #[allow(unreachable_code)]
+    Some(executable_path.parent()?.join("resources"))
+}
+
+// BetterDiscord patches the entry point of the discord_desktop_core module,
+// which Windows keeps next to the executable and macOS and Linux keep in a
+// versioned folder under the client's data folder
+fn desktop_core_entries(channel: DiscordChannel, executable_path: &Path) -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = channel;
+        let Some(modules) = executable_path.parent().map(|dir| dir.join("modules")) else {
+            return Vec::new();
+        };
+        return subfolders(&modules)
+            .into_iter()
+            .filter(|dir| {
+                dir.file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with("discord_desktop_core"))
+            })
+            .map(|dir| dir.join("discord_desktop_core").join("index.js"))
+            .collect();
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = executable_path;
+        let Some(data_dir) = client_data_dir(channel) else {
+            return Vec::new();
+        };
+        subfolders(&data_dir)
+            .into_iter()
+            .filter(|dir| {
+                dir.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(|c: char| c.is_ascii_digit()))
+            })
+            .map(|dir| {
+                dir.join("modules")
+                    .join("discord_desktop_core")
+                    .join("index.js")
+            })
+            .collect()
+    }
+}
+
+fn subfolders(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// a case-insensitive look through the first megabyte
+fn file_mentions(path: &Path, needle: &str) -> bool {
+    let mut content = Vec::new();
+    let read = File::open(path)
+        .and_then(|file| file.take(PATCHED_ASAR_MAX_BYTES).read_to_end(&mut content));
+    read.is_ok()
+        && content
+            .to_ascii_lowercase()
+            .windows(needle.len())
+            .any(|window| window == needle.as_bytes())
+}
@@ -0,0 +1,125 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+use zeroize::Zeroizing;
+
+use crate::{
+    audit::{record_audit, AuditAction},
+    secret::SecretToken,
+    tokens::authorize_reveal,
+    DataDir,
+};
+
+pub const MIN_CLIPBOARD_TTL_SECS: u64 = 5;
+pub const MAX_CLIPBOARD_TTL_SECS: u64 = 600;
+
+/// Put the profile's token on the clipboard and wipe it again after
+/// `ttl_secs`, unless something else has been copied since. Goes through the
+/// same checks as revealing the token and is audited like one.
+pub fn copy_token_to_clipboard(
+    dir: &DataDir,
+    profile_id: &str,
+    ttl_secs: u64,
+) -> Result<String, String> {
+    let result = copy_and_schedule_clear(dir, profile_id, ttl_secs);
+    record_audit(
+        dir,
+        AuditAction::Reveal,
+        Some(profile_id),
+        Some("clipboard"),
+        &result,
+    );
+    result
+}
+
+fn copy_and_schedule_clear(
+    dir: &DataDir,
+    profile_id: &str,
+    ttl_secs: u64,
+) -> Result<String, String> {
+    if !(MIN_CLIPBOARD_TTL_SECS..=MAX_CLIPBOARD_TTL_SECS).contains(&ttl_secs) {
+        return Err(format!(
+            "Clipboard timeout must be between {MIN_CLIPBOARD_TTL_SECS} and {MAX_CLIPBOARD_TTL_SECS} seconds."
+        ));
+    }
+    let token = authorize_reveal(dir, profile_id)?;
+    write_clipboard(token.expose())?;
+
+    thread::spawn(move || clear_after(token, Duration::from_secs(ttl_secs)));
+    Ok(format!(
+        "Token copied. The clipboard will be cleared in {ttl_secs} seconds."
+    ))
+}
+
+fn clear_after(token: SecretToken, ttl: Duration) {
+    thread::sleep(ttl);
+    // the user may have copied something else in the meantime; leave that alone
+    if read_clipboard().is_some_and(|current| current.trim_end() == token.expose()) {
+        if let Err(e) = write_clipboard("") {
+            eprintln!("Warning: could not clear the clipboard: {e}");
+        }
+    }
+}
+
+// ── Platform clipboard ──
+
+#[cfg(target_os = "windows")]
+const COPY_COMMAND: Option<(&str, &[&str])> = Some(("clip", &[]));
+#[cfg(target_os = "macos")]
+const COPY_COMMAND: Option<(&str, &[&str])> = Some(("pbcopy", &[]));
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const COPY_COMMAND: Option<(&str, &[&str])> = None;
+
+#[cfg(target_os = "windows")]
+const PASTE_COMMAND: Option<(&str, &[&str])> = Some((
+    "powershell",
+    &["-NoProfile", "-NonInteractive", "-Command", "Get-Clipboard"],
+));
+#[cfg(target_os = "macos")]
+const PASTE_COMMAND: Option<(&str, &[&str])> = Some(("pbpaste", &[]));
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PASTE_COMMAND: Option<(&str, &[&str])> = None;
+
+fn write_clipboard(text: &str) -> Result<(), String> {
+    let (program, args) = COPY_COMMAND
+        .ok_or_else(|| "Clipboard access isn't supported on this system.".to_string())?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Could not open the clipboard: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Could not write to the clipboard: {e}"))?;
+    }
+    let status = child
+        .wait()
+        .map_err(|e| format!("Could not write to the clipboard: {e}"))?;
+    if !status.success() {
+        return Err(format!(
+            "Could not write to the clipboard: {program} exited with {status}."
+        ));
+    }
+    Ok(())
+}
+
+// may well hold the token, so the copy we read is wiped too
+fn read_clipboard() -> Option<Zeroizing<String>> {
+    let (program, args) = PASTE_COMMAND?;
+    let output = Command::new(program)
+        .args(args)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let stdout = Zeroizing::new(output.stdout);
+    output
+        .status
+        .success()
+        .then(|| Zeroizing::new(String::from_utf8_lossy(&stdout).into_owned()))
+}
@@ -0,0 +1,26 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Milliseconds since the unix epoch; 0 if the system clock is before it.
+pub fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// `YYYY-MM-DD` (UTC) for a unix timestamp in milliseconds.
+// civil date by hand so we don't need chrono just for notes
+pub fn format_utc_date(ms: u128) -> String {
+    let days = (ms / 86_400_000) as i64;
+    // Howard Hinnant's days_from_civil, run backwards
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{year:04}-{month:02}-{day:02}")
+}
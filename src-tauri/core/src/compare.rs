@@ -0,0 +1,100 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::{
+    api::{fetch_discord_user, fetch_guilds, snowflake_created_at_ms},
+    audit::{last_successful, AuditAction},
+    profiles::{find_profile, StoredProfile, TokenStatus},
+    tokens::load_profile_token,
+    DataDir,
+};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NitroTier {
+    None,
+    Classic,
+    Nitro,
+    Basic,
+}
+
+impl NitroTier {
+    // `premium_type` on /users/@me
+    fn from_premium_type(premium_type: u8) -> Self {
+        match premium_type {
+            1 => Self::Classic,
+            2 => Self::Nitro,
+            3 => Self::Basic,
+            _ => Self::None,
+        }
+    }
+}
+
+/// One column of a side-by-side comparison, for deciding which alt to retire.
+/// Fields Discord has to answer are None when it couldn't be asked.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileComparison {
+    pub profile_id: String,
+    pub nickname: String,
+    /// When the Discord account was created, not the profile.
+    pub account_created_at_ms: Option<u128>,
+    pub nitro: Option<NitroTier>,
+    pub guild_count: Option<usize>,
+    pub token_status: TokenStatus,
+    pub token_checked_at_ms: Option<u128>,
+    /// The last successful switch to this profile.
+    pub last_used_ms: Option<u128>,
+    /// Why the Discord-side fields are missing.
+    pub error: Option<String>,
+}
+
+/// Compare `profile_ids` side by side, in the order given. Asks Discord about
+/// each account; nothing is stored.
+pub fn compare_profiles(
+    dir: &DataDir,
+    profile_ids: &[String],
+) -> Result<Vec<ProfileComparison>, String> {
+    let mut profiles: Vec<StoredProfile> = Vec::new();
+    for id in profile_ids {
+        if !profiles.iter().any(|p| &p.id == id) {
+            profiles.push(find_profile(dir, id)?);
+        }
+    }
+    if profiles.len() < 2 {
+        return Err("Pick at least two profiles to compare.".to_string());
+    }
+    let last_switches: HashMap<String, u128> = last_successful(dir, AuditAction::Switch);
+
+    Ok(profiles
+        .into_iter()
+        .map(|profile| {
+            let remote = load_profile_token(dir, &profile.id).and_then(|token| {
+                let user = fetch_discord_user(token.expose())?;
+                let guilds = fetch_guilds(token.expose())?;
+                Ok((user, guilds.len()))
+            });
+            let (user, guild_count, error) = match remote {
+                Ok((user, guild_count)) => (Some(user), Some(guild_count), None),
+                Err(e) => (None, None, Some(e)),
+            };
+            // the saved user id still dates the account when Discord can't be asked
+            let account_created_at_ms = user
+                .as_ref()
+                .map(|user| user.id.as_str())
+                .or(profile.discord_user_id.as_deref())
+                .and_then(snowflake_created_at_ms);
+            ProfileComparison {
+                last_used_ms: last_switches.get(&profile.id).copied(),
+                profile_id: profile.id,
+                nickname: profile.nickname,
+                account_created_at_ms,
+                nitro: user.map(|user| NitroTier::from_premium_type(user.premium_type)),
+                guild_count,
+                token_status: profile.token_status,
+                token_checked_at_ms: profile.token_checked_at_ms,
+                error,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,66 @@
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use zeroize::Zeroizing;
+
+const NONCE_LEN: usize = 12;
+
+/// An AES-256 key that is wiped when dropped.
+pub type SecretKey = Zeroizing<[u8; 32]>;
+
+pub fn random_key() -> SecretKey {
+    let mut key = Zeroizing::new([0u8; 32]);
+    OsRng.fill_bytes(key.as_mut());
+    key
+}
+
+pub fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// `N` random bytes as lowercase hex, for one-time confirmation tokens.
+pub fn random_hex<const N: usize>() -> String {
+    random_bytes::<N>()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// AES-256-GCM with a fresh nonce; returns base64(nonce || ciphertext).
+pub fn seal(key: &SecretKey, plaintext: &[u8]) -> Result<String, String> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref()));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| "Could not encrypt token.".to_string())?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(sealed))
+}
+
+/// Reverse of [`seal`].
+pub fn open(key: &SecretKey, sealed: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+    let bytes = STANDARD
+        .decode(sealed.trim())
+        .map_err(|_| "Could not decrypt token: data is corrupted.".to_string())?;
+    if bytes.len() < NONCE_LEN {
+        return Err("Could not decrypt token: data is corrupted.".to_string());
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref()));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map(Zeroizing::new)
+        .map_err(|_| "Could not decrypt token: wrong key or tampered data.".to_string())
+}
+
+/// Sealed text back into a token string.
+pub fn open_string(key: &SecretKey, sealed: &str) -> Result<String, String> {
+    String::from_utf8(open(key, sealed)?.to_vec())
+        .map_err(|_| "Could not decrypt token: not valid text.".to_string())
+}
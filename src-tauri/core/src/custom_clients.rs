@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+pub const MAX_CLIENT_NAME_CHARS: usize = 40;
+
+/// A Discord fork the detector doesn't know about, registered by the user.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomClient {
+    pub name: String,
+    pub executable_path: String,
+    /// Its localStorage LevelDB folder, e.g. `%APPDATA%\lightcord\Local Storage\leveldb`.
+    pub storage_dir: String,
+    /// The process name as the OS lists it (`Lightcord.exe` on Windows), used to close it.
+    pub process_name: String,
+}
+
+struct Registry {
+    clients: Vec<CustomClient>,
+    active: Option<String>,
+}
+
+// follows the `customClients` setting and the one launches use; updated
+// whenever settings are loaded or saved, so storage and process control
+// can honor it without threading settings through every call
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    clients: Vec::new(),
+    active: None,
+});
+
+// paths aren't checked for existence: the client may not have run yet
+pub fn sanitize_custom_client(client: CustomClient) -> Result<CustomClient, String> {
+    let name = client.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_CLIENT_NAME_CHARS {
+        return Err(format!(
+            "Client names must be between 1 and {MAX_CLIENT_NAME_CHARS} characters."
+        ));
+    }
+    let required = |value: &str, what: &str| {
+        let value = value.trim();
+        if value.is_empty() {
+            Err(format!("Give {name} {what}."))
+        } else {
+            Ok(value.to_string())
+        }
+    };
+    Ok(CustomClient {
+        executable_path: required(&client.executable_path, "an executable path")?,
+        storage_dir: required(&client.storage_dir, "a storage folder")?,
+        process_name: required(&client.process_name, "a process name")?,
+        name,
+    })
+}
+
+pub(crate) fn set_custom_clients(clients: &[CustomClient], active: Option<&str>) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        registry.clients = clients.to_vec();
+        registry.active = active.map(str::to_string);
+    }
+}
+
+pub fn custom_clients() -> Vec<CustomClient> {
+    REGISTRY
+        .lock()
+        .map(|registry| registry.clients.clone())
+        .unwrap_or_default()
+}
+
+/// The registered client launches currently use, if any.
+pub fn active_custom_client() -> Option<CustomClient> {
+    let registry = REGISTRY.lock().ok()?;
+    let active = registry.active.as_deref()?;
+    registry.clients.iter().find(|c| c.name == active).cloned()
+}
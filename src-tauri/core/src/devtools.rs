@@ -0,0 +1,184 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    net::TcpListener,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+use tungstenite::{stream::MaybeTlsStream, Message};
+use zeroize::Zeroizing;
+
+use crate::{
+    http::loopback_request,
+    launch::{launch_discord_with_args, resolve_launch_target, DiscordInstallation},
+    os_crypt::decrypt_token,
+    progress::{report_progress, Operation, ProgressStep},
+    secret::SecretToken,
+    settings::{launch_channel, load_launcher_settings},
+    storage::discord_storage_dir,
+    switch::with_discord_closed,
+    DataDir,
+};
+
+// the debugging port of the Discord this app last started; forgotten on exit
+static DEVTOOLS_PORT: Mutex<Option<u16>> = Mutex::new(None);
+
+// how long a restarted Discord gets to load its page and sign in from storage
+const PAGE_TIMEOUT: Duration = Duration::from_secs(45);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Discord deletes `window.localStorage` once it has loaded, but a fresh
+// iframe on the same origin still gets its own handle to it
+const READ_TOKEN: &str = "(() => { const frame = document.createElement('iframe'); document.body.appendChild(frame); const token = frame.contentWindow.localStorage.getItem('token'); frame.remove(); return token; })()";
+
+// an entry of the endpoint's /json/list
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DevToolsTarget {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+    web_socket_debugger_url: Option<String>,
+}
+
+/// Read the live token from Discord's page through the Chrome DevTools
+/// Protocol instead of its LevelDB files. Attaches to the Discord this app
+/// started with a debugging port, or restarts Discord with one first. The
+/// port stays open, on this computer only, until Discord is restarted.
+pub(crate) fn read_token_via_devtools(dir: &DataDir) -> Result<SecretToken, String> {
+    let attached = DEVTOOLS_PORT
+        .lock()
+        .ok()
+        .and_then(|port| *port)
+        .and_then(discord_page);
+    let token = match attached {
+        Some(page) => {
+            report_progress(dir, Operation::Capture, ProgressStep::ReadingToken);
+            read_local_storage_token(&page)?
+        }
+        None => {
+            let page = restart_with_devtools(dir)?;
+            report_progress(dir, Operation::Capture, ProgressStep::ReadingToken);
+            // the page signs in from storage a moment after it has loaded
+            wait_for(|| read_local_storage_token(&page).ok().flatten())
+        }
+    };
+    let token = token.ok_or_else(|| {
+        "No account is logged in to Discord. Log in, then capture again.".to_string()
+    })?;
+    decrypt_token(&discord_storage_dir(launch_channel(dir)?)?, &token)
+}
+
+/// Start Discord with a debugging port on a free local port, so a later
+/// DevTools capture can attach to it without restarting it.
+pub(crate) fn launch_with_devtools(target: &DiscordInstallation) -> Result<u16, String> {
+    let port = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Could not find a free port for Discord's DevTools: {e}"))?;
+    launch_discord_with_args(target, &[format!("--remote-debugging-port={port}")])?;
+    if let Ok(mut current) = DEVTOOLS_PORT.lock() {
+        *current = Some(port);
+    }
+    Ok(port)
+}
+
+// closes Discord, starts it again with a debugging port and waits for its page
+fn restart_with_devtools(dir: &DataDir) -> Result<String, String> {
+    let target = resolve_launch_target(load_launcher_settings(&dir.launcher_settings_file())?)?;
+    let port = with_discord_closed(dir, Operation::Capture, || {
+        report_progress(
+            dir,
+            Operation::Capture,
+            ProgressStep::LaunchingDiscord {
+                label: target.label.clone(),
+            },
+        );
+        launch_with_devtools(&target)
+    })?;
+    wait_for(|| discord_page(port)).ok_or_else(|| {
+        format!(
+            "{} didn't open its DevTools port. Some builds refuse remote debugging; use another capture mode.",
+            target.label
+        )
+    })
+}
+
+// the debugger URL of the Discord app page listening on `port`
+fn discord_page(port: u16) -> Option<String> {
+    let targets: Vec<DevToolsTarget> =
+        loopback_request("GET", &format!("http://127.0.0.1:{port}/json/list"))
+            .call()
+            .ok()?
+            .into_json()
+            .ok()?;
+    targets
+        .into_iter()
+        .filter(|target| target.kind == "page" && is_discord_url(&target.url))
+        .find_map(|target| target.web_socket_debugger_url)
+}
+
+// discord.com, ptb.discord.com, canary.discord.com
+fn is_discord_url(url: &str) -> bool {
+    url.strip_prefix("https://")
+        .and_then(|rest| rest.split('/').next())
+        .is_some_and(|host| host == "discord.com" || host.ends_with(".discord.com"))
+}
+
+fn read_local_storage_token(debugger_url: &str) -> Result<Option<Zeroizing<String>>, String> {
+    let (mut socket, _) = tungstenite::connect(debugger_url)
+        .map_err(|e| format!("Could not attach to Discord's DevTools: {e}"))?;
+    if let MaybeTlsStream::Plain(stream) = socket.get_mut() {
+        let _ = stream.set_read_timeout(Some(REPLY_TIMEOUT));
+    }
+    let request = json!({
+        "id": 1,
+        "method": "Runtime.evaluate",
+        "params": { "expression": READ_TOKEN, "returnByValue": true },
+    });
+    socket
+        .send(Message::Text(request.to_string()))
+        .map_err(|e| format!("Could not talk to Discord's DevTools: {e}"))?;
+
+    // events for other domains may arrive before the reply
+    let reply = loop {
+        let message = socket
+            .read()
+            .map_err(|e| format!("Could not read from Discord's DevTools: {e}"))?;
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let reply: Value = serde_json::from_str(&text)
+            .map_err(|e| format!("Could not parse Discord's DevTools reply: {e}"))?;
+        if reply["id"] == 1 {
+            break reply;
+        }
+    };
+    let _ = socket.close(None);
+
+    if let Some(exception) = reply["result"]["exceptionDetails"]["text"].as_str() {
+        return Err(format!(
+            "Discord's page refused to read its storage: {exception}"
+        ));
+    }
+    // localStorage holds JSON, so the token comes back quoted
+    Ok(reply["result"]["result"]["value"]
+        .as_str()
+        .and_then(|raw| serde_json::from_str::<String>(raw).ok())
+        .map(Zeroizing::new))
+}
+
+fn wait_for<T>(check: impl Fn() -> Option<T>) -> Option<T> {
+    let deadline = Instant::now() + PAGE_TIMEOUT;
+    loop {
+        if let Some(found) = check() {
+            return Some(found);
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,255 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::DefaultHasher,
+    env, fs,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    clock::now_ms,
+    launch::{detect_installations_for_current_os, DiscordInstallation},
+    managed_env::{check_environment, EnvironmentFinding},
+    markers::{load_modification_markers, machine_name, ModificationMarker},
+    os_crypt::ENCRYPTED_TOKEN_PREFIX,
+    settings::{launch_channel, load_launcher_settings, TokenBackend},
+    storage::{discord_storage_dir, escape_token_key, read_key_values_from_snapshot, token_keys},
+    switch, DataDir,
+};
+
+// how much of a raw value a snapshot shows: the encoding byte, the opening quote
+// and two characters, which is enough to tell formats apart without leaking the token
+const SNAPSHOT_PREFIX_BYTES: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataFileDiagnostics {
+    pub name: String,
+    pub exists: bool,
+    pub size_bytes: u64,
+    pub last_modified_by: Option<ModificationMarker>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub machine: String,
+    pub data_dir: String,
+    pub data_files: Vec<DataFileDiagnostics>,
+    pub token_backend: TokenBackend,
+    pub discord_storage_dir: Result<String, String>,
+    pub installations: Vec<DiscordInstallation>,
+    /// Restrictions on a managed machine that can break launching or closing Discord.
+    pub environment: Vec<EnvironmentFinding>,
+}
+
+pub fn get_diagnostics(dir: &DataDir) -> Result<DiagnosticsReport, String> {
+    let markers = load_modification_markers(dir.root());
+    let data_files = [
+        dir.profiles_file(),
+        dir.launcher_settings_file(),
+        dir.token_keys_file(),
+        dir.token_database(),
+    ]
+    .iter()
+    .map(|path| {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let metadata = fs::metadata(path).ok();
+        DataFileDiagnostics {
+            exists: metadata.is_some(),
+            size_bytes: metadata.map(|m| m.len()).unwrap_or(0),
+            last_modified_by: markers.get(&name).cloned(),
+            name,
+        }
+    })
+    .collect();
+    let settings = load_launcher_settings(&dir.launcher_settings_file())?;
+
+    Ok(DiagnosticsReport {
+        app_version: crate::VERSION.to_string(),
+        os: env::consts::OS.to_string(),
+        arch: env::consts::ARCH.to_string(),
+        machine: machine_name(),
+        data_dir: dir.root().to_string_lossy().to_string(),
+        data_files,
+        token_backend: settings.token_backend,
+        discord_storage_dir: settings
+            .launch_channel()
+            .and_then(discord_storage_dir)
+            .map(|p| p.to_string_lossy().to_string()),
+        installations: detect_installations_for_current_os(),
+        environment: check_environment(),
+    })
+}
+
+// ── Storage snapshots ──
+
+/// What one token key held at snapshot time, without the value itself.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeySnapshot {
+    pub key: String,
+    pub present: bool,
+    pub value_len: usize,
+    pub prefix: String,
+    pub encrypted: bool,
+    // only used to spot same-length rewrites; never leaves the process
+    #[serde(skip)]
+    fingerprint: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageSnapshot {
+    pub taken_at_ms: u128,
+    pub keys: Vec<KeySnapshot>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyChange {
+    Unchanged,
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyDiff {
+    pub key: String,
+    pub change: KeyChange,
+    pub before: KeySnapshot,
+    pub after: KeySnapshot,
+}
+
+/// Operations worth watching when a switch "doesn't stick".
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosedOperation {
+    Switch,
+    PrepareLogin,
+}
+
+/// Redacted before/after view of Discord's token keys around one operation.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDiffReport {
+    pub operation: String,
+    pub outcome: Result<String, String>,
+    pub before: StorageSnapshot,
+    pub after: StorageSnapshot,
+    pub changes: Vec<KeyDiff>,
+}
+
+/// Read every known token key from a copy of Discord's storage, redacted.
+pub fn snapshot_token_storage(dir: &DataDir) -> Result<StorageSnapshot, String> {
+    let keys = token_keys(dir)?;
+    let values = read_key_values_from_snapshot(dir, &keys, launch_channel(dir)?)?;
+    Ok(StorageSnapshot {
+        taken_at_ms: now_ms(),
+        keys: keys
+            .iter()
+            .zip(values)
+            .map(|(key, value)| snapshot_key(key, value.as_deref()))
+            .collect(),
+    })
+}
+
+/// Compare two snapshots key by key.
+pub fn diff_storage_snapshots(before: &StorageSnapshot, after: &StorageSnapshot) -> Vec<KeyDiff> {
+    after
+        .keys
+        .iter()
+        .map(|after_key| {
+            let before_key = before
+                .keys
+                .iter()
+                .find(|k| k.key == after_key.key)
+                .cloned()
+                .unwrap_or_else(|| KeySnapshot {
+                    key: after_key.key.clone(),
+                    ..snapshot_key(&[], None)
+                });
+            let change = match (before_key.present, after_key.present) {
+                (false, false) => KeyChange::Unchanged,
+                (false, true) => KeyChange::Added,
+                (true, false) => KeyChange::Removed,
+                (true, true) if before_key.fingerprint == after_key.fingerprint => {
+                    KeyChange::Unchanged
+                }
+                (true, true) => KeyChange::Modified,
+            };
+            KeyDiff {
+                key: after_key.key.clone(),
+                change,
+                before: before_key,
+                after: after_key.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Snapshot the token keys, run `operation`, snapshot again and diff.
+/// The operation's own failure is part of the report, not an error.
+pub fn diff_storage_around(
+    dir: &DataDir,
+    label: &str,
+    operation: impl FnOnce() -> Result<String, String>,
+) -> Result<StorageDiffReport, String> {
+    let before = snapshot_token_storage(dir)?;
+    let outcome = operation();
+    let after = snapshot_token_storage(dir)?;
+    let changes = diff_storage_snapshots(&before, &after);
+
+    Ok(StorageDiffReport {
+        operation: label.to_string(),
+        outcome,
+        before,
+        after,
+        changes,
+    })
+}
+
+pub fn diagnose_storage_change(
+    dir: &DataDir,
+    operation: DiagnosedOperation,
+    profile_id: Option<&str>,
+) -> Result<StorageDiffReport, String> {
+    match operation {
+        DiagnosedOperation::Switch => {
+            let profile_id =
+                profile_id.ok_or_else(|| "Pick a profile to switch to.".to_string())?;
+            diff_storage_around(dir, "switch", || {
+                switch::switch_to_profile(dir, profile_id, false, false, None)
+                    .map(|outcome| outcome.message)
+            })
+        }
+        DiagnosedOperation::PrepareLogin => diff_storage_around(dir, "prepareLogin", || {
+            switch::prepare_login(dir, false, false)
+        }),
+    }
+}
+
+fn snapshot_key(key: &[u8], value: Option<&[u8]>) -> KeySnapshot {
+    let value = value.unwrap_or_default();
+    let body = value.get(1..).unwrap_or_default();
+    let encrypted = body.starts_with(format!("\"{ENCRYPTED_TOKEN_PREFIX}").as_bytes());
+
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+
+    KeySnapshot {
+        key: escape_token_key(key),
+        present: !value.is_empty(),
+        value_len: value.len(),
+        prefix: escape_token_key(&value[..value.len().min(SNAPSHOT_PREFIX_BYTES)]),
+        encrypted,
+        fingerprint: hasher.finish(),
+    }
+}
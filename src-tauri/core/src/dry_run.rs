@@ -0,0 +1,141 @@
+use serde::Serialize;
+use std::{fs, path::Path};
+use zeroize::Zeroizing;
+
+use crate::{
+    api::{check_discord_token, TokenCheck},
+    os_crypt::decrypt_token,
+    profiles::find_profile,
+    settings::{launch_channel, load_launcher_settings},
+    storage::{
+        copy_leveldb_files, discord_storage_dir, read_token_from_dir, seal_token_for, token_keys,
+        write_token_to_dir,
+    },
+    tokens::load_switch_token,
+    DataDir,
+};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunStep {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// Outcome of rehearsing a switch against a throwaway copy of Discord's storage.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunReport {
+    pub profile_id: String,
+    pub steps: Vec<DryRunStep>,
+    pub success: bool,
+}
+
+/// Rehearse a switch on a copy of Discord's storage; the live session is never touched.
+pub fn dry_run_switch(dir: &DataDir, profile_id: &str) -> Result<DryRunReport, String> {
+    find_profile(dir, profile_id)?;
+
+    let sandbox_dir = dir.scratch_dir("dry-run")?;
+    let steps = run_dry_run_steps(dir, profile_id, &sandbox_dir);
+    let _ = fs::remove_dir_all(&sandbox_dir);
+
+    let success = steps.iter().all(|s| s.ok);
+    Ok(DryRunReport {
+        profile_id: profile_id.to_string(),
+        steps,
+        success,
+    })
+}
+
+// each step records what happened and the run stops at the first failure
+fn run_dry_run_steps(dir: &DataDir, profile_id: &str, sandbox_dir: &Path) -> Vec<DryRunStep> {
+    let mut steps = Vec::new();
+    let mut record = |name: &str, result: Result<String, String>| {
+        let ok = result.is_ok();
+        steps.push(DryRunStep {
+            name: name.to_string(),
+            ok,
+            detail: result.unwrap_or_else(|e| e),
+        });
+        ok
+    };
+
+    let channel = match launch_channel(dir) {
+        Ok(channel) => channel,
+        Err(e) => {
+            record("Load saved token", Err(e));
+            return steps;
+        }
+    };
+    let token = match load_switch_token(dir, profile_id, channel) {
+        Ok(token) => {
+            record("Load saved token", Ok("Token loaded.".to_string()));
+            token
+        }
+        Err(e) => {
+            record("Load saved token", Err(e));
+            return steps;
+        }
+    };
+
+    let keys = match token_keys(dir) {
+        Ok(keys) => {
+            record(
+                "Load token keys",
+                Ok(format!("{} key patterns.", keys.len())),
+            );
+            keys
+        }
+        Err(e) => {
+            record("Load token keys", Err(e));
+            return steps;
+        }
+    };
+
+    let storage_dir = match discord_storage_dir(channel) {
+        Ok(storage_dir) => storage_dir,
+        Err(e) => {
+            record("Copy Discord storage", Err(e));
+            return steps;
+        }
+    };
+    let copied = copy_leveldb_files(&storage_dir, sandbox_dir)
+        .map(|_| format!("Copied storage to {}.", sandbox_dir.display()));
+    if !record("Copy Discord storage", copied) {
+        return steps;
+    }
+
+    let strategy = load_launcher_settings(&dir.launcher_settings_file())
+        .map(|s| s.write_strategy)
+        .unwrap_or_default();
+    // sealed with the live install's key, exactly as a real switch would
+    let written = seal_token_for(&storage_dir, token.expose())
+        .and_then(|sealed| write_token_to_dir(sandbox_dir, &sealed, &keys, strategy))
+        .map(|_| format!("Token written ({strategy:?})."));
+    if !record("Write token into copy", written) {
+        return steps;
+    }
+
+    let verified = read_token_from_dir(sandbox_dir, &keys)
+        .and_then(|raw| decrypt_token(&storage_dir, &Zeroizing::new(raw)))
+        .and_then(|read_back| {
+            if read_back.expose() == token.expose() {
+                Ok("Stored value matches.".to_string())
+            } else {
+                Err("Read-back value differs from the saved token.".to_string())
+            }
+        });
+    if !record("Read back token", verified) {
+        return steps;
+    }
+
+    let checked = match check_discord_token(token.expose()) {
+        TokenCheck::Valid(user) => Ok(format!("Discord accepted the token for {}.", user.username)),
+        TokenCheck::Invalid => Err("Discord rejected the token.".to_string()),
+        TokenCheck::Unreachable(e) => Err(e),
+    };
+    record("Validate with Discord", checked);
+
+    steps
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::{
+    api::{check_discord_token, TokenCheck},
+    clock::now_ms,
+    http::request,
+    profiles::{load_profiles, save_profiles, Profile, StoredProfile, TokenStatus},
+    settings::load_launcher_settings,
+    tokens::{load_profile_token, profile_has_token},
+    DataDir,
+};
+
+/// A token that hasn't been confirmed valid for this long counts as stale.
+pub const TOKEN_STALE_AFTER_MS: u128 = 7 * 24 * 60 * 60 * 1000;
+
+/// When the last digest went out, kept apart from user-facing settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestState {
+    pub last_sent_at_ms: Option<u128>,
+}
+
+/// Profile nicknames grouped by token health.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthSummary {
+    pub valid: Vec<String>,
+    pub stale: Vec<String>,
+    pub invalid: Vec<String>,
+    pub missing_token: Vec<String>,
+}
+
+/// Ask Discord about every saved token and remember the answers.
+pub fn validate_profile_tokens(dir: &DataDir) -> Result<Vec<Profile>, String> {
+    let profiles = refresh_token_statuses(dir)?;
+    Ok(profiles
+        .into_iter()
+        .map(|p| {
+            let has = profile_has_token(dir, &p.id);
+            p.into_profile(has)
+        })
+        .collect())
+}
+
+pub fn refresh_token_statuses(dir: &DataDir) -> Result<Vec<StoredProfile>, String> {
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+
+    for profile in profiles.iter_mut() {
+        let Ok(token) = load_profile_token(dir, &profile.id) else {
+            continue;
+        };
+        // network trouble says nothing about the token, keep the old verdict
+        match check_discord_token(token.expose()) {
+            TokenCheck::Valid(user) => {
+                profile.discord_user_id = Some(user.id);
+                profile.token_status = TokenStatus::Valid;
+                profile.token_checked_at_ms = Some(now_ms());
+            }
+            TokenCheck::Invalid => {
+                profile.token_status = TokenStatus::Quarantined;
+                profile.token_checked_at_ms = Some(now_ms());
+            }
+            TokenCheck::Unreachable(e) => {
+                eprintln!("Warning: could not validate '{}': {e}", profile.nickname);
+            }
+        }
+    }
+
+    save_profiles(&file_path, &profiles)?;
+    Ok(profiles)
+}
+
+pub fn summarize_token_health(dir: &DataDir, profiles: &[StoredProfile]) -> HealthSummary {
+    let now = now_ms();
+    let mut summary = HealthSummary {
+        valid: Vec::new(),
+        stale: Vec::new(),
+        invalid: Vec::new(),
+        missing_token: Vec::new(),
+    };
+    for profile in profiles {
+        let name = profile.nickname.clone();
+        if !profile_has_token(dir, &profile.id) {
+            summary.missing_token.push(name);
+            continue;
+        }
+        let fresh = profile
+            .token_checked_at_ms
+            .map(|at| now.saturating_sub(at) < TOKEN_STALE_AFTER_MS)
+            .unwrap_or(false);
+        match profile.token_status {
+            TokenStatus::Quarantined => summary.invalid.push(name),
+            TokenStatus::Valid if fresh => summary.valid.push(name),
+            _ => summary.stale.push(name),
+        }
+    }
+    summary
+}
+
+pub fn format_health_digest(summary: &HealthSummary) -> String {
+    let section = |title: &str, names: &[String]| {
+        if names.is_empty() {
+            format!("**{title}:** none")
+        } else {
+            format!("**{title} ({}):** {}", names.len(), names.join(", "))
+        }
+    };
+    let mut message = [
+        "**Discord Alt Manager — account health**".to_string(),
+        section("Valid", &summary.valid),
+        section("Stale", &summary.stale),
+        section("Quarantined", &summary.invalid),
+        section("No token", &summary.missing_token),
+    ]
+    .join("\n");
+    // webhook messages cap out at 2000 characters
+    if message.chars().count() > 2000 {
+        message = message.chars().take(1997).collect::<String>() + "...";
+    }
+    message
+}
+
+/// Send the digest to the webhook configured in settings right now.
+pub fn send_health_digest(dir: &DataDir) -> Result<HealthSummary, String> {
+    let settings = load_launcher_settings(&dir.launcher_settings_file())?;
+    let webhook_url = settings
+        .health_digest
+        .webhook_url
+        .ok_or_else(|| "Set a webhook URL for the health digest first.".to_string())?;
+    deliver_health_digest(dir, &webhook_url)
+}
+
+pub fn deliver_health_digest(dir: &DataDir, webhook_url: &str) -> Result<HealthSummary, String> {
+    let mut profiles = refresh_token_statuses(dir)?;
+    // the digest leaves the machine, so never-export accounts stay out of it
+    profiles.retain(|p| !p.never_export);
+    let summary = summarize_token_health(dir, &profiles);
+
+    request("POST", webhook_url)?
+        .send_json(serde_json::json!({ "content": format_health_digest(&summary) }))
+        .map_err(|e| format!("Could not send the health digest: {e}"))?;
+
+    save_digest_state(
+        &dir.digest_state_file(),
+        &DigestState {
+            last_sent_at_ms: Some(now_ms()),
+        },
+    )?;
+
+    Ok(summary)
+}
+
+/// Send the digest if the configured frequency says one is due.
+pub fn send_digest_if_due(dir: &DataDir) -> Result<(), String> {
+    let settings = load_launcher_settings(&dir.launcher_settings_file())?;
+    // nothing could be checked or sent anyway
+    if settings.offline_mode {
+        return Ok(());
+    }
+    let digest = settings.health_digest;
+    let (Some(interval), Some(webhook_url)) = (digest.frequency.interval_ms(), digest.webhook_url)
+    else {
+        return Ok(());
+    };
+
+    let state = load_digest_state(&dir.digest_state_file())?;
+    let due = state
+        .last_sent_at_ms
+        .map(|at| now_ms().saturating_sub(at) >= interval)
+        .unwrap_or(true);
+    if due {
+        deliver_health_digest(dir, &webhook_url)?;
+    }
+    Ok(())
+}
+
+// ── Persistence ──
+
+pub fn load_digest_state(file_path: &Path) -> Result<DigestState, String> {
+    if !file_path.exists() {
+        return Ok(DigestState::default());
+    }
+    let content =
+        fs::read_to_string(file_path).map_err(|e| format!("Could not read digest state: {e}"))?;
+    if content.trim().is_empty() {
+        return Ok(DigestState::default());
+    }
+    serde_json::from_str(&content).map_err(|e| format!("Could not parse digest state: {e}"))
+}
+
+pub fn save_digest_state(file_path: &Path, state: &DigestState) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Could not encode digest state: {e}"))?;
+    fs::write(file_path, payload).map_err(|e| format!("Could not save digest state: {e}"))
+}
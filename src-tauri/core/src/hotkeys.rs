@@ -0,0 +1,259 @@
+use serde::Serialize;
+
+/// Why a hotkey can't be used, with combos that would work instead.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyProblem {
+    pub message: String,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Modifier {
+    // ⌘ on macOS, Ctrl elsewhere
+    CommandOrControl,
+    Control,
+    Alt,
+    Shift,
+    Super,
+}
+
+impl Modifier {
+    fn parse(token: &str) -> Option<Self> {
+        match token.to_ascii_lowercase().as_str() {
+            "commandorcontrol" | "cmdorctrl" | "commandorctrl" | "cmdorcontrol" => {
+                Some(Self::CommandOrControl)
+            }
+            "ctrl" | "control" => Some(Self::Control),
+            "alt" | "option" => Some(Self::Alt),
+            "shift" => Some(Self::Shift),
+            "super" | "cmd" | "command" | "meta" | "win" | "windows" => Some(Self::Super),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::CommandOrControl => "CommandOrControl",
+            Self::Control => "Control",
+            Self::Alt => "Alt",
+            Self::Shift => "Shift",
+            Self::Super => "Super",
+        }
+    }
+
+    // what the OS actually receives
+    fn resolved(self) -> Self {
+        match self {
+            Self::CommandOrControl if cfg!(target_os = "macos") => Self::Super,
+            Self::CommandOrControl => Self::Control,
+            other => other,
+        }
+    }
+}
+
+// keys with the same position and meaning on every layout, by the name an
+// accelerator uses for them
+const NAMED_KEYS: &[&str] = &[
+    "Space",
+    "Tab",
+    "Enter",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "Up",
+    "Down",
+    "Left",
+    "Right",
+    "Escape",
+    "Num0",
+    "Num1",
+    "Num2",
+    "Num3",
+    "Num4",
+    "Num5",
+    "Num6",
+    "Num7",
+    "Num8",
+    "Num9",
+    "NumAdd",
+    "NumSubtract",
+    "NumMultiply",
+    "NumDivide",
+];
+
+// dead keys on common European layouts: they wait for the next key and
+// never reach the OS as a key of their own
+const DEAD_KEYS: &[char] = &['´', '`', '^', '¨', '~', '¸', '˝', '˘', '°'];
+
+// Windows sends AltGr as Ctrl+Alt, so these Ctrl+Alt combos type a character
+// (@, €, µ, {, [ and so on) on German, French, Polish and Nordic layouts
+const ALTGR_KEYS: &[char] = &['Q', 'E', 'M', 'L', '2', '3', '4', '5', '7', '8', '9', '0'];
+
+// taken by the OS before any app sees them, after CommandOrControl is resolved
+const RESERVED: &[(&str, &str)] = &[
+    ("Super+L", "locks the computer"),
+    ("Super+D", "shows the desktop"),
+    ("Super+Tab", "opens the task switcher"),
+    ("Alt+Tab", "switches windows"),
+    ("Alt+F4", "closes the focused window"),
+    ("Control+Alt+Delete", "opens the security screen"),
+    (
+        "Super+Space",
+        "opens Spotlight or switches the input language",
+    ),
+    ("Control+Space", "switches the input source"),
+    ("Shift+Super+3", "takes a screenshot"),
+    ("Shift+Super+4", "takes a screenshot"),
+    ("Shift+Super+5", "opens the screenshot tools"),
+];
+
+// tried in order when a hotkey is rejected; None stands for the key that was asked for
+const SUGGESTIONS: &[(&[Modifier], Option<&str>)] = &[
+    (&[Modifier::CommandOrControl, Modifier::Shift], None),
+    (
+        &[Modifier::CommandOrControl, Modifier::Alt, Modifier::Shift],
+        None,
+    ),
+    (&[Modifier::CommandOrControl, Modifier::Shift], Some("K")),
+    (
+        &[Modifier::CommandOrControl, Modifier::Alt, Modifier::Shift],
+        Some("F9"),
+    ),
+];
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Check a global hotkey (`CommandOrControl+Shift+K` style) before it's
+/// registered and return it in canonical form. Rejects combos that depend on
+/// the keyboard layout, like dead keys, AltGr characters and symbols that move
+/// between layouts, and ones the OS keeps for itself.
+pub fn validate_hotkey(accelerator: &str) -> Result<String, HotkeyProblem> {
+    let message = match check_hotkey(accelerator) {
+        Ok(canonical) => return Ok(canonical),
+        Err(message) => message,
+    };
+
+    let wanted_key = accelerator
+        .rsplit('+')
+        .next()
+        .and_then(|key| canonical_key(key.trim()).ok());
+    let mut suggestions: Vec<String> = Vec::new();
+    for (modifiers, key) in SUGGESTIONS {
+        let Some(key) = key.map(str::to_string).or_else(|| wanted_key.clone()) else {
+            continue;
+        };
+        let candidate = join(modifiers, &key);
+        if check_hotkey(&candidate).is_ok() && !suggestions.contains(&candidate) {
+            suggestions.push(candidate);
+        }
+    }
+    suggestions.truncate(MAX_SUGGESTIONS);
+    Err(HotkeyProblem {
+        message,
+        suggestions,
+    })
+}
+
+fn check_hotkey(accelerator: &str) -> Result<String, String> {
+    let tokens: Vec<&str> = accelerator.split('+').map(str::trim).collect();
+    // a trailing "+" is the plus key itself, e.g. "Ctrl++"
+    let (modifier_tokens, key_token) = match tokens.as_slice() {
+        [] | [""] => return Err("Press a key combination first.".to_string()),
+        [rest @ .., "", ""] => (rest, "+"),
+        [_, .., ""] => return Err("Finish the combination with a key.".to_string()),
+        [rest @ .., key] => (rest, *key),
+    };
+
+    let mut modifiers = Vec::new();
+    for token in modifier_tokens {
+        if token.eq_ignore_ascii_case("altgr") {
+            return Err("AltGr can't be part of a global hotkey: Windows sees it as Ctrl+Alt, and on Linux it picks a character instead of acting as a modifier.".to_string());
+        }
+        let modifier =
+            Modifier::parse(token).ok_or_else(|| format!("\"{token}\" isn't a modifier key."))?;
+        if modifiers.contains(&modifier) {
+            return Err(format!("{} is in there twice.", modifier.name()));
+        }
+        modifiers.push(modifier);
+    }
+    modifiers.sort();
+
+    let key = canonical_key(key_token)?;
+    let is_function_key = key.len() > 1
+        && key
+            .strip_prefix('F')
+            .is_some_and(|n| n.parse::<u8>().is_ok());
+    if !is_function_key && modifiers.iter().all(|m| *m == Modifier::Shift) {
+        return Err(format!(
+            "{} would fire while you type. Add Ctrl, Alt or Super.",
+            join(&modifiers, &key)
+        ));
+    }
+
+    let mut resolved: Vec<Modifier> = modifiers.iter().map(|m| m.resolved()).collect();
+    resolved.sort();
+    resolved.dedup();
+    if resolved.len() != modifiers.len() {
+        return Err("CommandOrControl already includes that modifier on this system.".to_string());
+    }
+    let layout_key = key.len() == 1 && key.chars().all(|c| ALTGR_KEYS.contains(&c));
+    if layout_key && resolved == [Modifier::Control, Modifier::Alt] {
+        return Err(format!(
+            "Ctrl+Alt+{key} types a character with AltGr on many keyboard layouts, so it may never fire."
+        ));
+    }
+    let resolved_combo = join(&resolved, &key);
+    if let Some((_, taken_by)) = RESERVED.iter().find(|(combo, _)| *combo == resolved_combo) {
+        return Err(format!(
+            "{resolved_combo} is taken by the system ({taken_by})."
+        ));
+    }
+    Ok(join(&modifiers, &key))
+}
+
+fn canonical_key(token: &str) -> Result<String, String> {
+    let mut chars = token.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphanumeric() {
+            return Ok(c.to_ascii_uppercase().to_string());
+        }
+        if DEAD_KEYS.contains(&c) {
+            return Err(format!(
+                "{c} is a dead key on many layouts: it waits for the next key instead of being pressed on its own."
+            ));
+        }
+        if !c.is_ascii() {
+            return Err(format!(
+                "{c} only exists on some keyboard layouts, so the hotkey would break on others."
+            ));
+        }
+        // punctuation moves between layouts and often needs Shift or AltGr
+        return Err(format!(
+            "{c} sits on a different key, or needs Shift or AltGr, depending on the keyboard layout."
+        ));
+    }
+
+    if let Some(named) = NAMED_KEYS.iter().find(|k| k.eq_ignore_ascii_case(token)) {
+        return Ok(named.to_string());
+    }
+    match token
+        .strip_prefix(['F', 'f'])
+        .and_then(|n| n.parse::<u8>().ok())
+    {
+        Some(number @ 1..=24) => Ok(format!("F{number}")),
+        _ => Err(format!("\"{token}\" isn't a key that can be bound.")),
+    }
+}
+
+fn join(modifiers: &[Modifier], key: &str) -> String {
+    modifiers
+        .iter()
+        .map(|m| m.name())
+        .chain([key])
+        .collect::<Vec<_>>()
+        .join("+")
+}
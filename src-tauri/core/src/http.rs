@@ -0,0 +1,37 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+/// Returned instead of making a request while offline mode is on.
+pub const OFFLINE_MODE: &str =
+    "Offline mode is on, so the app does not contact Discord or any other server.";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// follows the `offlineMode` setting; updated whenever settings are loaded or saved
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Turn outbound HTTP off (or back on) for the whole process.
+pub fn set_offline_mode(enabled: bool) {
+    OFFLINE.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::SeqCst)
+}
+
+/// The only way the backend builds an HTTP request: refuses with
+/// [`OFFLINE_MODE`] while offline mode is on, and sets the shared timeout.
+pub(crate) fn request(method: &str, url: &str) -> Result<ureq::Request, String> {
+    if is_offline() {
+        return Err(OFFLINE_MODE.to_string());
+    }
+    Ok(ureq::request(method, url).timeout(REQUEST_TIMEOUT))
+}
+
+/// A request to a server on this computer, such as Discord's DevTools
+/// endpoint. Nothing leaves the machine, so offline mode doesn't apply.
+pub(crate) fn loopback_request(method: &str, url: &str) -> ureq::Request {
+    ureq::request(method, url).timeout(REQUEST_TIMEOUT)
+}
@@ -0,0 +1,1165 @@
+use serde::Serialize;
+use std::{
+    env,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{
+    build_info::describe_build,
+    client_mods::ClientMod,
+    custom_clients::custom_clients,
+    managed_env::explain_launch_error,
+    settings::{DiscordChannel, LauncherSettings},
+};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscordInstallation {
+    pub channel: DiscordChannel,
+    pub label: String,
+    pub executable_path: String,
+    /// Set for Microsoft Store installs, which are launched by this id
+    /// instead of their executable.
+    pub app_user_model_id: Option<String>,
+    /// Set for the `discord://` fallback, which opens whichever client handles
+    /// the link instead of a known executable.
+    pub launch_uri: Option<String>,
+    /// The build's version, e.g. `1.0.9163`, when the install says.
+    pub version: Option<String>,
+    /// `x64`, `x86`, `arm64` or `universal`, read from the executable.
+    pub arch: Option<String>,
+    /// Client mods patched into this install.
+    pub mods: Vec<ClientMod>,
+}
+
+// follows the `installSearchRoots` setting; updated whenever settings are loaded or saved
+static SEARCH_ROOTS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Extra folders the detector looks through for installs it can't find in
+/// their usual place.
+pub(crate) fn set_install_search_roots(roots: &[String]) {
+    if let Ok(mut current) = SEARCH_ROOTS.lock() {
+        *current = roots.iter().map(PathBuf::from).collect();
+    }
+}
+
+// follows the `launchThroughUpdater` setting, the same way
+static THROUGH_UPDATER: AtomicBool = AtomicBool::new(true);
+
+/// Whether Squirrel installs are started through their `Update.exe`.
+pub(crate) fn set_launch_through_updater(enabled: bool) {
+    THROUGH_UPDATER.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg(target_os = "windows")]
+fn install_search_roots() -> Vec<PathBuf> {
+    SEARCH_ROOTS
+        .lock()
+        .map(|roots| roots.clone())
+        .unwrap_or_default()
+}
+
+// ── Launch target resolution ──
+
+/// A registered custom client if one is picked, else the custom executable if one
+/// is set, otherwise the preferred detected channel, all taken from the active
+/// launch preset when there is one. With nothing detected at all, falls back to
+/// opening a `discord://` link, whatever channel was asked for.
+pub fn resolve_launch_target(settings: LauncherSettings) -> Result<DiscordInstallation, String> {
+    let preset = settings.effective_launch_preset();
+    if let Some(name) = preset.custom_client {
+        let client = settings
+            .custom_clients
+            .into_iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| format!("No custom client named '{name}' is registered."))?;
+        let mut installation = DiscordInstallation {
+            channel: DiscordChannel::Custom,
+            label: client.name,
+            executable_path: client.executable_path,
+            app_user_model_id: None,
+            launch_uri: None,
+            version: None,
+            arch: None,
+            mods: Vec::new(),
+        };
+        describe_build(&mut installation);
+        return Ok(installation);
+    }
+    if let Some(custom_path) = preset.custom_executable_path {
+        let mut installation = DiscordInstallation {
+            channel: channel_from_executable(&custom_path).unwrap_or_default(),
+            label: "Custom Discord executable".to_string(),
+            executable_path: custom_path,
+            app_user_model_id: None,
+            launch_uri: None,
+            version: None,
+            arch: None,
+            mods: Vec::new(),
+        };
+        describe_build(&mut installation);
+        return Ok(installation);
+    }
+
+    let detected = detect_installations_for_current_os();
+
+    if detected.is_empty() {
+        return protocol_fallback().ok_or_else(|| {
+            "Discord was not auto-detected. Set a custom executable path in settings.".to_string()
+        });
+    }
+
+    if preset.preferred_channel == DiscordChannel::Auto {
+        return detected
+            .first()
+            .cloned()
+            .ok_or_else(|| "No Discord installations were detected.".to_string());
+    }
+
+    detected
+        .into_iter()
+        .find(|i| i.channel == preset.preferred_channel)
+        .ok_or_else(|| {
+            "Preferred Discord channel was not found. Use Auto or set a custom path.".to_string()
+        })
+}
+
+// folder and file names each channel installs under, most specific first
+// since every official one contains "discord"; compared without case,
+// spaces, dashes or underscores
+const EXECUTABLE_CHANNELS: &[(&str, DiscordChannel)] = &[
+    ("discordcanary", DiscordChannel::Canary),
+    ("discordptb", DiscordChannel::Ptb),
+    ("discorddevelopment", DiscordChannel::Development),
+    ("vesktop", DiscordChannel::Vesktop),
+    ("webcord", DiscordChannel::WebCord),
+    ("armcord", DiscordChannel::ArmCord),
+    ("discord", DiscordChannel::Stable),
+];
+
+/// The channel a custom executable belongs to, told from its path, like
+/// `DiscordCanary.exe` or `Discord Canary.app`. None when the path names no
+/// channel the app knows.
+pub fn channel_from_executable(path: &str) -> Option<DiscordChannel> {
+    let folded: String = path
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect();
+    EXECUTABLE_CHANNELS
+        .iter()
+        .find(|(name, _)| folded.contains(name))
+        .map(|(_, channel)| *channel)
+}
+
+/// The link Discord registers for itself on install.
+pub const DISCORD_URI: &str = "discord://";
+
+/// Launch Discord through its `discord://` handler, for installs the detector
+/// can't find (moved, portable or packaged in an unusual way). The handler
+/// picks the client, so this is never tied to a channel; None when no app
+/// has claimed the link.
+pub fn protocol_fallback() -> Option<DiscordInstallation> {
+    uri_handler_registered().then(|| DiscordInstallation {
+        channel: DiscordChannel::Auto,
+        label: "Discord (through its discord:// link)".to_string(),
+        executable_path: String::new(),
+        app_user_model_id: None,
+        launch_uri: Some(DISCORD_URI.to_string()),
+        version: None,
+        arch: None,
+        mods: Vec::new(),
+    })
+}
+
+fn uri_handler_registered() -> bool {
+    #[cfg(target_os = "windows")]
+    return Command::new("reg")
+        .args(["query", r"HKCR\discord", "/v", "URL Protocol"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+
+    #[cfg(target_os = "linux")]
+    return Command::new("xdg-mime")
+        .args(["query", "default", "x-scheme-handler/discord"])
+        .stderr(Stdio::null())
+        .output()
+        .is_ok_and(|output| !String::from_utf8_lossy(&output.stdout).trim().is_empty());
+
+    // LaunchServices has no quick lookup from the shell; `open` reports a
+    // missing handler when the link is opened instead
+    #[allow(unreachable_code)]
+    {
+        cfg!(target_os = "macos")
+    }
+}
+
+fn open_uri(uri: &str) -> Result<(), String> {
+    let unhandled = || {
+        format!("No app is set up to open {uri} links. Reinstall Discord or set a custom executable path in settings.")
+    };
+
+    #[cfg(target_os = "macos")]
+    {
+        let status = Command::new("open")
+            .arg(uri)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|e| format!("Failed to launch Discord: {e}"))?;
+        return if status.success() {
+            Ok(())
+        } else {
+            Err(unhandled())
+        };
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // explorer hands the link to its registered handler and returns, so a
+        // missing handler was ruled out when the target was resolved
+        let _ = unhandled;
+        Command::new("explorer.exe")
+            .arg(uri)
+            .spawn()
+            .map_err(|e| format!("Failed to launch Discord: {e}"))?;
+        return Ok(());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let status = Command::new("xdg-open")
+            .arg(uri)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|e| format!("Failed to launch Discord: {e}"))?;
+        return if status.success() {
+            Ok(())
+        } else {
+            Err(unhandled())
+        };
+    }
+
+    #[allow(unreachable_code)]
+    {
+        Err(unhandled())
+    }
+}
+
+// ── Process control ──
+
+/// Each channel's process name; the channels run as separate instances.
+#[cfg(target_os = "macos")]
+pub const CHANNEL_PROCESSES: &[(DiscordChannel, &str)] = &[
+    (DiscordChannel::Stable, "Discord"),
+    (DiscordChannel::Ptb, "Discord PTB"),
+    (DiscordChannel::Canary, "Discord Canary"),
+    (DiscordChannel::Development, "Discord Development"),
+    (DiscordChannel::Vesktop, "Vesktop"),
+    (DiscordChannel::WebCord, "WebCord"),
+    (DiscordChannel::ArmCord, "ArmCord"),
+];
+#[cfg(target_os = "windows")]
+pub const CHANNEL_PROCESSES: &[(DiscordChannel, &str)] = &[
+    (DiscordChannel::Stable, "Discord.exe"),
+    (DiscordChannel::Ptb, "DiscordPTB.exe"),
+    (DiscordChannel::Canary, "DiscordCanary.exe"),
+    (DiscordChannel::Development, "DiscordDevelopment.exe"),
+    (DiscordChannel::Vesktop, "Vesktop.exe"),
+    (DiscordChannel::WebCord, "WebCord.exe"),
+    (DiscordChannel::ArmCord, "ArmCord.exe"),
+];
+#[cfg(target_os = "linux")]
+pub const CHANNEL_PROCESSES: &[(DiscordChannel, &str)] = &[
+    (DiscordChannel::Stable, "Discord"),
+    (DiscordChannel::Ptb, "DiscordPTB"),
+    (DiscordChannel::Canary, "DiscordCanary"),
+    (DiscordChannel::Development, "DiscordDevelopment"),
+    (DiscordChannel::Vesktop, "vesktop"),
+    (DiscordChannel::WebCord, "webcord"),
+    (DiscordChannel::ArmCord, "armcord"),
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub const CHANNEL_PROCESSES: &[(DiscordChannel, &str)] = &[];
+
+// the process names behind a channel; every registered client counts as `Custom`
+fn channel_process_names(channel: DiscordChannel) -> Vec<String> {
+    if channel == DiscordChannel::Custom {
+        return custom_clients()
+            .into_iter()
+            .map(|client| client.process_name)
+            .collect();
+    }
+    CHANNEL_PROCESSES
+        .iter()
+        .filter(|(c, _)| *c == channel)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Every error meaning "only an administrator can do this" starts with this;
+/// closing Discord can be retried through [`terminate_discord_elevated`].
+pub const NEEDS_ELEVATION: &str = "Needs administrator rights: ";
+
+/// Kill every Discord channel that's running, registered custom clients included.
+/// Fails with [`NEEDS_ELEVATION`] when one of them runs with higher privileges.
+pub fn terminate_discord() -> Result<(), String> {
+    kill_all(all_process_names())
+}
+
+/// Kill one channel's processes and leave the others running.
+pub fn terminate_channel(channel: DiscordChannel) -> Result<(), String> {
+    kill_all(channel_process_names(channel))
+}
+
+fn all_process_names() -> Vec<String> {
+    CHANNEL_PROCESSES
+        .iter()
+        .map(|(_, name)| name.to_string())
+        .chain(channel_process_names(DiscordChannel::Custom))
+        .collect()
+}
+
+fn kill_all(names: Vec<String>) -> Result<(), String> {
+    let refused: Vec<String> = names
+        .into_iter()
+        .filter(|name| !kill_process(name))
+        .collect();
+    if refused.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "{NEEDS_ELEVATION}{} is running as administrator (or another user), so it can't be closed from here.",
+        refused.join(", ")
+    ))
+}
+
+// pgrep and pkill match the kernel's copy of the process name, which Linux
+// cuts to 15 bytes and macOS to 16, so "DiscordDevelopment" is "DiscordDevelopm"
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn comm_name(name: &str) -> &str {
+    const COMM_LEN: usize = if cfg!(target_os = "macos") { 16 } else { 15 };
+    let mut end = name.len().min(COMM_LEN);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    &name[..end]
+}
+
+// false when the process is still there after the OS refused to kill it
+fn kill_process(name: &str) -> bool {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        // pkill exits 1 both when nothing matched and when nothing could be signalled
+        let killed = Command::new("pkill")
+            .args(["-x", comm_name(name)])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        return killed || !process_running(name);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let killed = Command::new("taskkill")
+            .args(["/IM", name, "/F"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        return killed || !process_running(name);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = name;
+        true
+    }
+}
+
+/// Close every Discord process with administrator rights, after the OS's own
+/// prompt (UAC, the macOS password dialog, polkit). For installs that run
+/// elevated, where [`terminate_discord`] fails with [`NEEDS_ELEVATION`].
+pub fn terminate_discord_elevated() -> Result<(), String> {
+    let names = all_process_names();
+
+    #[cfg(target_os = "windows")]
+    {
+        let image_args = names
+            .iter()
+            .map(|name| format!("'/IM','{}'", name.replace('\'', "''")))
+            .collect::<Vec<_>>()
+            .join(",");
+        let script = format!(
+            "Start-Process -FilePath taskkill -ArgumentList '/F',{image_args} -Verb RunAs -Wait -WindowStyle Hidden"
+        );
+        run_elevation_prompt(Command::new("powershell").args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &script,
+        ]))?;
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        // `; true` so a channel that isn't running doesn't read as a cancelled prompt
+        let kills = names
+            .iter()
+            .map(|name| format!("pkill -x '{}'", comm_name(name).replace('\'', r"'\''")))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let shell = format!("{kills}; true");
+
+        #[cfg(target_os = "macos")]
+        {
+            let escaped = shell.replace('\\', "\\\\").replace('"', "\\\"");
+            run_elevation_prompt(Command::new("osascript").args([
+                "-e",
+                &format!("do shell script \"{escaped}\" with administrator privileges"),
+            ]))?;
+        }
+
+        #[cfg(target_os = "linux")]
+        run_elevation_prompt(Command::new("pkexec").args(["sh", "-c", &shell]))?;
+    }
+
+    #[allow(unreachable_code)]
+    if names.iter().any(|name| process_running(name)) {
+        return Err(
+            "Discord is still running after closing it with administrator rights.".to_string(),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+fn run_elevation_prompt(command: &mut Command) -> Result<(), String> {
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Could not ask for administrator rights: {e}"))?;
+    if !status.success() {
+        return Err("Administrator rights were not granted.".to_string());
+    }
+    Ok(())
+}
+
+/// Process ids of one channel, the main process and its helpers alike.
+pub fn channel_pids(channel: DiscordChannel) -> Vec<u32> {
+    channel_process_names(channel)
+        .iter()
+        .flat_map(|name| process_pids(name))
+        .collect()
+}
+
+fn process_pids(name: &str) -> Vec<u32> {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        let Ok(output) = Command::new("pgrep")
+            .args(["-x", comm_name(name)])
+            .stderr(Stdio::null())
+            .output()
+        else {
+            return Vec::new();
+        };
+        return String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .collect();
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // CSV rows look like "Discord.exe","1234","Console","1","120,000 K"
+        let Ok(output) = Command::new("tasklist")
+            .args(["/FI", &format!("IMAGENAME eq {name}"), "/NH", "/FO", "CSV"])
+            .stderr(Stdio::null())
+            .output()
+        else {
+            return Vec::new();
+        };
+        return String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split("\",\"").nth(1)?.parse().ok())
+            .collect();
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = name;
+        Vec::new()
+    }
+}
+
+/// Whether any Discord channel (or registered custom client) is running right now.
+pub fn is_discord_running() -> bool {
+    let custom = channel_process_names(DiscordChannel::Custom);
+    CHANNEL_PROCESSES
+        .iter()
+        .map(|(_, name)| *name)
+        .chain(custom.iter().map(String::as_str))
+        .any(process_running)
+}
+
+fn process_running(name: &str) -> bool {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        return Command::new("pgrep")
+            .args(["-x", comm_name(name)])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // tasklist prints an "INFO: No tasks" line instead of failing
+        return Command::new("tasklist")
+            .args(["/FI", &format!("IMAGENAME eq {name}"), "/NH"])
+            .stderr(Stdio::null())
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains(name))
+            .unwrap_or(false);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = name;
+        false
+    }
+}
+
+/// Launch Discord normally (no --user-data-dir, tokens live in the default location).
+pub fn launch_discord(installation: &DiscordInstallation) -> Result<(), String> {
+    launch_discord_with_args(installation, &[])
+}
+
+/// Launch Discord with extra command-line switches, e.g. a debugging port.
+/// Store installs and the `discord://` fallback can't be given any.
+pub(crate) fn launch_discord_with_args(
+    installation: &DiscordInstallation,
+    args: &[String],
+) -> Result<(), String> {
+    if let Some(uri) = &installation.launch_uri {
+        if !args.is_empty() {
+            return Err(format!(
+                "{} can't be started with extra options. Pick a detected install or set a custom path in settings.",
+                installation.label
+            ));
+        }
+        return open_uri(uri);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let binary = if installation.executable_path.ends_with(".app") {
+            let app_path = PathBuf::from(&installation.executable_path);
+            let app_name = app_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Discord")
+                .to_string();
+            let inner = app_path.join("Contents").join("MacOS").join(&app_name);
+            if !inner.exists() {
+                return Err(format!(
+                    "Could not find binary inside {}: expected {}",
+                    installation.executable_path,
+                    inner.display()
+                ));
+            }
+            inner.to_string_lossy().to_string()
+        } else {
+            installation.executable_path.clone()
+        };
+
+        Command::new(&binary)
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| explain_launch_error(&e, &binary))?;
+
+        return Ok(());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // packaged apps can't be started from their folder under WindowsApps
+        if let Some(aumid) = &installation.app_user_model_id {
+            if !args.is_empty() {
+                return Err(format!(
+                    "{} can't be started with extra options. Install Discord from discord.com and pick it in the launch settings.",
+                    installation.label
+                ));
+            }
+            Command::new("explorer.exe")
+                .arg(format!(r"shell:AppsFolder\{aumid}"))
+                .spawn()
+                .map_err(|e| format!("Failed to launch Discord: {e}"))?;
+            return Ok(());
+        }
+
+        let executable = std::path::Path::new(&installation.executable_path);
+        if THROUGH_UPDATER.load(Ordering::Relaxed) {
+            if let Some((updater, exe_name)) = squirrel_updater(executable) {
+                let mut command = Command::new(&updater);
+                command.args(["--processStart", &exe_name]);
+                if !args.is_empty() {
+                    command.args(["--process-start-args", &args.join(" ")]);
+                }
+                command
+                    .spawn()
+                    .map_err(|e| explain_launch_error(&e, &updater.to_string_lossy()))?;
+                return Ok(());
+            }
+        }
+
+        Command::new(executable)
+            .args(args)
+            .spawn()
+            .map_err(|e| explain_launch_error(&e, &installation.executable_path))?;
+
+        return Ok(());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        Command::new(&installation.executable_path)
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| explain_launch_error(&e, &installation.executable_path))?;
+
+        return Ok(());
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (installation, args);
+        Err("This app currently supports macOS, Windows and Linux only.".to_string())
+    }
+}
+
+// ── Installation detection ──
+
+pub fn detect_installations_for_current_os() -> Vec<DiscordInstallation> {
+    let mut installations = detect_platform_installations();
+    installations.iter_mut().for_each(describe_build);
+    installations
+}
+
+fn detect_platform_installations() -> Vec<DiscordInstallation> {
+    #[cfg(target_os = "macos")]
+    {
+        return detect_macos_installations();
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return detect_windows_installations();
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        return detect_linux_installations();
+    }
+
+    #[allow(unreachable_code)]
+    Vec::new()
+}
+
+#[cfg(target_os = "macos")]
+fn detect_macos_installations() -> Vec<DiscordInstallation> {
+    let home = env::var("HOME").unwrap_or_default();
+    let home_apps = PathBuf::from(&home).join("Applications");
+    let mut installations = Vec::new();
+
+    let candidates = [
+        (
+            DiscordChannel::Stable,
+            "Discord",
+            [
+                PathBuf::from("/Applications/Discord.app"),
+                home_apps.join("Discord.app"),
+            ],
+        ),
+        (
+            DiscordChannel::Ptb,
+            "Discord PTB",
+            [
+                PathBuf::from("/Applications/Discord PTB.app"),
+                home_apps.join("Discord PTB.app"),
+            ],
+        ),
+        (
+            DiscordChannel::Canary,
+            "Discord Canary",
+            [
+                PathBuf::from("/Applications/Discord Canary.app"),
+                home_apps.join("Discord Canary.app"),
+            ],
+        ),
+        (
+            DiscordChannel::Development,
+            "Discord Development",
+            [
+                PathBuf::from("/Applications/Discord Development.app"),
+                home_apps.join("Discord Development.app"),
+            ],
+        ),
+        (
+            DiscordChannel::Vesktop,
+            "Vesktop",
+            [
+                PathBuf::from("/Applications/Vesktop.app"),
+                home_apps.join("Vesktop.app"),
+            ],
+        ),
+        (
+            DiscordChannel::WebCord,
+            "WebCord",
+            [
+                PathBuf::from("/Applications/WebCord.app"),
+                home_apps.join("WebCord.app"),
+            ],
+        ),
+        (
+            DiscordChannel::ArmCord,
+            "ArmCord",
+            [
+                PathBuf::from("/Applications/ArmCord.app"),
+                home_apps.join("ArmCord.app"),
+            ],
+        ),
+    ];
+
+    for (channel, label, paths) in candidates {
+        let found = paths
+            .into_iter()
+            .find(|p| p.exists())
+            .or_else(|| spotlight_bundle_path(channel));
+        if let Some(found) = found {
+            installations.push(DiscordInstallation {
+                channel,
+                label: label.to_string(),
+                executable_path: found.to_string_lossy().to_string(),
+                app_user_model_id: None,
+                launch_uri: None,
+                version: None,
+                arch: None,
+                mods: Vec::new(),
+            });
+        }
+    }
+
+    installations
+}
+
+// com.hnc.Discord prefixes every official channel's bundle id
+#[cfg(target_os = "macos")]
+const MACOS_BUNDLE_IDS: &[(DiscordChannel, &str)] = &[
+    (DiscordChannel::Stable, "com.hnc.Discord"),
+    (DiscordChannel::Ptb, "com.hnc.DiscordPTB"),
+    (DiscordChannel::Canary, "com.hnc.DiscordCanary"),
+    (DiscordChannel::Development, "com.hnc.DiscordDevelopment"),
+];
+
+// for apps kept outside /Applications, e.g. on another volume or in a
+// Setapp-style folder; returns nothing when Spotlight indexing is off
+#[cfg(target_os = "macos")]
+fn spotlight_bundle_path(channel: DiscordChannel) -> Option<PathBuf> {
+    let (_, bundle_id) = MACOS_BUNDLE_IDS.iter().find(|(c, _)| *c == channel)?;
+    let output = Command::new("mdfind")
+        .arg(format!("kMDItemCFBundleIdentifier == \"{bundle_id}\""))
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    // skip copies inside other bundles or the Trash
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(PathBuf::from)
+        .find(|path| {
+            path.extension().is_some_and(|ext| ext == "app")
+                && !path.to_string_lossy().contains("/.Trash/")
+                && !path
+                    .parent()
+                    .is_some_and(|parent| parent.to_string_lossy().contains(".app/"))
+                && path.exists()
+        })
+}
+
+#[cfg(target_os = "windows")]
+fn detect_windows_installations() -> Vec<DiscordInstallation> {
+    let mut installations = Vec::new();
+
+    if let Some(s) = detect_windows_channel_install(
+        "Discord",
+        "discord",
+        DiscordChannel::Stable,
+        "Discord",
+        &[
+            "Discord.exe",
+            "DiscordPTB.exe",
+            "DiscordCanary.exe",
+            "DiscordDevelopment.exe",
+        ],
+    ) {
+        installations.push(s);
+    }
+
+    if let Some(p) = detect_windows_channel_install(
+        "DiscordPTB",
+        "discord-ptb",
+        DiscordChannel::Ptb,
+        "Discord PTB",
+        &["DiscordPTB.exe", "Discord.exe"],
+    ) {
+        installations.push(p);
+    }
+
+    if let Some(c) = detect_windows_channel_install(
+        "DiscordCanary",
+        "discord-canary",
+        DiscordChannel::Canary,
+        "Discord Canary",
+        &["DiscordCanary.exe", "Discord.exe"],
+    ) {
+        installations.push(c);
+    }
+
+    // not packaged for Scoop or Chocolatey; the package name never matches
+    if let Some(d) = detect_windows_channel_install(
+        "DiscordDevelopment",
+        "discord-development",
+        DiscordChannel::Development,
+        "Discord Development",
+        &["DiscordDevelopment.exe", "Discord.exe"],
+    ) {
+        installations.push(d);
+    }
+
+    installations.extend(detect_windows_store_install());
+    installations.extend(detect_windows_third_party_installs());
+    installations
+}
+
+// the Store build is Stable packaged as MSIX; only the package manager knows
+// where it lives and which id starts it
+#[cfg(target_os = "windows")]
+fn detect_windows_store_install() -> Option<DiscordInstallation> {
+    let script = "Get-AppxPackage -Name '*Discord*' | Select-Object -First 1 | ForEach-Object { \
+                  $_.PackageFamilyName + '|' + \
+                  (Get-AppxPackageManifest $_).Package.Applications.Application[0].Id + '|' + \
+                  $_.InstallLocation }";
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let mut fields = line.splitn(3, '|');
+    let (family, app_id, location) = (fields.next()?, fields.next()?, fields.next()?);
+    if family.is_empty() || app_id.is_empty() {
+        return None;
+    }
+    Some(DiscordInstallation {
+        channel: DiscordChannel::Stable,
+        label: "Discord (Microsoft Store)".to_string(),
+        executable_path: location.to_string(),
+        app_user_model_id: Some(format!("{family}!{app_id}")),
+        launch_uri: None,
+        version: None,
+        arch: None,
+        mods: Vec::new(),
+    })
+}
+
+// third-party clients use a per-user installer without Squirrel's app-* folders
+#[cfg(target_os = "windows")]
+fn detect_windows_third_party_installs() -> Vec<DiscordInstallation> {
+    let Ok(local_app_data) = env::var("LOCALAPPDATA") else {
+        return Vec::new();
+    };
+    let programs = PathBuf::from(local_app_data).join("Programs");
+    [
+        (DiscordChannel::Vesktop, "Vesktop", "vesktop", "Vesktop.exe"),
+        (DiscordChannel::WebCord, "WebCord", "webcord", "WebCord.exe"),
+        (DiscordChannel::ArmCord, "ArmCord", "ArmCord", "ArmCord.exe"),
+    ]
+    .into_iter()
+    .filter_map(|(channel, label, folder, exe)| {
+        let path = programs.join(folder).join(exe);
+        path.exists().then(|| DiscordInstallation {
+            channel,
+            label: label.to_string(),
+            executable_path: path.to_string_lossy().to_string(),
+            app_user_model_id: None,
+            launch_uri: None,
+            version: None,
+            arch: None,
+            mods: Vec::new(),
+        })
+    })
+    .collect()
+}
+
+// the official installer puts each channel under %LOCALAPPDATA%; package managers
+// and people moving it to another drive put it elsewhere, so look there too
+#[cfg(target_os = "windows")]
+fn detect_windows_channel_install(
+    folder_name: &str,
+    package_name: &str,
+    channel: DiscordChannel,
+    label: &str,
+    executable_names: &[&str],
+) -> Option<DiscordInstallation> {
+    let path = windows_install_roots(folder_name)
+        .iter()
+        .find_map(|root| find_squirrel_executable(root, executable_names))
+        .or_else(|| find_scoop_executable(package_name, executable_names[0]))
+        .or_else(|| find_chocolatey_executable(package_name, executable_names[0]))
+        .or_else(|| {
+            install_search_roots()
+                .iter()
+                .find_map(|root| find_executable_below(root, executable_names[0], HINT_SCAN_DEPTH))
+        })?;
+
+    // per-machine installs run from a folder only administrators can change,
+    // and closing or updating them may need elevation
+    let label = if is_machine_wide_path(&path) {
+        format!("{label} (all users)")
+    } else {
+        label.to_string()
+    };
+    Some(DiscordInstallation {
+        channel,
+        label,
+        executable_path: path.to_string_lossy().to_string(),
+        app_user_model_id: None,
+        launch_uri: None,
+        version: None,
+        arch: None,
+        mods: Vec::new(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn is_machine_wide_path(path: &std::path::Path) -> bool {
+    ["ProgramFiles", "ProgramFiles(x86)", "ProgramW6432"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .any(|base| path.starts_with(base))
+}
+
+#[cfg(target_os = "windows")]
+fn windows_install_roots(folder_name: &str) -> Vec<PathBuf> {
+    ["LOCALAPPDATA", "ProgramFiles", "ProgramFiles(x86)"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .map(|base| PathBuf::from(base).join(folder_name))
+        .collect()
+}
+
+// `<root>\app-1.0.9\Discord.exe` belongs to `<root>\Update.exe`, which starts the
+// newest version and applies any update it has downloaded first
+#[cfg(target_os = "windows")]
+pub(crate) fn squirrel_updater(executable: &std::path::Path) -> Option<(PathBuf, String)> {
+    let version_dir = executable.parent()?;
+    if !version_dir
+        .file_name()?
+        .to_string_lossy()
+        .starts_with("app-")
+    {
+        return None;
+    }
+    let updater = version_dir.parent()?.join("Update.exe");
+    let exe_name = executable.file_name()?.to_string_lossy().to_string();
+    updater.exists().then_some((updater, exe_name))
+}
+
+// Squirrel keeps every version side by side in app-<version> folders; newest wins
+pub(crate) fn find_squirrel_executable(
+    root: &std::path::Path,
+    executable_names: &[&str],
+) -> Option<PathBuf> {
+    let mut app_dirs: Vec<PathBuf> = std::fs::read_dir(root)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with("app-"))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    app_dirs.sort();
+    app_dirs.reverse();
+
+    app_dirs.iter().find_map(|dir| {
+        executable_names
+            .iter()
+            .map(|exe| dir.join(exe))
+            .find(|path| path.exists())
+    })
+}
+
+// per-user and global Scoop installs; a shim's .shim file names the real executable
+#[cfg(target_os = "windows")]
+fn find_scoop_executable(package_name: &str, executable_name: &str) -> Option<PathBuf> {
+    let roots = [
+        env::var("SCOOP").ok().map(PathBuf::from),
+        env::var("USERPROFILE")
+            .ok()
+            .map(|home| PathBuf::from(home).join("scoop")),
+        env::var("SCOOP_GLOBAL").ok().map(PathBuf::from),
+        env::var("ProgramData")
+            .ok()
+            .map(|data| PathBuf::from(data).join("scoop")),
+    ];
+    roots.into_iter().flatten().find_map(|root| {
+        let current = root
+            .join("apps")
+            .join(package_name)
+            .join("current")
+            .join(executable_name);
+        if current.exists() {
+            return Some(current);
+        }
+        let shim = std::fs::read_to_string(root.join("shims").join(format!("{package_name}.shim")))
+            .ok()?;
+        shim.lines()
+            .find_map(|line| line.trim().strip_prefix("path"))
+            .map(|rest| {
+                rest.trim_start()
+                    .trim_start_matches('=')
+                    .trim()
+                    .trim_matches('"')
+            })
+            .map(PathBuf::from)
+            .filter(|path| path.exists())
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn find_chocolatey_executable(package_name: &str, executable_name: &str) -> Option<PathBuf> {
+    let root = env::var("ChocolateyInstall")
+        .map(PathBuf::from)
+        .or_else(|_| env::var("ProgramData").map(|data| PathBuf::from(data).join("chocolatey")))
+        .ok()?;
+    find_executable_below(
+        &root.join("lib").join(package_name),
+        executable_name,
+        HINT_SCAN_DEPTH,
+    )
+}
+
+// how many folder levels below a hint root to look; enough for
+// <root>/Discord/app-1.0.9000/Discord.exe without crawling a whole drive
+#[cfg(target_os = "windows")]
+const HINT_SCAN_DEPTH: usize = 3;
+
+/// The newest-looking `executable_name` up to `depth` folders below `root`.
+#[cfg(target_os = "windows")]
+fn find_executable_below(
+    root: &std::path::Path,
+    executable_name: &str,
+    depth: usize,
+) -> Option<PathBuf> {
+    let mut found = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, level)) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.is_dir() {
+                if level < depth {
+                    pending.push((path, level + 1));
+                }
+            } else if entry
+                .file_name()
+                .to_string_lossy()
+                .eq_ignore_ascii_case(executable_name)
+            {
+                found.push(path);
+            }
+        }
+    }
+    // app-1.0.9010 sorts after app-1.0.9003, like the Squirrel scan above
+    found.into_iter().max()
+}
+
+// distro packages unpack to /usr/share, the official tarball is usually put in /opt
+// (or under the home folder without root); packages put a launcher on the PATH as well.
+// third-party clients' packages tend to use their display name for the /opt folder
+#[cfg(target_os = "linux")]
+fn detect_linux_installations() -> Vec<DiscordInstallation> {
+    let home_share = PathBuf::from(env::var("HOME").unwrap_or_default()).join(".local/share");
+    let candidates = [
+        (DiscordChannel::Stable, "Discord", "discord", "Discord"),
+        (
+            DiscordChannel::Ptb,
+            "Discord PTB",
+            "discord-ptb",
+            "DiscordPTB",
+        ),
+        (
+            DiscordChannel::Canary,
+            "Discord Canary",
+            "discord-canary",
+            "DiscordCanary",
+        ),
+        (
+            DiscordChannel::Development,
+            "Discord Development",
+            "discord-development",
+            "DiscordDevelopment",
+        ),
+        (DiscordChannel::Vesktop, "Vesktop", "vesktop", "vesktop"),
+        (DiscordChannel::WebCord, "WebCord", "webcord", "webcord"),
+        (DiscordChannel::ArmCord, "ArmCord", "armcord", "armcord"),
+    ];
+
+    let mut installations = Vec::new();
+    for (channel, label, folder, binary) in candidates {
+        let paths = [
+            PathBuf::from("/usr/share").join(folder).join(binary),
+            PathBuf::from("/opt").join(folder).join(binary),
+            PathBuf::from("/opt").join(binary).join(binary),
+            PathBuf::from("/opt").join(label).join(binary),
+            home_share.join(binary).join(binary),
+            PathBuf::from("/usr/bin").join(folder),
+        ];
+        if let Some(found) = paths.into_iter().find(|p| p.exists()) {
+            installations.push(DiscordInstallation {
+                channel,
+                label: label.to_string(),
+                executable_path: found.to_string_lossy().to_string(),
+                app_user_model_id: None,
+                launch_uri: None,
+                version: None,
+                arch: None,
+                mods: Vec::new(),
+            });
+        }
+    }
+
+    installations
+}
@@ -0,0 +1,82 @@
+//! Profiles, saved tokens and Discord storage switching for Discord Alt Manager.
+//!
+//! The desktop app is a thin Tauri layer over this crate, so anything it does
+//! can be scripted from another Rust tool by pointing a [`DataDir`] at the
+//! app's data folder:
+//!
+//! ```no_run
+//! use altmng_core::{profiles, switch, DataDir};
+//!
+//! let dir = DataDir::open("/path/to/com.filip.alt-mngr")?;
+//! let profile = profiles::resolve_profile(&dir, "main")?;
+//! println!("{}", switch::switch_to_profile(&dir, &profile.id, false, false, None)?.message);
+//! # Ok::<(), String>(())
+//! ```
+//!
+//! Every fallible call returns `Result<_, String>` with a message that is
+//! ready to show to a user.
+
+pub mod api;
+pub mod app_lock;
+pub mod audit;
+pub mod backup;
+pub mod biometric;
+pub(crate) mod build_info;
+pub mod call_guard;
+pub mod capabilities;
+pub mod capture_wizard;
+pub mod client_mods;
+pub mod clipboard;
+pub mod clock;
+pub mod compare;
+pub(crate) mod crypto;
+pub mod custom_clients;
+pub(crate) mod devtools;
+pub mod diagnostics;
+pub mod dry_run;
+pub mod health;
+pub mod hotkeys;
+pub mod http;
+pub mod launch;
+pub mod managed_env;
+pub mod markers;
+pub mod mod_bridge;
+pub mod notifications;
+pub mod operation_history;
+pub mod os_crypt;
+pub mod overlap;
+pub mod path_repair;
+pub mod paths;
+pub mod permissions;
+pub mod plugins;
+pub mod preflight;
+pub mod presence;
+pub mod presets;
+pub mod profile_events;
+pub mod profiles;
+pub mod progress;
+pub mod protection;
+pub mod retention;
+pub mod run_as;
+pub mod sandbox;
+pub mod secret;
+pub mod session_import;
+pub mod sessions;
+pub mod settings;
+pub mod storage;
+pub mod storage_backup;
+pub mod storage_repair;
+pub mod support;
+pub mod switch;
+pub mod tokens;
+pub mod trust;
+pub mod updater;
+pub mod upgrades;
+pub mod vault;
+pub mod window_tag;
+pub mod wipe;
+
+pub use paths::DataDir;
+
+/// Version shared by the app and this crate; recorded in modification markers.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
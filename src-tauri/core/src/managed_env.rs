@@ -0,0 +1,155 @@
+use serde::Serialize;
+use std::{
+    env, io,
+    process::{Command, Stdio},
+};
+
+/// What about a locked-down machine can get in the way of switching.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FindingKind {
+    /// The tools used to list and close Discord can't be run.
+    ProcessToolsBlocked,
+    /// AppLocker, Software Restriction Policies or similar decide what may start.
+    LaunchPolicy,
+    /// App data lives on a network share or a redirected folder.
+    RedirectedProfile,
+    /// The app runs in a sandbox that hides Discord's processes or files.
+    Sandboxed,
+}
+
+/// One thing a managed environment does that explains a failure, and what to do about it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentFinding {
+    pub kind: FindingKind,
+    pub detail: String,
+    pub advice: String,
+}
+
+/// Look for restrictions that make launching or closing Discord fail on
+/// corporate or school machines. Nothing is changed.
+pub fn check_environment() -> Vec<EnvironmentFinding> {
+    let mut findings = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        findings.extend(
+            ["tasklist", "taskkill"]
+                .iter()
+                .filter_map(|tool| blocked_tool(tool, &["/?"])),
+        );
+        findings.extend(windows_launch_policies());
+        findings.extend(redirected_folder("APPDATA"));
+        findings.extend(redirected_folder("LOCALAPPDATA"));
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        findings.extend(
+            ["pgrep", "pkill"]
+                .iter()
+                .filter_map(|tool| blocked_tool(tool, &["-V"])),
+        );
+        findings.extend(redirected_folder("HOME"));
+    }
+
+    #[cfg(target_os = "linux")]
+    if env::var_os("FLATPAK_ID").is_some() || env::var_os("SNAP").is_some() {
+        findings.push(EnvironmentFinding {
+            kind: FindingKind::Sandboxed,
+            detail: "The app is running as a Flatpak or Snap.".to_string(),
+            advice: "Its sandbox hides Discord's processes and storage unless given access; install the app natively, or grant it access to your home folder and the host's processes.".to_string(),
+        });
+    }
+
+    findings
+}
+
+/// Reword a failed launch when the OS says a policy blocked it, so the user
+/// knows to ask whoever manages the machine instead of reinstalling Discord.
+pub fn explain_launch_error(error: &io::Error, executable: &str) -> String {
+    // ERROR_ACCESS_DISABLED_BY_POLICY, ERROR_SYSTEM_INTEGRITY_POLICY_VIOLATION
+    #[cfg(target_os = "windows")]
+    if matches!(error.raw_os_error(), Some(1260) | Some(4551)) {
+        return format!(
+            "Windows blocked {executable} from starting (AppLocker, Smart App Control or another policy set by your organisation). Ask your IT department to allow it."
+        );
+    }
+    if error.kind() == io::ErrorKind::PermissionDenied {
+        return format!(
+            "Not allowed to start {executable}: {error}. If this machine is managed by an organisation, a policy may be blocking it."
+        );
+    }
+    format!("Failed to launch Discord: {error}")
+}
+
+// spawning fails outright when the tool is missing or a policy forbids it
+fn blocked_tool(tool: &str, probe_args: &[&str]) -> Option<EnvironmentFinding> {
+    let error = Command::new(tool)
+        .args(probe_args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .err()?;
+    let detail = match error.kind() {
+        io::ErrorKind::NotFound => format!("`{tool}` isn't installed or isn't on the PATH."),
+        _ => format!("`{tool}` can't be run: {error}"),
+    };
+    Some(EnvironmentFinding {
+        kind: FindingKind::ProcessToolsBlocked,
+        detail,
+        advice: "Without it the app can't tell whether Discord is running or close it. Quit Discord yourself and use a queued switch, or ask for the tool to be allowed.".to_string(),
+    })
+}
+
+fn redirected_folder(var: &str) -> Option<EnvironmentFinding> {
+    let path = env::var(var).ok()?;
+    // UNC shares on Windows, network homes on macOS
+    let on_network = path.starts_with(r"\\") || path.starts_with("/Network/");
+    if !on_network {
+        return None;
+    }
+    Some(EnvironmentFinding {
+        kind: FindingKind::RedirectedProfile,
+        detail: format!("{var} points to a network location: {path}"),
+        advice: "Discord's storage on a redirected or roaming profile can be locked by sync and lags behind a switch. Sign in once on this machine before switching, or ask for Discord's folder to be excluded from redirection.".to_string(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn windows_launch_policies() -> Vec<EnvironmentFinding> {
+    let policies = [
+        (
+            r"HKLM\SOFTWARE\Policies\Microsoft\Windows\SrpV2\Exe",
+            "AppLocker rules decide which programs may start.",
+        ),
+        (
+            r"HKLM\SOFTWARE\Policies\Microsoft\Windows\Safer\CodeIdentifiers",
+            "Software Restriction Policies decide which programs may start.",
+        ),
+    ];
+    policies
+        .iter()
+        .filter(|(key, _)| registry_key_has_values(key))
+        .map(|(_, detail)| EnvironmentFinding {
+            kind: FindingKind::LaunchPolicy,
+            detail: detail.to_string(),
+            advice: "Discord installs per user under %LOCALAPPDATA%, which these rules often block. If launching fails, ask your IT department to allow Discord, or use an install from Program Files.".to_string(),
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn registry_key_has_values(key: &str) -> bool {
+    Command::new("reg")
+        .args(["query", key, "/s"])
+        .stderr(Stdio::null())
+        .output()
+        .is_ok_and(|output| {
+            output.status.success()
+                && String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .any(|line| line.starts_with("    "))
+        })
+}
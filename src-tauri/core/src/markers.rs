@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, env, fs, path::Path, process::Command};
+
+use crate::clock::now_ms;
+
+/// Sidecar next to the data files recording who wrote each one last.
+pub const MODIFICATION_MARKERS_FILE: &str = "modification-markers.json";
+
+/// Which machine and app build last wrote a file, for sync/backup forensics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModificationMarker {
+    pub machine: String,
+    pub app_version: String,
+    pub at_ms: u128,
+}
+
+pub fn current_modification_marker() -> ModificationMarker {
+    ModificationMarker {
+        machine: machine_name(),
+        app_version: crate::VERSION.to_string(),
+        at_ms: now_ms(),
+    }
+}
+
+pub fn machine_name() -> String {
+    let from_env = env::var("COMPUTERNAME")
+        .or_else(|_| env::var("HOSTNAME"))
+        .ok()
+        .filter(|name| !name.trim().is_empty());
+    if let Some(name) = from_env {
+        return name;
+    }
+    Command::new("hostname")
+        .output()
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// File name -> last writer, for every data file in `dir` that has a marker.
+pub fn load_modification_markers(dir: &Path) -> HashMap<String, ModificationMarker> {
+    fs::read_to_string(dir.join(MODIFICATION_MARKERS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// best effort: a missing marker should never fail the save it describes
+pub(crate) fn record_modification(file_path: &Path) {
+    let (Some(dir), Some(name)) = (file_path.parent(), file_path.file_name()) else {
+        return;
+    };
+    let mut markers = load_modification_markers(dir);
+    markers.insert(
+        name.to_string_lossy().to_string(),
+        current_modification_marker(),
+    );
+    if let Ok(payload) = serde_json::to_string_pretty(&markers) {
+        let _ = fs::write(dir.join(MODIFICATION_MARKERS_FILE), payload);
+    }
+}
@@ -0,0 +1,494 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+use zeroize::Zeroizing;
+
+use crate::{
+    api::fetch_discord_user,
+    app_lock::ensure_app_unlocked,
+    audit::{record_audit, AuditAction},
+    crypto::random_hex,
+    permissions::restrict_permissions,
+    plugins::{dispatch_plugin_event, PluginEventKind},
+    profiles::{find_profile, load_profiles, StoredProfile},
+    switch::store_captured_token,
+    vault::ensure_unlocked,
+    DataDir,
+};
+
+/// Where a client-mod plugin connects, and the key it has to send along.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModBridgeInfo {
+    /// A Unix socket in the data folder, or a named pipe on Windows.
+    pub endpoint: String,
+    pub key: String,
+}
+
+// one JSON line from the plugin
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenPush {
+    key: String,
+    token: String,
+    /// Which profile to save it to; found by the account's user id when left out.
+    #[serde(default)]
+    profile_id: Option<String>,
+}
+
+#[cfg(windows)]
+const PIPE_NAME: &str = r"\\.\pipe\alt-mngr-mod-bridge";
+
+// a push is one short line; anything longer isn't one
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// follows the `modBridge` setting; updated whenever settings are loaded or saved
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_mod_bridge_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// The endpoint and key to enter in the Vencord or BetterDiscord plugin;
+/// the key is created the first time it's asked for.
+pub fn mod_bridge_info(dir: &DataDir) -> Result<ModBridgeInfo, String> {
+    let file_path = dir.mod_bridge_key_file();
+    let key = match fs::read_to_string(&file_path) {
+        Ok(key) if !key.trim().is_empty() => key.trim().to_string(),
+        _ => write_key(dir)?,
+    };
+    Ok(ModBridgeInfo {
+        endpoint: endpoint(dir),
+        key,
+    })
+}
+
+/// Replace the key, so plugins set up with the old one are refused.
+pub fn reset_mod_bridge_key(dir: &DataDir) -> Result<ModBridgeInfo, String> {
+    let key = write_key(dir)?;
+    Ok(ModBridgeInfo {
+        endpoint: endpoint(dir),
+        key,
+    })
+}
+
+fn write_key(dir: &DataDir) -> Result<String, String> {
+    let file_path = dir.mod_bridge_key_file();
+    let key = random_hex::<16>();
+    fs::write(&file_path, &key).map_err(|e| format!("Could not save the mod bridge key: {e}"))?;
+    restrict_permissions(&file_path, false)?;
+    Ok(key)
+}
+
+fn endpoint(dir: &DataDir) -> String {
+    #[cfg(windows)]
+    {
+        let _ = dir;
+        PIPE_NAME.to_string()
+    }
+    #[cfg(unix)]
+    {
+        dir.mod_bridge_socket().to_string_lossy().to_string()
+    }
+}
+
+/// Accept token pushes from a client-mod plugin for as long as the app runs,
+/// while the setting is on; meant for its own thread.
+pub fn serve_mod_bridge(dir: DataDir) {
+    loop {
+        if ENABLED.load(Ordering::Relaxed) {
+            if let Err(e) = listen(&dir) {
+                eprintln!("Warning: the mod bridge stopped: {e}");
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+// a listener that doesn't block, so turning the setting off closes it promptly
+#[cfg(unix)]
+fn listen(dir: &DataDir) -> Result<(), String> {
+    use std::{io::ErrorKind, os::unix::net::UnixListener};
+
+    let path = dir.mod_bridge_socket();
+    // left behind when the app last exited
+    let _ = fs::remove_file(&path);
+    let listener =
+        UnixListener::bind(&path).map_err(|e| format!("Could not open {}: {e}", path.display()))?;
+    restrict_permissions(&path, false)?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Could not open {}: {e}", path.display()))?;
+
+    while ENABLED.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let _ = stream.set_nonblocking(false);
+                let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+                handle_connection(dir, &stream, &stream);
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                let _ = fs::remove_file(&path);
+                return Err(e.to_string());
+            }
+        }
+    }
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
+
+// a PIPE_NOWAIT pipe reports whether a client is there instead of blocking
+#[cfg(windows)]
+fn listen(dir: &DataDir) -> Result<(), String> {
+    use std::{io, time::Instant};
+    use windows_sys::Win32::{
+        Foundation::{
+            ERROR_NO_DATA, ERROR_PIPE_CONNECTED, ERROR_PIPE_LISTENING, INVALID_HANDLE_VALUE,
+        },
+        Security::SECURITY_ATTRIBUTES,
+        Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX},
+        System::Pipes::{
+            ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_NOWAIT,
+            PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE,
+        },
+    };
+
+    let name: Vec<u16> = PIPE_NAME.encode_utf16().chain([0]).collect();
+    // only this user may connect; another user's processes get access denied
+    let owner_only =
+        pipe::OwnerOnly::new().map_err(|e| format!("Could not secure {PIPE_NAME}: {e}"))?;
+    let attributes = SECURITY_ATTRIBUTES {
+        nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+        lpSecurityDescriptor: owner_only.0,
+        bInheritHandle: 0,
+    };
+    while ENABLED.load(Ordering::Relaxed) {
+        // SAFETY: the name is NUL-terminated and the attributes outlive the call.
+        // The previous instance is closed by now, so FIRST_PIPE_INSTANCE only
+        // fails when some other process got the name first and would be
+        // listening in; remote clients are refused
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_NOWAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                1,
+                4096,
+                4096,
+                0,
+                &attributes,
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(format!(
+                "Could not open {PIPE_NAME}: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        let pipe = pipe::Pipe(handle);
+
+        let mut connected = false;
+        while ENABLED.load(Ordering::Relaxed) {
+            // SAFETY: the handle is a valid pipe; no overlapped I/O
+            unsafe { ConnectNamedPipe(pipe.0, std::ptr::null_mut()) };
+            match io::Error::last_os_error()
+                .raw_os_error()
+                .map(|code| code as u32)
+            {
+                Some(ERROR_PIPE_CONNECTED) => {
+                    connected = true;
+                    break;
+                }
+                Some(ERROR_PIPE_LISTENING) => thread::sleep(POLL_INTERVAL),
+                // a client came and went; make room for the next one
+                Some(ERROR_NO_DATA) => {
+                    // SAFETY: the handle is a valid pipe
+                    unsafe { DisconnectNamedPipe(pipe.0) };
+                }
+                _ => break,
+            }
+        }
+        // the pipe stays PIPE_NOWAIT, so a client that sends nothing can't
+        // hold the bridge past the deadline
+        if connected {
+            let connection = pipe::Connection {
+                pipe: &pipe,
+                deadline: Instant::now() + REQUEST_TIMEOUT,
+            };
+            handle_connection(dir, &connection, &connection);
+        }
+    }
+    Ok(())
+}
+
+// std has no named pipe server, so reads and writes go through the handle
+#[cfg(windows)]
+mod pipe {
+    use std::{
+        io::{self, Read, Write},
+        thread,
+        time::{Duration, Instant},
+    };
+    use windows_sys::{
+        core::PWSTR,
+        Win32::{
+            Foundation::{CloseHandle, LocalFree, ERROR_BROKEN_PIPE, ERROR_NO_DATA, HANDLE},
+            Security::{
+                Authorization::{
+                    ConvertSidToStringSidW, ConvertStringSecurityDescriptorToSecurityDescriptorW,
+                    SDDL_REVISION_1,
+                },
+                GetTokenInformation, TokenUser, PSECURITY_DESCRIPTOR, TOKEN_QUERY, TOKEN_USER,
+            },
+            Storage::FileSystem::{FlushFileBuffers, ReadFile, WriteFile},
+            System::{
+                Pipes::DisconnectNamedPipe,
+                Threading::{GetCurrentProcess, OpenProcessToken},
+            },
+        },
+    };
+
+    pub struct Pipe(pub HANDLE);
+
+    // how often a read looks again while the client hasn't sent anything
+    const READ_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+    /// A connected client, served until `deadline`.
+    pub struct Connection<'a> {
+        pub pipe: &'a Pipe,
+        pub deadline: Instant,
+    }
+
+    /// A security descriptor whose ACL lets the current user in, and nobody
+    /// else, not even through inherited entries.
+    pub struct OwnerOnly(pub PSECURITY_DESCRIPTOR);
+
+    impl OwnerOnly {
+        pub fn new() -> io::Result<Self> {
+            let sddl: Vec<u16> = format!("D:P(A;;GA;;;{})", current_user_sid()?)
+                .encode_utf16()
+                .chain([0])
+                .collect();
+            let mut descriptor: PSECURITY_DESCRIPTOR = std::ptr::null_mut();
+            // SAFETY: the string is NUL-terminated; the descriptor is released on drop
+            let ok = unsafe {
+                ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                    sddl.as_ptr(),
+                    SDDL_REVISION_1,
+                    &mut descriptor,
+                    std::ptr::null_mut(),
+                )
+            };
+            if ok == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self(descriptor))
+        }
+    }
+
+    impl Drop for OwnerOnly {
+        fn drop(&mut self) {
+            // SAFETY: allocated with LocalAlloc by the conversion above
+            unsafe { LocalFree(self.0) };
+        }
+    }
+
+    // the SID the app runs as, like "S-1-5-21-..."
+    fn current_user_sid() -> io::Result<String> {
+        let mut token: HANDLE = std::ptr::null_mut();
+        // SAFETY: the process pseudo handle needs no closing; the token is closed below
+        if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // a TOKEN_USER and the SID it points to; u64s keep it aligned
+        let mut buffer = [0u64; 64];
+        let mut needed = 0;
+        // SAFETY: the buffer is valid for its whole size
+        let ok = unsafe {
+            GetTokenInformation(
+                token,
+                TokenUser,
+                buffer.as_mut_ptr().cast(),
+                std::mem::size_of_val(&buffer) as u32,
+                &mut needed,
+            )
+        };
+        let error = io::Error::last_os_error();
+        // SAFETY: opened above and not used after this
+        unsafe { CloseHandle(token) };
+        if ok == 0 {
+            return Err(error);
+        }
+
+        // SAFETY: GetTokenInformation filled the buffer with a TOKEN_USER
+        let sid = unsafe { (*buffer.as_ptr().cast::<TOKEN_USER>()).User.Sid };
+        let mut text: PWSTR = std::ptr::null_mut();
+        // SAFETY: `sid` points into `buffer`, still alive; the string is released below
+        if unsafe { ConvertSidToStringSidW(sid, &mut text) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: a NUL-terminated string from ConvertSidToStringSidW, freed once copied
+        let sid = unsafe {
+            let len = (0..).take_while(|&i| *text.add(i) != 0).count();
+            let sid = String::from_utf16_lossy(std::slice::from_raw_parts(text, len));
+            LocalFree(text.cast());
+            sid
+        };
+        Ok(sid)
+    }
+
+    impl Read for &Connection<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+            loop {
+                let mut read = 0;
+                // SAFETY: the buffer is valid for `len` bytes; no overlapped I/O
+                let ok = unsafe {
+                    ReadFile(
+                        self.pipe.0,
+                        buf.as_mut_ptr(),
+                        len,
+                        &mut read,
+                        std::ptr::null_mut(),
+                    )
+                };
+                if ok != 0 {
+                    return Ok(read as usize);
+                }
+                let error = io::Error::last_os_error();
+                match error.raw_os_error().map(|code| code as u32) {
+                    // the client closed its end
+                    Some(ERROR_BROKEN_PIPE) => return Ok(0),
+                    // a PIPE_NOWAIT pipe with nothing in it yet
+                    Some(ERROR_NO_DATA) if Instant::now() < self.deadline => {
+                        thread::sleep(READ_POLL_INTERVAL)
+                    }
+                    Some(ERROR_NO_DATA) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            "the plugin sent nothing in time",
+                        ))
+                    }
+                    _ => return Err(error),
+                }
+            }
+        }
+    }
+
+    impl Write for &Connection<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let mut written = 0;
+            let len = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+            // SAFETY: the buffer is valid for `len` bytes; no overlapped I/O
+            let ok = unsafe {
+                WriteFile(
+                    self.pipe.0,
+                    buf.as_ptr(),
+                    len,
+                    &mut written,
+                    std::ptr::null_mut(),
+                )
+            };
+            if ok == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(written as usize)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Drop for Pipe {
+        fn drop(&mut self) {
+            // SAFETY: the handle is ours and not used after this
+            unsafe {
+                FlushFileBuffers(self.0);
+                DisconnectNamedPipe(self.0);
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+fn handle_connection(dir: &DataDir, reader: impl Read, mut writer: impl Write) {
+    let mut line = Zeroizing::new(String::new());
+    let read = BufReader::new(reader.take(MAX_REQUEST_BYTES)).read_line(&mut line);
+    let result = read
+        .map_err(|e| format!("Could not read the request: {e}"))
+        .and_then(|_| {
+            serde_json::from_str::<TokenPush>(&line)
+                .map_err(|e| format!("Could not parse the request: {e}"))
+        })
+        .and_then(|push| accept_push(dir, push));
+    let reply = match result {
+        Ok(profile) => json!({ "ok": true, "profileId": profile.id, "nickname": profile.nickname }),
+        Err(error) => json!({ "ok": false, "error": error }),
+    };
+    let _ = writeln!(writer, "{reply}");
+}
+
+fn accept_push(dir: &DataDir, push: TokenPush) -> Result<StoredProfile, String> {
+    let expected = mod_bridge_info(dir)?.key;
+    if !keys_match(push.key.trim(), &expected) {
+        return Err("Wrong mod bridge key. Copy it again from the app's settings.".to_string());
+    }
+    let token = Zeroizing::new(push.token);
+    let token = token.trim();
+    if token.is_empty() || token.chars().any(char::is_whitespace) {
+        return Err("That doesn't look like a Discord token.".to_string());
+    }
+    ensure_app_unlocked(dir)?;
+    ensure_unlocked(dir)?;
+
+    let profile_id = match push.profile_id {
+        Some(id) => find_profile(dir, &id)?.id,
+        None => linked_profile(dir, token)?,
+    };
+    let result = store_captured_token(dir, &profile_id, token);
+    record_audit(
+        dir,
+        AuditAction::Capture,
+        Some(&profile_id),
+        Some("mod bridge"),
+        &result,
+    );
+    dispatch_plugin_event(dir, PluginEventKind::Capture, Some(&profile_id), &result);
+    result
+}
+
+// looks at every byte whatever the first difference, so how long a wrong
+// key takes to refuse says nothing about how much of it was right
+fn keys_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| std::hint::black_box(diff | (a ^ b)))
+            == 0
+}
+
+// the profile a token belongs to, by the account's user id
+fn linked_profile(dir: &DataDir, token: &str) -> Result<String, String> {
+    let user = fetch_discord_user(token)?;
+    load_profiles(&dir.profiles_file())?
+        .into_iter()
+        .find(|p| p.discord_user_id.as_deref() == Some(user.id.as_str()))
+        .map(|p| p.id)
+        .ok_or_else(|| {
+            format!(
+                "No profile is linked to {} yet. Capture it in the app once, or send a profileId.",
+                user.username
+            )
+        })
+}
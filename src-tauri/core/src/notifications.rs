@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api::{fetch_guilds, patch_guild_notification_settings};
+
+// Discord's message_notifications levels
+const NOTIFY_ONLY_MENTIONS: u8 = 1;
+const NOTIFY_NOTHING: u8 = 2;
+
+/// How loud Discord should be for a profile; applied to all of its servers
+/// every time the user switches to it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationPreset {
+    /// Leave the account's own settings alone.
+    #[default]
+    Unchanged,
+    MentionsOnly,
+    /// Mute every server, e.g. for burner alts.
+    MuteAll,
+}
+
+/// Push `preset` to every server the account is in. Returns how many servers
+/// were updated; 0 for [`NotificationPreset::Unchanged`].
+pub fn apply_notification_preset(token: &str, preset: NotificationPreset) -> Result<usize, String> {
+    let settings = match preset {
+        NotificationPreset::Unchanged => return Ok(0),
+        NotificationPreset::MentionsOnly => json!({
+            "muted": false,
+            "message_notifications": NOTIFY_ONLY_MENTIONS,
+            "suppress_everyone": true,
+            "suppress_roles": true,
+        }),
+        NotificationPreset::MuteAll => json!({
+            "muted": true,
+            "message_notifications": NOTIFY_NOTHING,
+            "suppress_everyone": true,
+            "suppress_roles": true,
+        }),
+    };
+
+    let guild_ids: Vec<String> = fetch_guilds(token)?.into_iter().map(|g| g.id).collect();
+    if guild_ids.is_empty() {
+        return Ok(0);
+    }
+    patch_guild_notification_settings(token, &guild_ids, &settings)?;
+    Ok(guild_ids.len())
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, collections::HashMap, fs, time::Instant};
+
+use crate::{
+    clock::now_ms,
+    profiles::Profile,
+    progress::{begin_operation, last_step, Operation, ProgressStep},
+    switch::{capture_token, switch_to_profile, SwitchOutcome},
+    DataDir,
+};
+
+/// How a profile's last capture or switch went, kept so it can be shown
+/// and retried once whatever broke it is fixed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationResult {
+    pub operation: Operation,
+    pub succeeded: bool,
+    pub finished_at_ms: u128,
+    pub duration_ms: u128,
+    /// The step it was on when it failed; None if it failed before the first one.
+    pub failed_step: Option<ProgressStep>,
+    pub error: Option<String>,
+}
+
+/// What [`retry_last_operation`] ran again.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "operation")]
+pub enum RetriedOperation {
+    Capture { profile: Box<Profile> },
+    Switch { outcome: SwitchOutcome },
+}
+
+// profile id -> the last result of each operation run on it
+type History = HashMap<String, Vec<OperationResult>>;
+
+/// Run one of a profile's long operations and remember how it went.
+/// Failing to save the history never fails the operation itself.
+pub(crate) fn run_recorded<T>(
+    dir: &DataDir,
+    profile_id: &str,
+    operation: Operation,
+    run: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    begin_operation(operation);
+    let started = Instant::now();
+    let result = run();
+    let entry = OperationResult {
+        operation,
+        succeeded: result.is_ok(),
+        finished_at_ms: now_ms(),
+        duration_ms: started.elapsed().as_millis(),
+        failed_step: result.as_ref().err().and_then(|_| last_step(operation)),
+        error: result.as_ref().err().cloned(),
+    };
+
+    let mut history = load_history(dir);
+    let results = history.entry(profile_id.to_string()).or_default();
+    results.retain(|r| r.operation != operation);
+    results.push(entry);
+    if let Err(e) = save_history(dir, &history) {
+        eprintln!("Warning: could not save operation history: {e}");
+    }
+    result
+}
+
+/// The last result of each operation run on every profile, newest first per profile.
+pub fn get_operation_history(dir: &DataDir) -> HashMap<String, Vec<OperationResult>> {
+    let mut history = load_history(dir);
+    for results in history.values_mut() {
+        results.sort_by_key(|r| Reverse(r.finished_at_ms));
+    }
+    history
+}
+
+/// Run this profile's most recent operation again, if it failed. A switch is
+/// retried without any override or confirmation, so the call, storage lock
+/// and protection checks apply as they would to a fresh one.
+pub fn retry_last_operation(dir: &DataDir, profile_id: &str) -> Result<RetriedOperation, String> {
+    let last = load_history(dir)
+        .remove(profile_id)
+        .unwrap_or_default()
+        .into_iter()
+        .max_by_key(|r| r.finished_at_ms)
+        .ok_or_else(|| "Nothing has been run on this account yet.".to_string())?;
+    if last.succeeded {
+        return Err(
+            "The last operation on this account worked; there's nothing to retry.".to_string(),
+        );
+    }
+    match last.operation {
+        Operation::Capture => Ok(RetriedOperation::Capture {
+            profile: Box::new(capture_token(dir, profile_id, None, None)?),
+        }),
+        Operation::Switch => Ok(RetriedOperation::Switch {
+            outcome: switch_to_profile(dir, profile_id, false, false, None)?,
+        }),
+        Operation::Login | Operation::Wipe | Operation::Restore | Operation::Repair => {
+            Err("That operation can't be retried from here.".to_string())
+        }
+    }
+}
+
+/// Drop a removed profile's history.
+pub(crate) fn forget_operations(dir: &DataDir, profile_id: &str) {
+    let mut history = load_history(dir);
+    if history.remove(profile_id).is_some() {
+        let _ = save_history(dir, &history);
+    }
+}
+
+fn load_history(dir: &DataDir) -> History {
+    fs::read_to_string(dir.operation_history_file())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_history(dir: &DataDir, history: &History) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(history)
+        .map_err(|e| format!("Could not encode operation history: {e}"))?;
+    fs::write(dir.operation_history_file(), payload)
+        .map_err(|e| format!("Could not save operation history: {e}"))
+}
@@ -0,0 +1,314 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::path::Path;
+use zeroize::Zeroizing;
+
+use crate::secret::SecretToken;
+
+/// Newer Discord builds store the token as this prefix plus base64 of an
+/// os_crypt blob instead of in plain text.
+pub const ENCRYPTED_TOKEN_PREFIX: &str = "dQw4w9WgXcQ:";
+
+// os_crypt tags every blob with a version before the ciphertext
+const BLOB_VERSION: &[u8] = b"v10";
+
+/// The key Discord's os_crypt seals tokens with; the cipher depends on the OS.
+enum MasterKey {
+    // Windows: AES-256-GCM, blob is nonce || ciphertext || tag
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Gcm(Zeroizing<Vec<u8>>),
+    // macOS: AES-128-CBC with a fixed IV, key derived from the Keychain password
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    Cbc(Zeroizing<[u8; 16]>),
+}
+
+pub fn is_encrypted_token(token: &str) -> bool {
+    token.starts_with(ENCRYPTED_TOKEN_PREFIX)
+}
+
+/// Turn a `dQw4w9WgXcQ:` token read from `storage_dir` back into the real token.
+/// Plain tokens come back unchanged.
+pub fn decrypt_token(storage_dir: &Path, token: &str) -> Result<SecretToken, String> {
+    let Some(encoded) = token.strip_prefix(ENCRYPTED_TOKEN_PREFIX) else {
+        return Ok(SecretToken::new(token.to_string()));
+    };
+    let key = master_key(storage_dir)?
+        .ok_or_else(|| "Could not decrypt Discord token: no encryption key found.".to_string())?;
+
+    let blob = STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Could not decode Discord token: {e}"))?;
+    let sealed = blob
+        .strip_prefix(BLOB_VERSION)
+        .ok_or_else(|| "Could not decrypt Discord token: unknown format.".to_string())?;
+
+    let plain = key.decrypt(sealed)?;
+    String::from_utf8(plain.to_vec())
+        .map(SecretToken::new)
+        .map_err(|_| "Could not decrypt Discord token: not valid text.".to_string())
+}
+
+/// Seal `token` for the install at `storage_dir` if it encrypts its tokens;
+/// otherwise (or if it's already sealed) it's returned unchanged.
+pub fn encrypt_token_for(storage_dir: &Path, token: &str) -> Result<String, String> {
+    if is_encrypted_token(token) {
+        return Ok(token.to_string());
+    }
+    let Some(key) = master_key(storage_dir)? else {
+        return Ok(token.to_string());
+    };
+
+    let mut blob = BLOB_VERSION.to_vec();
+    blob.extend_from_slice(&key.encrypt(token.as_bytes())?);
+    Ok(format!("{ENCRYPTED_TOKEN_PREFIX}{}", STANDARD.encode(blob)))
+}
+
+impl MasterKey {
+    fn decrypt(&self, sealed: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+        match self {
+            Self::Gcm(key) => gcm::decrypt(key, sealed),
+            Self::Cbc(key) => cbc_mode::decrypt(key, sealed),
+        }
+    }
+
+    fn encrypt(&self, plain: &[u8]) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Gcm(key) => gcm::encrypt(key, plain),
+            Self::Cbc(key) => cbc_mode::encrypt(key, plain),
+        }
+    }
+}
+
+// ── Key lookup ──
+
+// None means this install doesn't encrypt its tokens
+#[cfg(target_os = "windows")]
+fn master_key(storage_dir: &Path) -> Result<Option<MasterKey>, String> {
+    use std::fs;
+
+    // `Local State` sits in the Discord profile folder, two levels above Local Storage/leveldb
+    let Some(path) = storage_dir
+        .parent()
+        .and_then(Path::parent)
+        .map(|profile| profile.join("Local State"))
+        .filter(|p| p.exists())
+    else {
+        return Ok(None);
+    };
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Could not read Local State: {e}"))?;
+    let state: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("Could not parse Local State: {e}"))?;
+    let Some(encoded) = state["os_crypt"]["encrypted_key"].as_str() else {
+        return Ok(None);
+    };
+
+    let wrapped = STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Could not decode Discord's encryption key: {e}"))?;
+    let protected = wrapped
+        .strip_prefix(b"DPAPI")
+        .ok_or_else(|| "Discord's encryption key is not DPAPI-protected.".to_string())?;
+    dpapi_unprotect(protected)
+        .map(|key| Some(MasterKey::Gcm(key)))
+        .map_err(|_| "Could not unlock Discord's encryption key (DPAPI).".to_string())
+}
+
+// Electron files the safeStorage password under "<app> Safe Storage" / "<app> Key",
+// where <app> is the profile folder name (discord, discordptb, discordcanary)
+#[cfg(target_os = "macos")]
+fn master_key(storage_dir: &Path) -> Result<Option<MasterKey>, String> {
+    let Some(app) = storage_dir
+        .parent()
+        .and_then(Path::parent)
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().to_string())
+    else {
+        return Ok(None);
+    };
+
+    let entry = keyring::Entry::new(&format!("{app} Safe Storage"), &format!("{app} Key"))
+        .map_err(|e| format!("Could not open Discord's keychain entry: {e}"))?;
+    let password = match entry.get_password() {
+        Ok(password) => Zeroizing::new(password),
+        Err(keyring::Error::NoEntry) => return Ok(None),
+        Err(e) => return Err(format!("Could not read Discord's keychain entry: {e}")),
+    };
+    Ok(Some(MasterKey::Cbc(cbc_mode::derive_key(&password))))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn master_key(_storage_dir: &Path) -> Result<Option<MasterKey>, String> {
+    Ok(None)
+}
+
+/// Encrypt `data` with DPAPI for the current user; only that user on this
+/// machine can decrypt it again.
+#[cfg(target_os = "windows")]
+pub(crate) fn dpapi_protect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use std::ptr;
+    use windows_sys::Win32::{
+        Foundation::LocalFree,
+        Security::Cryptography::{CryptProtectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB},
+    };
+
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB {
+        cbData: 0,
+        pbData: ptr::null_mut(),
+    };
+
+    // SAFETY: `input` borrows `data` for the duration of the call, and `output`
+    // is allocated by DPAPI, copied out and released with LocalFree below
+    unsafe {
+        let ok = CryptProtectData(
+            &input,
+            ptr::null(),
+            ptr::null(),
+            ptr::null(),
+            ptr::null(),
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        );
+        if ok == 0 {
+            return Err("DPAPI could not encrypt the data.".to_string());
+        }
+        let sealed = std::slice::from_raw_parts(output.pbData, output.cbData as usize).to_vec();
+        LocalFree(output.pbData as _);
+        Ok(sealed)
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn dpapi_unprotect(data: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+    use std::ptr;
+    use windows_sys::Win32::{
+        Foundation::LocalFree,
+        Security::Cryptography::{CryptUnprotectData, CRYPT_INTEGER_BLOB},
+    };
+
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB {
+        cbData: 0,
+        pbData: ptr::null_mut(),
+    };
+
+    // SAFETY: `input` borrows `data` for the duration of the call, and `output`
+    // is allocated by DPAPI, copied out, wiped and released with LocalFree below
+    unsafe {
+        let ok = CryptUnprotectData(
+            &input,
+            ptr::null_mut(),
+            ptr::null(),
+            ptr::null(),
+            ptr::null(),
+            0,
+            &mut output,
+        );
+        if ok == 0 {
+            return Err("DPAPI could not decrypt the data.".to_string());
+        }
+        let bytes = std::slice::from_raw_parts_mut(output.pbData, output.cbData as usize);
+        let key = Zeroizing::new(bytes.to_vec());
+        bytes.fill(0);
+        LocalFree(output.pbData as _);
+        Ok(key)
+    }
+}
+
+// ── Ciphers ──
+
+mod gcm {
+    use aes_gcm::{
+        aead::{Aead, AeadCore, KeyInit, OsRng},
+        Aes256Gcm, Key, Nonce,
+    };
+    use zeroize::Zeroizing;
+
+    const NONCE_LEN: usize = 12;
+
+    fn cipher(key: &[u8]) -> Result<Aes256Gcm, String> {
+        if key.len() != 32 {
+            return Err("Discord's encryption key has an unexpected length.".to_string());
+        }
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)))
+    }
+
+    pub(super) fn decrypt(key: &[u8], sealed: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+        if sealed.len() <= NONCE_LEN {
+            return Err("Could not decrypt Discord token: unknown format.".to_string());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        cipher(key)?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map(Zeroizing::new)
+            .map_err(|_| "Could not decrypt Discord token: key mismatch.".to_string())
+    }
+
+    pub(super) fn encrypt(key: &[u8], plain: &[u8]) -> Result<Vec<u8>, String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher(key)?
+            .encrypt(&nonce, plain)
+            .map_err(|_| "Could not encrypt Discord token.".to_string())?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+}
+
+// named so it doesn't shadow the `cbc` crate
+mod cbc_mode {
+    use zeroize::Zeroizing;
+
+    // Chromium's fixed parameters for the macOS os_crypt key
+    #[cfg(target_os = "macos")]
+    const SALT: &[u8] = b"saltysalt";
+    #[cfg(target_os = "macos")]
+    const ITERATIONS: u32 = 1003;
+
+    #[cfg(target_os = "macos")]
+    pub(super) fn derive_key(password: &str) -> Zeroizing<[u8; 16]> {
+        let mut key = Zeroizing::new([0u8; 16]);
+        pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password.as_bytes(), SALT, ITERATIONS, key.as_mut());
+        key
+    }
+
+    #[cfg(target_os = "macos")]
+    pub(super) fn decrypt(key: &[u8; 16], sealed: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+        use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+
+        cbc::Decryptor::<aes::Aes128>::new(key.into(), &iv().into())
+            .decrypt_padded_vec_mut::<Pkcs7>(sealed)
+            .map(Zeroizing::new)
+            .map_err(|_| "Could not decrypt Discord token: key mismatch.".to_string())
+    }
+
+    #[cfg(target_os = "macos")]
+    pub(super) fn encrypt(key: &[u8; 16], plain: &[u8]) -> Result<Vec<u8>, String> {
+        use aes::cipher::{block_padding::Pkcs7, BlockEncryptMut, KeyIvInit};
+
+        Ok(cbc::Encryptor::<aes::Aes128>::new(key.into(), &iv().into())
+            .encrypt_padded_vec_mut::<Pkcs7>(plain))
+    }
+
+    // sixteen spaces
+    #[cfg(target_os = "macos")]
+    fn iv() -> [u8; 16] {
+        [b' '; 16]
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub(super) fn decrypt(_key: &[u8; 16], _sealed: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+        Err("This token format is only used on macOS.".to_string())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub(super) fn encrypt(_key: &[u8; 16], _plain: &[u8]) -> Result<Vec<u8>, String> {
+        Err("This token format is only used on macOS.".to_string())
+    }
+}
@@ -0,0 +1,177 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::{
+    api::{fetch_friends, fetch_guilds, DiscordUser},
+    profiles::{find_profile, load_profiles},
+    tokens::load_profile_token,
+    DataDir,
+};
+
+/// Something more than one of the compared profiles has in common.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedItem {
+    pub id: String,
+    pub name: String,
+    pub profile_ids: Vec<String>,
+}
+
+/// How the lookup went for one profile.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileLookup {
+    pub profile_id: String,
+    pub count: Option<usize>,
+    /// Why this profile is missing from the comparison (no token, rejected, offline).
+    pub error: Option<String>,
+}
+
+/// Two managed accounts that are friends with each other.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectLink {
+    pub profile_id: String,
+    pub friend_profile_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendOverlapReport {
+    pub profiles: Vec<ProfileLookup>,
+    /// Friends of two or more of the profiles, most shared first.
+    pub shared: Vec<SharedItem>,
+    pub direct_links: Vec<DirectLink>,
+}
+
+/// Fetch the friend lists of `profile_ids` and report who they share.
+/// Only runs when asked; nothing is stored.
+pub fn friend_overlap(
+    dir: &DataDir,
+    profile_ids: &[String],
+) -> Result<FriendOverlapReport, String> {
+    let lists = fetch_for_profiles(dir, profile_ids, fetch_friends)?;
+
+    // a friend that is itself one of our accounts ties the two together directly
+    let managed: BTreeMap<String, String> = load_profiles(&dir.profiles_file())?
+        .into_iter()
+        .filter_map(|p| p.discord_user_id.map(|user_id| (user_id, p.id)))
+        .collect();
+    let mut direct_links = Vec::new();
+    for (profile_id, friends) in &lists {
+        for friend in friends.iter().flatten() {
+            if let Some(friend_profile_id) = managed.get(&friend.id) {
+                direct_links.push(DirectLink {
+                    profile_id: profile_id.clone(),
+                    friend_profile_id: friend_profile_id.clone(),
+                });
+            }
+        }
+    }
+
+    let (profiles, shared) = summarize(lists, |user| (user.id.clone(), display_name(user)));
+    Ok(FriendOverlapReport {
+        profiles,
+        shared,
+        direct_links,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuildOverlapReport {
+    pub profiles: Vec<ProfileLookup>,
+    /// Servers two or more of the profiles are in, most shared first.
+    pub shared: Vec<SharedItem>,
+}
+
+/// Fetch the server lists of `profile_ids` and report the ones they share.
+/// Only runs when asked; nothing is stored.
+pub fn guild_overlap(dir: &DataDir, profile_ids: &[String]) -> Result<GuildOverlapReport, String> {
+    let lists = fetch_for_profiles(dir, profile_ids, fetch_guilds)?;
+    let (profiles, shared) = summarize(lists, |guild| (guild.id.clone(), guild.name.clone()));
+    Ok(GuildOverlapReport { profiles, shared })
+}
+
+// ── Shared comparison helpers ──
+
+type Lookup<T> = (String, Result<Vec<T>, String>);
+
+fn fetch_for_profiles<T>(
+    dir: &DataDir,
+    profile_ids: &[String],
+    fetch: impl Fn(&str) -> Result<Vec<T>, String>,
+) -> Result<Vec<Lookup<T>>, String> {
+    let mut unique: Vec<&String> = Vec::new();
+    for id in profile_ids {
+        if !unique.contains(&id) {
+            find_profile(dir, id)?;
+            unique.push(id);
+        }
+    }
+    if unique.len() < 2 {
+        return Err("Pick at least two profiles to compare.".to_string());
+    }
+
+    Ok(unique
+        .into_iter()
+        .map(|id| {
+            let items = load_profile_token(dir, id).and_then(|token| fetch(token.expose()));
+            (id.clone(), items)
+        })
+        .collect())
+}
+
+fn summarize<T>(
+    lists: Vec<Lookup<T>>,
+    key: impl Fn(&T) -> (String, String),
+) -> (Vec<ProfileLookup>, Vec<SharedItem>) {
+    let mut lookups = Vec::new();
+    let mut by_id: BTreeMap<String, SharedItem> = BTreeMap::new();
+    for (profile_id, items) in lists {
+        match items {
+            Ok(items) => {
+                lookups.push(ProfileLookup {
+                    profile_id: profile_id.clone(),
+                    count: Some(items.len()),
+                    error: None,
+                });
+                for item in &items {
+                    let (id, name) = key(item);
+                    by_id
+                        .entry(id.clone())
+                        .or_insert_with(|| SharedItem {
+                            id,
+                            name,
+                            profile_ids: Vec::new(),
+                        })
+                        .profile_ids
+                        .push(profile_id.clone());
+                }
+            }
+            Err(e) => lookups.push(ProfileLookup {
+                profile_id,
+                count: None,
+                error: Some(e),
+            }),
+        }
+    }
+
+    let mut shared: Vec<SharedItem> = by_id
+        .into_values()
+        .filter(|item| item.profile_ids.len() > 1)
+        .collect();
+    shared.sort_by(|a, b| {
+        b.profile_ids
+            .len()
+            .cmp(&a.profile_ids.len())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    (lookups, shared)
+}
+
+fn display_name(user: &DiscordUser) -> String {
+    user.global_name
+        .clone()
+        .unwrap_or_else(|| user.username.clone())
+}
@@ -0,0 +1,86 @@
+use serde::Serialize;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use crate::{launch::find_squirrel_executable, settings::LauncherSettings};
+
+/// A custom executable path that pointed into an `app-x.y.z` folder Discord's
+/// updater has since removed, moved to the newest version next to it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathRepair {
+    /// The launch preset the path belonged to; None for the top-level setting.
+    pub preset: Option<String>,
+    pub old_path: String,
+    pub new_path: String,
+}
+
+type Listener = Arc<dyn Fn(PathRepair) + Send + Sync>;
+
+static LISTENER: Mutex<Option<Listener>> = Mutex::new(None);
+
+/// Receive every custom path the app repairs; replaces any earlier listener.
+pub fn set_path_repair_listener(listener: impl Fn(PathRepair) + Send + Sync + 'static) {
+    if let Ok(mut current) = LISTENER.lock() {
+        *current = Some(Arc::new(listener));
+    }
+}
+
+pub(crate) fn paths_repaired(repairs: Vec<PathRepair>) {
+    let Some(listener) = LISTENER.lock().ok().and_then(|current| current.clone()) else {
+        return;
+    };
+    for repair in repairs {
+        listener(repair);
+    }
+}
+
+/// Point the top-level and preset custom paths that went stale with a
+/// Discord update at the newest version installed next to them.
+pub(crate) fn repair_custom_paths(settings: &mut LauncherSettings) -> Vec<PathRepair> {
+    let mut repairs = Vec::new();
+    let paths = std::iter::once((None, &mut settings.custom_executable_path)).chain(
+        settings.presets.iter_mut().map(|preset| {
+            (
+                Some(preset.name.clone()),
+                &mut preset.custom_executable_path,
+            )
+        }),
+    );
+    for (preset, path) in paths {
+        let Some(old_path) = path.clone() else {
+            continue;
+        };
+        if let Some(new_path) = newer_sibling(&old_path) {
+            *path = Some(new_path.clone());
+            repairs.push(PathRepair {
+                preset,
+                old_path,
+                new_path,
+            });
+        }
+    }
+    repairs
+}
+
+// `<root>\app-1.0.9163\Discord.exe` once the updater has replaced it with
+// `<root>\app-1.0.9164\Discord.exe`; a path that exists is left alone
+fn newer_sibling(old_path: &str) -> Option<String> {
+    let path = Path::new(old_path.trim());
+    if path.exists() {
+        return None;
+    }
+    let version_dir = path.parent()?;
+    if !version_dir
+        .file_name()?
+        .to_string_lossy()
+        .starts_with("app-")
+    {
+        return None;
+    }
+    let executable = path.file_name()?.to_str()?;
+    find_squirrel_executable(version_dir.parent()?, &[executable])
+        .map(|found| found.to_string_lossy().to_string())
+}
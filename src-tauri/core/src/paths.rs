@@ -0,0 +1,143 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{crypto::random_hex, permissions::restrict_permissions};
+
+/// The folder the app keeps its own files in: accounts, settings and saved tokens.
+#[derive(Debug, Clone)]
+pub struct DataDir {
+    root: PathBuf,
+}
+
+impl DataDir {
+    /// Use `root` as the data folder, creating it if it doesn't exist yet.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self, String> {
+        let root = root.into();
+        fs::create_dir_all(&root)
+            .map_err(|e| format!("Could not create app data directory: {e}"))?;
+        Ok(Self { root })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// `accounts.json`, the list of profiles.
+    pub fn profiles_file(&self) -> PathBuf {
+        self.root.join("accounts.json")
+    }
+
+    pub fn launcher_settings_file(&self) -> PathBuf {
+        self.root.join("launcher-settings.json")
+    }
+
+    pub fn digest_state_file(&self) -> PathBuf {
+        self.root.join("digest-state.json")
+    }
+
+    /// Backing file for the SQLite token store.
+    pub fn token_database(&self) -> PathBuf {
+        self.root.join("tokens.sqlite3")
+    }
+
+    /// Salt and password check for the encrypted token vault; present only while it's enabled.
+    pub fn vault_file(&self) -> PathBuf {
+        self.root.join("vault.json")
+    }
+
+    /// Append-only record of captures, switches, exports and reveals, one JSON object per line.
+    pub fn audit_log_file(&self) -> PathBuf {
+        self.root.join("audit.jsonl")
+    }
+
+    /// One folder per plugin, each with a `plugin.json` manifest.
+    pub fn plugins_dir(&self) -> PathBuf {
+        self.root.join("plugins")
+    }
+
+    /// The last result of each capture and switch, per profile.
+    pub fn operation_history_file(&self) -> PathBuf {
+        self.root.join("operations.json")
+    }
+
+    /// Which profile was last launched in each Discord channel.
+    pub fn sessions_file(&self) -> PathBuf {
+        self.root.join("sessions.json")
+    }
+
+    /// User-added LevelDB token keys.
+    pub fn token_keys_file(&self) -> PathBuf {
+        self.root.join("token-keys.json")
+    }
+
+    /// The key a client-mod plugin sends with every token it pushes.
+    pub fn mod_bridge_key_file(&self) -> PathBuf {
+        self.root.join("mod-bridge-key")
+    }
+
+    /// The Unix socket the mod bridge listens on; Windows uses a named pipe.
+    pub fn mod_bridge_socket(&self) -> PathBuf {
+        self.root.join("mod-bridge.sock")
+    }
+
+    /// Copies of Discord's Local Storage taken before each write; created on demand.
+    pub fn storage_backups_dir(&self) -> Result<PathBuf, String> {
+        let dir = self.root.join("storage-backups");
+        if !dir.exists() {
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Could not create the storage backup folder: {e}"))?;
+            restrict_permissions(&dir, true)?;
+        }
+        Ok(dir)
+    }
+
+    /// A fresh, empty folder only this user can open, for private copies of
+    /// Discord's storage; the caller removes it when done. The name is random
+    /// and has to be new, so nobody can read or plant it ahead of time.
+    pub fn scratch_dir(&self, label: &str) -> Result<PathBuf, String> {
+        let parent = self.root.join("scratch");
+        if !parent.exists() {
+            fs::create_dir_all(&parent)
+                .map_err(|e| format!("Could not create the scratch folder: {e}"))?;
+            restrict_permissions(&parent, true)?;
+        }
+        let dir = parent.join(format!("{label}-{}", random_hex::<8>()));
+        fs::create_dir(&dir).map_err(|e| format!("Could not create the scratch folder: {e}"))?;
+        restrict_permissions(&dir, true)?;
+        Ok(dir)
+    }
+
+    /// The private home folder a profile's Discord gets under firejail or
+    /// bubblewrap; created on demand.
+    pub fn sandbox_home(&self, profile_id: &str) -> Result<PathBuf, String> {
+        let home = self.root.join("sandboxes").join(profile_id);
+        if !home.exists() {
+            fs::create_dir_all(&home)
+                .map_err(|e| format!("Could not create the sandbox folder: {e}"))?;
+            restrict_permissions(&home, true)?;
+        }
+        Ok(home)
+    }
+
+    /// Where the file token store keeps a profile's token; creates `tokens/` on demand.
+    pub fn token_file(&self, profile_id: &str) -> Result<PathBuf, String> {
+        Ok(self.tokens_dir()?.join(format!("{profile_id}.token")))
+    }
+
+    /// Where the encrypted file token store keeps a profile's token.
+    pub fn encrypted_token_file(&self, profile_id: &str) -> Result<PathBuf, String> {
+        Ok(self.tokens_dir()?.join(format!("{profile_id}.token.enc")))
+    }
+
+    fn tokens_dir(&self) -> Result<PathBuf, String> {
+        let dir = self.root.join("tokens");
+        if !dir.exists() {
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Could not create tokens directory: {e}"))?;
+            restrict_permissions(&dir, true)?;
+        }
+        Ok(dir)
+    }
+}
@@ -0,0 +1,165 @@
+use serde::Serialize;
+use std::{fs, path::Path};
+
+use crate::DataDir;
+
+/// A token file or folder other accounts on this machine could read.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaxPermission {
+    pub path: String,
+    pub problem: String,
+    /// Whether `check_token_permissions` tightened it.
+    pub fixed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionReport {
+    pub checked: usize,
+    pub lax: Vec<LaxPermission>,
+}
+
+/// Look at everything holding tokens (`tokens/`, its files and the SQLite
+/// store) and report what's readable by other users; with `fix`, tighten it.
+pub fn check_token_permissions(dir: &DataDir, fix: bool) -> Result<PermissionReport, String> {
+    let mut targets = Vec::new();
+    let tokens_dir = dir.root().join("tokens");
+    if tokens_dir.is_dir() {
+        targets.push((tokens_dir.clone(), true));
+        let entries = fs::read_dir(&tokens_dir)
+            .map_err(|e| format!("Could not read tokens directory: {e}"))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() {
+                targets.push((path, false));
+            }
+        }
+    }
+    let database = dir.token_database();
+    if database.is_file() {
+        targets.push((database, false));
+    }
+
+    let mut lax = Vec::new();
+    for (path, is_dir) in &targets {
+        let Some(problem) = permission_problem(path, *is_dir) else {
+            continue;
+        };
+        let fixed = fix && restrict_permissions(path, *is_dir).is_ok();
+        lax.push(LaxPermission {
+            path: path.display().to_string(),
+            problem,
+            fixed,
+        });
+    }
+    Ok(PermissionReport {
+        checked: targets.len(),
+        lax,
+    })
+}
+
+/// Make `path` readable by the current user only: 0700/0600 on macOS and
+/// Linux, an ACL without inherited entries on Windows.
+pub fn restrict_permissions(path: &Path, is_dir: bool) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = if is_dir { 0o700 } else { 0o600 };
+        return fs::set_permissions(path, fs::Permissions::from_mode(mode))
+            .map_err(|e| format!("Could not restrict permissions: {e}"));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return windows_acl::restrict(path, is_dir);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (path, is_dir);
+        Ok(())
+    }
+}
+
+// None when only the current user can get at `path`
+fn permission_problem(path: &Path, is_dir: bool) -> Option<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = fs::metadata(path).ok()?.permissions().mode() & 0o777;
+        let wanted = if is_dir { 0o700 } else { 0o600 };
+        return (mode & !wanted != 0).then(|| format!("mode {mode:o}, expected {wanted:o}"));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return windows_acl::problem(path, is_dir);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (path, is_dir);
+        None
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_acl {
+    use std::{
+        env,
+        path::Path,
+        process::{Command, Stdio},
+    };
+
+    // groups that mean "anyone on this machine" when they show up in an ACL
+    const BROAD_PRINCIPALS: [&str; 4] = [
+        "Everyone",
+        "BUILTIN\\Users",
+        "NT AUTHORITY\\Authenticated Users",
+        "NT AUTHORITY\\INTERACTIVE",
+    ];
+
+    pub fn problem(path: &Path, is_dir: bool) -> Option<String> {
+        let output = Command::new("icacls")
+            .arg(path)
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let listing = String::from_utf8_lossy(&output.stdout);
+        let broad: Vec<&str> = BROAD_PRINCIPALS
+            .into_iter()
+            .filter(|principal| listing.contains(&format!("{principal}:")))
+            .collect();
+        if !broad.is_empty() {
+            return Some(format!("readable by {}", broad.join(", ")));
+        }
+        // "(I)" marks inherited entries; files should inherit from the locked-down
+        // tokens folder, but the folder itself shouldn't inherit from the data folder
+        (is_dir && listing.contains("(I)"))
+            .then(|| "inherits access from its parent folder".to_string())
+    }
+
+    pub fn restrict(path: &Path, is_dir: bool) -> Result<(), String> {
+        let user = env::var("USERNAME")
+            .map_err(|_| "Could not tell which user to restrict access to.".to_string())?;
+        let grant = if is_dir {
+            format!("{user}:(OI)(CI)F")
+        } else {
+            format!("{user}:F")
+        };
+        let status = Command::new("icacls")
+            .arg(path)
+            .args(["/inheritance:r", "/grant:r", &grant])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|e| format!("Could not run icacls: {e}"))?;
+        if !status.success() {
+            return Err(format!("icacls exited with {status}."));
+        }
+        Ok(())
+    }
+}
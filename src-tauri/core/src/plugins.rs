@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    app_lock::ensure_app_unlocked,
+    clock::now_ms,
+    profiles::find_profile,
+    settings::{load_launcher_settings, save_launcher_settings_to_file},
+    DataDir,
+};
+
+pub const PLUGIN_MANIFEST: &str = "plugin.json";
+// a hook or command that runs longer than this is killed
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(30);
+const PLUGIN_POLL: Duration = Duration::from_millis(100);
+// plugin output shown to the user is cut off here
+const MAX_PLUGIN_OUTPUT_CHARS: usize = 2000;
+
+/// Something a plugin can subscribe to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginEventKind {
+    Switch,
+    Capture,
+}
+
+/// `plugins/<id>/plugin.json`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Program and leading arguments, e.g. `["python3", "hook.py"]`. A program
+    /// given as a path (`./hook.sh`) is found relative to the plugin's folder;
+    /// a bare name is looked up on PATH.
+    pub run: Vec<String>,
+    #[serde(default)]
+    pub events: Vec<PluginEventKind>,
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+}
+
+/// An extra action a plugin adds to the app.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginCommand {
+    pub id: String,
+    pub title: String,
+    /// Run against the selected profile rather than on its own.
+    #[serde(default)]
+    pub needs_profile: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    /// The plugin's folder name.
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub enabled: bool,
+    pub events: Vec<PluginEventKind>,
+    pub commands: Vec<PluginCommand>,
+    /// Why the manifest couldn't be used; such plugins never run.
+    pub error: Option<String>,
+}
+
+/// What a hook gets on stdin. Never holds a token.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginEvent<'a> {
+    event: PluginEventKind,
+    at_ms: u128,
+    profile_id: Option<&'a str>,
+    nickname: Option<String>,
+    succeeded: bool,
+    error: Option<&'a str>,
+}
+
+/// What a plugin command gets on stdin.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginCommandInput<'a> {
+    command: &'a str,
+    profile_id: Option<&'a str>,
+    nickname: Option<String>,
+}
+
+// ── Discovery ──
+
+/// Every folder under `plugins/`, with broken manifests reported rather than skipped.
+pub fn list_plugins(dir: &DataDir) -> Result<Vec<PluginInfo>, String> {
+    let plugins_dir = dir.plugins_dir();
+    if !plugins_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let enabled = load_launcher_settings(&dir.launcher_settings_file())?.enabled_plugins;
+    let entries =
+        fs::read_dir(&plugins_dir).map_err(|e| format!("Could not read plugins folder: {e}"))?;
+
+    let mut plugins: Vec<PluginInfo> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .map(|id| {
+            let enabled = enabled.contains(&id);
+            match load_manifest(dir, &id) {
+                Ok(manifest) => PluginInfo {
+                    id,
+                    name: manifest.name,
+                    description: manifest.description,
+                    enabled,
+                    events: manifest.events,
+                    commands: manifest.commands,
+                    error: None,
+                },
+                Err(e) => PluginInfo {
+                    name: id.clone(),
+                    id,
+                    description: String::new(),
+                    enabled,
+                    events: Vec::new(),
+                    commands: Vec::new(),
+                    error: Some(e),
+                },
+            }
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(plugins)
+}
+
+/// Allow or stop a plugin from running. Plugins run code on this machine, so
+/// nothing under `plugins/` runs until the user enables it here.
+pub fn set_plugin_enabled(
+    dir: &DataDir,
+    plugin_id: &str,
+    enabled: bool,
+) -> Result<Vec<PluginInfo>, String> {
+    ensure_app_unlocked(dir)?;
+    if enabled {
+        load_manifest(dir, plugin_id)?;
+    }
+
+    let file_path = dir.launcher_settings_file();
+    let mut settings = load_launcher_settings(&file_path)?;
+    settings.enabled_plugins.retain(|id| id != plugin_id);
+    if enabled {
+        settings.enabled_plugins.push(plugin_id.to_string());
+    }
+    save_launcher_settings_to_file(&file_path, &settings)?;
+    list_plugins(dir)
+}
+
+fn load_manifest(dir: &DataDir, plugin_id: &str) -> Result<PluginManifest, String> {
+    let folder = plugin_folder(dir, plugin_id)?;
+    let content = fs::read_to_string(folder.join(PLUGIN_MANIFEST))
+        .map_err(|e| format!("Could not read {PLUGIN_MANIFEST}: {e}"))?;
+    let manifest: PluginManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("Could not parse {PLUGIN_MANIFEST}: {e}"))?;
+    if manifest
+        .run
+        .first()
+        .is_none_or(|program| program.trim().is_empty())
+    {
+        return Err(format!("{PLUGIN_MANIFEST} doesn't say what to run."));
+    }
+    Ok(manifest)
+}
+
+fn plugin_folder(dir: &DataDir, plugin_id: &str) -> Result<PathBuf, String> {
+    // ids come from the frontend; keep them to a single folder name
+    let valid = !plugin_id.is_empty()
+        && plugin_id != "."
+        && plugin_id != ".."
+        && !plugin_id.contains(['/', '\\']);
+    let folder = dir.plugins_dir().join(plugin_id);
+    if !valid || !folder.is_dir() {
+        return Err("Plugin not found.".to_string());
+    }
+    Ok(folder)
+}
+
+fn enabled_manifest(dir: &DataDir, plugin_id: &str) -> Result<PluginManifest, String> {
+    let settings = load_launcher_settings(&dir.launcher_settings_file())?;
+    if !settings.enabled_plugins.iter().any(|id| id == plugin_id) {
+        return Err("This plugin is not enabled.".to_string());
+    }
+    load_manifest(dir, plugin_id)
+}
+
+// ── Hooks ──
+
+/// Tell every enabled plugin subscribed to `event` how it went. Runs in the
+/// background; a failing or slow plugin never holds up or fails the operation.
+pub fn dispatch_plugin_event<T>(
+    dir: &DataDir,
+    event: PluginEventKind,
+    profile_id: Option<&str>,
+    result: &Result<T, String>,
+) {
+    let Ok(settings) = load_launcher_settings(&dir.launcher_settings_file()) else {
+        return;
+    };
+    if settings.enabled_plugins.is_empty() {
+        return;
+    }
+
+    let payload = PluginEvent {
+        event,
+        at_ms: now_ms(),
+        profile_id,
+        nickname: profile_id.and_then(|id| find_profile(dir, id).ok().map(|p| p.nickname)),
+        succeeded: result.is_ok(),
+        error: result.as_ref().err().map(String::as_str),
+    };
+    let Ok(input) = serde_json::to_vec(&payload) else {
+        return;
+    };
+
+    for plugin_id in settings.enabled_plugins {
+        let Ok(manifest) = load_manifest(dir, &plugin_id) else {
+            continue;
+        };
+        if !manifest.events.contains(&event) {
+            continue;
+        }
+        let Ok(folder) = plugin_folder(dir, &plugin_id) else {
+            continue;
+        };
+        let input = input.clone();
+        let event_name = event_name(event);
+        thread::spawn(move || {
+            if let Err(e) = run_plugin(&folder, &manifest, &["event", event_name], &input) {
+                eprintln!("Warning: plugin '{plugin_id}' failed on {event_name}: {e}");
+            }
+        });
+    }
+}
+
+fn event_name(event: PluginEventKind) -> &'static str {
+    match event {
+        PluginEventKind::Switch => "switch",
+        PluginEventKind::Capture => "capture",
+    }
+}
+
+// ── Commands ──
+
+/// Run one of a plugin's commands and return what it printed.
+pub fn run_plugin_command(
+    dir: &DataDir,
+    plugin_id: &str,
+    command_id: &str,
+    profile_id: Option<&str>,
+) -> Result<String, String> {
+    ensure_app_unlocked(dir)?;
+    let manifest = enabled_manifest(dir, plugin_id)?;
+    let command = manifest
+        .commands
+        .iter()
+        .find(|c| c.id == command_id)
+        .ok_or_else(|| "The plugin has no such command.".to_string())?;
+    let nickname = match profile_id {
+        Some(id) => Some(find_profile(dir, id)?.nickname),
+        None if command.needs_profile => return Err("Pick a profile first.".to_string()),
+        None => None,
+    };
+
+    let input = serde_json::to_vec(&PluginCommandInput {
+        command: command_id,
+        profile_id,
+        nickname,
+    })
+    .map_err(|e| format!("Could not encode plugin input: {e}"))?;
+    let folder = plugin_folder(dir, plugin_id)?;
+    let output = run_plugin(&folder, &manifest, &["command", command_id], &input)?;
+
+    let trimmed = output.trim();
+    Ok(if trimmed.is_empty() {
+        format!("{} finished.", command.title)
+    } else {
+        trimmed.chars().take(MAX_PLUGIN_OUTPUT_CHARS).collect()
+    })
+}
+
+// ── Running ──
+
+// feeds `input` on stdin and returns stdout; a non-zero exit is an error
+fn run_plugin(
+    folder: &Path,
+    manifest: &PluginManifest,
+    args: &[&str],
+    input: &[u8],
+) -> Result<String, String> {
+    let (program, leading) = manifest
+        .run
+        .split_first()
+        .ok_or_else(|| format!("{PLUGIN_MANIFEST} doesn't say what to run."))?;
+    let program_path = Path::new(program);
+    let program = if program_path.is_relative() && program_path.components().count() > 1 {
+        folder.join(program_path)
+    } else {
+        program_path.to_path_buf()
+    };
+
+    let mut child = Command::new(&program)
+        .args(leading)
+        .args(args)
+        .current_dir(folder)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Could not start {}: {e}", program.display()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // a plugin that ignores its input may close stdin early; that's fine
+        let _ = stdin.write_all(input);
+    }
+
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let status = wait_with_timeout(&mut child)?;
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if status.success() {
+        Ok(stdout)
+    } else {
+        let reason = stderr.lines().rev().find(|line| !line.trim().is_empty());
+        Err(match reason {
+            Some(line) => format!("Plugin exited with {status}: {}", line.trim()),
+            None => format!("Plugin exited with {status}."),
+        })
+    }
+}
+
+// drained on a thread so a chatty plugin can't block on a full pipe
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut text);
+        }
+        text
+    })
+}
+
+fn wait_with_timeout(child: &mut Child) -> Result<ExitStatus, String> {
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Ok(status),
+            Ok(None) if started.elapsed() > PLUGIN_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "Plugin took longer than {} seconds and was stopped.",
+                    PLUGIN_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(None) => thread::sleep(PLUGIN_POLL),
+            Err(e) => return Err(format!("Could not wait for plugin: {e}")),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::{fs, io, path::Path};
+
+use crate::{
+    crypto::random_hex, launch::NEEDS_ELEVATION, settings::DiscordChannel,
+    storage::discord_storage_dir, DataDir,
+};
+
+/// Every preflight error starts with this (or with [`NEEDS_ELEVATION`] when a
+/// folder is off limits), so callers can tell "nothing was touched" apart from
+/// an operation that failed halfway.
+pub const PREFLIGHT_FAILED: &str = "Preflight check failed: ";
+
+// room for accounts.json, the token store and the audit log to be rewritten
+const DATA_DIR_HEADROOM: u64 = 16 * 1024 * 1024;
+// LevelDB compacts into new table files before dropping the old ones, so a
+// write can briefly need as much again as the database already takes
+const STORAGE_HEADROOM: u64 = 32 * 1024 * 1024;
+
+/// Make sure the app's data folder and Discord's storage can be written to
+/// and have room to spare before a switch or capture changes anything in
+/// `channel`.
+pub fn run_preflight(dir: &DataDir, channel: DiscordChannel) -> Result<(), String> {
+    check_folder(dir.root(), "The app's data folder", DATA_DIR_HEADROOM)?;
+
+    let storage_dir = discord_storage_dir(channel)?;
+    let needed = STORAGE_HEADROOM.saturating_add(folder_size(&storage_dir));
+    check_folder(&storage_dir, "Discord's storage", needed)
+}
+
+fn check_folder(path: &Path, what: &str, needed: u64) -> Result<(), String> {
+    let probe = path.join(format!(".alt-mngr-preflight-{}", random_hex::<4>()));
+    fs::write(&probe, b"").map_err(|e| match e.kind() {
+        // typically a per-machine install, or a folder an elevated Discord created
+        io::ErrorKind::PermissionDenied => format!(
+            "{NEEDS_ELEVATION}{what} at {} belongs to an administrator and can't be written to.",
+            path.display()
+        ),
+        _ => format!(
+            "{PREFLIGHT_FAILED}{what} at {} can't be written to: {e}",
+            path.display()
+        ),
+    })?;
+    let _ = fs::remove_file(&probe);
+
+    // when free space can't be read, go ahead rather than block on a guess
+    if let Some(free) = free_space(path) {
+        if free < needed {
+            return Err(format!(
+                "{PREFLIGHT_FAILED}{what} at {} has {} free and needs at least {}. Free up some space and try again.",
+                path.display(),
+                format_mib(free),
+                format_mib(needed)
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn folder_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok()?.metadata().ok())
+                .filter(|meta| meta.is_file())
+                .map(|meta| meta.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn format_mib(bytes: u64) -> String {
+    format!("{} MB", bytes.div_ceil(1024 * 1024))
+}
+
+// ── Platform free space ──
+
+fn free_space(path: &Path) -> Option<u64> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+        let wide: Vec<u16> = path.to_string_lossy().encode_utf16().chain([0]).collect();
+        let mut available = 0u64;
+        // SAFETY: `wide` is NUL-terminated and outlives the call; the totals we
+        // don't need may be null
+        let ok = unsafe {
+            GetDiskFreeSpaceExW(
+                wide.as_ptr(),
+                &mut available,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        return (ok != 0).then_some(available);
+    }
+
+    #[cfg(unix)]
+    {
+        // POSIX df output: filesystem, 1024-blocks, used, available, capacity, mount
+        let output = std::process::Command::new("df")
+            .args(["-Pk"])
+            .arg(path)
+            .stderr(std::process::Stdio::null())
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let available_kib: u64 = stdout
+            .lines()
+            .nth(1)?
+            .split_whitespace()
+            .nth(3)?
+            .parse()
+            .ok()?;
+        return Some(available_kib * 1024);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = path;
+        None
+    }
+}
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    io::{Read, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::crypto::random_hex;
+
+pub const MAX_PRESENCE_TEXT_CHARS: usize = 128;
+
+// a freshly launched client needs a while before its IPC socket is up
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+const CONNECT_RETRY: Duration = Duration::from_secs(2);
+// every running client takes the lowest free discord-ipc-N
+const IPC_SLOTS: u32 = 10;
+
+// IPC frame opcodes
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+
+/// A status card shown on the account after switching to it, e.g. for
+/// streaming setups where viewers should see which alt is live.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RichPresence {
+    /// Id of a Discord application the user created for this; its name is the title.
+    pub client_id: String,
+    #[serde(default)]
+    pub details: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Asset key uploaded to the application, or an image URL.
+    #[serde(default)]
+    pub large_image: Option<String>,
+    #[serde(default)]
+    pub large_text: Option<String>,
+}
+
+trait IpcStream: Read + Write + Send {}
+impl<T: Read + Write + Send> IpcStream for T {}
+
+// Discord drops the activity as soon as the connection closes, so the one
+// showing it is kept open here until the next switch replaces it
+static ACTIVE: Mutex<Option<Box<dyn IpcStream>>> = Mutex::new(None);
+// bumped on every show/clear so a connect still retrying for an older switch gives up
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Trim `presence` and check it against Discord's limits.
+pub fn sanitize_rich_presence(presence: RichPresence) -> Result<RichPresence, String> {
+    let client_id = presence.client_id.trim().to_string();
+    if client_id.len() < 17 || !client_id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(
+            "The Rich Presence application id must be a Discord id (17+ digits).".to_string(),
+        );
+    }
+    let clean = |field: Option<String>, name: &str| -> Result<Option<String>, String> {
+        let Some(text) = field
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+        else {
+            return Ok(None);
+        };
+        let chars = text.chars().count();
+        if !(2..=MAX_PRESENCE_TEXT_CHARS).contains(&chars) {
+            return Err(format!(
+                "The Rich Presence {name} must be between 2 and {MAX_PRESENCE_TEXT_CHARS} characters."
+            ));
+        }
+        Ok(Some(text.to_string()))
+    };
+    let presence = RichPresence {
+        client_id,
+        details: clean(presence.details, "details")?,
+        state: clean(presence.state, "state")?,
+        large_image: clean(presence.large_image, "image")?,
+        large_text: clean(presence.large_text, "image text")?,
+    };
+    if presence.details.is_none() && presence.state.is_none() {
+        return Err("Give the Rich Presence some details or a state.".to_string());
+    }
+    Ok(presence)
+}
+
+/// Show `presence` on the client that was just launched, in the background:
+/// keeps trying until its IPC socket comes up, then holds the connection.
+pub fn show_rich_presence(presence: RichPresence) {
+    let generation = drop_active();
+    thread::spawn(move || {
+        let started = Instant::now();
+        loop {
+            if GENERATION.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            match connect_and_set(&presence) {
+                Ok(stream) => {
+                    if let Ok(mut active) = ACTIVE.lock() {
+                        if GENERATION.load(Ordering::SeqCst) == generation {
+                            *active = Some(stream);
+                        }
+                    }
+                    return;
+                }
+                Err(e) if started.elapsed() >= CONNECT_TIMEOUT => {
+                    eprintln!("Warning: could not set Rich Presence: {e}");
+                    return;
+                }
+                Err(_) => thread::sleep(CONNECT_RETRY),
+            }
+        }
+    });
+}
+
+/// Drop the connection holding the current Rich Presence, which clears it.
+pub fn clear_rich_presence() {
+    drop_active();
+}
+
+fn drop_active() -> u64 {
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active = None;
+    }
+    generation
+}
+
+fn connect_and_set(presence: &RichPresence) -> Result<Box<dyn IpcStream>, String> {
+    let mut stream = open_ipc()?;
+    write_frame(
+        &mut stream,
+        OP_HANDSHAKE,
+        &json!({ "v": 1, "client_id": presence.client_id }),
+    )?;
+    // READY on success, a close frame when the application id is wrong
+    read_reply(&mut stream)?;
+
+    let mut activity = serde_json::Map::new();
+    if let Some(details) = &presence.details {
+        activity.insert("details".to_string(), Value::from(details.as_str()));
+    }
+    if let Some(state) = &presence.state {
+        activity.insert("state".to_string(), Value::from(state.as_str()));
+    }
+    if presence.large_image.is_some() || presence.large_text.is_some() {
+        activity.insert(
+            "assets".to_string(),
+            json!({ "large_image": presence.large_image, "large_text": presence.large_text }),
+        );
+    }
+    write_frame(
+        &mut stream,
+        OP_FRAME,
+        &json!({
+            "cmd": "SET_ACTIVITY",
+            "args": { "pid": std::process::id(), "activity": activity },
+            "nonce": random_hex::<8>(),
+        }),
+    )?;
+    read_reply(&mut stream)?;
+    Ok(stream)
+}
+
+fn open_ipc() -> Result<Box<dyn IpcStream>, String> {
+    for slot in 0..IPC_SLOTS {
+        #[cfg(target_os = "windows")]
+        {
+            let pipe = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(format!(r"\\?\pipe\discord-ipc-{slot}"));
+            if let Ok(pipe) = pipe {
+                return Ok(Box::new(pipe));
+            }
+        }
+
+        #[cfg(unix)]
+        {
+            let base = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+                .iter()
+                .find_map(|var| std::env::var(var).ok().filter(|dir| !dir.is_empty()))
+                .unwrap_or_else(|| "/tmp".to_string());
+            let path = std::path::Path::new(&base).join(format!("discord-ipc-{slot}"));
+            if let Ok(socket) = std::os::unix::net::UnixStream::connect(path) {
+                return Ok(Box::new(socket));
+            }
+        }
+
+        #[cfg(not(any(target_os = "windows", unix)))]
+        let _ = slot;
+    }
+    Err("Discord is not accepting Rich Presence connections yet.".to_string())
+}
+
+// frames are opcode and length as little-endian u32s, then JSON
+fn write_frame(stream: &mut Box<dyn IpcStream>, op: u32, payload: &Value) -> Result<(), String> {
+    let body = payload.to_string();
+    let mut frame = Vec::with_capacity(8 + body.len());
+    frame.extend_from_slice(&op.to_le_bytes());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(body.as_bytes());
+    stream
+        .write_all(&frame)
+        .map_err(|e| format!("Could not talk to Discord: {e}"))
+}
+
+fn read_reply(stream: &mut Box<dyn IpcStream>) -> Result<Value, String> {
+    let mut header = [0u8; 8];
+    stream
+        .read_exact(&mut header)
+        .map_err(|e| format!("Could not read Discord's reply: {e}"))?;
+    let op = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let mut body = vec![0u8; len];
+    stream
+        .read_exact(&mut body)
+        .map_err(|e| format!("Could not read Discord's reply: {e}"))?;
+    let reply: Value = serde_json::from_slice(&body)
+        .map_err(|e| format!("Could not parse Discord's reply: {e}"))?;
+
+    let error = if op == OP_CLOSE || reply["evt"] == "ERROR" {
+        Some(
+            reply["message"]
+                .as_str()
+                .or_else(|| reply["data"]["message"].as_str())
+                .unwrap_or("unknown error"),
+        )
+    } else {
+        None
+    };
+    match error {
+        Some(message) => Err(format!("Discord refused the Rich Presence: {message}")),
+        None => Ok(reply),
+    }
+}
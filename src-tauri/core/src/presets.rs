@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::{
+    clock::now_ms,
+    markers::{current_modification_marker, ModificationMarker},
+    settings::{
+        load_launcher_settings, sanitize_launch_preset, save_launcher_settings_to_file,
+        LaunchPreset, LauncherSettings,
+    },
+    DataDir,
+};
+
+pub const PRESET_EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Launch presets as written to an export file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetExport {
+    pub version: u32,
+    pub exported_at_ms: u128,
+    pub presets: Vec<LaunchPreset>,
+    #[serde(default)]
+    pub last_modified_by: Option<ModificationMarker>,
+}
+
+/// Add a preset, or replace the one with the same name.
+pub fn save_launch_preset(dir: &DataDir, preset: LaunchPreset) -> Result<LauncherSettings, String> {
+    let preset = sanitize_launch_preset(preset)?;
+    update_settings(dir, |settings| {
+        match settings
+            .presets
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(&preset.name))
+        {
+            Some(existing) => *existing = preset,
+            None => settings.presets.push(preset),
+        }
+        Ok(())
+    })
+}
+
+pub fn remove_launch_preset(dir: &DataDir, name: &str) -> Result<LauncherSettings, String> {
+    update_settings(dir, |settings| {
+        let start_len = settings.presets.len();
+        settings.presets.retain(|p| p.name != name);
+        if settings.presets.len() == start_len {
+            return Err(format!("No preset named '{name}'."));
+        }
+        if settings.active_preset.as_deref() == Some(name) {
+            settings.active_preset = None;
+        }
+        Ok(())
+    })
+}
+
+/// Make `name` the preset launches use; `None` goes back to the top-level options.
+pub fn activate_launch_preset(
+    dir: &DataDir,
+    name: Option<&str>,
+) -> Result<LauncherSettings, String> {
+    update_settings(dir, |settings| {
+        if let Some(name) = name {
+            if !settings.presets.iter().any(|p| p.name == name) {
+                return Err(format!("No preset named '{name}'."));
+            }
+        }
+        settings.active_preset = name.map(str::to_string);
+        Ok(())
+    })
+}
+
+pub fn export_launch_presets(dir: &DataDir, path: &Path) -> Result<usize, String> {
+    let settings = load_launcher_settings(&dir.launcher_settings_file())?;
+    let export = PresetExport {
+        version: PRESET_EXPORT_FORMAT_VERSION,
+        exported_at_ms: now_ms(),
+        presets: settings.presets,
+        last_modified_by: Some(current_modification_marker()),
+    };
+    let payload = serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Could not encode presets: {e}"))?;
+    fs::write(path, payload).map_err(|e| format!("Could not write presets: {e}"))?;
+    Ok(export.presets.len())
+}
+
+/// Merge presets from an export file. Presets whose name already exists are
+/// replaced when `overwrite` is set and skipped otherwise.
+pub fn import_launch_presets(
+    dir: &DataDir,
+    path: &Path,
+    overwrite: bool,
+) -> Result<LauncherSettings, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Could not read presets: {e}"))?;
+    let export: PresetExport =
+        serde_json::from_str(&content).map_err(|e| format!("Could not parse presets: {e}"))?;
+    if export.version > PRESET_EXPORT_FORMAT_VERSION {
+        return Err("These presets were exported by a newer version of the app.".to_string());
+    }
+
+    update_settings(dir, |settings| {
+        for incoming in export.presets {
+            let incoming = sanitize_launch_preset(incoming)?;
+            match settings
+                .presets
+                .iter_mut()
+                .find(|p| p.name.eq_ignore_ascii_case(&incoming.name))
+            {
+                Some(existing) if overwrite => *existing = incoming,
+                Some(_) => {}
+                None => settings.presets.push(incoming),
+            }
+        }
+        Ok(())
+    })
+}
+
+fn update_settings(
+    dir: &DataDir,
+    change: impl FnOnce(&mut LauncherSettings) -> Result<(), String>,
+) -> Result<LauncherSettings, String> {
+    let file_path = dir.launcher_settings_file();
+    let mut settings = load_launcher_settings(&file_path)?;
+    change(&mut settings)?;
+    save_launcher_settings_to_file(&file_path, &settings)?;
+    Ok(settings)
+}
@@ -0,0 +1,70 @@
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+use crate::profiles::StoredProfile;
+
+/// Which profiles a change touched, so the frontend can patch its list
+/// instead of fetching all of it again.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilesDelta {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl ProfilesDelta {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+type Listener = Arc<dyn Fn(ProfilesDelta) + Send + Sync>;
+
+static LISTENER: Mutex<Option<Listener>> = Mutex::new(None);
+
+/// Receive every change to the saved profiles; replaces any earlier listener.
+pub fn set_profiles_listener(listener: impl Fn(ProfilesDelta) + Send + Sync + 'static) {
+    if let Ok(mut current) = LISTENER.lock() {
+        *current = Some(Arc::new(listener));
+    }
+}
+
+/// Compare two versions of the profile list by id; a profile counts as
+/// updated when any stored field differs.
+pub(crate) fn diff_profiles(before: &[StoredProfile], after: &[StoredProfile]) -> ProfilesDelta {
+    let mut delta = ProfilesDelta::default();
+    for profile in after {
+        match before.iter().find(|p| p.id == profile.id) {
+            None => delta.added.push(profile.id.clone()),
+            // compared as JSON so every stored field counts without extra derives
+            Some(old) if serde_json::to_value(old).ok() != serde_json::to_value(profile).ok() => {
+                delta.updated.push(profile.id.clone())
+            }
+            Some(_) => {}
+        }
+    }
+    delta.removed = before
+        .iter()
+        .filter(|p| !after.iter().any(|other| other.id == p.id))
+        .map(|p| p.id.clone())
+        .collect();
+    delta
+}
+
+pub(crate) fn profiles_changed(delta: ProfilesDelta) {
+    if delta.is_empty() {
+        return;
+    }
+    if let Some(listener) = LISTENER.lock().ok().and_then(|current| current.clone()) {
+        listener(delta);
+    }
+}
+
+/// For changes outside `accounts.json`, like a token being saved.
+pub(crate) fn profile_updated(profile_id: &str) {
+    profiles_changed(ProfilesDelta {
+        updated: vec![profile_id.to_string()],
+        ..ProfilesDelta::default()
+    });
+}
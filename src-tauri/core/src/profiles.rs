@@ -0,0 +1,641 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::{
+    api::{snowflake_created_at_ms, DiscordUser},
+    clock::{format_utc_date, now_ms},
+    markers::record_modification,
+    notifications::NotificationPreset,
+    operation_history::forget_operations,
+    presence::{sanitize_rich_presence, RichPresence},
+    profile_events::{diff_profiles, profiles_changed},
+    run_as::forget_windows_password,
+    sandbox::{remove_sandbox_home, SandboxTool},
+    settings::peek_launcher_settings,
+    tokens::{delete_profile_token, profile_has_token},
+    upgrades::{
+        back_up_before_upgrade, parse_profiles_document, upgrade_profiles, PROFILES_SCHEMA_VERSION,
+    },
+    vault::{open_profiles_payload, seal_profiles_payload},
+    DataDir,
+};
+
+pub const DEFAULT_AVATAR_COLOR: &str = "#4F7BFF";
+pub const MAX_NOTES_CHARS: usize = 2000;
+pub const MAX_SWITCH_REMINDER_CHARS: usize = 280;
+
+/// Built-in profile icons; the frontend ships the matching SVGs, we only store the id.
+pub const PROFILE_ICONS: &[&str] = &[
+    "gaming",
+    "work",
+    "test-tube",
+    "ghost",
+    "star",
+    "shield",
+    "code",
+    "music",
+    "heart",
+    "bot",
+];
+
+/// What gets persisted to `accounts.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredProfile {
+    pub id: String,
+    pub nickname: String,
+    #[serde(default = "default_avatar_color")]
+    pub avatar_color: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub notes: String,
+    /// Shown every time the user switches to this profile.
+    #[serde(default)]
+    pub switch_reminder: Option<String>,
+    /// Pushed to the account's servers on every switch.
+    #[serde(default)]
+    pub notification_preset: NotificationPreset,
+    /// Delete Discord's caches and session storage when switching to this profile.
+    #[serde(default)]
+    pub clear_cache_on_switch: bool,
+    /// Switching here needs the vault password or a fresh confirmation token.
+    #[serde(default)]
+    pub protected: bool,
+    /// Set on the launched client after every switch to this profile.
+    #[serde(default)]
+    pub rich_presence: Option<RichPresence>,
+    /// Left out of backups, sync files and health digests entirely.
+    #[serde(default)]
+    pub never_export: bool,
+    /// The token never goes into a backup or sync file, nor is it replaced
+    /// by one on import; the rest of the profile still travels.
+    #[serde(default)]
+    pub never_sync: bool,
+    #[serde(default)]
+    pub discord_user_id: Option<String>,
+    /// The Windows user this profile's Discord runs as; None for the current one.
+    #[serde(default)]
+    pub windows_account: Option<String>,
+    /// The sandbox this profile's Discord is started in; None for a normal launch.
+    #[serde(default)]
+    pub sandbox: Option<SandboxTool>,
+    #[serde(default)]
+    pub token_status: TokenStatus,
+    #[serde(default)]
+    pub token_checked_at_ms: Option<u128>,
+    pub created_at_ms: u128,
+}
+
+/// A stored profile plus whether a token is saved for it; what the UI sees.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub nickname: String,
+    pub avatar_color: String,
+    pub icon: Option<String>,
+    pub notes: String,
+    pub switch_reminder: Option<String>,
+    pub notification_preset: NotificationPreset,
+    pub clear_cache_on_switch: bool,
+    pub protected: bool,
+    pub rich_presence: Option<RichPresence>,
+    pub never_export: bool,
+    pub never_sync: bool,
+    pub discord_user_id: Option<String>,
+    pub windows_account: Option<String>,
+    pub sandbox: Option<SandboxTool>,
+    pub token_status: TokenStatus,
+    pub token_checked_at_ms: Option<u128>,
+    pub created_at_ms: u128,
+    pub has_token: bool,
+}
+
+impl StoredProfile {
+    pub fn into_profile(self, has_token: bool) -> Profile {
+        Profile {
+            id: self.id,
+            nickname: self.nickname,
+            avatar_color: self.avatar_color,
+            icon: self.icon,
+            notes: self.notes,
+            switch_reminder: self.switch_reminder,
+            notification_preset: self.notification_preset,
+            clear_cache_on_switch: self.clear_cache_on_switch,
+            protected: self.protected,
+            rich_presence: self.rich_presence,
+            never_export: self.never_export,
+            never_sync: self.never_sync,
+            discord_user_id: self.discord_user_id,
+            windows_account: self.windows_account,
+            sandbox: self.sandbox,
+            token_status: self.token_status,
+            token_checked_at_ms: self.token_checked_at_ms,
+            created_at_ms: self.created_at_ms,
+            has_token,
+        }
+    }
+}
+
+/// Returned instead of switching to a profile whose token is quarantined.
+pub const TOKEN_QUARANTINED: &str =
+    "Discord rejected this account's saved token, so it was quarantined. Log in again to capture a fresh one.";
+
+/// Result of the last time we asked Discord whether a saved token still works.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenStatus {
+    #[default]
+    Unknown,
+    Valid,
+    /// Discord rejected the token; switches refuse it until a new capture.
+    Quarantined,
+}
+
+// how accounts.json is laid out on disk
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfilesDocument<'a> {
+    schema_version: u32,
+    profiles: &'a [StoredProfile],
+}
+
+fn default_avatar_color() -> String {
+    DEFAULT_AVATAR_COLOR.to_string()
+}
+
+// ── Profile CRUD ──
+
+/// Every profile, sorted by nickname in the configured locale.
+pub fn list_profiles(dir: &DataDir) -> Result<Vec<Profile>, String> {
+    let mut stored = load_profiles(&dir.profiles_file())?;
+    // only the locale is needed, so a stale custom path mustn't empty the list
+    let settings = peek_launcher_settings(&dir.launcher_settings_file())?;
+    sort_profiles_by_nickname(&mut stored, settings.locale.as_deref());
+    let profiles = stored
+        .into_iter()
+        .map(|s| {
+            let has = profile_has_token(dir, &s.id);
+            s.into_profile(has)
+        })
+        .collect();
+    Ok(profiles)
+}
+
+pub fn add_profile(
+    dir: &DataDir,
+    nickname: &str,
+    avatar_color: Option<&str>,
+    icon: Option<&str>,
+) -> Result<Profile, String> {
+    let clean_nickname = normalize_nickname(nickname)?;
+    let clean_avatar_color = normalize_avatar_color(avatar_color)?;
+    let clean_icon = normalize_profile_icon(icon)?;
+
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+
+    if profiles
+        .iter()
+        .any(|p| p.nickname.eq_ignore_ascii_case(&clean_nickname))
+    {
+        return Err("An account with this nickname already exists.".to_string());
+    }
+
+    let now_ms = now_ms();
+    // an import adds several within the same millisecond
+    let mut id_ms = now_ms;
+    while profiles.iter().any(|p| p.id == format!("profile-{id_ms}")) {
+        id_ms += 1;
+    }
+    let stored = StoredProfile {
+        id: format!("profile-{id_ms}"),
+        nickname: clean_nickname,
+        avatar_color: clean_avatar_color,
+        icon: clean_icon,
+        notes: String::new(),
+        switch_reminder: None,
+        notification_preset: NotificationPreset::Unchanged,
+        clear_cache_on_switch: false,
+        protected: false,
+        rich_presence: None,
+        never_export: false,
+        never_sync: false,
+        discord_user_id: None,
+        windows_account: None,
+        sandbox: None,
+        token_status: TokenStatus::Unknown,
+        token_checked_at_ms: None,
+        created_at_ms: now_ms,
+    };
+
+    profiles.push(stored.clone());
+    save_profiles(&file_path, &profiles)?;
+
+    Ok(stored.into_profile(false))
+}
+
+/// `notes: None` keeps the existing notes so older callers don't wipe them;
+/// the same goes for `icon` and `switch_reminder`, where an empty string
+/// clears them.
+pub fn update_profile(
+    dir: &DataDir,
+    profile_id: &str,
+    nickname: &str,
+    avatar_color: &str,
+    icon: Option<&str>,
+    notes: Option<&str>,
+    switch_reminder: Option<&str>,
+) -> Result<Profile, String> {
+    let clean_nickname = normalize_nickname(nickname)?;
+    let clean_avatar_color = normalize_avatar_color(Some(avatar_color))?;
+    let clean_icon = icon
+        .map(|icon| normalize_profile_icon(Some(icon)))
+        .transpose()?;
+    let clean_notes = notes.map(normalize_notes).transpose()?;
+    let clean_reminder = switch_reminder.map(normalize_switch_reminder).transpose()?;
+
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+
+    if profiles
+        .iter()
+        .any(|p| p.id != profile_id && p.nickname.eq_ignore_ascii_case(&clean_nickname))
+    {
+        return Err("Another account already uses this nickname.".to_string());
+    }
+
+    let target = profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Account not found.".to_string())?;
+
+    target.nickname = clean_nickname;
+    target.avatar_color = clean_avatar_color;
+    if let Some(icon) = clean_icon {
+        target.icon = icon;
+    }
+    if let Some(notes) = clean_notes {
+        target.notes = notes;
+    }
+    if let Some(reminder) = clean_reminder {
+        target.switch_reminder = reminder;
+    }
+
+    let updated = target.clone();
+    save_profiles(&file_path, &profiles)?;
+
+    let has = profile_has_token(dir, &updated.id);
+    Ok(updated.into_profile(has))
+}
+
+/// Mark an important account so switching to it has to be confirmed.
+pub fn set_profile_protected(
+    dir: &DataDir,
+    profile_id: &str,
+    protected: bool,
+) -> Result<Profile, String> {
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let target = profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Account not found.".to_string())?;
+    target.protected = protected;
+
+    let updated = target.clone();
+    save_profiles(&file_path, &profiles)?;
+
+    let has = profile_has_token(dir, &updated.id);
+    Ok(updated.into_profile(has))
+}
+
+/// Whether switching to this profile wipes Discord's caches first, e.g. on for
+/// throwaway alts and off for the main account.
+pub fn set_clear_cache_on_switch(
+    dir: &DataDir,
+    profile_id: &str,
+    enabled: bool,
+) -> Result<Profile, String> {
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let target = profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Account not found.".to_string())?;
+    target.clear_cache_on_switch = enabled;
+
+    let updated = target.clone();
+    save_profiles(&file_path, &profiles)?;
+
+    let has = profile_has_token(dir, &updated.id);
+    Ok(updated.into_profile(has))
+}
+
+/// Keep this profile (`never_export`) or just its token (`never_sync`) out of
+/// everything that leaves the machine.
+pub fn set_export_policy(
+    dir: &DataDir,
+    profile_id: &str,
+    never_export: bool,
+    never_sync: bool,
+) -> Result<Profile, String> {
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let target = profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Account not found.".to_string())?;
+    target.never_export = never_export;
+    target.never_sync = never_sync;
+
+    let updated = target.clone();
+    save_profiles(&file_path, &profiles)?;
+
+    let has = profile_has_token(dir, &updated.id);
+    Ok(updated.into_profile(has))
+}
+
+/// The Rich Presence shown after switching to this profile; None turns it off.
+pub fn set_rich_presence(
+    dir: &DataDir,
+    profile_id: &str,
+    presence: Option<RichPresence>,
+) -> Result<Profile, String> {
+    let presence = presence.map(sanitize_rich_presence).transpose()?;
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let target = profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Account not found.".to_string())?;
+    target.rich_presence = presence;
+
+    let updated = target.clone();
+    save_profiles(&file_path, &profiles)?;
+
+    let has = profile_has_token(dir, &updated.id);
+    Ok(updated.into_profile(has))
+}
+
+/// Remove a profile and wipe its saved token.
+pub fn remove_profile(dir: &DataDir, profile_id: &str) -> Result<(), String> {
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+
+    let start_len = profiles.len();
+    profiles.retain(|p| p.id != profile_id);
+
+    if profiles.len() == start_len {
+        return Err("Account not found.".to_string());
+    }
+
+    save_profiles(&file_path, &profiles)?;
+
+    // Also delete the saved token file
+    let _ = delete_profile_token(dir, profile_id);
+    forget_operations(dir, profile_id);
+    forget_windows_password(profile_id);
+    remove_sandbox_home(dir, profile_id);
+
+    Ok(())
+}
+
+/// Pick the notification preset applied when switching to this profile.
+pub fn set_notification_preset(
+    dir: &DataDir,
+    profile_id: &str,
+    preset: NotificationPreset,
+) -> Result<Profile, String> {
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let target = profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Account not found.".to_string())?;
+    target.notification_preset = preset;
+
+    let updated = target.clone();
+    save_profiles(&file_path, &profiles)?;
+
+    let has = profile_has_token(dir, &updated.id);
+    Ok(updated.into_profile(has))
+}
+
+/// Look a profile up the way a human would type it (CLI, deep links, hotkeys).
+pub fn resolve_profile(dir: &DataDir, selector: &str) -> Result<Profile, String> {
+    let profiles = load_profiles(&dir.profiles_file())?;
+    let stored = resolve_profile_selector(&profiles, selector)?.clone();
+    let has = profile_has_token(dir, &stored.id);
+    Ok(stored.into_profile(has))
+}
+
+/// Exact id, then exact nickname, then a nickname prefix that only matches one profile.
+pub fn resolve_profile_selector<'a>(
+    profiles: &'a [StoredProfile],
+    selector: &str,
+) -> Result<&'a StoredProfile, String> {
+    let needle = selector.trim();
+    if needle.is_empty() {
+        return Err("Profile selector cannot be empty.".to_string());
+    }
+
+    if let Some(profile) = profiles.iter().find(|p| p.id == needle) {
+        return Ok(profile);
+    }
+    if let Some(profile) = profiles
+        .iter()
+        .find(|p| p.nickname.eq_ignore_ascii_case(needle))
+    {
+        return Ok(profile);
+    }
+
+    let lowered = needle.to_lowercase();
+    let matches: Vec<&StoredProfile> = profiles
+        .iter()
+        .filter(|p| p.nickname.to_lowercase().starts_with(&lowered))
+        .collect();
+    match matches.as_slice() {
+        [] => Err(format!("No profile matches '{needle}'.")),
+        [only] => Ok(only),
+        many => Err(format!(
+            "'{needle}' is ambiguous: {}.",
+            many.iter()
+                .map(|p| p.nickname.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Unicode collation so "Émile" sorts next to "Emma" instead of after "Zed".
+pub fn sort_profiles_by_nickname(profiles: &mut [StoredProfile], locale: Option<&str>) {
+    let locale: icu_locid::Locale = locale.and_then(|tag| tag.parse().ok()).unwrap_or_default();
+    match icu_collator::Collator::try_new(&(&locale).into(), icu_collator::CollatorOptions::new()) {
+        Ok(collator) => profiles.sort_by(|a, b| collator.compare(&a.nickname, &b.nickname)),
+        Err(e) => {
+            eprintln!("Warning: could not load collation data: {e}");
+            profiles.sort_by_key(|p| p.nickname.to_lowercase());
+        }
+    }
+}
+
+// ── Notes ──
+
+/// Structured header dropped into a profile's notes on first capture;
+/// plain text so the user can keep writing below it.
+pub fn build_capture_notes(user: Option<&DiscordUser>, captured_at_ms: u128) -> String {
+    let unknown = "unknown".to_string();
+    let username = user
+        .map(|u| match &u.global_name {
+            Some(display) => format!("{} ({display})", u.username),
+            None => u.username.clone(),
+        })
+        .unwrap_or_else(|| unknown.clone());
+    let user_id = user
+        .map(|u| u.id.clone())
+        .unwrap_or_else(|| unknown.clone());
+    let created = user
+        .and_then(|u| snowflake_created_at_ms(&u.id))
+        .map(format_utc_date)
+        .unwrap_or(unknown);
+
+    format!(
+        "Username: {username}\nUser ID: {user_id}\nAccount created: {created}\nCaptured: {}\n\n",
+        format_utc_date(captured_at_ms)
+    )
+}
+
+// ── Validation ──
+
+pub fn normalize_nickname(input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err("Nickname cannot be empty.".to_string());
+    }
+    if trimmed.chars().count() > 48 {
+        return Err("Nickname must be at most 48 characters.".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+pub fn normalize_avatar_color(input: Option<&str>) -> Result<String, String> {
+    let source = input
+        .map(|raw| raw.trim())
+        .filter(|raw| !raw.is_empty())
+        .unwrap_or(DEFAULT_AVATAR_COLOR);
+    let normalized = source.to_ascii_uppercase();
+    if !is_valid_hex_color(&normalized) {
+        return Err("Avatar color must be a valid hex color like #4F7BFF.".to_string());
+    }
+    Ok(normalized)
+}
+
+/// No icon is fine (the avatar falls back to initials), an unknown one is not.
+pub fn normalize_profile_icon(input: Option<&str>) -> Result<Option<String>, String> {
+    let Some(raw) = input.map(str::trim).filter(|raw| !raw.is_empty()) else {
+        return Ok(None);
+    };
+    let normalized = raw.to_ascii_lowercase();
+    if !PROFILE_ICONS.contains(&normalized.as_str()) {
+        return Err(format!("Unknown profile icon '{raw}'."));
+    }
+    Ok(Some(normalized))
+}
+
+pub fn normalize_notes(input: &str) -> Result<String, String> {
+    let trimmed = input.trim_end();
+    if trimmed.chars().count() > MAX_NOTES_CHARS {
+        return Err(format!(
+            "Notes must be at most {MAX_NOTES_CHARS} characters."
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Blank means "no reminder".
+pub fn normalize_switch_reminder(input: &str) -> Result<Option<String>, String> {
+    let trimmed = input.trim();
+    if trimmed.chars().count() > MAX_SWITCH_REMINDER_CHARS {
+        return Err(format!(
+            "Switch reminders must be at most {MAX_SWITCH_REMINDER_CHARS} characters."
+        ));
+    }
+    Ok((!trimmed.is_empty()).then(|| trimmed.to_string()))
+}
+
+/// Profile ids end up in file names (`tokens/{id}.token`), so one from outside,
+/// e.g. a backup, has to be a single plain path segment.
+pub fn ensure_valid_profile_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("'{id}' isn't a valid account id."));
+    }
+    Ok(())
+}
+
+fn is_valid_hex_color(value: &str) -> bool {
+    value.len() == 7
+        && value.starts_with('#')
+        && value.chars().skip(1).all(|c| c.is_ascii_hexdigit())
+}
+
+// ── Persistence ──
+
+pub fn load_profiles(file_path: &Path) -> Result<Vec<StoredProfile>, String> {
+    let (version, profiles) = read_profiles(file_path)?;
+    if version < PROFILES_SCHEMA_VERSION {
+        back_up_before_upgrade(file_path, version)?;
+        save_profiles(file_path, &profiles)?;
+    }
+    Ok(profiles)
+}
+
+// the profiles, upgraded in memory, and the schema version they were stored with
+fn read_profiles(file_path: &Path) -> Result<(u32, Vec<StoredProfile>), String> {
+    if !file_path.exists() {
+        return Ok((PROFILES_SCHEMA_VERSION, Vec::new()));
+    }
+    let content =
+        fs::read_to_string(file_path).map_err(|e| format!("Could not read account file: {e}"))?;
+    if content.trim().is_empty() {
+        return Ok((PROFILES_SCHEMA_VERSION, Vec::new()));
+    }
+    let content = open_profiles_payload(file_path, content)?;
+    let (version, mut profiles) = parse_profiles_document(&content)?;
+    upgrade_profiles(&mut profiles, version)?;
+    let profiles = serde_json::from_value(profiles)
+        .map_err(|e| format!("Could not parse account file: {e}"))?;
+    Ok((version, profiles))
+}
+
+pub fn save_profiles(file_path: &Path, profiles: &[StoredProfile]) -> Result<(), String> {
+    // an unreadable old file counts as empty: everything saved now is new
+    let before = read_profiles(file_path)
+        .map(|(_, profiles)| profiles)
+        .unwrap_or_default();
+    let document = ProfilesDocument {
+        schema_version: PROFILES_SCHEMA_VERSION,
+        profiles,
+    };
+    let payload = serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("Could not encode accounts: {e}"))?;
+    let payload = seal_profiles_payload(file_path, payload)?;
+    fs::write(file_path, payload).map_err(|e| format!("Could not save account file: {e}"))?;
+    record_modification(file_path);
+    profiles_changed(diff_profiles(&before, profiles));
+    Ok(())
+}
+
+/// Load the profile list and find one by id.
+pub fn find_profile(dir: &DataDir, profile_id: &str) -> Result<StoredProfile, String> {
+    load_profiles(&dir.profiles_file())?
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Profile not found.".to_string())
+}
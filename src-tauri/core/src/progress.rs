@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+use crate::{settings::load_launcher_settings, DataDir};
+
+/// The long-running operation a step belongs to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Operation {
+    Login,
+    Capture,
+    Switch,
+    Wipe,
+    Restore,
+    Repair,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum ProgressStep {
+    ClosingDiscord,
+    /// Discord came back (usually its updater) and is being closed again.
+    RetryingAfterRestart {
+        attempt: u32,
+    },
+    ClearingToken,
+    ReadingToken,
+    SavingToken,
+    /// Discord's storage is copied before a switch writes to it.
+    BackingUpStorage,
+    WritingToken,
+    /// The written token is read back before Discord is started.
+    VerifyingToken,
+    ClearingCache,
+    UpdatingDiscord {
+        label: String,
+    },
+    LaunchingDiscord {
+        label: String,
+    },
+    ApplyingNotificationPreset,
+}
+
+/// A structured step plus a sentence for screen readers, in the configured locale.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressEvent {
+    pub operation: Operation,
+    pub step: ProgressStep,
+    pub narration: String,
+}
+
+type Listener = Arc<dyn Fn(ProgressEvent) + Send + Sync>;
+
+static LISTENER: Mutex<Option<Listener>> = Mutex::new(None);
+
+// the step each operation got to last, for saying where a failure happened
+static LAST_STEPS: Mutex<Vec<(Operation, ProgressStep)>> = Mutex::new(Vec::new());
+
+/// Receive every step of long operations; replaces any earlier listener.
+pub fn set_progress_listener(listener: impl Fn(ProgressEvent) + Send + Sync + 'static) {
+    if let Ok(mut current) = LISTENER.lock() {
+        *current = Some(Arc::new(listener));
+    }
+}
+
+pub(crate) fn report_progress(dir: &DataDir, operation: Operation, step: ProgressStep) {
+    if let Ok(mut steps) = LAST_STEPS.lock() {
+        steps.retain(|(op, _)| *op != operation);
+        steps.push((operation, step.clone()));
+    }
+    let Some(listener) = LISTENER.lock().ok().and_then(|current| current.clone()) else {
+        return;
+    };
+    let locale = load_launcher_settings(&dir.launcher_settings_file())
+        .ok()
+        .and_then(|settings| settings.locale);
+    let narration = narrate(&step, locale.as_deref());
+    listener(ProgressEvent {
+        operation,
+        step,
+        narration,
+    });
+}
+
+pub(crate) fn begin_operation(operation: Operation) {
+    if let Ok(mut steps) = LAST_STEPS.lock() {
+        steps.retain(|(op, _)| *op != operation);
+    }
+}
+
+pub(crate) fn last_step(operation: Operation) -> Option<ProgressStep> {
+    let steps = LAST_STEPS.lock().ok()?;
+    steps
+        .iter()
+        .find(|(op, _)| *op == operation)
+        .map(|(_, step)| step.clone())
+}
+
+/// `step` as a short sentence; falls back to English for languages we don't have.
+pub fn narrate(step: &ProgressStep, locale: Option<&str>) -> String {
+    let language = locale
+        .and_then(|tag| tag.split(['-', '_']).next())
+        .unwrap_or("en")
+        .to_ascii_lowercase();
+    match language.as_str() {
+        "de" => narrate_de(step),
+        "es" => narrate_es(step),
+        "fr" => narrate_fr(step),
+        "sv" => narrate_sv(step),
+        _ => narrate_en(step),
+    }
+}
+
+fn narrate_en(step: &ProgressStep) -> String {
+    match step {
+        ProgressStep::ClosingDiscord => "Closing Discord…".to_string(),
+        ProgressStep::RetryingAfterRestart { attempt } => {
+            format!("Discord restarted. Closing it again, attempt {attempt}…")
+        }
+        ProgressStep::ClearingToken => "Signing Discord out…".to_string(),
+        ProgressStep::ReadingToken => "Reading account token…".to_string(),
+        ProgressStep::SavingToken => "Saving account token…".to_string(),
+        ProgressStep::BackingUpStorage => "Backing up Discord's storage…".to_string(),
+        ProgressStep::WritingToken => "Writing account token…".to_string(),
+        ProgressStep::VerifyingToken => "Checking the written token…".to_string(),
+        ProgressStep::ClearingCache => "Clearing Discord's cache…".to_string(),
+        ProgressStep::UpdatingDiscord { label } => format!("Updating {label}…"),
+        ProgressStep::LaunchingDiscord { label } => format!("Launching {label}."),
+        ProgressStep::ApplyingNotificationPreset => "Applying notification settings…".to_string(),
+    }
+}
+
+fn narrate_de(step: &ProgressStep) -> String {
+    match step {
+        ProgressStep::ClosingDiscord => "Discord wird geschlossen…".to_string(),
+        ProgressStep::RetryingAfterRestart { attempt } => {
+            format!("Discord wurde neu gestartet. Wird erneut geschlossen, Versuch {attempt}…")
+        }
+        ProgressStep::ClearingToken => "Discord wird abgemeldet…".to_string(),
+        ProgressStep::ReadingToken => "Konto-Token wird gelesen…".to_string(),
+        ProgressStep::SavingToken => "Konto-Token wird gespeichert…".to_string(),
+        ProgressStep::BackingUpStorage => "Discord-Speicher wird gesichert…".to_string(),
+        ProgressStep::WritingToken => "Konto-Token wird geschrieben…".to_string(),
+        ProgressStep::VerifyingToken => "Geschriebenes Token wird geprüft…".to_string(),
+        ProgressStep::ClearingCache => "Discord-Cache wird geleert…".to_string(),
+        ProgressStep::UpdatingDiscord { label } => format!("{label} wird aktualisiert…"),
+        ProgressStep::LaunchingDiscord { label } => format!("{label} wird gestartet."),
+        ProgressStep::ApplyingNotificationPreset => {
+            "Benachrichtigungseinstellungen werden angewendet…".to_string()
+        }
+    }
+}
+
+fn narrate_es(step: &ProgressStep) -> String {
+    match step {
+        ProgressStep::ClosingDiscord => "Cerrando Discord…".to_string(),
+        ProgressStep::RetryingAfterRestart { attempt } => {
+            format!("Discord se reinició. Cerrándolo de nuevo, intento {attempt}…")
+        }
+        ProgressStep::ClearingToken => "Cerrando la sesión de Discord…".to_string(),
+        ProgressStep::ReadingToken => "Leyendo el token de la cuenta…".to_string(),
+        ProgressStep::SavingToken => "Guardando el token de la cuenta…".to_string(),
+        ProgressStep::BackingUpStorage => {
+            "Haciendo una copia del almacenamiento de Discord…".to_string()
+        }
+        ProgressStep::WritingToken => "Escribiendo el token de la cuenta…".to_string(),
+        ProgressStep::VerifyingToken => "Comprobando el token escrito…".to_string(),
+        ProgressStep::ClearingCache => "Vaciando la caché de Discord…".to_string(),
+        ProgressStep::UpdatingDiscord { label } => format!("Actualizando {label}…"),
+        ProgressStep::LaunchingDiscord { label } => format!("Abriendo {label}."),
+        ProgressStep::ApplyingNotificationPreset => {
+            "Aplicando la configuración de notificaciones…".to_string()
+        }
+    }
+}
+
+fn narrate_fr(step: &ProgressStep) -> String {
+    match step {
+        ProgressStep::ClosingDiscord => "Fermeture de Discord…".to_string(),
+        ProgressStep::RetryingAfterRestart { attempt } => {
+            format!("Discord a redémarré. Nouvelle fermeture, tentative {attempt}…")
+        }
+        ProgressStep::ClearingToken => "Déconnexion de Discord…".to_string(),
+        ProgressStep::ReadingToken => "Lecture du jeton du compte…".to_string(),
+        ProgressStep::SavingToken => "Enregistrement du jeton du compte…".to_string(),
+        ProgressStep::BackingUpStorage => "Sauvegarde du stockage de Discord…".to_string(),
+        ProgressStep::WritingToken => "Écriture du jeton du compte…".to_string(),
+        ProgressStep::VerifyingToken => "Vérification du jeton écrit…".to_string(),
+        ProgressStep::ClearingCache => "Vidage du cache de Discord…".to_string(),
+        ProgressStep::UpdatingDiscord { label } => format!("Mise à jour de {label}…"),
+        ProgressStep::LaunchingDiscord { label } => format!("Lancement de {label}."),
+        ProgressStep::ApplyingNotificationPreset => {
+            "Application des réglages de notification…".to_string()
+        }
+    }
+}
+
+fn narrate_sv(step: &ProgressStep) -> String {
+    match step {
+        ProgressStep::ClosingDiscord => "Stänger Discord…".to_string(),
+        ProgressStep::RetryingAfterRestart { attempt } => {
+            format!("Discord startade om. Stänger det igen, försök {attempt}…")
+        }
+        ProgressStep::ClearingToken => "Loggar ut Discord…".to_string(),
+        ProgressStep::ReadingToken => "Läser kontots token…".to_string(),
+        ProgressStep::SavingToken => "Sparar kontots token…".to_string(),
+        ProgressStep::BackingUpStorage => "Säkerhetskopierar Discords lagring…".to_string(),
+        ProgressStep::WritingToken => "Skriver kontots token…".to_string(),
+        ProgressStep::VerifyingToken => "Kontrollerar den skrivna token…".to_string(),
+        ProgressStep::ClearingCache => "Rensar Discords cache…".to_string(),
+        ProgressStep::UpdatingDiscord { label } => format!("Uppdaterar {label}…"),
+        ProgressStep::LaunchingDiscord { label } => format!("Startar {label}."),
+        ProgressStep::ApplyingNotificationPreset => {
+            "Tillämpar aviseringsinställningar…".to_string()
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Deserialize;
+use std::{
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{crypto::random_hex, profiles::StoredProfile, vault::unlock_vault, DataDir};
+
+/// Returned when switching to a protected profile without a confirmation; the
+/// frontend matches on it to ask the user and retry.
+pub const PROFILE_PROTECTED: &str = "This account is protected. Confirm the switch to go ahead.";
+
+/// Like [`PROFILE_PROTECTED`], for switching away from the protected account
+/// that's logged in now.
+pub const LEAVING_PROTECTED: &str =
+    "The account logged in now is protected. Confirm the switch to go ahead.";
+
+// how long a switch confirmation token stays valid
+const CONFIRMATION_TTL: Duration = Duration::from_secs(60);
+
+// outstanding confirmation tokens: data folder, profile id, token, issued at
+static PENDING: Mutex<Vec<(PathBuf, String, String, Instant)>> = Mutex::new(Vec::new());
+
+/// What a switch into or out of a protected profile has to come with.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind", content = "value")]
+pub enum SwitchConfirmation {
+    /// From [`request_switch_confirmation`]; single use, valid for a minute.
+    Token(String),
+    /// The vault's master password.
+    VaultPassword(String),
+}
+
+/// A one-time token that lets a single switch to `profile_id` through.
+pub fn request_switch_confirmation(dir: &DataDir, profile_id: &str) -> Result<String, String> {
+    let token = random_hex::<16>();
+    let mut pending = PENDING
+        .lock()
+        .map_err(|_| "Switch confirmation state is poisoned.".to_string())?;
+    pending.retain(|(_, _, _, issued)| issued.elapsed() < CONFIRMATION_TTL);
+    pending.push((
+        dir.root().to_path_buf(),
+        profile_id.to_string(),
+        token.clone(),
+        Instant::now(),
+    ));
+    Ok(token)
+}
+
+/// Fail with [`PROFILE_PROTECTED`] or [`LEAVING_PROTECTED`] unless neither
+/// `profile` nor the `leaving` one it replaces is protected, or `confirmation`
+/// checks out. Tokens are used up either way.
+pub fn ensure_switch_confirmed(
+    dir: &DataDir,
+    profile: &StoredProfile,
+    leaving: Option<&StoredProfile>,
+    confirmation: Option<&SwitchConfirmation>,
+) -> Result<(), String> {
+    // switching to the account that's already logged in leaves nothing behind
+    let leaving_protected = leaving.is_some_and(|left| left.protected && left.id != profile.id);
+    if !profile.protected && !leaving_protected {
+        return Ok(());
+    }
+    match confirmation {
+        None if profile.protected => Err(PROFILE_PROTECTED.to_string()),
+        None => Err(LEAVING_PROTECTED.to_string()),
+        Some(SwitchConfirmation::VaultPassword(password)) => unlock_vault(dir, password).map(drop),
+        Some(SwitchConfirmation::Token(token)) => take_token(dir, &profile.id, token),
+    }
+}
+
+fn take_token(dir: &DataDir, profile_id: &str, token: &str) -> Result<(), String> {
+    let mut pending = PENDING
+        .lock()
+        .map_err(|_| "Switch confirmation state is poisoned.".to_string())?;
+    let position = pending.iter().position(|(root, id, expected, issued)| {
+        root == dir.root()
+            && id == profile_id
+            && expected == token
+            && issued.elapsed() < CONFIRMATION_TTL
+    });
+    match position {
+        Some(index) => {
+            pending.remove(index);
+            Ok(())
+        }
+        None => Err("The switch confirmation expired. Confirm again.".to_string()),
+    }
+}
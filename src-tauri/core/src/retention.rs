@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::{
+    audit::{AuditAction, AuditEntry},
+    clock::now_ms,
+    settings::load_launcher_settings,
+    DataDir,
+};
+
+pub const MAX_RETENTION_DAYS: u32 = 10 * 365;
+
+const DAY_MS: u128 = 24 * 60 * 60 * 1000;
+
+/// How long history is kept; None keeps it forever.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionSettings {
+    /// Switch entries in the activity log, the bulk of it for most people.
+    #[serde(default)]
+    pub switch_history_days: Option<u32>,
+    /// Everything else in the activity log: captures, exports, reveals, wipes.
+    #[serde(default)]
+    pub audit_log_days: Option<u32>,
+}
+
+pub fn sanitize_retention(retention: RetentionSettings) -> Result<RetentionSettings, String> {
+    for days in [retention.switch_history_days, retention.audit_log_days]
+        .into_iter()
+        .flatten()
+    {
+        if days == 0 || days > MAX_RETENTION_DAYS {
+            return Err(format!(
+                "History can be kept between 1 and {MAX_RETENTION_DAYS} days."
+            ));
+        }
+    }
+    Ok(retention)
+}
+
+/// Drop activity log entries older than the configured limits. Returns how
+/// many were removed; lines too damaged to read go with them.
+pub fn prune_history(dir: &DataDir) -> Result<usize, String> {
+    let retention = load_launcher_settings(&dir.launcher_settings_file())?.retention;
+    if retention == RetentionSettings::default() {
+        return Ok(0);
+    }
+
+    let file_path = dir.audit_log_file();
+    if !file_path.exists() {
+        return Ok(0);
+    }
+    let content =
+        fs::read_to_string(&file_path).map_err(|e| format!("Could not read audit log: {e}"))?;
+
+    let now = now_ms();
+    let cutoff = |days: Option<u32>| days.map(|days| now.saturating_sub(u128::from(days) * DAY_MS));
+    let switch_cutoff = cutoff(retention.switch_history_days);
+    let audit_cutoff = cutoff(retention.audit_log_days);
+
+    let mut kept = String::new();
+    let mut removed = 0;
+    for line in content.lines() {
+        let keep = serde_json::from_str::<AuditEntry>(line).is_ok_and(|entry| {
+            let limit = match entry.action {
+                AuditAction::Switch => switch_cutoff,
+                _ => audit_cutoff,
+            };
+            limit.is_none_or(|limit| entry.at_ms >= limit)
+        });
+        if keep {
+            kept.push_str(line);
+            kept.push('\n');
+        } else {
+            removed += 1;
+        }
+    }
+    if removed == 0 {
+        return Ok(0);
+    }
+
+    // write aside and swap, so a crash mid-write can't truncate the log
+    let staged = file_path.with_extension("jsonl.tmp");
+    fs::write(&staged, kept).map_err(|e| format!("Could not prune audit log: {e}"))?;
+    fs::rename(&staged, &file_path).map_err(|e| format!("Could not prune audit log: {e}"))?;
+    Ok(removed)
+}
@@ -0,0 +1,205 @@
+use zeroize::Zeroizing;
+
+use crate::{
+    launch::DiscordInstallation,
+    profiles::{load_profiles, save_profiles, Profile},
+    tokens::profile_has_token,
+    DataDir,
+};
+
+// Credential Manager entries holding each profile's Windows password, by profile id
+const RUN_AS_SERVICE: &str = "alt-mngr run-as";
+
+/// Have this profile's Discord started as another Windows user, e.g.
+/// `alt1`, `PC\alt1` or `alt1@example.com`, or as the current user again with
+/// None. Every Windows user has their own Discord storage, so the account
+/// stays logged in there and switching to it leaves this user's Discord
+/// alone. The password goes to Credential Manager, never to accounts.json.
+pub fn set_windows_account(
+    dir: &DataDir,
+    profile_id: &str,
+    account: Option<&str>,
+    password: Option<&str>,
+) -> Result<Profile, String> {
+    let account = account.map(str::trim).filter(|a| !a.is_empty());
+    if account.is_some() && !cfg!(target_os = "windows") {
+        return Err("Starting Discord as another user is only possible on Windows.".to_string());
+    }
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let target = profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Account not found.".to_string())?;
+
+    match account {
+        Some(account) => {
+            if target.sandbox.is_some() {
+                return Err(
+                    "This account is started in a sandbox. Turn that off first.".to_string()
+                );
+            }
+            let password = password
+                .filter(|p| !p.is_empty())
+                .ok_or_else(|| format!("Enter the Windows password of {account}."))?;
+            credential(profile_id)?
+                .set_password(password)
+                .map_err(|e| format!("Could not save the Windows password: {e}"))?;
+            target.windows_account = Some(account.to_string());
+        }
+        None => {
+            forget_windows_password(profile_id);
+            target.windows_account = None;
+        }
+    }
+
+    let updated = target.clone();
+    save_profiles(&file_path, &profiles)?;
+    let has = profile_has_token(dir, &updated.id);
+    Ok(updated.into_profile(has))
+}
+
+/// Drop a profile's saved Windows password, e.g. when the profile is removed.
+pub(crate) fn forget_windows_password(profile_id: &str) {
+    if let Ok(entry) = credential(profile_id) {
+        let _ = entry.delete_credential();
+    }
+}
+
+/// Start `installation` as `account`, with the password saved for this profile.
+pub(crate) fn launch_as_windows_user(
+    profile_id: &str,
+    account: &str,
+    installation: &DiscordInstallation,
+) -> Result<(), String> {
+    let password = match credential(profile_id)?.get_password() {
+        Ok(password) => Zeroizing::new(password),
+        Err(keyring::Error::NoEntry) => {
+            return Err(format!(
+                "No Windows password is saved for {account}. Set the account again."
+            ))
+        }
+        Err(e) => return Err(format!("Could not read the Windows password: {e}")),
+    };
+    if installation.app_user_model_id.is_some() || installation.launch_uri.is_some() {
+        return Err(format!(
+            "{} can't be started as another user. Install Discord from discord.com, for all users or for {account}, and pick it in the launch settings.",
+            installation.label
+        ));
+    }
+
+    #[cfg(target_os = "windows")]
+    return windows::spawn(account, &password, installation);
+
+    #[allow(unreachable_code)]
+    {
+        let _ = password;
+        Err("Starting Discord as another user is only possible on Windows.".to_string())
+    }
+}
+
+fn credential(profile_id: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(RUN_AS_SERVICE, profile_id)
+        .map_err(|e| format!("Could not open Credential Manager: {e}"))
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::{io, mem, path::Path, ptr};
+    use windows_sys::Win32::{
+        Foundation::CloseHandle,
+        System::Threading::{
+            CreateProcessWithLogonW, LOGON_WITH_PROFILE, PROCESS_INFORMATION, STARTUPINFOW,
+        },
+    };
+    use zeroize::Zeroize;
+
+    use crate::launch::{squirrel_updater, DiscordInstallation};
+
+    // ERROR_ACCESS_DENIED, ERROR_LOGON_FAILURE, ERROR_ACCOUNT_RESTRICTION,
+    // ERROR_LOGON_TYPE_NOT_GRANTED
+    const ACCESS_DENIED: i32 = 5;
+    const LOGON_FAILURE: i32 = 1326;
+    const ACCOUNT_RESTRICTION: i32 = 1327;
+    const LOGON_TYPE_NOT_GRANTED: i32 = 1385;
+
+    pub fn spawn(
+        account: &str,
+        password: &str,
+        installation: &DiscordInstallation,
+    ) -> Result<(), String> {
+        let executable = Path::new(&installation.executable_path);
+        // Update.exe starts the newest version, as it would for that user
+        let (program, args) = match squirrel_updater(executable) {
+            Some((updater, name)) => (updater, format!(" --processStart \"{name}\"")),
+            None => (executable.to_path_buf(), String::new()),
+        };
+        let mut command_line = wide(&format!("\"{}\"{args}", program.display()));
+        let working_dir = program.parent().map(|dir| wide(&dir.to_string_lossy()));
+
+        // DOMAIN\user, or a local user; a UPN carries its own domain
+        let (domain, user) = match account.split_once('\\') {
+            Some((domain, user)) => (Some(domain), user),
+            None if account.contains('@') => (None, account),
+            None => (Some("."), account),
+        };
+        let user = wide(user);
+        let domain = domain.map(wide);
+        let mut password = wide(password);
+
+        // SAFETY: all-zero is a valid STARTUPINFOW/PROCESS_INFORMATION; cb is set below
+        let mut startup: STARTUPINFOW = unsafe { mem::zeroed() };
+        startup.cb = mem::size_of::<STARTUPINFOW>() as u32;
+        let mut process: PROCESS_INFORMATION = unsafe { mem::zeroed() };
+        // SAFETY: every string is NUL-terminated and outlives the call, and
+        // the command line is a writable buffer as the API requires
+        let ok = unsafe {
+            CreateProcessWithLogonW(
+                user.as_ptr(),
+                domain.as_ref().map_or(ptr::null(), |d| d.as_ptr()),
+                password.as_ptr(),
+                LOGON_WITH_PROFILE,
+                ptr::null(),
+                command_line.as_mut_ptr(),
+                0,
+                ptr::null(),
+                working_dir.as_ref().map_or(ptr::null(), |d| d.as_ptr()),
+                &startup,
+                &mut process,
+            )
+        };
+        let error = io::Error::last_os_error();
+        password.zeroize();
+        if ok == 0 {
+            return Err(explain(&error, account, &program.to_string_lossy()));
+        }
+        // SAFETY: both handles were just returned to us and aren't used again
+        unsafe {
+            CloseHandle(process.hProcess);
+            CloseHandle(process.hThread);
+        }
+        Ok(())
+    }
+
+    fn explain(error: &io::Error, account: &str, program: &str) -> String {
+        match error.raw_os_error() {
+            Some(LOGON_FAILURE) => {
+                format!("Windows rejected the password for {account}. Set the account again.")
+            }
+            Some(ACCOUNT_RESTRICTION) => format!(
+                "{account} has no password or is restricted, and Windows won't start programs for it."
+            ),
+            Some(LOGON_TYPE_NOT_GRANTED) => {
+                format!("{account} isn't allowed to sign in on this computer.")
+            }
+            Some(ACCESS_DENIED) => format!(
+                "{account} can't open {program}. Discord installs for one user by default; install it for {account} or for all users, and pick that install in the launch settings."
+            ),
+            _ => format!("Could not start Discord as {account}: {error}"),
+        }
+    }
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain([0]).collect()
+    }
+}
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    process::{Command, Stdio},
+};
+
+use crate::{
+    launch::DiscordInstallation,
+    managed_env::explain_launch_error,
+    profiles::{load_profiles, save_profiles, Profile},
+    tokens::profile_has_token,
+    DataDir,
+};
+
+/// A sandbox a profile's Discord can be started in. Each profile gets its own
+/// box or home folder, so its Discord keeps a separate login and runs next to
+/// the normal one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SandboxTool {
+    /// Sandboxie-Plus, on Windows.
+    Sandboxie,
+    /// firejail with a private home folder, on Linux.
+    Firejail,
+    /// bubblewrap with a private home folder bound over the real one, on Linux.
+    Bubblewrap,
+}
+
+impl SandboxTool {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            SandboxTool::Sandboxie => "Sandboxie-Plus",
+            SandboxTool::Firejail => "firejail",
+            SandboxTool::Bubblewrap => "bubblewrap",
+        }
+    }
+}
+
+/// The sandbox tools installed on this computer.
+pub fn available_sandbox_tools() -> Vec<SandboxTool> {
+    #[cfg(target_os = "windows")]
+    return windows::sandboxie_dir()
+        .map(|_| vec![SandboxTool::Sandboxie])
+        .unwrap_or_default();
+
+    #[cfg(target_os = "linux")]
+    return [
+        (SandboxTool::Firejail, "firejail"),
+        (SandboxTool::Bubblewrap, "bwrap"),
+    ]
+    .into_iter()
+    .filter(|(_, program)| {
+        Command::new(program)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+    .map(|(tool, _)| tool)
+    .collect();
+
+    #[allow(unreachable_code)]
+    Vec::new()
+}
+
+/// Have this profile's Discord started in `tool`'s sandbox, or normally again
+/// with None. The sandbox keeps its own Discord storage, so the account stays
+/// logged in there and switching to it leaves the normal Discord alone.
+pub fn set_profile_sandbox(
+    dir: &DataDir,
+    profile_id: &str,
+    tool: Option<SandboxTool>,
+) -> Result<Profile, String> {
+    if let Some(tool) = tool {
+        if !available_sandbox_tools().contains(&tool) {
+            return Err(format!(
+                "{} isn't installed on this computer.",
+                tool.label()
+            ));
+        }
+    }
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let target = profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Account not found.".to_string())?;
+    if let (Some(_), Some(account)) = (tool, &target.windows_account) {
+        return Err(format!(
+            "This account already runs as {account}. Switch it back to your own Windows user first."
+        ));
+    }
+    target.sandbox = tool;
+
+    let updated = target.clone();
+    save_profiles(&file_path, &profiles)?;
+    let has = profile_has_token(dir, &updated.id);
+    Ok(updated.into_profile(has))
+}
+
+/// Start `installation` in this profile's sandbox, creating it on first use.
+pub(crate) fn launch_sandboxed(
+    dir: &DataDir,
+    profile_id: &str,
+    tool: SandboxTool,
+    installation: &DiscordInstallation,
+) -> Result<(), String> {
+    if installation.app_user_model_id.is_some() || installation.launch_uri.is_some() {
+        return Err(format!(
+            "{} can't be started in a sandbox. Install Discord from discord.com and pick it in the launch settings.",
+            installation.label
+        ));
+    }
+    if !available_sandbox_tools().contains(&tool) {
+        return Err(format!(
+            "{} isn't installed on this computer anymore.",
+            tool.label()
+        ));
+    }
+    let name = sandbox_name(profile_id);
+
+    let mut command = match tool {
+        #[cfg(target_os = "windows")]
+        SandboxTool::Sandboxie => windows::start_command(&name, installation)?,
+        SandboxTool::Firejail => {
+            let mut command = Command::new("firejail");
+            command
+                .arg(format!("--name={name}"))
+                .arg(format!(
+                    "--private={}",
+                    dir.sandbox_home(profile_id)?.display()
+                ))
+                .arg(&installation.executable_path);
+            command
+        }
+        SandboxTool::Bubblewrap => {
+            let home = dir.sandbox_home(profile_id)?;
+            let real_home =
+                env::var("HOME").map_err(|_| "Could not find your home folder.".to_string())?;
+            // sources are resolved outside the sandbox, so the X11 cookie can
+            // still be bound into the private home
+            let xauthority = format!("{real_home}/.Xauthority");
+            let mut command = Command::new("bwrap");
+            command
+                .args(["--dev-bind", "/", "/", "--bind"])
+                .arg(&home)
+                .arg(&real_home)
+                .args(["--ro-bind-try", &xauthority, &xauthority, "--"])
+                .arg(&installation.executable_path);
+            command
+        }
+        #[allow(unreachable_patterns)]
+        _ => return Err(format!("{} isn't available on this OS.", tool.label())),
+    };
+    command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| explain_launch_error(&e, tool.label()))?;
+    Ok(())
+}
+
+// Sandboxie box names allow letters, digits and underscores, up to 32 characters
+fn sandbox_name(profile_id: &str) -> String {
+    let id: String = profile_id
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect();
+    let mut name = format!("altmng_{id}");
+    name.truncate(32);
+    name
+}
+
+/// Drop the private home folder firejail and bubblewrap used for a profile,
+/// e.g. when the profile is removed.
+pub(crate) fn remove_sandbox_home(dir: &DataDir, profile_id: &str) {
+    if let Ok(home) = dir.sandbox_home(profile_id) {
+        let _ = fs::remove_dir_all(home);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::{
+        env,
+        path::{Path, PathBuf},
+        process::{Command, Stdio},
+    };
+
+    use crate::launch::{squirrel_updater, DiscordInstallation};
+
+    pub fn sandboxie_dir() -> Option<PathBuf> {
+        ["ProgramW6432", "ProgramFiles"]
+            .into_iter()
+            .filter_map(|var| env::var(var).ok())
+            .flat_map(|root| {
+                ["Sandboxie-Plus", "Sandboxie"].map(|folder| Path::new(&root).join(folder))
+            })
+            .find(|dir| dir.join("Start.exe").exists())
+    }
+
+    // boxes live in Sandboxie.ini; SbieIni adds one through the service,
+    // and setting Enabled again on an existing box changes nothing
+    pub fn start_command(
+        name: &str,
+        installation: &DiscordInstallation,
+    ) -> Result<Command, String> {
+        let sandboxie = sandboxie_dir()
+            .ok_or_else(|| "Sandboxie-Plus isn't installed on this computer.".to_string())?;
+        let created = Command::new(sandboxie.join("SbieIni.exe"))
+            .args(["set", name, "Enabled", "y"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|e| format!("Could not create the Sandboxie box {name}: {e}"))?;
+        if !created.success() {
+            return Err(format!("Could not create the Sandboxie box {name}."));
+        }
+
+        let mut command = Command::new(sandboxie.join("Start.exe"));
+        command.arg(format!("/box:{name}"));
+        let executable = Path::new(&installation.executable_path);
+        match squirrel_updater(executable) {
+            Some((updater, exe_name)) => {
+                command.arg(updater).args(["--processStart", &exe_name]);
+            }
+            None => {
+                command.arg(executable);
+            }
+        }
+        Ok(command)
+    }
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+use zeroize::Zeroizing;
+
+/// A Discord token held in memory. The buffer is wiped when it's dropped, and
+/// it can't be printed or serialized, so it never ends up in a command result
+/// or a log line by accident. Use [`SecretToken::expose`] where the raw text
+/// is really needed.
+#[derive(Clone)]
+pub struct SecretToken(Zeroizing<String>);
+
+impl SecretToken {
+    pub fn new(token: String) -> Self {
+        Self(Zeroizing::new(token))
+    }
+
+    /// The raw token. Keep the borrow short and don't copy it into a plain `String`.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretToken {
+    fn from(token: String) -> Self {
+        Self::new(token)
+    }
+}
+
+impl fmt::Debug for SecretToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretToken(<redacted>)")
+    }
+}
@@ -0,0 +1,230 @@
+use serde::Serialize;
+
+use crate::{
+    api::{check_discord_token, DiscordUser, TokenCheck},
+    app_lock::ensure_app_unlocked,
+    audit::{record_audit, AuditAction},
+    clock::now_ms,
+    profiles::{
+        add_profile, build_capture_notes, load_profiles, save_profiles, Profile, TokenStatus,
+    },
+    secret::SecretToken,
+    settings::DiscordChannel,
+    storage::{
+        discord_storage_dirs_by_channel, read_all_discord_tokens, resolve_storage_channel,
+        token_keys, token_user_id,
+    },
+    tokens::{
+        channel_token_id, profile_token_ids, save_channel_token, save_profile_token, token_store,
+    },
+    vault::ensure_unlocked,
+    DataDir,
+};
+
+/// What [`import_existing_sessions`] found and did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionImport {
+    /// Profiles created, one per account that didn't have one yet.
+    pub imported: Vec<Profile>,
+    /// Accounts that were logged in but already had a profile.
+    pub already_saved: usize,
+    /// Clients that couldn't be read and tokens Discord turned down, as messages.
+    pub skipped: Vec<String>,
+}
+
+/// Create a profile for every account logged in to any installed Discord
+/// client, the ones in Discord's own account switcher included. Reads private
+/// copies of the storage, so Discord can keep running. Names come from
+/// Discord; offline, or when it can't be reached, from the user id. Accounts
+/// that already have a profile and tokens Discord rejects are left out.
+pub fn import_existing_sessions(dir: &DataDir) -> Result<SessionImport, String> {
+    let result = import_sessions(dir);
+    let detail = result
+        .as_ref()
+        .ok()
+        .map(|import| format!("{} imported", import.imported.len()));
+    record_audit(dir, AuditAction::Capture, None, detail.as_deref(), &result);
+    result
+}
+
+fn import_sessions(dir: &DataDir) -> Result<SessionImport, String> {
+    ensure_app_unlocked(dir)?;
+    ensure_unlocked(dir)?;
+    let keys = token_keys(dir)?;
+
+    let mut skipped = Vec::new();
+    let mut tokens: Vec<SecretToken> = Vec::new();
+    for (_, storage_dir) in discord_storage_dirs_by_channel()? {
+        match read_all_discord_tokens(dir, &storage_dir, &keys) {
+            Ok(found) => tokens.extend(found),
+            Err(e) => skipped.push(format!("{}: {e}", storage_dir.display())),
+        }
+    }
+
+    let mut seen: Vec<String> = Vec::new();
+    let mut already_saved = 0;
+    let mut imported = Vec::new();
+    for token in tokens {
+        let (user, user_id) = match check_discord_token(token.expose()) {
+            TokenCheck::Valid(user) => {
+                let id = user.id.clone();
+                (Some(user), Some(id))
+            }
+            TokenCheck::Invalid => {
+                let who = token_user_id(token.expose()).unwrap_or_else(|| "unknown".to_string());
+                skipped.push(format!(
+                    "Discord rejected the token of user {who}; it's logged out."
+                ));
+                continue;
+            }
+            TokenCheck::Unreachable(_) => (None, token_user_id(token.expose())),
+        };
+        // the same account is often logged in to several clients
+        let seen_as = user_id
+            .clone()
+            .unwrap_or_else(|| token.expose().to_string());
+        if seen.contains(&seen_as) {
+            continue;
+        }
+        seen.push(seen_as);
+
+        let profiles = load_profiles(&dir.profiles_file())?;
+        if user_id.is_some() && profiles.iter().any(|p| p.discord_user_id == user_id) {
+            already_saved += 1;
+            continue;
+        }
+        let nickname = unique_nickname(
+            &profiles
+                .iter()
+                .map(|p| p.nickname.clone())
+                .collect::<Vec<_>>(),
+            &session_nickname(user.as_ref(), user_id.as_deref()),
+        );
+        imported.push(create_imported_profile(
+            dir,
+            &nickname,
+            token.expose(),
+            user.as_ref(),
+            user_id,
+        )?);
+    }
+
+    Ok(SessionImport {
+        imported,
+        already_saved,
+        skipped,
+    })
+}
+
+/// What a session that's about to be logged out is kept as when it has no
+/// profile yet.
+const RECOVERED_NICKNAME: &str = "Recovered session";
+
+/// Keep the session `channel` is logged in to before it's cleared: its token
+/// goes to the same account's profile as that channel's token, or to a new
+/// "Recovered session" profile when there is none. Returns the nickname it
+/// went to; None when it was saved already or Discord has rejected it anyway.
+pub(crate) fn preserve_session(
+    dir: &DataDir,
+    token: &SecretToken,
+    channel: DiscordChannel,
+) -> Result<Option<String>, String> {
+    let (user, user_id) = match check_discord_token(token.expose()) {
+        TokenCheck::Valid(user) => {
+            let id = user.id.clone();
+            (Some(user), Some(id))
+        }
+        // a logged-out session has nothing left to lose
+        TokenCheck::Invalid => return Ok(None),
+        TokenCheck::Unreachable(_) => (None, token_user_id(token.expose())),
+    };
+
+    let profiles = load_profiles(&dir.profiles_file())?;
+    let store = token_store(dir)?;
+    // the main token or any channel's
+    let is_saved = |profile_id: &str| {
+        profile_token_ids(profile_id).iter().any(|token_id| {
+            store
+                .load(token_id)
+                .is_ok_and(|saved| saved.is_some_and(|saved| saved.expose() == token.expose()))
+        })
+    };
+    if profiles.iter().any(|p| is_saved(&p.id)) {
+        return Ok(None);
+    }
+    // the live session is newer than whatever that profile had for the channel
+    if let Some(existing) = profiles
+        .iter()
+        .find(|p| user_id.is_some() && p.discord_user_id == user_id)
+    {
+        match resolve_storage_channel(channel)
+            .filter(|channel| channel_token_id(&existing.id, *channel).is_some())
+        {
+            Some(channel) => save_channel_token(dir, &existing.id, channel, token.expose())?,
+            None => save_profile_token(dir, &existing.id, token.expose())?,
+        }
+        return Ok(Some(existing.nickname.clone()));
+    }
+    let nickname = unique_nickname(
+        &profiles
+            .iter()
+            .map(|p| p.nickname.clone())
+            .collect::<Vec<_>>(),
+        RECOVERED_NICKNAME,
+    );
+    create_imported_profile(dir, &nickname, token.expose(), user.as_ref(), user_id)?;
+    Ok(Some(nickname))
+}
+
+fn create_imported_profile(
+    dir: &DataDir,
+    nickname: &str,
+    token: &str,
+    user: Option<&DiscordUser>,
+    user_id: Option<String>,
+) -> Result<Profile, String> {
+    let profile = add_profile(dir, nickname, None, None)?;
+    save_profile_token(dir, &profile.id, token)?;
+
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let stored = profiles
+        .iter_mut()
+        .find(|p| p.id == profile.id)
+        .ok_or_else(|| "Profile not found.".to_string())?;
+    stored.discord_user_id = user_id;
+    stored.notes = build_capture_notes(user, now_ms());
+    if user.is_some() {
+        stored.token_status = TokenStatus::Valid;
+        stored.token_checked_at_ms = Some(now_ms());
+    }
+    let updated = stored.clone();
+    save_profiles(&file_path, &profiles)?;
+    Ok(updated.into_profile(true))
+}
+
+fn session_nickname(user: Option<&DiscordUser>, user_id: Option<&str>) -> String {
+    match (user, user_id) {
+        (Some(user), _) => user
+            .global_name
+            .clone()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| user.username.clone()),
+        (None, Some(id)) => format!("User {id}"),
+        (None, None) => "Imported account".to_string(),
+    }
+}
+
+// "name", then "name (2)", "name (3)", ... as profile nicknames must differ
+fn unique_nickname(taken: &[String], wanted: &str) -> String {
+    let wanted: String = wanted.trim().chars().take(40).collect();
+    let is_taken = |name: &str| taken.iter().any(|t| t.eq_ignore_ascii_case(name));
+    if !is_taken(&wanted) {
+        return wanted;
+    }
+    (2..)
+        .map(|n| format!("{wanted} ({n})"))
+        .find(|name| !is_taken(name))
+        .unwrap_or(wanted)
+}
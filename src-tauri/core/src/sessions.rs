@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::{
+    api::{check_discord_token, TokenCheck},
+    clock::now_ms,
+    launch::{channel_pids, terminate_channel, CHANNEL_PROCESSES},
+    profiles::{find_profile, load_profiles},
+    settings::{launch_channel, DiscordChannel},
+    storage::{
+        read_discord_token_from_snapshot, resolve_storage_channel, token_keys, token_user_id,
+        NO_TOKEN_FOUND,
+    },
+    tokens::load_profile_token,
+    DataDir,
+};
+
+/// The profile a switch last launched into a channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionRecord {
+    channel: DiscordChannel,
+    profile_id: String,
+    launched_at_ms: u128,
+}
+
+/// A Discord channel that is running right now and who it's logged in as.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningSession {
+    pub channel: DiscordChannel,
+    pub pids: Vec<u32>,
+    /// None when Discord was started outside the app, or the profile is gone.
+    pub profile_id: Option<String>,
+    pub nickname: Option<String>,
+    pub launched_at_ms: Option<u128>,
+}
+
+/// Who a Discord client's storage says is logged in right now.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentSession {
+    /// None when no installed client was found for Auto.
+    pub channel: Option<DiscordChannel>,
+    pub logged_in: bool,
+    pub user_id: Option<String>,
+    /// None offline or when Discord can't be reached.
+    pub username: Option<String>,
+    /// Discord turned the token down; the client will ask to log in again.
+    pub token_rejected: bool,
+    /// The saved profile for this account, if there is one.
+    pub profile_id: Option<String>,
+    pub nickname: Option<String>,
+}
+
+/// Read who is logged in to `channel` (the one launches use when None) from a
+/// copy of its storage, so nothing is changed and Discord can keep running,
+/// and match it to a saved profile by user id, or by the token itself when
+/// the user can't be looked up.
+pub fn get_current_session(
+    dir: &DataDir,
+    channel: Option<DiscordChannel>,
+) -> Result<CurrentSession, String> {
+    let read_from = match channel {
+        Some(channel) => channel,
+        None => launch_channel(dir)?,
+    };
+    // Auto is reported as the channel it turned out to be
+    let channel = resolve_storage_channel(read_from);
+    let mut session = CurrentSession {
+        channel,
+        logged_in: false,
+        user_id: None,
+        username: None,
+        token_rejected: false,
+        profile_id: None,
+        nickname: None,
+    };
+    let token = match read_discord_token_from_snapshot(dir, &token_keys(dir)?, None, read_from) {
+        Ok(token) => token,
+        Err(e) if e == NO_TOKEN_FOUND => return Ok(session),
+        Err(e) => return Err(e),
+    };
+    session.logged_in = true;
+    match check_discord_token(token.expose()) {
+        TokenCheck::Valid(user) => {
+            session.user_id = Some(user.id);
+            session.username = Some(user.username);
+        }
+        TokenCheck::Invalid => {
+            session.token_rejected = true;
+            session.user_id = token_user_id(token.expose());
+        }
+        TokenCheck::Unreachable(_) => session.user_id = token_user_id(token.expose()),
+    }
+
+    let profiles = load_profiles(&dir.profiles_file())?;
+    let matched = profiles
+        .iter()
+        .find(|p| session.user_id.is_some() && p.discord_user_id == session.user_id)
+        .or_else(|| {
+            profiles.iter().find(|p| {
+                load_profile_token(dir, &p.id).is_ok_and(|saved| saved.expose() == token.expose())
+            })
+        });
+    if let Some(profile) = matched {
+        session.profile_id = Some(profile.id.clone());
+        session.nickname = Some(profile.nickname.clone());
+    }
+    Ok(session)
+}
+
+/// Remember that `profile_id` was just launched in `channel`.
+pub fn record_session(dir: &DataDir, channel: DiscordChannel, profile_id: &str) {
+    let mut records = load_session_records(dir);
+    records.retain(|record| record.channel != channel);
+    records.push(SessionRecord {
+        channel,
+        profile_id: profile_id.to_string(),
+        launched_at_ms: now_ms(),
+    });
+    if let Err(e) = save_session_records(dir, &records) {
+        eprintln!("Warning: {e}");
+    }
+}
+
+/// The profile last launched in `channel`, i.e. the one a switch there replaces.
+pub(crate) fn session_profile_id(dir: &DataDir, channel: DiscordChannel) -> Option<String> {
+    load_session_records(dir)
+        .into_iter()
+        .find(|record| record.channel == channel)
+        .map(|record| record.profile_id)
+}
+
+/// Every running channel with its process ids and the profile it was launched with.
+/// Registered custom clients show up together as one `Custom` session.
+pub fn list_running_sessions(dir: &DataDir) -> Vec<RunningSession> {
+    let records = load_session_records(dir);
+    CHANNEL_PROCESSES
+        .iter()
+        .map(|(channel, _)| *channel)
+        .chain([DiscordChannel::Custom])
+        .filter_map(|channel| {
+            let pids = channel_pids(channel);
+            if pids.is_empty() {
+                return None;
+            }
+            let record = records.iter().find(|record| record.channel == channel);
+            let profile = record.and_then(|record| find_profile(dir, &record.profile_id).ok());
+            Some(RunningSession {
+                channel,
+                pids,
+                profile_id: profile.as_ref().map(|profile| profile.id.clone()),
+                nickname: profile.map(|profile| profile.nickname),
+                launched_at_ms: record.map(|record| record.launched_at_ms),
+            })
+        })
+        .collect()
+}
+
+/// Close one channel and leave the others running.
+pub fn terminate_session(dir: &DataDir, channel: DiscordChannel) -> Result<(), String> {
+    if channel == DiscordChannel::Auto {
+        return Err("Pick a channel to close.".to_string());
+    }
+    terminate_channel(channel)?;
+    let mut records = load_session_records(dir);
+    records.retain(|record| record.channel != channel);
+    save_session_records(dir, &records)
+}
+
+fn load_session_records(dir: &DataDir) -> Vec<SessionRecord> {
+    fs::read_to_string(dir.sessions_file())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_session_records(dir: &DataDir, records: &[SessionRecord]) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(records)
+        .map_err(|e| format!("Could not encode sessions: {e}"))?;
+    fs::write(dir.sessions_file(), payload).map_err(|e| format!("Could not save sessions: {e}"))
+}
@@ -0,0 +1,494 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    biometric::{confirm_gate_change, BiometricGate},
+    capabilities::ensure_token_backend_supported,
+    custom_clients::{sanitize_custom_client, set_custom_clients, CustomClient},
+    http::set_offline_mode,
+    launch::{channel_from_executable, set_install_search_roots, set_launch_through_updater},
+    markers::record_modification,
+    mod_bridge::set_mod_bridge_enabled,
+    path_repair::{paths_repaired, repair_custom_paths},
+    retention::{sanitize_retention, RetentionSettings},
+    tokens::migrate_tokens,
+    DataDir,
+};
+
+pub const MAX_PRESET_NAME_CHARS: usize = 40;
+pub const MAX_AUTO_LOCK_MINUTES: u32 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DiscordChannel {
+    #[default]
+    Auto,
+    Stable,
+    Ptb,
+    Canary,
+    /// The internal build some testers run.
+    Development,
+    /// Third-party clients around Discord's web app; see [`DiscordChannel::is_third_party`].
+    Vesktop,
+    WebCord,
+    ArmCord,
+    /// A client from the user's own registry; see [`LauncherSettings::custom_clients`].
+    Custom,
+}
+
+impl DiscordChannel {
+    /// Clients that run Discord's web app in their own shell. They keep the
+    /// token in plain localStorage, never sealed with os_crypt like the
+    /// official client does.
+    pub fn is_third_party(self) -> bool {
+        matches!(self, Self::Vesktop | Self::WebCord | Self::ArmCord)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherSettings {
+    #[serde(default)]
+    pub preferred_channel: DiscordChannel,
+    pub custom_executable_path: Option<String>,
+    #[serde(default)]
+    pub health_digest: HealthDigestSettings,
+    #[serde(default)]
+    pub token_backend: TokenBackend,
+    #[serde(default)]
+    pub write_strategy: WriteStrategy,
+    #[serde(default)]
+    pub capture_mode: CaptureMode,
+    /// BCP-47 tag used for sorting, e.g. "sv-SE"; None means root collation.
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Argon2 PHC string for the optional app password; only `app_lock` changes it.
+    #[serde(default)]
+    pub app_password_hash: Option<String>,
+    /// Commands that need Windows Hello / Touch ID first.
+    #[serde(default)]
+    pub biometric: BiometricGate,
+    /// Re-lock the vault and app after this many idle minutes; None never does.
+    #[serde(default)]
+    pub auto_lock_minutes: Option<u32>,
+    /// Prefix Discord's window title with the active profile's nickname.
+    #[serde(default)]
+    pub tag_discord_window: bool,
+    #[serde(default)]
+    pub presets: Vec<LaunchPreset>,
+    /// Name of the preset launches use; None means the channel and path above.
+    #[serde(default)]
+    pub active_preset: Option<String>,
+    /// Folder names under `plugins/` that may run; only `plugins` changes it.
+    #[serde(default)]
+    pub enabled_plugins: Vec<String>,
+    /// Refuse every outbound HTTP request: token checks, account info, digests.
+    #[serde(default)]
+    pub offline_mode: bool,
+    #[serde(default)]
+    pub retention: RetentionSettings,
+    /// Discord forks the detector doesn't know, registered by the user.
+    #[serde(default)]
+    pub custom_clients: Vec<CustomClient>,
+    /// Name of a registered client to launch instead of a detected channel.
+    #[serde(default)]
+    pub custom_client: Option<String>,
+    /// Folders searched a few levels deep for Discord installs that aren't
+    /// where the installer or a package manager puts them (Windows).
+    #[serde(default)]
+    pub install_search_roots: Vec<String>,
+    /// Start Squirrel installs through `Update.exe --processStart` so Discord's
+    /// updater and shortcuts keep working (Windows).
+    #[serde(default = "default_launch_through_updater")]
+    pub launch_through_updater: bool,
+    /// Let Discord's updater finish before a switch writes the token, so the
+    /// client doesn't restart itself to update right after launching (Windows).
+    #[serde(default)]
+    pub update_before_switch: bool,
+    /// Accept tokens pushed by a Vencord or BetterDiscord plugin over a local
+    /// socket or named pipe.
+    #[serde(default)]
+    pub mod_bridge: bool,
+}
+
+fn default_launch_through_updater() -> bool {
+    true
+}
+
+/// A named set of launch options, e.g. "work laptop" or "gaming rig".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchPreset {
+    pub name: String,
+    #[serde(default)]
+    pub preferred_channel: DiscordChannel,
+    #[serde(default)]
+    pub custom_executable_path: Option<String>,
+    #[serde(default)]
+    pub custom_client: Option<String>,
+}
+
+impl LauncherSettings {
+    /// The launch options in effect: the active preset, or the top-level fields.
+    pub fn effective_launch_preset(&self) -> LaunchPreset {
+        self.active_preset
+            .as_deref()
+            .and_then(|name| self.presets.iter().find(|p| p.name == name))
+            .cloned()
+            .unwrap_or_else(|| LaunchPreset {
+                name: String::new(),
+                preferred_channel: self.preferred_channel,
+                custom_executable_path: self.custom_executable_path.clone(),
+                custom_client: self.custom_client.clone(),
+            })
+    }
+
+    /// The channel launches start, and so the one whose storage switches and
+    /// captures use. A custom executable's channel is told from its path;
+    /// one that names no known channel is refused rather than guessed, as a
+    /// token written to another client's storage logs the wrong one in.
+    pub fn launch_channel(&self) -> Result<DiscordChannel, String> {
+        let preset = self.effective_launch_preset();
+        match (&preset.custom_client, &preset.custom_executable_path) {
+            (Some(_), _) => Ok(DiscordChannel::Custom),
+            (None, Some(path)) => channel_from_executable(path).ok_or_else(|| {
+                "Could not tell which Discord channel the custom executable is. Pick the channel instead, or register it as a custom client."
+                    .to_string()
+            }),
+            (None, None) => Ok(preset.preferred_channel),
+        }
+    }
+}
+
+impl Default for LauncherSettings {
+    fn default() -> Self {
+        Self {
+            preferred_channel: DiscordChannel::Auto,
+            custom_executable_path: None,
+            health_digest: HealthDigestSettings::default(),
+            token_backend: TokenBackend::File,
+            write_strategy: WriteStrategy::Put,
+            capture_mode: CaptureMode::CloseDiscord,
+            locale: None,
+            app_password_hash: None,
+            biometric: BiometricGate::default(),
+            auto_lock_minutes: None,
+            tag_discord_window: false,
+            presets: Vec::new(),
+            active_preset: None,
+            enabled_plugins: Vec::new(),
+            offline_mode: false,
+            retention: RetentionSettings::default(),
+            custom_clients: Vec::new(),
+            custom_client: None,
+            install_search_roots: Vec::new(),
+            launch_through_updater: true,
+            update_before_switch: false,
+            mod_bridge: false,
+        }
+    }
+}
+
+/// Where saved profile tokens live.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenBackend {
+    /// One file per profile under `tokens/`, bound to this user and machine
+    /// (DPAPI on Windows, a keychain key on macOS); plain text elsewhere.
+    #[default]
+    File,
+    /// One AES-256-GCM encrypted file per profile, keyed from the system keychain.
+    EncryptedFile,
+    /// Windows Credential Manager / macOS Keychain.
+    Keychain,
+    /// A single `tokens.sqlite3` next to `accounts.json`.
+    Sqlite,
+}
+
+/// How a token gets written into Discord's LevelDB on switch.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum WriteStrategy {
+    /// Overwrite the key Discord is using (or the default key) in place.
+    #[default]
+    Put,
+    /// Delete every known key variant and flush before writing, for builds
+    /// that keep serving a cached token after a plain overwrite.
+    DeleteThenPut,
+    /// Write the token under every known key variant.
+    AllKeys,
+}
+
+/// How a capture gets at the token in Discord's LevelDB.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum CaptureMode {
+    /// Close Discord first, so its storage is complete and unlocked.
+    #[default]
+    CloseDiscord,
+    /// Read a copy of the storage while Discord keeps running, so calls aren't
+    /// interrupted; a login from the last few seconds may not be in it yet.
+    Snapshot,
+    /// Ask Discord's page for the token over the Chrome DevTools Protocol,
+    /// which doesn't depend on the LevelDB format. Discord is restarted with a
+    /// debugging port once, and later captures attach to it while it runs.
+    DevTools,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DigestFrequency {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn interval_ms(self) -> Option<u128> {
+        match self {
+            Self::Off => None,
+            Self::Daily => Some(24 * 60 * 60 * 1000),
+            Self::Weekly => Some(7 * 24 * 60 * 60 * 1000),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthDigestSettings {
+    #[serde(default)]
+    pub frequency: DigestFrequency,
+    pub webhook_url: Option<String>,
+}
+
+/// Validate and store new settings, moving saved tokens over if the backend changed.
+/// The app password hash is always kept as it was.
+pub fn save_launcher_settings(
+    dir: &DataDir,
+    settings: LauncherSettings,
+) -> Result<LauncherSettings, String> {
+    let file_path = dir.launcher_settings_file();
+    let previous = load_launcher_settings(&file_path)?;
+    let cleaned = sanitize_launcher_settings(LauncherSettings {
+        app_password_hash: previous.app_password_hash.clone(),
+        enabled_plugins: previous.enabled_plugins.clone(),
+        ..settings
+    })?;
+    confirm_gate_change(&previous.biometric, &cleaned.biometric)?;
+
+    // carry existing tokens over; the new backend takes effect once they're all there
+    if previous.token_backend != cleaned.token_backend {
+        ensure_token_backend_supported(cleaned.token_backend)?;
+        migrate_tokens(dir, previous.token_backend, cleaned.token_backend, || {
+            save_launcher_settings_to_file(&file_path, &cleaned)
+        })?;
+    } else {
+        save_launcher_settings_to_file(&file_path, &cleaned)?;
+    }
+    Ok(cleaned)
+}
+
+pub fn sanitize_launcher_settings(settings: LauncherSettings) -> Result<LauncherSettings, String> {
+    let clean_custom_path = settings
+        .custom_executable_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(str::to_string);
+    if let Some(path) = &clean_custom_path {
+        if !PathBuf::from(path).exists() {
+            return Err("Custom executable path does not exist.".to_string());
+        }
+    }
+    let clean_webhook_url = settings
+        .health_digest
+        .webhook_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(str::to_string);
+    if let Some(url) = &clean_webhook_url {
+        if !is_discord_webhook_url(url) {
+            return Err("Health digest webhook must be a Discord webhook URL.".to_string());
+        }
+    } else if settings.health_digest.frequency != DigestFrequency::Off {
+        return Err("Set a webhook URL to enable the health digest.".to_string());
+    }
+    let clean_locale = settings
+        .locale
+        .as_deref()
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| {
+            tag.parse::<icu_locid::Locale>()
+                .map(|locale| locale.to_string())
+                .map_err(|_| format!("'{tag}' is not a valid locale like en-US."))
+        })
+        .transpose()?;
+    if let Some(minutes) = settings.auto_lock_minutes {
+        if minutes == 0 || minutes > MAX_AUTO_LOCK_MINUTES {
+            return Err(format!(
+                "Auto-lock must be between 1 and {MAX_AUTO_LOCK_MINUTES} minutes."
+            ));
+        }
+    }
+    let clean_presets = settings
+        .presets
+        .into_iter()
+        .map(sanitize_launch_preset)
+        .collect::<Result<Vec<_>, _>>()?;
+    for (i, preset) in clean_presets.iter().enumerate() {
+        if clean_presets[i + 1..]
+            .iter()
+            .any(|other| other.name.eq_ignore_ascii_case(&preset.name))
+        {
+            return Err(format!("There are two presets named '{}'.", preset.name));
+        }
+    }
+    // a preset that was removed or renamed falls back to the top-level options
+    let clean_active_preset = settings
+        .active_preset
+        .filter(|name| clean_presets.iter().any(|p| &p.name == name));
+    let clean_retention = sanitize_retention(settings.retention)?;
+    let clean_clients = settings
+        .custom_clients
+        .into_iter()
+        .map(sanitize_custom_client)
+        .collect::<Result<Vec<_>, _>>()?;
+    for (i, client) in clean_clients.iter().enumerate() {
+        if clean_clients[i + 1..]
+            .iter()
+            .any(|other| other.name.eq_ignore_ascii_case(&client.name))
+        {
+            return Err(format!("There are two clients named '{}'.", client.name));
+        }
+    }
+    // like presets, a client that was removed falls back to detection
+    let clean_custom_client = settings
+        .custom_client
+        .filter(|name| clean_clients.iter().any(|c| &c.name == name));
+    Ok(LauncherSettings {
+        custom_executable_path: clean_custom_path,
+        retention: clean_retention,
+        locale: clean_locale,
+        presets: clean_presets,
+        active_preset: clean_active_preset,
+        custom_clients: clean_clients,
+        custom_client: clean_custom_client,
+        install_search_roots: settings
+            .install_search_roots
+            .iter()
+            .map(|root| root.trim())
+            .filter(|root| !root.is_empty())
+            .map(str::to_string)
+            .collect(),
+        health_digest: HealthDigestSettings {
+            frequency: settings.health_digest.frequency,
+            webhook_url: clean_webhook_url,
+        },
+        ..settings
+    })
+}
+
+// preset paths aren't checked for existence: a preset may be meant for another machine
+pub fn sanitize_launch_preset(preset: LaunchPreset) -> Result<LaunchPreset, String> {
+    let name = preset.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_PRESET_NAME_CHARS {
+        return Err(format!(
+            "Preset names must be between 1 and {MAX_PRESET_NAME_CHARS} characters."
+        ));
+    }
+    Ok(LaunchPreset {
+        name,
+        preferred_channel: preset.preferred_channel,
+        custom_executable_path: preset
+            .custom_executable_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(str::to_string),
+        custom_client: preset
+            .custom_client
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string),
+    })
+}
+
+fn is_discord_webhook_url(url: &str) -> bool {
+    [
+        "https://discord.com/api/webhooks/",
+        "https://ptb.discord.com/api/webhooks/",
+        "https://canary.discord.com/api/webhooks/",
+        "https://discordapp.com/api/webhooks/",
+    ]
+    .iter()
+    .any(|prefix| url.starts_with(prefix))
+}
+
+// ── Persistence ──
+
+/// [`LauncherSettings::launch_channel`] of the saved settings.
+pub fn launch_channel(dir: &DataDir) -> Result<DiscordChannel, String> {
+    peek_launcher_settings(&dir.launcher_settings_file())?.launch_channel()
+}
+
+pub fn load_launcher_settings(file_path: &Path) -> Result<LauncherSettings, String> {
+    let settings = read_launcher_settings(file_path)?;
+    apply_process_settings(&settings);
+    Ok(settings)
+}
+
+// settings the whole process follows rather than reading them per call
+fn apply_process_settings(settings: &LauncherSettings) {
+    set_offline_mode(settings.offline_mode);
+    let preset = settings.effective_launch_preset();
+    set_custom_clients(&settings.custom_clients, preset.custom_client.as_deref());
+    set_install_search_roots(&settings.install_search_roots);
+    set_launch_through_updater(settings.launch_through_updater);
+    set_mod_bridge_enabled(settings.mod_bridge);
+}
+
+/// The settings as saved, neither validated nor applied to the process. For
+/// reads that need one field, like the token backend, and mustn't fail
+/// because some other one went stale, like a removed custom executable.
+pub(crate) fn peek_launcher_settings(file_path: &Path) -> Result<LauncherSettings, String> {
+    if !file_path.exists() {
+        return Ok(LauncherSettings::default());
+    }
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| format!("Could not read launcher settings: {e}"))?;
+    if content.trim().is_empty() {
+        return Ok(LauncherSettings::default());
+    }
+    serde_json::from_str(&content).map_err(|e| format!("Could not parse launcher settings: {e}"))
+}
+
+fn read_launcher_settings(file_path: &Path) -> Result<LauncherSettings, String> {
+    let mut parsed = peek_launcher_settings(file_path)?;
+    // before sanitizing, which would refuse a custom path a Discord update removed
+    let repairs = repair_custom_paths(&mut parsed);
+    let settings = sanitize_launcher_settings(parsed)?;
+    if !repairs.is_empty() {
+        save_launcher_settings_to_file(file_path, &settings)?;
+        paths_repaired(repairs);
+    }
+    Ok(settings)
+}
+
+pub fn save_launcher_settings_to_file(
+    file_path: &Path,
+    settings: &LauncherSettings,
+) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Could not encode launcher settings: {e}"))?;
+    fs::write(file_path, payload).map_err(|e| format!("Could not save launcher settings: {e}"))?;
+    record_modification(file_path);
+    apply_process_settings(settings);
+    Ok(())
+}
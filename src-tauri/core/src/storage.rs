@@ -0,0 +1,1311 @@
+use base64::{
+    engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use rusty_leveldb::LdbIterator;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    env,
+    fs::{self, TryLockError},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use zeroize::Zeroizing;
+
+use crate::{
+    api::DiscordUser,
+    custom_clients::active_custom_client,
+    launch::is_discord_running,
+    markers::record_modification,
+    os_crypt::{decrypt_token, encrypt_token_for, ENCRYPTED_TOKEN_PREFIX},
+    secret::SecretToken,
+    settings::{launch_channel, DiscordChannel, WriteStrategy},
+    storage_backup::back_up_discord_storage,
+    DataDir,
+};
+
+/// All the LevelDB key variants Discord has used over the years.
+pub const TOKEN_KEYS: &[&[u8]] = &[
+    b"_https://discord.com\x00\x01token",
+    b"_https://discord.com/\x00\x01token",
+    b"_https://discord.com\x00token",
+    b"_https://discord.com/\x00token",
+    b"_https://discordapp.com\x00\x01token",
+    b"_https://discordapp.com/\x00\x01token",
+];
+
+/// Where Discord's built-in account switcher keeps its JSON map of user id to
+/// token, one entry per account it has logged in.
+pub const TOKENS_MAP_KEYS: &[&[u8]] = &[
+    b"_https://discord.com\x00\x01tokens",
+    b"_https://discord.com/\x00\x01tokens",
+    b"_https://discordapp.com\x00\x01tokens",
+];
+
+/// Where Discord's account switcher keeps the accounts it lists: a JSON store
+/// whose `_state.users` holds each one's id, username and avatar. An account
+/// in the `tokens` map without an entry here doesn't show up in the switcher.
+pub const MULTI_ACCOUNT_STORE_KEYS: &[&[u8]] = &[
+    b"_https://discord.com\x00\x01MultiAccountStore",
+    b"_https://discord.com/\x00\x01MultiAccountStore",
+    b"_https://discordapp.com\x00\x01MultiAccountStore",
+];
+
+/// Extra LevelDB keys to try, stored escaped (e.g. `_https://discord.com\x00\x01token`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenKeyConfig {
+    #[serde(default)]
+    pub extra_keys: Vec<String>,
+}
+
+/// Where `channel` keeps its localStorage LevelDB, usually the one from
+/// [`launch_channel`](crate::settings::launch_channel): Custom is the selected
+/// custom client's, and Auto the first installed channel's, Stable before the
+/// others.
+pub fn discord_storage_dir(channel: DiscordChannel) -> Result<PathBuf, String> {
+    if channel == DiscordChannel::Custom {
+        let client = active_custom_client()
+            .ok_or_else(|| "No custom client is selected in the settings.".to_string())?;
+        let path = PathBuf::from(&client.storage_dir);
+        if !path.exists() {
+            return Err(format!(
+                "{}'s Local Storage was not found at {}. Has it been started once?",
+                client.name,
+                path.display()
+            ));
+        }
+        return Ok(path);
+    }
+    let dirs = discord_storage_dirs_by_channel()?;
+    if channel == DiscordChannel::Auto {
+        return dirs
+            .into_iter()
+            .next()
+            .map(|(_, path)| path)
+            .ok_or_else(|| "Discord Local Storage not found. Is Discord installed?".to_string());
+    }
+    // never another channel's: a token written there would log the wrong client in
+    dirs.into_iter()
+        .find(|(found, _)| *found == channel)
+        .map(|(_, path)| path)
+        .ok_or_else(|| {
+            "The chosen Discord channel's Local Storage was not found. Has it been started once?"
+                .to_string()
+        })
+}
+
+/// The localStorage LevelDB of every installed channel, Stable first.
+pub fn discord_storage_dirs() -> Result<Vec<PathBuf>, String> {
+    Ok(discord_storage_dirs_by_channel()?
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect())
+}
+
+// each client's folder under the per-user app data root, and where its
+// Local Storage sits inside; Vesktop keeps its web data in a subfolder
+const CLIENT_STORAGE: &[(DiscordChannel, &str, &str)] = &[
+    (DiscordChannel::Stable, "discord", "Local Storage/leveldb"),
+    (DiscordChannel::Ptb, "discordptb", "Local Storage/leveldb"),
+    (
+        DiscordChannel::Canary,
+        "discordcanary",
+        "Local Storage/leveldb",
+    ),
+    (
+        DiscordChannel::Development,
+        "discorddevelopment",
+        "Local Storage/leveldb",
+    ),
+    (
+        DiscordChannel::Vesktop,
+        "vesktop",
+        "sessionData/Local Storage/leveldb",
+    ),
+    (DiscordChannel::WebCord, "WebCord", "Local Storage/leveldb"),
+    (DiscordChannel::ArmCord, "ArmCord", "Local Storage/leveldb"),
+];
+
+/// Like [`discord_storage_dirs`], with the client each folder belongs to.
+pub fn discord_storage_dirs_by_channel() -> Result<Vec<(DiscordChannel, PathBuf)>, String> {
+    let root = app_data_root()?;
+    #[allow(unused_mut)]
+    let mut dirs: Vec<_> = CLIENT_STORAGE
+        .iter()
+        .map(|(channel, folder, storage)| {
+            let path = storage
+                .split('/')
+                .fold(root.join(folder), |path, part| path.join(part));
+            (*channel, path)
+        })
+        .filter(|(_, path)| path.exists())
+        .collect();
+
+    // the Store build is Stable too, so it goes right after the regular one
+    #[cfg(target_os = "windows")]
+    {
+        let stable_end = dirs
+            .iter()
+            .take_while(|(channel, _)| *channel == DiscordChannel::Stable)
+            .count();
+        let store = store_storage_dirs()
+            .into_iter()
+            .map(|path| (DiscordChannel::Stable, path));
+        dirs.splice(stable_end..stable_end, store);
+    }
+
+    Ok(dirs)
+}
+
+// MSIX packages see a virtualized %APPDATA%; what the Store build writes
+// there really lands in its package's LocalCache
+#[cfg(target_os = "windows")]
+fn store_storage_dirs() -> Vec<PathBuf> {
+    let Ok(local_app_data) = env::var("LOCALAPPDATA") else {
+        return Vec::new();
+    };
+    let Ok(packages) = fs::read_dir(PathBuf::from(local_app_data).join("Packages")) else {
+        return Vec::new();
+    };
+    packages
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .to_lowercase()
+                .contains("discord")
+        })
+        .map(|entry| {
+            entry
+                .path()
+                .join(r"LocalCache\Roaming\discord\Local Storage\leveldb")
+        })
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Which client a Local Storage folder belongs to, if it's one we know.
+pub fn storage_dir_channel(storage_dir: &Path) -> Option<DiscordChannel> {
+    discord_storage_dirs_by_channel()
+        .ok()?
+        .into_iter()
+        .find(|(_, path)| path == storage_dir)
+        .map(|(channel, _)| channel)
+}
+
+/// The channel whose storage `channel` reads: Auto becomes the installed one
+/// it picks, the others stay as they are. None when Auto finds nothing, or
+/// picks a folder the app can't place.
+pub fn resolve_storage_channel(channel: DiscordChannel) -> Option<DiscordChannel> {
+    match channel {
+        DiscordChannel::Auto => discord_storage_dir(channel)
+            .ok()
+            .and_then(|storage_dir| storage_dir_channel(&storage_dir)),
+        channel => Some(channel),
+    }
+}
+
+/// A client's per-user data folder, whether or not it has been started yet.
+pub(crate) fn client_data_dir(channel: DiscordChannel) -> Option<PathBuf> {
+    let (_, folder, _) = CLIENT_STORAGE.iter().find(|(c, _, _)| *c == channel)?;
+    app_data_root().ok().map(|root| root.join(folder))
+}
+
+// where Electron apps keep per-user data on this OS
+fn app_data_root() -> Result<PathBuf, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let home = env::var("HOME").map_err(|_| "HOME not set.".to_string())?;
+        return Ok(PathBuf::from(home).join("Library/Application Support"));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return env::var("APPDATA")
+            .map(PathBuf::from)
+            .map_err(|_| "APPDATA not set.".to_string());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        return env::var("XDG_CONFIG_HOME")
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var("HOME")
+                    .ok()
+                    .map(|home| PathBuf::from(home).join(".config"))
+            })
+            .ok_or_else(|| "HOME not set.".to_string());
+    }
+
+    #[allow(unreachable_code)]
+    Err("Unsupported platform.".to_string())
+}
+
+// ── Token key patterns ──
+
+/// Built-in keys first, then whatever the user added to `token-keys.json`.
+pub fn token_keys(dir: &DataDir) -> Result<Vec<Vec<u8>>, String> {
+    let config = load_token_key_config(&dir.token_keys_file())?;
+    let mut keys: Vec<Vec<u8>> = TOKEN_KEYS.iter().map(|k| k.to_vec()).collect();
+    for pattern in &config.extra_keys {
+        let key = unescape_token_key(pattern)?;
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    Ok(keys)
+}
+
+/// Every known key, escaped for display.
+pub fn list_token_key_patterns(dir: &DataDir) -> Result<Vec<String>, String> {
+    Ok(token_keys(dir)?
+        .iter()
+        .map(|key| escape_token_key(key))
+        .collect())
+}
+
+/// Teach the app a new LevelDB key without waiting for a release.
+pub fn add_token_key_pattern(dir: &DataDir, pattern: &str) -> Result<Vec<String>, String> {
+    let key = unescape_token_key(pattern.trim())?;
+    if key.is_empty() || key.len() > 256 {
+        return Err("Token key pattern must be between 1 and 256 bytes.".to_string());
+    }
+
+    let file_path = dir.token_keys_file();
+    let mut config = load_token_key_config(&file_path)?;
+    let escaped = escape_token_key(&key);
+    if TOKEN_KEYS.contains(&key.as_slice()) || config.extra_keys.contains(&escaped) {
+        return Err("This token key pattern is already known.".to_string());
+    }
+    config.extra_keys.push(escaped);
+    save_token_key_config(&file_path, &config)?;
+
+    list_token_key_patterns(dir)
+}
+
+pub fn remove_token_key_pattern(dir: &DataDir, pattern: &str) -> Result<Vec<String>, String> {
+    let escaped = escape_token_key(&unescape_token_key(pattern.trim())?);
+    let file_path = dir.token_keys_file();
+    let mut config = load_token_key_config(&file_path)?;
+
+    let start_len = config.extra_keys.len();
+    config.extra_keys.retain(|k| *k != escaped);
+    if config.extra_keys.len() == start_len {
+        return Err("Only custom token key patterns can be removed.".to_string());
+    }
+    save_token_key_config(&file_path, &config)?;
+
+    list_token_key_patterns(dir)
+}
+
+/// Keys hold raw control bytes, so the config stores them with `\xNN` escapes.
+pub fn escape_token_key(key: &[u8]) -> String {
+    let mut out = String::new();
+    for &byte in key {
+        match byte {
+            b'\\' => out.push_str("\\\\"),
+            0x20..=0x7e => out.push(byte as char),
+            _ => out.push_str(&format!("\\x{byte:02x}")),
+        }
+    }
+    out
+}
+
+pub fn unescape_token_key(pattern: &str) -> Result<Vec<u8>, String> {
+    let bytes = pattern.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        match bytes.get(i + 1) {
+            Some(b'\\') => {
+                out.push(b'\\');
+                i += 2;
+            }
+            Some(b'x') => {
+                let hex = pattern
+                    .get(i + 2..i + 4)
+                    .ok_or_else(|| format!("Incomplete \\x escape in '{pattern}'."))?;
+                let byte = u8::from_str_radix(hex, 16)
+                    .map_err(|_| format!("Invalid \\x escape in '{pattern}'."))?;
+                out.push(byte);
+                i += 4;
+            }
+            _ => {
+                return Err(format!(
+                    "Unsupported escape in '{pattern}'. Use \\xNN or \\\\."
+                ))
+            }
+        }
+    }
+    Ok(out)
+}
+
+pub fn load_token_key_config(file_path: &Path) -> Result<TokenKeyConfig, String> {
+    if !file_path.exists() {
+        return Ok(TokenKeyConfig::default());
+    }
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| format!("Could not read token key config: {e}"))?;
+    if content.trim().is_empty() {
+        return Ok(TokenKeyConfig::default());
+    }
+    serde_json::from_str(&content).map_err(|e| format!("Could not parse token key config: {e}"))
+}
+
+pub fn save_token_key_config(file_path: &Path, config: &TokenKeyConfig) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Could not encode token key config: {e}"))?;
+    fs::write(file_path, payload).map_err(|e| format!("Could not save token key config: {e}"))?;
+    record_modification(file_path);
+    Ok(())
+}
+
+// ── Values ──
+
+// pull the token string out of a raw LevelDB value
+// there's sometimes an encoding prefix byte (0x01 = Latin-1) we need to skip
+fn extract_token_from_value(raw: &[u8]) -> Option<String> {
+    if raw.is_empty() {
+        return None;
+    }
+
+    // Skip encoding prefix if present
+    let data = if raw[0] == 0x00 || raw[0] == 0x01 {
+        &raw[1..]
+    } else {
+        raw
+    };
+
+    let s = std::str::from_utf8(data).ok()?;
+    let s = s.trim_matches('"').trim();
+
+    if s.is_empty() {
+        return None;
+    }
+
+    Some(s.to_string())
+}
+
+// wrap a token string in the format LevelDB expects
+fn encode_token_value(token: &str) -> Vec<u8> {
+    let mut value = Vec::new();
+    value.push(0x01); // Latin-1 encoding prefix
+    value.extend_from_slice(format!("\"{token}\"").as_bytes());
+    value
+}
+
+/// Every error for a Discord that is still running, or a LOCK file someone
+/// still holds, starts with this; callers close Discord again and retry, or go
+/// ahead with `force_unlock` when the user insists.
+pub const STORAGE_LOCKED: &str = "Discord is still running and holding its storage.";
+
+/// Make sure nothing has Discord's database open before we do. Discord's
+/// updater can bring it back right after we kill it, and opening the database
+/// under it races its writes, so this refuses with [`STORAGE_LOCKED`] instead
+/// of taking the LOCK file away. Only `force_unlock` removes LOCK and goes
+/// ahead; overriding the call check doesn't.
+pub(crate) fn unlock_storage(storage_dir: &Path, force_unlock: bool) -> Result<(), String> {
+    let lock = storage_dir.join("LOCK");
+    if force_unlock {
+        if fs::remove_file(&lock).is_err() && lock.exists() {
+            return Err(STORAGE_LOCKED.to_string());
+        }
+        return Ok(());
+    }
+    if is_discord_running() {
+        return Err(STORAGE_LOCKED.to_string());
+    }
+    ensure_lock_free(&lock)
+}
+
+// a stale LOCK nobody holds is fine; LevelDB takes it over when it opens
+fn ensure_lock_free(lock: &Path) -> Result<(), String> {
+    let file = match fs::File::open(lock) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        // Chromium on Windows keeps it open without sharing
+        Err(_) => return Err(STORAGE_LOCKED.to_string()),
+    };
+    #[cfg(unix)]
+    if let Some(pid) = fcntl_lock_holder(&file) {
+        return Err(format!("{STORAGE_LOCKED} Process {pid} has it locked."));
+    }
+    match file.try_lock() {
+        Err(TryLockError::WouldBlock) => Err(STORAGE_LOCKED.to_string()),
+        // dropping the file releases the lock again
+        _ => Ok(()),
+    }
+}
+
+// Chromium locks with fcntl, which `try_lock` (flock) doesn't see on Linux
+#[cfg(unix)]
+fn fcntl_lock_holder(file: &fs::File) -> Option<i32> {
+    use std::os::fd::AsRawFd;
+
+    // SAFETY: an all-zero flock is a valid value to fill in
+    let mut query: libc::flock = unsafe { std::mem::zeroed() };
+    query.l_type = libc::F_WRLCK as libc::c_short;
+    query.l_whence = libc::SEEK_SET as libc::c_short;
+    // SAFETY: the descriptor is open and `query` outlives the call
+    let queried = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut query) };
+    (queried == 0 && query.l_type != libc::F_UNLCK as libc::c_short).then_some(query.l_pid)
+}
+
+// ── Reading ──
+
+/// Read the Discord auth token straight from the LevelDB database, decrypted
+/// if Discord stored it encrypted. With `account`, that user's token is taken
+/// from Discord's multi-account `tokens` map instead of the active one.
+/// Discord must not be running; when it has started again, or the database
+/// won't open, the raw files are scanned instead, and [`STORAGE_LOCKED`] comes
+/// back if that finds nothing either. `channel` is the client to read.
+pub fn read_discord_token(
+    dir: &DataDir,
+    keys: &[Vec<u8>],
+    account: Option<&str>,
+    channel: DiscordChannel,
+) -> Result<SecretToken, String> {
+    let storage_dir = discord_storage_dir(channel)?;
+    if let Some(user_id) = account {
+        unlock_storage(&storage_dir, false)?;
+        let token = Zeroizing::new(read_account_token_from_dir(&storage_dir, user_id)?);
+        return decrypt_token(&storage_dir, &token);
+    }
+    let token = match unlock_storage(&storage_dir, false) {
+        Ok(()) => read_token_from_dir(&storage_dir, keys)
+            .or_else(|e| read_token_from_indexeddb(dir, &storage_dir).ok_or(e))
+            .or_else(|e| scan_token_in_raw_files(&storage_dir, keys).ok_or(e)),
+        // reading the files themselves is safe under a live Discord
+        Err(e) => scan_token_in_raw_files(&storage_dir, keys).ok_or(e),
+    }
+    .map(Zeroizing::new)?;
+    decrypt_token(&storage_dir, &token)
+}
+
+/// Copy the LevelDB files somewhere private and read from the copy,
+/// which works while Discord is still running and holding the lock. `account`
+/// and `channel` work as in [`read_discord_token`].
+pub fn read_discord_token_from_snapshot(
+    dir: &DataDir,
+    keys: &[Vec<u8>],
+    account: Option<&str>,
+    channel: DiscordChannel,
+) -> Result<SecretToken, String> {
+    let storage_dir = discord_storage_dir(channel)?;
+    let snapshot_dir = dir.scratch_dir("snapshot")?;
+
+    let copied = copy_leveldb_files(&storage_dir, &snapshot_dir);
+    let result = match account {
+        Some(user_id) => copied.and_then(|_| read_account_token_from_dir(&snapshot_dir, user_id)),
+        None => copied
+            .and_then(|_| read_token_from_dir(&snapshot_dir, keys))
+            .or_else(|e| read_token_from_indexeddb(dir, &storage_dir).ok_or(e))
+            .or_else(|e| scan_token_in_raw_files(&storage_dir, keys).ok_or(e)),
+    }
+    .map(Zeroizing::new);
+
+    let _ = fs::remove_dir_all(&snapshot_dir);
+    // the copy has no Local State, so decrypt with the live install's key
+    decrypt_token(&storage_dir, &result?)
+}
+
+/// The user ids in Discord's multi-account `tokens` map, read from a private
+/// copy so Discord can keep running. Empty when the switcher was never used.
+/// `channel` is the one launches use when None.
+pub fn list_discord_logged_in_accounts(
+    dir: &DataDir,
+    channel: Option<DiscordChannel>,
+) -> Result<Vec<String>, String> {
+    let channel = match channel {
+        Some(channel) => channel,
+        None => launch_channel(dir)?,
+    };
+    let storage_dir = discord_storage_dir(channel)?;
+    let snapshot_dir = dir.scratch_dir("accounts")?;
+
+    let result = copy_leveldb_files(&storage_dir, &snapshot_dir)
+        .and_then(|_| read_tokens_map_from_dir(&snapshot_dir))
+        .map(|tokens| tokens.into_keys().collect());
+
+    let _ = fs::remove_dir_all(&snapshot_dir);
+    result
+}
+
+/// Every token in the client at `storage_dir`, decrypted: the active one and
+/// the accounts in its `tokens` map. Read from a private copy so the client
+/// can keep running; a client nobody is logged in to gives an empty list.
+pub fn read_all_discord_tokens(
+    dir: &DataDir,
+    storage_dir: &Path,
+    keys: &[Vec<u8>],
+) -> Result<Vec<SecretToken>, String> {
+    let snapshot_dir = dir.scratch_dir("sessions")?;
+
+    let result = copy_leveldb_files(storage_dir, &snapshot_dir).map(|_| {
+        let active = read_token_from_dir(&snapshot_dir, keys)
+            .ok()
+            .or_else(|| read_token_from_indexeddb(dir, storage_dir))
+            .or_else(|| scan_token_in_raw_files(storage_dir, keys));
+        let switcher = read_tokens_map_from_dir(&snapshot_dir).unwrap_or_default();
+        active
+            .into_iter()
+            .chain(switcher.into_values())
+            .map(Zeroizing::new)
+            .collect::<Vec<_>>()
+    });
+
+    let _ = fs::remove_dir_all(&snapshot_dir);
+    result?
+        .iter()
+        .map(|token| decrypt_token(storage_dir, token))
+        .collect()
+}
+
+/// Raw values (if any) stored under each of `keys` in `channel`, read from a
+/// private copy so Discord can keep running.
+pub fn read_key_values_from_snapshot(
+    dir: &DataDir,
+    keys: &[Vec<u8>],
+    channel: DiscordChannel,
+) -> Result<Vec<Option<Vec<u8>>>, String> {
+    let storage_dir = discord_storage_dir(channel)?;
+    let snapshot_dir = dir.scratch_dir("key-values")?;
+
+    let result = copy_leveldb_files(&storage_dir, &snapshot_dir).and_then(|_| {
+        let mut db = open_storage_db(&snapshot_dir)?;
+        Ok(keys
+            .iter()
+            .map(|key| db.get(key).map(|value| value.to_vec()))
+            .collect())
+    });
+
+    let _ = fs::remove_dir_all(&snapshot_dir);
+    result
+}
+
+// Chromium's IndexedDB LevelDB is written with its own "idb_cmp1" comparator,
+// which rusty_leveldb refuses to open under the default one. Byte order is
+// close enough for walking every entry, which is all we do here.
+struct IndexedDbCmp;
+
+impl rusty_leveldb::Cmp for IndexedDbCmp {
+    fn cmp(&self, a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+        a.cmp(b)
+    }
+
+    fn find_shortest_sep(&self, from: &[u8], _to: &[u8]) -> Vec<u8> {
+        from.to_vec()
+    }
+
+    fn find_short_succ(&self, key: &[u8]) -> Vec<u8> {
+        key.to_vec()
+    }
+
+    fn id(&self) -> &'static str {
+        "idb_cmp1"
+    }
+}
+
+// secondary path for builds that keep auth material in IndexedDB;
+// works on a copy so the comparator trick never touches Discord's files
+fn read_token_from_indexeddb(dir: &DataDir, local_storage_dir: &Path) -> Option<String> {
+    let indexeddb_root = local_storage_dir.parent()?.parent()?.join("IndexedDB");
+    let stores = fs::read_dir(&indexeddb_root).ok()?;
+
+    for store in stores.filter_map(|e| e.ok()) {
+        let name = store.file_name().to_string_lossy().to_string();
+        let is_discord =
+            name.starts_with("https_discord.com") || name.starts_with("https_discordapp.com");
+        if !is_discord || !name.ends_with(".indexeddb.leveldb") {
+            continue;
+        }
+
+        let Ok(copy_dir) = dir.scratch_dir("indexeddb") else {
+            continue;
+        };
+        let found = copy_leveldb_files(&store.path(), &copy_dir)
+            .ok()
+            .and_then(|_| scan_indexeddb_copy(&copy_dir));
+        let _ = fs::remove_dir_all(&copy_dir);
+
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+fn scan_indexeddb_copy(dir: &Path) -> Option<String> {
+    let opt = rusty_leveldb::Options {
+        create_if_missing: false,
+        cmp: std::rc::Rc::new(Box::new(IndexedDbCmp)),
+        ..Default::default()
+    };
+    let mut db = rusty_leveldb::DB::open(dir, opt).ok()?;
+    let mut iter = db.new_iter().ok()?;
+
+    let mut key_buf = Vec::new();
+    let mut val_buf = Vec::new();
+    iter.reset();
+    while iter.advance() {
+        if iter.current(&mut key_buf, &mut val_buf) {
+            if let Some(token) = find_token_in_bytes(&val_buf) {
+                return Some(token);
+            }
+        }
+    }
+    None
+}
+
+// how far past a key its value can start in a raw file
+const RAW_VALUE_WINDOW: usize = 1024;
+
+/// Last resort for storage that's locked or too damaged to open: look for the
+/// token in the raw `.log` and `.ldb` files without opening the database.
+/// Newest file first, and only a value right after one of `keys`: a
+/// token-shaped string anywhere else could be another account's, from the
+/// switcher map or an old entry. Compressed table blocks can hide it.
+pub fn scan_token_in_raw_files(storage_dir: &Path, keys: &[Vec<u8>]) -> Option<String> {
+    let mut files: Vec<(u64, PathBuf)> = fs::read_dir(storage_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let extension = path.extension()?.to_str()?;
+            if !matches!(extension, "log" | "ldb" | "sst") {
+                return None;
+            }
+            let number = path.file_stem()?.to_str()?.parse().ok()?;
+            Some((number, path))
+        })
+        .collect();
+    files.sort_by_key(|(number, _)| Reverse(*number));
+    let contents = files.iter().filter_map(|(_, path)| fs::read(path).ok());
+
+    contents
+        .into_iter()
+        .find_map(|data| find_token_after_keys(&data, keys))
+}
+
+// the token in the value right after one of `keys` in a raw file's bytes
+fn find_token_after_keys(data: &[u8], keys: &[Vec<u8>]) -> Option<String> {
+    keys.iter().filter(|key| !key.is_empty()).find_map(|key| {
+        // a later write to the key sits further into the file
+        data.windows(key.len())
+            .enumerate()
+            .filter(|(_, window)| window == key)
+            .map(|(at, _)| at + key.len())
+            .rev()
+            .find_map(|start| {
+                let end = (start + RAW_VALUE_WINDOW).min(data.len());
+                find_token_in_bytes(&data[start..end])
+            })
+    })
+}
+
+// IndexedDB values are V8-serialized blobs, so look for anything shaped like
+// a token: the encrypted "dQw4w9WgXcQ:" form or the classic three-part one
+fn find_token_in_bytes(data: &[u8]) -> Option<String> {
+    const ENCRYPTED_PREFIX: &[u8] = ENCRYPTED_TOKEN_PREFIX.as_bytes();
+    let is_b64 = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=' | b'-' | b'_');
+
+    if let Some(start) = data
+        .windows(ENCRYPTED_PREFIX.len())
+        .position(|w| w == ENCRYPTED_PREFIX)
+    {
+        let body_start = start + ENCRYPTED_PREFIX.len();
+        let body_len = data[body_start..]
+            .iter()
+            .take_while(|b| is_b64(**b))
+            .count();
+        if body_len > 20 {
+            let token = &data[start..body_start + body_len];
+            return Some(String::from_utf8_lossy(token).to_string());
+        }
+    }
+
+    let is_segment = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_');
+    let mut i = 0;
+    while i < data.len() {
+        let run = data[i..]
+            .iter()
+            .take_while(|b| is_segment(**b) || **b == b'.')
+            .count();
+        if run == 0 {
+            i += 1;
+            continue;
+        }
+        let candidate = &data[i..i + run];
+        let parts: Vec<&[u8]> = candidate.split(|b| *b == b'.').collect();
+        if let [id, stamp, hmac] = parts.as_slice() {
+            if id.len() >= 24 && stamp.len() == 6 && hmac.len() >= 27 {
+                return Some(String::from_utf8_lossy(candidate).to_string());
+            }
+        }
+        i += run;
+    }
+    None
+}
+
+/// Copy a LevelDB directory's files (minus LOCK) into `to`.
+pub fn copy_leveldb_files(from: &Path, to: &Path) -> Result<(), String> {
+    let entries = fs::read_dir(from).map_err(|e| format!("Could not read Discord storage: {e}"))?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.is_file() || entry.file_name() == "LOCK" {
+            continue;
+        }
+        fs::copy(&path, to.join(entry.file_name()))
+            .map_err(|e| format!("Could not copy Discord storage: {e}"))?;
+    }
+    Ok(())
+}
+
+/// What reading the token gives when nobody is logged in to Discord.
+pub const NO_TOKEN_FOUND: &str =
+    "No Discord token found. Make sure you logged in to Discord first.";
+
+/// Every error for a database that can't be opened starts with this; the
+/// settings offer [`crate::storage_repair::repair_discord_storage`] for it.
+pub const STORAGE_DAMAGED: &str = "Discord's storage is damaged and couldn't be opened.";
+
+/// Open the existing LevelDB at `storage_dir`. Nothing on disk is fixed up
+/// here: a `CURRENT` file that's missing or names a manifest that's gone fails
+/// with [`STORAGE_DAMAGED`] instead of LevelDB starting an empty database over
+/// the old tables, and repairing it is left to the repair, after its backup.
+pub(crate) fn open_storage_db(storage_dir: &Path) -> Result<rusty_leveldb::DB, String> {
+    let options = rusty_leveldb::Options {
+        create_if_missing: false,
+        ..Default::default()
+    };
+    rusty_leveldb::DB::open(storage_dir, options)
+        .map_err(|e| format!("{STORAGE_DAMAGED} Repair it from the settings. ({e})"))
+}
+
+/// Point `CURRENT` at the newest `MANIFEST-*` when it doesn't name one that
+/// exists. Returns whether anything was changed. Only the repair calls this,
+/// once the storage is backed up.
+pub(crate) fn repoint_current(storage_dir: &Path) -> bool {
+    let current = storage_dir.join("CURRENT");
+    let named = fs::read_to_string(&current).unwrap_or_default();
+    let named = named.trim();
+    if !named.is_empty() && storage_dir.join(named).is_file() {
+        return false;
+    }
+    let newest = fs::read_dir(storage_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let number: u64 = name.strip_prefix("MANIFEST-")?.parse().ok()?;
+            Some((number, name))
+        })
+        .max();
+    match newest {
+        Some((_, manifest)) => fs::write(&current, format!("{manifest}\n")).is_ok(),
+        None => false,
+    }
+}
+
+// the switcher's map, user id to token (each sealed like the active one)
+fn read_tokens_map_from_dir(storage_dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    let mut db = open_storage_db(storage_dir)?;
+    Ok(read_tokens_map(&mut db)?.1)
+}
+
+// the map and the key it's kept under, the first variant when there's none yet
+fn read_tokens_map(
+    db: &mut rusty_leveldb::DB,
+) -> Result<(Vec<u8>, BTreeMap<String, String>), String> {
+    let found = TOKENS_MAP_KEYS
+        .iter()
+        .find_map(|key| Some((key.to_vec(), db.get(key)?)));
+    let Some((key, raw)) = found else {
+        return Ok((TOKENS_MAP_KEYS[0].to_vec(), BTreeMap::new()));
+    };
+    let json = Zeroizing::new(
+        std::str::from_utf8(without_encoding_prefix(&raw))
+            .map_err(|_| "Discord's account list isn't readable text.".to_string())?
+            .to_string(),
+    );
+    let tokens = serde_json::from_str(&json)
+        .map_err(|e| format!("Could not parse Discord's account list: {e}"))?;
+    Ok((key, tokens))
+}
+
+// the accounts Discord's switcher lists, and the key and store they came from;
+// an empty store under the first variant when there's none yet
+fn read_multi_account_store(
+    db: &mut rusty_leveldb::DB,
+) -> Result<(Vec<u8>, serde_json::Value), String> {
+    let found = MULTI_ACCOUNT_STORE_KEYS
+        .iter()
+        .find_map(|key| Some((key.to_vec(), db.get(key)?)));
+    let Some((key, raw)) = found else {
+        return Ok((
+            MULTI_ACCOUNT_STORE_KEYS[0].to_vec(),
+            serde_json::json!({ "_state": { "users": [] }, "_version": 2 }),
+        ));
+    };
+    let store = serde_json::from_slice(without_encoding_prefix(&raw))
+        .map_err(|e| format!("Could not parse Discord's account switcher: {e}"))?;
+    Ok((key, store))
+}
+
+/// One account as Discord's switcher lists it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SwitcherUser {
+    pub id: String,
+    pub username: String,
+    #[serde(default)]
+    pub avatar: Option<String>,
+}
+
+fn read_switcher_users_from_dir(storage_dir: &Path) -> Result<Vec<SwitcherUser>, String> {
+    let mut db = open_storage_db(storage_dir)?;
+    let (_, store) = read_multi_account_store(&mut db)?;
+    let users = store
+        .pointer("/_state/users")
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!([]));
+    serde_json::from_value(users)
+        .map_err(|e| format!("Could not parse Discord's account switcher: {e}"))
+}
+
+// localStorage values start with a byte telling how the string is encoded
+fn without_encoding_prefix(raw: &[u8]) -> &[u8] {
+    match raw.split_first() {
+        Some((0x00 | 0x01, rest)) => rest,
+        _ => raw,
+    }
+}
+
+// stored the way Discord writes its own values
+fn put_latin1(db: &mut rusty_leveldb::DB, key: &[u8], json: &str) -> Result<(), String> {
+    let mut value = Zeroizing::new(vec![0x01]); // Latin-1 encoding prefix
+    value.extend_from_slice(json.as_bytes());
+    db.put(key, &value)
+        .map_err(|e| format!("Failed to write token: {e}"))
+}
+
+fn read_account_token_from_dir(storage_dir: &Path, user_id: &str) -> Result<String, String> {
+    read_tokens_map_from_dir(storage_dir)?
+        .remove(user_id)
+        .ok_or_else(|| "That account isn't logged in to Discord anymore.".to_string())
+}
+
+pub fn read_token_from_dir(storage_dir: &Path, keys: &[Vec<u8>]) -> Result<String, String> {
+    let mut db = open_storage_db(storage_dir)?;
+
+    // Try known key patterns first
+    for key in keys {
+        if let Some(val) = db.get(key) {
+            if let Some(token) = extract_token_from_value(&val) {
+                if token.contains(':') || token.len() > 30 {
+                    return Ok(token);
+                }
+            }
+        }
+    }
+
+    // Fallback: iterate all entries looking for encrypted token marker
+    let mut iter = db
+        .new_iter()
+        .map_err(|e| format!("Failed to iterate Discord storage: {e}"))?;
+
+    let mut key_buf = Vec::new();
+    let mut val_buf = Vec::new();
+
+    iter.reset();
+    while iter.advance() {
+        if iter.current(&mut key_buf, &mut val_buf) {
+            if let Some(token) = extract_token_from_value(&val_buf) {
+                if token.starts_with(ENCRYPTED_TOKEN_PREFIX) {
+                    return Ok(token);
+                }
+            }
+        }
+    }
+
+    Err(NO_TOKEN_FOUND.to_string())
+}
+
+// ── Writing ──
+
+// folders under Discord's user data that only hold caches and per-window
+// state; Discord rebuilds them on the next start
+const CACHE_DIRS: [&str; 5] = [
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "DawnCache",
+    "Session Storage",
+];
+
+/// Delete Discord's caches and session storage next to its Local Storage, so
+/// nothing from the previous account is left around. Discord has to be closed.
+/// Returns how many folders were removed.
+pub fn clear_discord_caches(channel: DiscordChannel) -> Result<usize, String> {
+    let storage_dir = discord_storage_dir(channel)?;
+    let user_data = storage_dir
+        .parent()
+        .and_then(Path::parent)
+        .ok_or_else(|| "Could not find Discord's data folder.".to_string())?;
+    let mut cleared = 0;
+    for name in CACHE_DIRS {
+        let path = user_data.join(name);
+        if !path.is_dir() {
+            continue;
+        }
+        fs::remove_dir_all(&path).map_err(|e| format!("Could not clear {name}: {e}"))?;
+        cleared += 1;
+    }
+    Ok(cleared)
+}
+
+/// Write a token into Discord's LevelDB so it logs in as this account,
+/// encrypted first if this install keeps its token encrypted. Callers back the
+/// database up right before this, so a failed write can be rolled back.
+pub fn write_discord_token(
+    token: &str,
+    keys: &[Vec<u8>],
+    channel: DiscordChannel,
+    strategy: WriteStrategy,
+    force_unlock: bool,
+) -> Result<(), String> {
+    let storage_dir = discord_storage_dir(channel)?;
+    unlock_storage(&storage_dir, force_unlock)?;
+
+    let token = seal_token_for(&storage_dir, token)?;
+    write_token_to_dir(&storage_dir, &token, keys, strategy)
+}
+
+/// Read the token back after [`write_discord_token`] and check it's the one
+/// that was written, so a write that didn't stick never launches Discord into
+/// another account or the login screen.
+pub fn verify_discord_token(
+    token: &str,
+    keys: &[Vec<u8>],
+    channel: DiscordChannel,
+) -> Result<(), String> {
+    let storage_dir = discord_storage_dir(channel)?;
+    let stored = read_token_from_dir(&storage_dir, keys)
+        .map(Zeroizing::new)
+        .map_err(|e| format!("Could not read the token back after writing it: {e}"))?;
+    let stored = decrypt_token(&storage_dir, &stored)
+        .map_err(|e| format!("Could not read the token back after writing it: {e}"))?;
+    if stored.expose() != token {
+        return Err(
+            "Discord's storage holds a different token than the one just written. Try another write strategy."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// `token` the way the client at `storage_dir` expects to find it: sealed
+/// for official installs that encrypt, as-is for third-party clients, which
+/// read localStorage directly and can't open a sealed token.
+pub fn seal_token_for(storage_dir: &Path, token: &str) -> Result<String, String> {
+    match storage_dir_channel(storage_dir) {
+        Some(channel) if channel.is_third_party() => Ok(token.to_string()),
+        _ => encrypt_token_for(storage_dir, token),
+    }
+}
+
+pub fn write_token_to_dir(
+    storage_dir: &Path,
+    token: &str,
+    keys: &[Vec<u8>],
+    strategy: WriteStrategy,
+) -> Result<(), String> {
+    let mut db = open_storage_db(storage_dir)?;
+
+    // Find existing key or use default
+    let key = keys
+        .iter()
+        .find(|k| db.get(k).is_some())
+        .map(|k| k.to_vec())
+        .unwrap_or_else(|| TOKEN_KEYS[0].to_vec());
+
+    let value = encode_token_value(token);
+    match strategy {
+        WriteStrategy::Put => {
+            db.put(&key, &value)
+                .map_err(|e| format!("Failed to write token: {e}"))?;
+        }
+        WriteStrategy::DeleteThenPut => {
+            for k in keys {
+                let _ = db.delete(k);
+            }
+            // make the delete durable on its own before the new value lands
+            db.flush()
+                .map_err(|e| format!("Failed to flush database: {e}"))?;
+            db.put(&key, &value)
+                .map_err(|e| format!("Failed to write token: {e}"))?;
+        }
+        WriteStrategy::AllKeys => {
+            for k in keys {
+                db.put(k, &value)
+                    .map_err(|e| format!("Failed to write token: {e}"))?;
+            }
+        }
+    }
+
+    db.flush()
+        .map_err(|e| format!("Failed to flush database: {e}"))?;
+
+    Ok(())
+}
+
+/// Add `token` to Discord's own account switcher as `user`, leaving the
+/// active account alone, and read it back to check it stuck. Discord has to be
+/// closed unless `force_unlock` is set; the caller backs the database up first.
+pub fn add_account_to_discord_switcher(
+    token: &str,
+    user: &DiscordUser,
+    channel: DiscordChannel,
+    force_unlock: bool,
+) -> Result<(), String> {
+    let storage_dir = discord_storage_dir(channel)?;
+    unlock_storage(&storage_dir, force_unlock)?;
+
+    let sealed = seal_token_for(&storage_dir, token)?;
+    write_switcher_account(&storage_dir, user, &sealed)?;
+
+    let stored = Zeroizing::new(
+        read_account_token_from_dir(&storage_dir, &user.id)
+            .map_err(|e| format!("Could not read the token back after writing it: {e}"))?,
+    );
+    if decrypt_token(&storage_dir, &stored)?.expose() != token {
+        return Err(
+            "Discord's account list holds a different token than the one just written.".to_string(),
+        );
+    }
+    if !read_switcher_users_from_dir(&storage_dir)?
+        .iter()
+        .any(|listed| listed.id == user.id)
+    {
+        return Err("Discord's account switcher doesn't list the account just added.".to_string());
+    }
+    Ok(())
+}
+
+// the `tokens` map entry and the switcher's user record, in one flush; the
+// database is closed again before anything reads it back
+fn write_switcher_account(
+    storage_dir: &Path,
+    user: &DiscordUser,
+    sealed_token: &str,
+) -> Result<(), String> {
+    let mut db = open_storage_db(storage_dir)?;
+
+    let (tokens_key, mut tokens) = read_tokens_map(&mut db)?;
+    tokens.insert(user.id.clone(), sealed_token.to_string());
+    let tokens_json = Zeroizing::new(
+        serde_json::to_string(&tokens)
+            .map_err(|e| format!("Could not serialize Discord's account list: {e}"))?,
+    );
+
+    let (store_key, mut store) = read_multi_account_store(&mut db)?;
+    let users = store
+        .as_object_mut()
+        .map(|store| {
+            store
+                .entry("_state")
+                .or_insert_with(|| serde_json::json!({}))
+        })
+        .and_then(serde_json::Value::as_object_mut)
+        .map(|state| {
+            state
+                .entry("users")
+                .or_insert_with(|| serde_json::json!([]))
+        })
+        .and_then(serde_json::Value::as_array_mut)
+        .ok_or_else(|| "Discord's account switcher isn't in a shape the app knows.".to_string())?;
+    // a stale record for the same account would show twice
+    users.retain(|listed| listed.get("id").and_then(serde_json::Value::as_str) != Some(&user.id));
+    users.push(serde_json::json!({
+        "id": user.id,
+        "username": user.username,
+        "avatar": user.avatar,
+    }));
+    let store_json = serde_json::to_string(&store)
+        .map_err(|e| format!("Could not serialize Discord's account switcher: {e}"))?;
+
+    put_latin1(&mut db, &tokens_key, &tokens_json)?;
+    put_latin1(&mut db, &store_key, &store_json)?;
+    db.flush()
+        .map_err(|e| format!("Failed to flush database: {e}"))
+}
+
+/// The user id a token belongs to: its first part is that id in base64.
+pub fn token_user_id(token: &str) -> Option<String> {
+    let encoded = token.split('.').next()?.trim_end_matches('=');
+    let decoded = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .or_else(|_| STANDARD_NO_PAD.decode(encoded))
+        .ok()?;
+    let id = String::from_utf8(decoded).ok()?;
+    (!id.is_empty() && id.bytes().all(|b| b.is_ascii_digit())).then_some(id)
+}
+
+/// Remove the token from Discord's LevelDB so it shows the login screen,
+/// after backing the database up.
+pub fn delete_discord_token(
+    dir: &DataDir,
+    keys: &[Vec<u8>],
+    channel: DiscordChannel,
+    force_unlock: bool,
+) -> Result<(), String> {
+    let storage_dir = discord_storage_dir(channel)?;
+    unlock_storage(&storage_dir, force_unlock)?;
+    back_up_discord_storage(dir, &storage_dir)?;
+    delete_keys(&storage_dir, keys)
+}
+
+/// Remove the token from one channel's LevelDB.
+pub fn delete_token_from_dir(
+    storage_dir: &Path,
+    keys: &[Vec<u8>],
+    force_unlock: bool,
+) -> Result<(), String> {
+    unlock_storage(storage_dir, force_unlock)?;
+    delete_keys(storage_dir, keys)
+}
+
+// the caller has made sure nothing holds the database
+fn delete_keys(storage_dir: &Path, keys: &[Vec<u8>]) -> Result<(), String> {
+    let mut db = open_storage_db(storage_dir)?;
+
+    for key in keys {
+        let _ = db.delete(key);
+    }
+
+    db.flush()
+        .map_err(|e| format!("Failed to flush database: {e}"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::random_hex;
+
+    // shaped like a token, but not one
+    const TOKEN: &str = "MTIzNDU2Nzg5MDEyMzQ1Njc4OTAx.GaBcDe.abcdefghijklmnopqrstuvwxyz01234";
+    const OTHER_TOKEN: &str = "OTg3NjU0MzIxMDk4NzY1NDMyMTA5.HxYzWv.zyxwvutsrqponmlkjihgfedcba98765";
+    const KEY: &[u8] = b"_https://discord.com\x00\x01token";
+
+    fn raw_value(token: &str) -> Vec<u8> {
+        [b"\x01\"", token.as_bytes(), b"\""].concat()
+    }
+
+    #[test]
+    fn token_after_key_is_found() {
+        let data = [b"\x00junk".as_slice(), KEY, &raw_value(TOKEN), b"\x00more"].concat();
+        assert_eq!(
+            find_token_after_keys(&data, &[KEY.to_vec()]).as_deref(),
+            Some(TOKEN)
+        );
+    }
+
+    #[test]
+    fn latest_write_to_key_wins() {
+        let data = [KEY, &raw_value(OTHER_TOKEN), KEY, &raw_value(TOKEN)].concat();
+        assert_eq!(
+            find_token_after_keys(&data, &[KEY.to_vec()]).as_deref(),
+            Some(TOKEN)
+        );
+    }
+
+    #[test]
+    fn token_without_key_is_ignored() {
+        // e.g. another account's, in the switcher map
+        let data = [b"tokens\x00{\"1234\":".as_slice(), &raw_value(TOKEN), b"}"].concat();
+        assert_eq!(find_token_after_keys(&data, &[KEY.to_vec()]), None);
+
+        let before_key = [raw_value(TOKEN).as_slice(), KEY, b"\x01\"\""].concat();
+        assert_eq!(find_token_after_keys(&before_key, &[KEY.to_vec()]), None);
+    }
+
+    #[test]
+    fn token_shapes_are_recognized() {
+        let encrypted = format!("{ENCRYPTED_TOKEN_PREFIX}djEwAAAAAAAAAAAAAAAAAAAAAAAAAAAA");
+        assert_eq!(
+            find_token_in_bytes(&raw_value(&encrypted)).as_deref(),
+            Some(encrypted.as_str())
+        );
+        assert_eq!(
+            find_token_in_bytes(&raw_value(TOKEN)).as_deref(),
+            Some(TOKEN)
+        );
+        assert_eq!(find_token_in_bytes(b"\x01\"abc.def.ghi\""), None);
+    }
+
+    #[test]
+    fn escaped_key_patterns_round_trip() {
+        let pattern = escape_token_key(KEY);
+        assert_eq!(pattern, "_https://discord.com\\x00\\x01token");
+        assert_eq!(unescape_token_key(&pattern).unwrap(), KEY);
+        assert_eq!(unescape_token_key("a\\\\b").unwrap(), b"a\\b");
+        assert_eq!(unescape_token_key("\\xFF").unwrap(), [0xff]);
+    }
+
+    #[test]
+    fn invalid_key_patterns_are_refused() {
+        for pattern in ["\\x0", "\\xzz", "\\n", "trailing\\"] {
+            assert!(unescape_token_key(pattern).is_err(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn added_account_is_in_tokens_and_switcher() {
+        let storage_dir =
+            env::temp_dir().join(format!("alt-mngr-switcher-test-{}", random_hex::<8>()));
+        fs::create_dir(&storage_dir).unwrap();
+        {
+            // an empty database, as a fresh Discord install has
+            rusty_leveldb::DB::open(&storage_dir, rusty_leveldb::Options::default()).unwrap();
+        }
+        let user: DiscordUser = serde_json::from_value(serde_json::json!({
+            "id": "1234",
+            "username": "alt",
+            "avatar": "abc",
+        }))
+        .unwrap();
+
+        let written = write_switcher_account(&storage_dir, &user, "sealed-token");
+        let tokens = read_tokens_map_from_dir(&storage_dir);
+        let users = read_switcher_users_from_dir(&storage_dir);
+        let _ = fs::remove_dir_all(&storage_dir);
+
+        written.unwrap();
+        assert_eq!(
+            tokens.unwrap().get("1234").map(String::as_str),
+            Some("sealed-token")
+        );
+        assert_eq!(
+            users.unwrap(),
+            vec![SwitcherUser {
+                id: "1234".to_string(),
+                username: "alt".to_string(),
+                avatar: Some("abc".to_string()),
+            }]
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    app_lock::ensure_app_unlocked,
+    audit::{record_audit, AuditAction},
+    clock::now_ms,
+    progress::Operation,
+    settings::DiscordChannel,
+    storage::{copy_leveldb_files, storage_dir_channel, unlock_storage},
+    switch::with_discord_closed,
+    DataDir,
+};
+
+// how many copies are kept; the oldest goes when another one is taken
+const KEPT_BACKUPS: usize = 5;
+const METADATA_FILE: &str = "backup.json";
+
+/// A copy of a Discord client's Local Storage, taken right before the app
+/// wrote to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageBackup {
+    #[serde(skip_deserializing)]
+    pub id: String,
+    /// The `Local Storage/leveldb` folder it was copied from and restores to.
+    pub storage_dir: String,
+    pub created_at_ms: u128,
+    #[serde(skip_deserializing)]
+    pub channel: Option<DiscordChannel>,
+}
+
+/// Copy `storage_dir` into the app's data folder, dropping the oldest copies
+/// beyond the last few. Discord has to be closed, so the files are consistent.
+/// Returns the new backup's id, or None when there was nothing to copy.
+pub(crate) fn back_up_discord_storage(
+    dir: &DataDir,
+    storage_dir: &Path,
+) -> Result<Option<String>, String> {
+    // a client that has never been started has nothing to lose yet
+    if !storage_dir.is_dir() {
+        return Ok(None);
+    }
+    let created_at_ms = now_ms();
+    let backup_dir = dir.storage_backups_dir()?.join(created_at_ms.to_string());
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("Could not back up Discord's storage: {e}"))?;
+    let metadata = StorageBackup {
+        id: String::new(),
+        storage_dir: storage_dir.to_string_lossy().to_string(),
+        created_at_ms,
+        channel: None,
+    };
+    let copied = copy_leveldb_files(storage_dir, &backup_dir).and_then(|_| {
+        let payload = serde_json::to_string_pretty(&metadata)
+            .map_err(|e| format!("Could not serialize backup details: {e}"))?;
+        fs::write(backup_dir.join(METADATA_FILE), payload)
+            .map_err(|e| format!("Could not back up Discord's storage: {e}"))
+    });
+    if let Err(e) = copied {
+        let _ = fs::remove_dir_all(&backup_dir);
+        return Err(e);
+    }
+
+    for old in list_discord_storage_backups(dir)?
+        .into_iter()
+        .skip(KEPT_BACKUPS)
+    {
+        let _ = fs::remove_dir_all(backup_path(dir, &old.id)?);
+    }
+    Ok(Some(created_at_ms.to_string()))
+}
+
+/// Undo a write that went wrong: put back the backup taken right before it,
+/// or remove the storage again when there was none yet. Discord has to be
+/// closed unless `force_unlock` is set.
+pub(crate) fn roll_back_discord_storage(
+    dir: &DataDir,
+    storage_dir: &Path,
+    backup_id: Option<&str>,
+    force_unlock: bool,
+) -> Result<(), String> {
+    unlock_storage(storage_dir, force_unlock)?;
+    match backup_id {
+        Some(backup_id) => replace_leveldb_files(&backup_path(dir, backup_id)?, storage_dir),
+        None => fs::remove_dir_all(storage_dir)
+            .map_err(|e| format!("Could not remove Discord's storage: {e}")),
+    }
+}
+
+/// The copies of Discord's storage taken before each write, newest first.
+pub fn list_discord_storage_backups(dir: &DataDir) -> Result<Vec<StorageBackup>, String> {
+    let entries = fs::read_dir(dir.storage_backups_dir()?)
+        .map_err(|e| format!("Could not read storage backups: {e}"))?;
+    let mut backups: Vec<StorageBackup> = entries
+        .flatten()
+        .filter_map(|entry| read_backup(&entry.path()))
+        .collect();
+    backups.sort_by_key(|backup| Reverse(backup.created_at_ms));
+    Ok(backups)
+}
+
+/// Put a backup's files back in place of the client's current Local Storage,
+/// e.g. after a write left Discord unable to start. Closes Discord first and
+/// leaves it closed; the files being replaced are backed up too, so a restore
+/// can be undone the same way.
+pub fn restore_discord_storage_backup(dir: &DataDir, backup_id: &str) -> Result<String, String> {
+    let result = restore_backup(dir, backup_id);
+    record_audit(dir, AuditAction::Restore, None, Some(backup_id), &result);
+    result
+}
+
+fn restore_backup(dir: &DataDir, backup_id: &str) -> Result<String, String> {
+    ensure_app_unlocked(dir)?;
+    let backup = read_backup(&backup_path(dir, backup_id)?)
+        .ok_or_else(|| "That storage backup doesn't exist anymore.".to_string())?;
+    let storage_dir = PathBuf::from(&backup.storage_dir);
+
+    // taking a backup of the current files may rotate this one away
+    let staged = dir.scratch_dir("restore")?;
+    let result = copy_leveldb_files(&backup_path(dir, backup_id)?, &staged).and_then(|_| {
+        with_discord_closed(dir, Operation::Restore, || {
+            unlock_storage(&storage_dir, false)?;
+            back_up_discord_storage(dir, &storage_dir)?;
+            replace_leveldb_files(&staged, &storage_dir)
+        })
+    });
+    let _ = fs::remove_dir_all(&staged);
+    result?;
+    Ok("Restored Discord's storage from the backup. Start Discord again to use it.".to_string())
+}
+
+// swaps every file of the LevelDB at `storage_dir` for the ones in `from`
+fn replace_leveldb_files(from: &Path, storage_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(storage_dir)
+        .map_err(|e| format!("Could not restore Discord's storage: {e}"))?;
+    let entries = fs::read_dir(storage_dir)
+        .map_err(|e| format!("Could not restore Discord's storage: {e}"))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_file() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Could not restore Discord's storage: {e}"))?;
+        }
+    }
+    copy_leveldb_files(from, storage_dir)?;
+    // the copy brought the metadata along; it isn't part of the database
+    let _ = fs::remove_file(storage_dir.join(METADATA_FILE));
+    Ok(())
+}
+
+fn read_backup(backup_dir: &Path) -> Option<StorageBackup> {
+    let content = fs::read_to_string(backup_dir.join(METADATA_FILE)).ok()?;
+    let mut backup: StorageBackup = serde_json::from_str(&content).ok()?;
+    backup.id = backup_dir.file_name()?.to_string_lossy().to_string();
+    backup.channel = storage_dir_channel(Path::new(&backup.storage_dir));
+    Some(backup)
+}
+
+// backup ids are the millisecond timestamps their folders are named after
+fn backup_path(dir: &DataDir, backup_id: &str) -> Result<PathBuf, String> {
+    if backup_id.is_empty() || !backup_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err("That isn't a storage backup.".to_string());
+    }
+    Ok(dir.storage_backups_dir()?.join(backup_id))
+}
+
+/// Delete every storage backup; they hold the tokens that were in Discord.
+pub(crate) fn remove_discord_storage_backups(dir: &DataDir) -> Result<(), String> {
+    let backups_dir = dir.storage_backups_dir()?;
+    fs::remove_dir_all(&backups_dir)
+        .map_err(|e| format!("Could not delete Discord storage backups: {e}"))
+}
@@ -0,0 +1,206 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{
+    app_lock::ensure_app_unlocked,
+    audit::{record_audit, AuditAction},
+    progress::{report_progress, Operation, ProgressStep},
+    settings::launch_channel,
+    storage::{discord_storage_dir, open_storage_db, repoint_current, unlock_storage},
+    storage_backup::back_up_discord_storage,
+    switch::with_discord_closed,
+    DataDir,
+};
+
+// LevelDB's write-ahead log: 32 KiB blocks of records, each behind a 7-byte
+// header (checksum, length, type); a record too big for a block is split up
+const BLOCK_SIZE: usize = 32 * 1024;
+const HEADER_SIZE: usize = 7;
+const FULL: u8 = 1;
+const FIRST: u8 = 2;
+const MIDDLE: u8 = 3;
+const LAST: u8 = 4;
+// a write batch starts with its sequence number and entry count
+const BATCH_HEADER_SIZE: usize = 12;
+const TAG_DELETION: u8 = 0;
+const TAG_VALUE: u8 = 1;
+
+/// Get Discord's Local Storage opening again after a crash or a bad write left
+/// it damaged (a corrupt MANIFEST, a missing CURRENT). Closes Discord and
+/// backs the damaged files up first. Tries pointing CURRENT at the newest
+/// manifest, which loses nothing; failing that, rebuilds the database from
+/// its write-ahead logs, which keeps recent changes only, so Discord may ask
+/// to log in again.
+pub fn repair_discord_storage(dir: &DataDir) -> Result<String, String> {
+    let result = repair(dir);
+    record_audit(dir, AuditAction::Restore, None, Some("repair"), &result);
+    result
+}
+
+fn repair(dir: &DataDir) -> Result<String, String> {
+    ensure_app_unlocked(dir)?;
+    let storage_dir = discord_storage_dir(launch_channel(dir)?)?;
+    if !storage_dir.is_dir() {
+        return Err(
+            "Discord hasn't created its storage yet; there's nothing to repair.".to_string(),
+        );
+    }
+
+    with_discord_closed(dir, Operation::Repair, || {
+        unlock_storage(&storage_dir, false)?;
+        // nothing is changed until the damaged files are backed up
+        if opens_cleanly(&storage_dir) {
+            return Ok("Discord's storage opens fine; nothing needed repairing.".to_string());
+        }
+        report_progress(dir, Operation::Repair, ProgressStep::BackingUpStorage);
+        back_up_discord_storage(dir, &storage_dir)?;
+
+        if repoint_current(&storage_dir) && open_storage_db(&storage_dir).is_ok() {
+            return Ok(
+                "Discord's storage pointed at a manifest that was gone. It's fixed and nothing was lost."
+                    .to_string(),
+            );
+        }
+        let recovered = rebuild_from_logs(&storage_dir)?;
+        Ok(format!(
+            "Discord's storage was rebuilt from its recent changes ({recovered} entries recovered). Older entries were lost, so Discord may ask you to log in again. The damaged files are in the storage backups."
+        ))
+    })
+}
+
+// CURRENT is checked first: opened without one, LevelDB would start over
+fn opens_cleanly(storage_dir: &Path) -> bool {
+    let current = fs::read_to_string(storage_dir.join("CURRENT")).unwrap_or_default();
+    let current = current.trim();
+    !current.is_empty()
+        && storage_dir.join(current).is_file()
+        && rusty_leveldb::DB::open(storage_dir, rusty_leveldb::Options::default()).is_ok()
+}
+
+// replays every log into a fresh database; tables the manifest lost track of
+// are compressed and can't be read back without it
+fn rebuild_from_logs(storage_dir: &Path) -> Result<usize, String> {
+    let mut logs: Vec<(u64, Vec<u8>)> = fs::read_dir(storage_dir)
+        .map_err(|e| format!("Could not read Discord storage: {e}"))?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let number = name.strip_suffix(".log")?.parse().ok()?;
+            Some((number, fs::read(entry.path()).ok()?))
+        })
+        .collect();
+    logs.sort_by_key(|(number, _)| *number);
+
+    let mut entries = BTreeMap::new();
+    for (_, log) in &logs {
+        for record in log_records(log) {
+            apply_batch(&record, &mut entries);
+        }
+    }
+
+    // everything is in the backup by now
+    for entry in fs::read_dir(storage_dir)
+        .map_err(|e| format!("Could not read Discord storage: {e}"))?
+        .flatten()
+    {
+        let path = entry.path();
+        if path.is_file() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Could not clear the damaged storage: {e}"))?;
+        }
+    }
+    // a new database in the emptied folder
+    let mut db = rusty_leveldb::DB::open(storage_dir, rusty_leveldb::Options::default())
+        .map_err(|e| format!("Could not create the rebuilt storage: {e}"))?;
+    let mut recovered = 0;
+    for (key, value) in &entries {
+        if let Some(value) = value {
+            db.put(key, value)
+                .map_err(|e| format!("Could not write the rebuilt storage: {e}"))?;
+            recovered += 1;
+        }
+    }
+    db.flush()
+        .map_err(|e| format!("Failed to flush database: {e}"))?;
+    Ok(recovered)
+}
+
+// the payloads of a log's records; stops at the first torn or cut-off one
+fn log_records(log: &[u8]) -> Vec<Vec<u8>> {
+    let mut records = Vec::new();
+    let mut pending: Option<Vec<u8>> = None;
+    let mut offset = 0;
+    while offset + HEADER_SIZE <= log.len() {
+        let block_left = BLOCK_SIZE - offset % BLOCK_SIZE;
+        // too little room for a header; the rest of the block is padding
+        if block_left < HEADER_SIZE {
+            offset += block_left;
+            continue;
+        }
+        let length = usize::from(u16::from_le_bytes([log[offset + 4], log[offset + 5]]));
+        let kind = log[offset + 6];
+        let start = offset + HEADER_SIZE;
+        let end = start + length;
+        if length > block_left - HEADER_SIZE || end > log.len() {
+            break;
+        }
+        let payload = &log[start..end];
+        match kind {
+            FULL => records.push(payload.to_vec()),
+            FIRST => pending = Some(payload.to_vec()),
+            MIDDLE => {
+                if let Some(record) = &mut pending {
+                    record.extend_from_slice(payload);
+                }
+            }
+            LAST => {
+                if let Some(mut record) = pending.take() {
+                    record.extend_from_slice(payload);
+                    records.push(record);
+                }
+            }
+            // zeroed space a crash left behind
+            _ => {}
+        }
+        offset = end;
+    }
+    records
+}
+
+// later batches win, and a deletion hides an earlier value
+fn apply_batch(batch: &[u8], entries: &mut BTreeMap<Vec<u8>, Option<Vec<u8>>>) {
+    let Some(mut rest) = batch.get(BATCH_HEADER_SIZE..) else {
+        return;
+    };
+    while let Some((&tag, after_tag)) = rest.split_first() {
+        let Some((key, after_key)) = length_prefixed(after_tag) else {
+            return;
+        };
+        match tag {
+            TAG_VALUE => {
+                let Some((value, after_value)) = length_prefixed(after_key) else {
+                    return;
+                };
+                entries.insert(key.to_vec(), Some(value.to_vec()));
+                rest = after_value;
+            }
+            TAG_DELETION => {
+                entries.insert(key.to_vec(), None);
+                rest = after_key;
+            }
+            _ => return,
+        }
+    }
+}
+
+// a varint32 length and that many bytes
+fn length_prefixed(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let mut length = 0usize;
+    for (i, byte) in data.iter().take(5).enumerate() {
+        length |= usize::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            let rest = &data[i + 1..];
+            return (length <= rest.len()).then(|| rest.split_at(length));
+        }
+    }
+    None
+}
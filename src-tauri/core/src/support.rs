@@ -0,0 +1,153 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    backup::BACKUP_FORMAT_VERSION, diagnostics::get_diagnostics,
+    markers::load_modification_markers, settings::load_launcher_settings,
+    storage::list_token_key_patterns, upgrades::PROFILES_SCHEMA_VERSION, DataDir,
+};
+
+// stands in for anything taken out of the bundle
+const REDACTED: &str = "<redacted>";
+// shortest string the token scrubber treats as a possible Discord token
+const MIN_TOKEN_LEN: usize = 50;
+
+/// Which format version wrote each file, so a report can be matched to the
+/// code that reads it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SchemaVersions {
+    app_version: String,
+    backup_format_version: u32,
+    profiles_schema_version: u32,
+    /// Data file name -> app version that last wrote it.
+    data_files: BTreeMap<String, String>,
+}
+
+/// Write a zip for bug reports: diagnostics, settings, token key patterns,
+/// the activity log and schema versions. Never holds a token; the app
+/// password hash, the home folder and the user name are redacted.
+pub fn export_support_bundle(dir: &DataDir, path: &Path) -> Result<String, String> {
+    let redactor = Redactor::for_current_user();
+    let mut entries: Vec<(&str, String)> = Vec::new();
+
+    let diagnostics = get_diagnostics(dir)?;
+    entries.push(("diagnostics.json", to_json(&diagnostics)?));
+
+    let mut settings = serde_json::to_value(load_launcher_settings(&dir.launcher_settings_file())?)
+        .map_err(|e| format!("Could not serialize settings: {e}"))?;
+    if let Some(hash) = settings.get_mut("appPasswordHash") {
+        if !hash.is_null() {
+            *hash = Value::from(REDACTED);
+        }
+    }
+    entries.push(("settings.json", to_json(&settings)?));
+
+    entries.push(("token-keys.json", to_json(&list_token_key_patterns(dir)?)?));
+
+    let schema = SchemaVersions {
+        app_version: crate::VERSION.to_string(),
+        backup_format_version: BACKUP_FORMAT_VERSION,
+        profiles_schema_version: PROFILES_SCHEMA_VERSION,
+        data_files: load_modification_markers(dir.root())
+            .into_iter()
+            .map(|(name, marker)| (name, marker.app_version))
+            .collect(),
+    };
+    entries.push(("schema.json", to_json(&schema)?));
+
+    // already token-free, but error messages can carry paths
+    if let Ok(log) = fs::read_to_string(dir.audit_log_file()) {
+        entries.push(("audit.jsonl", log));
+    }
+
+    let file =
+        fs::File::create(path).map_err(|e| format!("Could not create support bundle: {e}"))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in &entries {
+        zip.start_file(*name, options)
+            .map_err(|e| format!("Could not write {name} to support bundle: {e}"))?;
+        zip.write_all(redactor.apply(content).as_bytes())
+            .map_err(|e| format!("Could not write {name} to support bundle: {e}"))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Could not finish support bundle: {e}"))?;
+
+    Ok(format!(
+        "Saved a support bundle with {} files to {}.",
+        entries.len(),
+        path.display()
+    ))
+}
+
+fn to_json(value: &impl Serialize) -> Result<String, String> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Could not serialize support bundle: {e}"))
+}
+
+// ── Redaction ──
+
+struct Redactor {
+    home: Option<PathBuf>,
+    user: Option<String>,
+}
+
+impl Redactor {
+    fn for_current_user() -> Self {
+        let home = env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(PathBuf::from);
+        let user = env::var("USER")
+            .or_else(|_| env::var("USERNAME"))
+            .ok()
+            // too short a name would blank out unrelated text
+            .filter(|user| user.len() >= 3);
+        Self { home, user }
+    }
+
+    fn apply(&self, text: &str) -> String {
+        let mut text = scrub_token_shapes(text);
+        if let Some(home) = &self.home {
+            let home = home.to_string_lossy();
+            if !home.is_empty() {
+                text = text.replace(home.as_ref(), "~");
+                // JSON escapes Windows path separators
+                text = text.replace(&home.replace('\\', "\\\\"), "~");
+            }
+        }
+        if let Some(user) = &self.user {
+            text = text.replace(user.as_str(), REDACTED);
+        }
+        text
+    }
+}
+
+// nothing in the bundle should hold a token, but blank anything shaped like
+// one (three base64 parts joined by dots) in case it ends up in an error message
+fn scrub_token_shapes(text: &str) -> String {
+    let is_token_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-');
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+    for c in text.chars().chain(std::iter::once('\n')) {
+        if is_token_char(c) {
+            word.push(c);
+            continue;
+        }
+        let looks_like_token = word.len() >= MIN_TOKEN_LEN
+            && word.split('.').count() == 3
+            && word.split('.').all(|part| !part.is_empty());
+        out.push_str(if looks_like_token { REDACTED } else { &word });
+        word.clear();
+        out.push(c);
+    }
+    out.pop();
+    out
+}
@@ -0,0 +1,669 @@
+use serde::Serialize;
+use std::{thread, time::Duration};
+
+use crate::{
+    api::fetch_discord_user,
+    app_lock::ensure_app_unlocked,
+    audit::{record_audit, AuditAction},
+    biometric::{require_biometric, BiometricAction},
+    call_guard::ensure_not_in_call,
+    capture_wizard::{awaiting_login, captured},
+    clock::now_ms,
+    devtools::{launch_with_devtools, read_token_via_devtools},
+    http::OFFLINE_MODE,
+    launch::{
+        is_discord_running, launch_discord, resolve_launch_target, terminate_discord,
+        NEEDS_ELEVATION,
+    },
+    notifications::{apply_notification_preset, NotificationPreset},
+    operation_history::run_recorded,
+    plugins::{dispatch_plugin_event, PluginEventKind},
+    preflight::run_preflight,
+    presence::{clear_rich_presence, show_rich_presence},
+    profiles::{
+        build_capture_notes, find_profile, load_profiles, save_profiles, Profile, StoredProfile,
+        TokenStatus, TOKEN_QUARANTINED,
+    },
+    progress::{report_progress, Operation, ProgressStep},
+    protection::{ensure_switch_confirmed, SwitchConfirmation},
+    run_as::launch_as_windows_user,
+    sandbox::{launch_sandboxed, SandboxTool},
+    session_import::preserve_session,
+    sessions::{record_session, session_profile_id},
+    settings::{launch_channel, load_launcher_settings, CaptureMode, DiscordChannel},
+    storage::{
+        add_account_to_discord_switcher, clear_discord_caches, delete_discord_token,
+        discord_storage_dir, read_discord_token, read_discord_token_from_snapshot, token_keys,
+        unlock_storage, verify_discord_token, write_discord_token, NO_TOKEN_FOUND, STORAGE_LOCKED,
+    },
+    storage_backup::{back_up_discord_storage, roll_back_discord_storage},
+    tokens::{
+        channel_token_id, load_profile_token, load_switch_token, profile_has_token,
+        save_channel_token, save_profile_token,
+    },
+    updater::{update_discord, UpdateOutcome},
+    vault::ensure_unlocked,
+    DataDir,
+};
+
+/// What a switch reports back: the status line plus the profile's reminder, if it has one.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchOutcome {
+    pub message: String,
+    pub reminder: Option<String>,
+}
+
+// how long Discord gets to exit and release its LevelDB after we kill it
+const DISCORD_EXIT_GRACE: Duration = Duration::from_millis(2000);
+// how many times we close Discord again when its updater keeps relaunching it
+const STORAGE_LOCK_ATTEMPTS: u32 = 3;
+
+/// Close Discord, wipe the stored token, and relaunch so the user
+/// lands on the login screen and can enter credentials.
+/// Refuses while Discord seems to be in a call unless `ignore_call` is set,
+/// and while it still holds its storage after being closed unless `force_unlock` is.
+pub fn prepare_login(
+    dir: &DataDir,
+    ignore_call: bool,
+    force_unlock: bool,
+) -> Result<String, String> {
+    // it reads the live session and saves it to a profile
+    ensure_app_unlocked(dir)?;
+    ensure_unlocked(dir)?;
+    ensure_not_in_call(ignore_call)?;
+    let keys = token_keys(dir)?;
+    let channel = launch_channel(dir)?;
+    run_preflight(dir, channel)?;
+
+    // whoever is logged in now would otherwise be gone for good
+    report_progress(dir, Operation::Login, ProgressStep::SavingToken);
+    let preserved = match read_discord_token_from_snapshot(dir, &keys, None, channel) {
+        Ok(token) => preserve_session(dir, &token, channel).map_err(|e| {
+            format!("Could not save the account that's logged in before logging it out: {e}")
+        })?,
+        Err(e) if e == NO_TOKEN_FOUND => None,
+        // the storage backup taken before clearing still has it
+        Err(e) => {
+            eprintln!("Warning: could not read the session before clearing it: {e}");
+            None
+        }
+    };
+
+    // Clear the token from Discord's LevelDB so login screen appears
+    let cleared = with_discord_closed(dir, Operation::Login, || {
+        report_progress(dir, Operation::Login, ProgressStep::ClearingToken);
+        delete_discord_token(dir, &keys, channel, force_unlock)
+    });
+    match cleared {
+        // launching on top of an elevated Discord would just focus it, logged in
+        Err(e) if e.starts_with(NEEDS_ELEVATION) => return Err(e),
+        // relaunching would leave the login half-cleared under a live Discord
+        Err(e) if e.starts_with(STORAGE_LOCKED) => return Err(e),
+        Err(e) => eprintln!("Warning: could not clear token: {e}"),
+        Ok(()) => {}
+    }
+
+    let settings = load_launcher_settings(&dir.launcher_settings_file())?;
+    let capture_mode = settings.capture_mode;
+    let target = resolve_launch_target(settings)?;
+    report_progress(
+        dir,
+        Operation::Login,
+        ProgressStep::LaunchingDiscord {
+            label: target.label.clone(),
+        },
+    );
+    // so the capture that follows can attach instead of restarting Discord
+    if capture_mode == CaptureMode::DevTools {
+        launch_with_devtools(&target)?;
+    } else {
+        launch_discord(&target)?;
+    }
+    awaiting_login();
+
+    let mut message =
+        "Discord launched. Log in with your account, then capture the token.".to_string();
+    if let Some(nickname) = preserved {
+        message.push_str(&format!(
+            " The account that was logged in was saved to '{nickname}'."
+        ));
+    }
+    Ok(message)
+}
+
+/// Pull the token out of Discord's LevelDB and stash it for this profile,
+/// closing Discord first unless the capture mode reads a snapshot. `account`
+/// picks one of the users logged in through Discord's own account switcher.
+/// `channel` reads another client than the one launches use and keeps the
+/// token as the profile's token for that channel.
+pub fn capture_token(
+    dir: &DataDir,
+    profile_id: &str,
+    account: Option<&str>,
+    channel: Option<DiscordChannel>,
+) -> Result<Profile, String> {
+    let result = run_recorded(dir, profile_id, Operation::Capture, || {
+        close_and_capture(dir, profile_id, account, channel)
+    });
+    record_audit(dir, AuditAction::Capture, Some(profile_id), None, &result);
+    dispatch_plugin_event(dir, PluginEventKind::Capture, Some(profile_id), &result);
+    result
+}
+
+fn close_and_capture(
+    dir: &DataDir,
+    profile_id: &str,
+    account: Option<&str>,
+    channel: Option<DiscordChannel>,
+) -> Result<Profile, String> {
+    find_profile(dir, profile_id)?;
+    ensure_app_unlocked(dir)?;
+    ensure_unlocked(dir)?;
+
+    let keys = token_keys(dir)?;
+    let mode = load_launcher_settings(&dir.launcher_settings_file())?.capture_mode;
+    let read_from = match channel {
+        Some(channel) => channel,
+        None => launch_channel(dir)?,
+    };
+    let token = match mode {
+        // nothing is written to Discord's storage, so the preflight has nothing to check
+        CaptureMode::Snapshot => {
+            report_progress(dir, Operation::Capture, ProgressStep::ReadingToken);
+            read_discord_token_from_snapshot(dir, &keys, account, read_from)?
+        }
+        // the page only knows the active account of the client it launched;
+        // anything else is in storage
+        CaptureMode::DevTools if account.is_some() || channel.is_some() => {
+            report_progress(dir, Operation::Capture, ProgressStep::ReadingToken);
+            read_discord_token_from_snapshot(dir, &keys, account, read_from)?
+        }
+        CaptureMode::DevTools => read_token_via_devtools(dir)?,
+        CaptureMode::CloseDiscord => {
+            run_preflight(dir, read_from)?;
+            with_discord_closed(dir, Operation::Capture, || {
+                report_progress(dir, Operation::Capture, ProgressStep::ReadingToken);
+                read_discord_token(dir, &keys, account, read_from)
+            })?
+        }
+    };
+    report_progress(dir, Operation::Capture, ProgressStep::SavingToken);
+    // from a chosen channel it becomes that channel's own token, and the main
+    // one only when there is none yet
+    if let Some(channel) = channel.filter(|c| channel_token_id(profile_id, *c).is_some()) {
+        save_channel_token(dir, profile_id, channel, token.expose())?;
+        if profile_has_token(dir, profile_id) {
+            captured(profile_id);
+            return Ok(find_profile(dir, profile_id)?.into_profile(true));
+        }
+    }
+    let updated = store_captured_token(dir, profile_id, token.expose())?;
+
+    Ok(updated.into_profile(true))
+}
+
+/// Save a freshly captured token and refresh the profile's account info.
+pub fn store_captured_token(
+    dir: &DataDir,
+    profile_id: &str,
+    token: &str,
+) -> Result<StoredProfile, String> {
+    save_profile_token(dir, profile_id, token)?;
+
+    // best effort: a failed lookup still leaves a template the user can fill in
+    let user = match fetch_discord_user(token) {
+        Ok(user) => Some(user),
+        Err(e) => {
+            eprintln!("Warning: could not fetch account info: {e}");
+            None
+        }
+    };
+
+    let file_path = dir.profiles_file();
+    let mut profiles = load_profiles(&file_path)?;
+    let stored = profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| "Profile not found.".to_string())?;
+    if let Some(user) = &user {
+        stored.discord_user_id = Some(user.id.clone());
+        stored.token_status = TokenStatus::Valid;
+        stored.token_checked_at_ms = Some(now_ms());
+    } else if stored.token_status == TokenStatus::Quarantined {
+        // the rejected token is gone; the new one hasn't been judged yet
+        stored.token_status = TokenStatus::Unknown;
+    }
+    if stored.notes.trim().is_empty() {
+        stored.notes = build_capture_notes(user.as_ref(), now_ms());
+    }
+
+    let updated = stored.clone();
+    save_profiles(&file_path, &profiles)?;
+    captured(profile_id);
+    Ok(updated)
+}
+
+/// Inject this profile's saved token back into Discord's storage and launch it.
+/// Refuses while Discord seems to be in a call unless `ignore_call` is set,
+/// while it still holds its storage after being closed unless `force_unlock`
+/// is, and for protected profiles without a `confirmation`.
+pub fn switch_to_profile(
+    dir: &DataDir,
+    profile_id: &str,
+    ignore_call: bool,
+    force_unlock: bool,
+    confirmation: Option<&SwitchConfirmation>,
+) -> Result<SwitchOutcome, String> {
+    let result = run_recorded(dir, profile_id, Operation::Switch, || {
+        close_and_switch(dir, profile_id, ignore_call, force_unlock, confirmation)
+    });
+    record_audit(dir, AuditAction::Switch, Some(profile_id), None, &result);
+    dispatch_plugin_event(dir, PluginEventKind::Switch, Some(profile_id), &result);
+    result
+}
+
+fn close_and_switch(
+    dir: &DataDir,
+    profile_id: &str,
+    ignore_call: bool,
+    force_unlock: bool,
+    confirmation: Option<&SwitchConfirmation>,
+) -> Result<SwitchOutcome, String> {
+    let separate = separate_launch(&find_profile(dir, profile_id)?);
+    // before the biometric prompt, so a refused switch doesn't ask for a fingerprint;
+    // a profile started separately doesn't close the normal Discord
+    if separate.is_none() {
+        ensure_not_in_call(ignore_call)?;
+    }
+    authorize_switch(dir, profile_id, confirmation)?;
+    if let Some(launch) = separate {
+        return launch_separately(dir, profile_id, launch);
+    }
+    let channel = launch_channel(dir)?;
+    let token = load_switch_token(dir, profile_id, channel)?;
+    run_preflight(dir, channel)?;
+    let update_note = update_before_switch(dir);
+
+    let mut outcome = with_discord_closed(dir, Operation::Switch, || {
+        inject_profile_token(dir, profile_id, token.expose(), channel, true, force_unlock)
+    })?;
+    if let Some(note) = update_note {
+        outcome.message.push_str(&format!(" {note}"));
+    }
+    Ok(outcome)
+}
+
+/// Hot add: put this profile's token into Discord's own account switcher next
+/// to whoever is logged in, instead of replacing them, and start Discord again
+/// so the account can be picked there. Goes through the same checks as a
+/// switch; a write that fails puts the storage back.
+pub fn hot_add_profile(
+    dir: &DataDir,
+    profile_id: &str,
+    ignore_call: bool,
+    force_unlock: bool,
+) -> Result<String, String> {
+    let result = close_and_hot_add(dir, profile_id, ignore_call, force_unlock);
+    record_audit(
+        dir,
+        AuditAction::Switch,
+        Some(profile_id),
+        Some("hot add"),
+        &result,
+    );
+    result
+}
+
+fn close_and_hot_add(
+    dir: &DataDir,
+    profile_id: &str,
+    ignore_call: bool,
+    force_unlock: bool,
+) -> Result<String, String> {
+    let profile = find_profile(dir, profile_id)?;
+    if separate_launch(&profile).is_some() {
+        return Err(
+            "This profile runs in its own Discord, so it can't join the normal one's account switcher."
+                .to_string(),
+        );
+    }
+    ensure_not_in_call(ignore_call)?;
+    authorize_switch(dir, profile_id, None)?;
+    let channel = launch_channel(dir)?;
+    let token = load_switch_token(dir, profile_id, channel)?;
+    // the switcher lists the account by name and avatar, not just its id
+    let user = fetch_discord_user(token.expose())
+        .map_err(|e| format!("Could not look up the account to add: {e}"))?;
+    run_preflight(dir, channel)?;
+
+    with_discord_closed(dir, Operation::Switch, || {
+        let storage_dir = discord_storage_dir(channel)?;
+        report_progress(dir, Operation::Switch, ProgressStep::BackingUpStorage);
+        unlock_storage(&storage_dir, force_unlock)?;
+        let backup = back_up_discord_storage(dir, &storage_dir)?;
+
+        report_progress(dir, Operation::Switch, ProgressStep::WritingToken);
+        let added = add_account_to_discord_switcher(token.expose(), &user, channel, force_unlock);
+        match added {
+            Err(e) if e.starts_with(STORAGE_LOCKED) => return Err(e),
+            Err(e) => {
+                let rolled_back =
+                    roll_back_discord_storage(dir, &storage_dir, backup.as_deref(), force_unlock);
+                return Err(match rolled_back {
+                    Ok(()) => format!(
+                        "Adding the account stopped, and Discord's storage was put back the way it was: {e}"
+                    ),
+                    Err(rollback_error) => format!(
+                        "Adding the account stopped: {e} Putting Discord's storage back failed too ({rollback_error}); restore it from a backup in the settings."
+                    ),
+                });
+            }
+            Ok(()) => {}
+        }
+
+        let target = resolve_launch_target(load_launcher_settings(&dir.launcher_settings_file())?)?;
+        report_progress(
+            dir,
+            Operation::Switch,
+            ProgressStep::LaunchingDiscord {
+                label: target.label.clone(),
+            },
+        );
+        launch_discord(&target)?;
+        Ok(format!(
+            "Added '{}' to Discord's account switcher. Pick it there to switch.",
+            profile.nickname
+        ))
+    })
+}
+
+// best effort, like the notification preset: an update that can't be checked
+// or fails shouldn't cost the user their switch
+fn update_before_switch(dir: &DataDir) -> Option<String> {
+    let settings = load_launcher_settings(&dir.launcher_settings_file()).ok()?;
+    if !settings.update_before_switch {
+        return None;
+    }
+    let target = resolve_launch_target(settings).ok()?;
+    report_progress(
+        dir,
+        Operation::Switch,
+        ProgressStep::UpdatingDiscord {
+            label: target.label.clone(),
+        },
+    );
+    match update_discord(&target) {
+        Ok(UpdateOutcome::Updated { from, to }) => {
+            Some(format!("{} was updated from {from} to {to}.", target.label))
+        }
+        Ok(UpdateOutcome::UpToDate | UpdateOutcome::Unsupported) => None,
+        // the user asked for no network traffic; that's not worth a warning
+        Err(e) if e == OFFLINE_MODE => None,
+        Err(e) => Some(format!("Could not update {} first: {e}", target.label)),
+    }
+}
+
+/// Close Discord and run `op` against its storage. When Discord is back before
+/// `op` gets to it (its updater relaunching it), close it again and start over,
+/// a few times at most, instead of failing on a locked database.
+pub(crate) fn with_discord_closed<T>(
+    dir: &DataDir,
+    operation: Operation,
+    op: impl Fn() -> Result<T, String>,
+) -> Result<T, String> {
+    let mut attempt = 1;
+    loop {
+        let step = if attempt == 1 {
+            ProgressStep::ClosingDiscord
+        } else {
+            ProgressStep::RetryingAfterRestart { attempt }
+        };
+        report_progress(dir, operation, step);
+        terminate_discord()?;
+        // give a freshly relaunched Discord a little longer to let go each time
+        thread::sleep(DISCORD_EXIT_GRACE * attempt);
+        match op() {
+            Err(e) if e.starts_with(STORAGE_LOCKED) && attempt < STORAGE_LOCK_ATTEMPTS => {
+                attempt += 1
+            }
+            Err(e) if e.starts_with(STORAGE_LOCKED) => {
+                return Err(format!(
+                    "{STORAGE_LOCKED} It kept starting again (probably to install an update) {STORAGE_LOCK_ATTEMPTS} times. Let the update finish, then try again."
+                ))
+            }
+            other => return other,
+        }
+    }
+}
+
+/// The checks a switch needs before anything is touched: app lock, a token
+/// that isn't quarantined, the confirmation for switching into or out of a
+/// protected profile, biometric
+/// confirmation and a loadable token. A queued switch runs them when queued, while the user is still at
+/// the keyboard.
+pub fn authorize_switch(
+    dir: &DataDir,
+    profile_id: &str,
+    confirmation: Option<&SwitchConfirmation>,
+) -> Result<(), String> {
+    ensure_app_unlocked(dir)?;
+    let profile = find_profile(dir, profile_id)?;
+    // injecting it would only restart Discord into the login screen
+    if profile.token_status == TokenStatus::Quarantined {
+        return Err(TOKEN_QUARANTINED.to_string());
+    }
+    let leaving = load_launcher_settings(&dir.launcher_settings_file())
+        .and_then(resolve_launch_target)
+        .ok()
+        .and_then(|target| session_profile_id(dir, target.channel))
+        .and_then(|id| find_profile(dir, &id).ok());
+    ensure_switch_confirmed(dir, &profile, leaving.as_ref(), confirmation)?;
+    require_biometric(dir, BiometricAction::SwitchProfile)?;
+    // its session lives in that Windows user's or sandbox's own Discord storage
+    if separate_launch(&profile).is_some() {
+        return Ok(());
+    }
+    load_profile_token(dir, profile_id).map(drop)
+}
+
+/// Finish a queued switch once the user has quit Discord themselves:
+/// inject the token and, if asked, start Discord again.
+/// Refuses while Discord is still running instead of killing it.
+pub fn switch_after_discord_exit(
+    dir: &DataDir,
+    profile_id: &str,
+    relaunch: bool,
+) -> Result<SwitchOutcome, String> {
+    let result = run_recorded(dir, profile_id, Operation::Switch, || {
+        switch_if_closed(dir, profile_id, relaunch)
+    });
+    record_audit(
+        dir,
+        AuditAction::Switch,
+        Some(profile_id),
+        Some("queued"),
+        &result,
+    );
+    dispatch_plugin_event(dir, PluginEventKind::Switch, Some(profile_id), &result);
+    result
+}
+
+fn switch_if_closed(
+    dir: &DataDir,
+    profile_id: &str,
+    relaunch: bool,
+) -> Result<SwitchOutcome, String> {
+    // the app may have been locked while the switch was waiting
+    ensure_app_unlocked(dir)?;
+    if let Some(launch) = separate_launch(&find_profile(dir, profile_id)?) {
+        if relaunch {
+            return launch_separately(dir, profile_id, launch);
+        }
+        return Ok(SwitchOutcome {
+            message: format!("Nothing to do: this account runs {}.", launch.describe()),
+            reminder: None,
+        });
+    }
+    if is_discord_running() {
+        return Err(STORAGE_LOCKED.to_string());
+    }
+    let channel = launch_channel(dir)?;
+    let token = load_switch_token(dir, profile_id, channel)?;
+    run_preflight(dir, channel)?;
+    inject_profile_token(dir, profile_id, token.expose(), channel, relaunch, false)
+}
+
+// profiles whose Discord keeps its own storage, as another Windows user or
+// in a sandbox, and so is started next to the normal one instead of switched into
+enum SeparateLaunch {
+    WindowsUser(String),
+    Sandbox(SandboxTool),
+}
+
+impl SeparateLaunch {
+    fn describe(&self) -> String {
+        match self {
+            SeparateLaunch::WindowsUser(account) => format!("as {account}"),
+            SeparateLaunch::Sandbox(tool) => format!("in its {} sandbox", tool.label()),
+        }
+    }
+}
+
+fn separate_launch(profile: &StoredProfile) -> Option<SeparateLaunch> {
+    match (&profile.windows_account, profile.sandbox) {
+        (Some(account), _) => Some(SeparateLaunch::WindowsUser(account.clone())),
+        (None, Some(tool)) => Some(SeparateLaunch::Sandbox(tool)),
+        (None, None) => None,
+    }
+}
+
+// the normal Discord is left alone
+fn launch_separately(
+    dir: &DataDir,
+    profile_id: &str,
+    launch: SeparateLaunch,
+) -> Result<SwitchOutcome, String> {
+    let profile = find_profile(dir, profile_id)?;
+    let target = resolve_launch_target(load_launcher_settings(&dir.launcher_settings_file())?)?;
+    let place = launch.describe();
+    report_progress(
+        dir,
+        Operation::Switch,
+        ProgressStep::LaunchingDiscord {
+            label: format!("{} {place}", target.label),
+        },
+    );
+    match launch {
+        SeparateLaunch::WindowsUser(account) => {
+            launch_as_windows_user(profile_id, &account, &target)?
+        }
+        SeparateLaunch::Sandbox(tool) => launch_sandboxed(dir, profile_id, tool, &target)?,
+    }
+    Ok(SwitchOutcome {
+        message: format!("Started Discord {place} for '{}'.", profile.nickname),
+        reminder: profile.switch_reminder,
+    })
+}
+
+// Discord must already be closed. Backs its storage up, then clears caches if
+// the profile asks for it, writes, checks and launches; when any of those fails
+// the backup goes back in place, so the previous account stays logged in
+// instead of a half-finished switch.
+fn inject_profile_token(
+    dir: &DataDir,
+    profile_id: &str,
+    token: &str,
+    channel: DiscordChannel,
+    relaunch: bool,
+    force_unlock: bool,
+) -> Result<SwitchOutcome, String> {
+    let profile = find_profile(dir, profile_id)?;
+    let keys = token_keys(dir)?;
+    let storage_dir = discord_storage_dir(channel)?;
+    report_progress(dir, Operation::Switch, ProgressStep::BackingUpStorage);
+    unlock_storage(&storage_dir, force_unlock)?;
+    let backup = back_up_discord_storage(dir, &storage_dir)?;
+
+    let written = write_and_launch(dir, &profile, token, &keys, channel, relaunch, force_unlock);
+    match written {
+        // Discord came back before anything was written; closed again and retried
+        Err((_, e)) if e.starts_with(STORAGE_LOCKED) => return Err(e),
+        Err((stage, e)) => {
+            let rolled_back =
+                roll_back_discord_storage(dir, &storage_dir, backup.as_deref(), force_unlock);
+            return Err(match rolled_back {
+                Ok(()) => format!(
+                    "The switch stopped while {stage}, and Discord's storage was put back the way it was: {e}"
+                ),
+                Err(rollback_error) => format!(
+                    "The switch stopped while {stage}: {e} Putting Discord's storage back failed too ({rollback_error}); restore it from a backup in the settings."
+                ),
+            });
+        }
+        Ok(()) => {}
+    }
+    // the old client, and with it any presence we set, is gone either way
+    match profile.rich_presence.clone().filter(|_| relaunch) {
+        Some(presence) => show_rich_presence(presence),
+        None => clear_rich_presence(),
+    }
+
+    let mut message = format!("Switched to '{}'.", profile.nickname);
+    if profile.notification_preset != NotificationPreset::Unchanged {
+        report_progress(
+            dir,
+            Operation::Switch,
+            ProgressStep::ApplyingNotificationPreset,
+        );
+    }
+    // best effort: the switch itself already worked
+    match apply_notification_preset(token, profile.notification_preset) {
+        Ok(0) => {}
+        Ok(count) => message.push_str(&format!(" Notification preset applied to {count} servers.")),
+        Err(e) => message.push_str(&format!(" Could not apply the notification preset: {e}")),
+    }
+
+    Ok(SwitchOutcome {
+        message,
+        reminder: profile.switch_reminder,
+    })
+}
+
+// the stages a switch can be rolled back from, each failing with its name
+fn write_and_launch(
+    dir: &DataDir,
+    profile: &StoredProfile,
+    token: &str,
+    keys: &[Vec<u8>],
+    channel: DiscordChannel,
+    relaunch: bool,
+    force_unlock: bool,
+) -> Result<(), (&'static str, String)> {
+    let settings = load_launcher_settings(&dir.launcher_settings_file())
+        .map_err(|e| ("writing the token", e))?;
+    // after the backup, so a switch refused before it leaves the caches alone
+    if profile.clear_cache_on_switch {
+        report_progress(dir, Operation::Switch, ProgressStep::ClearingCache);
+        clear_discord_caches(channel).map_err(|e| ("clearing Discord's caches", e))?;
+    }
+    report_progress(dir, Operation::Switch, ProgressStep::WritingToken);
+    write_discord_token(token, keys, channel, settings.write_strategy, force_unlock)
+        .map_err(|e| ("writing the token", e))?;
+    report_progress(dir, Operation::Switch, ProgressStep::VerifyingToken);
+    verify_discord_token(token, keys, channel).map_err(|e| ("checking the written token", e))?;
+
+    if relaunch {
+        let target = resolve_launch_target(settings).map_err(|e| ("launching Discord", e))?;
+        report_progress(
+            dir,
+            Operation::Switch,
+            ProgressStep::LaunchingDiscord {
+                label: target.label.clone(),
+            },
+        );
+        launch_discord(&target).map_err(|e| ("launching Discord", e))?;
+        record_session(dir, target.channel, &profile.id);
+    }
+    Ok(())
+}
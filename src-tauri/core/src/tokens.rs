@@ -0,0 +1,731 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use zeroize::Zeroizing;
+
+use crate::{
+    app_lock::ensure_app_unlocked,
+    audit::{record_audit, AuditAction},
+    biometric::{require_biometric, BiometricAction},
+    capabilities::ensure_token_backend_supported,
+    crypto::{open_string, random_key, seal, SecretKey},
+    permissions::restrict_permissions,
+    profile_events::profile_updated,
+    profiles::{find_profile, load_profiles, Profile},
+    secret::SecretToken,
+    settings::{
+        load_launcher_settings, peek_launcher_settings, save_launcher_settings_to_file,
+        DiscordChannel, TokenBackend,
+    },
+    storage::resolve_storage_channel,
+    vault::wrap_token_store,
+    DataDir,
+};
+
+// characters shown at each end of a masked token
+const PREVIEW_CHARS: usize = 4;
+
+/// Keychain entries are filed under the app's bundle identifier.
+pub const KEYCHAIN_SERVICE: &str = "com.filip.alt-mngr";
+// keychain account holding the encrypted file store's key; can't clash with a profile id
+const FILE_KEY_ACCOUNT: &str = "token-file-key";
+// plain token files carry this prefix once they're bound to this machine
+const MACHINE_SEALED_PREFIX: &str = "machine:v1:";
+// what a machine-bound token that can't be opened here most likely means
+const FOREIGN_MACHINE: &str =
+    "This token was saved on another computer or by another user. Log in to the account again.";
+
+/// What moving tokens to another backend did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenMigration {
+    pub from: TokenBackend,
+    pub to: TokenBackend,
+    pub moved: usize,
+}
+
+/// Somewhere to keep each profile's Discord token.
+pub trait TokenStore {
+    fn save(&self, profile_id: &str, token: &str) -> Result<(), String>;
+    fn load(&self, profile_id: &str) -> Result<Option<SecretToken>, String>;
+    fn delete(&self, profile_id: &str) -> Result<(), String>;
+
+    fn has(&self, profile_id: &str) -> bool {
+        matches!(self.load(profile_id), Ok(Some(_)))
+    }
+}
+
+/// One file per profile under `tokens/`, bound to this user on this machine
+/// where the OS allows it, so a copied data folder doesn't carry usable tokens.
+pub struct FileTokenStore {
+    dir: DataDir,
+}
+
+impl FileTokenStore {
+    pub fn new(dir: DataDir) -> Self {
+        Self { dir }
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn save(&self, profile_id: &str, token: &str) -> Result<(), String> {
+        let path = self.dir.token_file(profile_id)?;
+        let content = match seal_for_machine(token)? {
+            Some(sealed) => Zeroizing::new(format!("{MACHINE_SEALED_PREFIX}{sealed}")),
+            None => Zeroizing::new(token.to_string()),
+        };
+        fs::write(&path, content.as_bytes()).map_err(|e| format!("Could not save token: {e}"))?;
+        restrict_permissions(&path, false)
+    }
+
+    fn load(&self, profile_id: &str) -> Result<Option<SecretToken>, String> {
+        let path = self.dir.token_file(profile_id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = SecretToken::new(
+            fs::read_to_string(&path).map_err(|e| format!("Could not read token: {e}"))?,
+        );
+        match content.expose().strip_prefix(MACHINE_SEALED_PREFIX) {
+            Some(sealed) => open_for_machine(sealed).map(Some),
+            // written before tokens were bound to the machine
+            None => Ok(Some(content)),
+        }
+    }
+
+    fn delete(&self, profile_id: &str) -> Result<(), String> {
+        wipe_token_file(&self.dir.token_file(profile_id)?)
+    }
+
+    fn has(&self, profile_id: &str) -> bool {
+        self.dir
+            .token_file(profile_id)
+            .map(|p| p.exists())
+            .unwrap_or(false)
+    }
+}
+
+/// One encrypted file per profile under `tokens/`; the key is random and lives
+/// in the system keychain, so a copied data folder alone doesn't leak tokens.
+pub struct EncryptedFileTokenStore {
+    dir: DataDir,
+}
+
+impl EncryptedFileTokenStore {
+    pub fn new(dir: DataDir) -> Self {
+        Self { dir }
+    }
+}
+
+// the random key for encrypted token files; created on first save, and reads
+// without one just find nothing to decrypt
+fn file_key(create: bool) -> Result<Option<SecretKey>, String> {
+    let entry = KeychainTokenStore::entry(FILE_KEY_ACCOUNT)?;
+    match entry.get_password() {
+        Ok(encoded) => {
+            let bytes = Zeroizing::new(
+                STANDARD
+                    .decode(encoded)
+                    .map_err(|e| format!("Could not read token file key: {e}"))?,
+            );
+            let key: [u8; 32] = bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| "Token file key has the wrong length.".to_string())?;
+            Ok(Some(Zeroizing::new(key)))
+        }
+        Err(keyring::Error::NoEntry) if create => {
+            let key = random_key();
+            entry
+                .set_password(&STANDARD.encode(key.as_ref()))
+                .map_err(|e| format!("Could not save token file key to keychain: {e}"))?;
+            Ok(Some(key))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Could not read token file key from keychain: {e}")),
+    }
+}
+
+impl TokenStore for EncryptedFileTokenStore {
+    fn save(&self, profile_id: &str, token: &str) -> Result<(), String> {
+        let key = file_key(true)?.ok_or_else(|| "Could not create token file key.".to_string())?;
+        let path = self.dir.encrypted_token_file(profile_id)?;
+        fs::write(&path, seal(&key, token.as_bytes())?)
+            .map_err(|e| format!("Could not save token: {e}"))?;
+        restrict_permissions(&path, false)
+    }
+
+    fn load(&self, profile_id: &str) -> Result<Option<SecretToken>, String> {
+        let path = self.dir.encrypted_token_file(profile_id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let sealed = fs::read_to_string(&path).map_err(|e| format!("Could not read token: {e}"))?;
+        let key = file_key(false)?.ok_or_else(|| {
+            "The key for encrypted token files is missing from the keychain.".to_string()
+        })?;
+        open_string(&key, &sealed).map(|token| Some(SecretToken::new(token)))
+    }
+
+    fn delete(&self, profile_id: &str) -> Result<(), String> {
+        wipe_token_file(&self.dir.encrypted_token_file(profile_id)?)
+    }
+
+    fn has(&self, profile_id: &str) -> bool {
+        self.dir
+            .encrypted_token_file(profile_id)
+            .map(|p| p.exists())
+            .unwrap_or(false)
+    }
+}
+
+// overwrite the token with zeros before unlinking so it doesn't linger on disk
+fn wipe_token_file(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let len = fs::metadata(path).map(|m| m.len() as usize).unwrap_or(0);
+    fs::write(path, vec![0u8; len]).map_err(|e| format!("Could not wipe token: {e}"))?;
+    fs::remove_file(path).map_err(|e| format!("Could not delete token: {e}"))
+}
+
+// ── Machine binding ──
+
+// DPAPI (current user) on Windows, the keychain-held file key on macOS;
+// None where there's nothing to bind to and the token stays as it is
+fn seal_for_machine(token: &str) -> Result<Option<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let sealed = crate::os_crypt::dpapi_protect(token.as_bytes())
+            .map_err(|e| format!("Could not protect token: {e}"))?;
+        return Ok(Some(STANDARD.encode(sealed)));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let key = file_key(true)?.ok_or_else(|| "Could not create token file key.".to_string())?;
+        return seal(&key, token.as_bytes()).map(Some);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = token;
+        Ok(None)
+    }
+}
+
+fn open_for_machine(sealed: &str) -> Result<SecretToken, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let bytes = STANDARD
+            .decode(sealed)
+            .map_err(|e| format!("Could not read token: {e}"))?;
+        let plain =
+            crate::os_crypt::dpapi_unprotect(&bytes).map_err(|_| FOREIGN_MACHINE.to_string())?;
+        return String::from_utf8(plain.to_vec())
+            .map(SecretToken::new)
+            .map_err(|_| "Could not read token: not valid text.".to_string());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let key = file_key(false)?.ok_or_else(|| FOREIGN_MACHINE.to_string())?;
+        return open_string(&key, sealed)
+            .map(SecretToken::new)
+            .map_err(|_| FOREIGN_MACHINE.to_string());
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = sealed;
+        Err(FOREIGN_MACHINE.to_string())
+    }
+}
+
+/// Re-save plain token files so they're bound to this machine; runs at
+/// startup, so nobody has to opt in. Returns how many were converted.
+pub fn bind_token_files_to_machine(dir: &DataDir) -> Result<usize, String> {
+    let settings = peek_launcher_settings(&dir.launcher_settings_file())?;
+    let bindable = cfg!(any(target_os = "windows", target_os = "macos"));
+    if !bindable
+        || settings.token_backend != TokenBackend::File
+        || !dir.root().join("tokens").exists()
+    {
+        return Ok(0);
+    }
+
+    let store = FileTokenStore::new(dir.clone());
+    let mut bound = 0;
+    for profile in load_profiles(&dir.profiles_file())? {
+        for token_id in profile_token_ids(&profile.id) {
+            let path = dir.token_file(&token_id)?;
+            let Ok(content) = fs::read_to_string(&path).map(Zeroizing::new) else {
+                continue;
+            };
+            if content.starts_with(MACHINE_SEALED_PREFIX) {
+                continue;
+            }
+            // overwriting in place also replaces the plaintext on disk
+            store.save(&token_id, &content)?;
+            bound += 1;
+        }
+    }
+    Ok(bound)
+}
+
+/// Windows Credential Manager / macOS Keychain.
+pub struct KeychainTokenStore;
+
+impl KeychainTokenStore {
+    fn entry(profile_id: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, profile_id)
+            .map_err(|e| format!("Could not open keychain entry: {e}"))
+    }
+}
+
+impl TokenStore for KeychainTokenStore {
+    fn save(&self, profile_id: &str, token: &str) -> Result<(), String> {
+        Self::entry(profile_id)?
+            .set_password(token)
+            .map_err(|e| format!("Could not save token to keychain: {e}"))
+    }
+
+    fn load(&self, profile_id: &str) -> Result<Option<SecretToken>, String> {
+        match Self::entry(profile_id)?.get_password() {
+            Ok(token) => Ok(Some(SecretToken::new(token))),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Could not read token from keychain: {e}")),
+        }
+    }
+
+    fn delete(&self, profile_id: &str) -> Result<(), String> {
+        match Self::entry(profile_id)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Could not delete token from keychain: {e}")),
+        }
+    }
+}
+
+/// All tokens in a single SQLite database.
+pub struct SqliteTokenStore {
+    path: PathBuf,
+}
+
+impl SqliteTokenStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn open(&self) -> Result<rusqlite::Connection, String> {
+        let is_new = !self.path.exists();
+        let conn = rusqlite::Connection::open(&self.path)
+            .map_err(|e| format!("Could not open token database: {e}"))?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tokens (profile_id TEXT PRIMARY KEY, token TEXT NOT NULL)",
+            [],
+        )
+        .map_err(|e| format!("Could not prepare token database: {e}"))?;
+        if is_new {
+            restrict_permissions(&self.path, false)?;
+        }
+        Ok(conn)
+    }
+}
+
+impl TokenStore for SqliteTokenStore {
+    fn save(&self, profile_id: &str, token: &str) -> Result<(), String> {
+        self.open()?
+            .execute(
+                "INSERT OR REPLACE INTO tokens (profile_id, token) VALUES (?1, ?2)",
+                [profile_id, token],
+            )
+            .map(|_| ())
+            .map_err(|e| format!("Could not save token: {e}"))
+    }
+
+    fn load(&self, profile_id: &str) -> Result<Option<SecretToken>, String> {
+        let conn = self.open()?;
+        match conn.query_row(
+            "SELECT token FROM tokens WHERE profile_id = ?1",
+            [profile_id],
+            |row| row.get(0),
+        ) {
+            Ok(token) => Ok(Some(SecretToken::new(token))),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Could not read token: {e}")),
+        }
+    }
+
+    fn delete(&self, profile_id: &str) -> Result<(), String> {
+        let conn = self.open()?;
+        // blank the row first so the old value isn't left in a free page
+        conn.execute(
+            "UPDATE tokens SET token = zeroblob(length(token)) WHERE profile_id = ?1",
+            [profile_id],
+        )
+        .and_then(|_| conn.execute("DELETE FROM tokens WHERE profile_id = ?1", [profile_id]))
+        .map(|_| ())
+        .map_err(|e| format!("Could not delete token: {e}"))
+    }
+}
+
+pub fn token_store_for(dir: &DataDir, backend: TokenBackend) -> Box<dyn TokenStore> {
+    match backend {
+        TokenBackend::File => Box::new(FileTokenStore::new(dir.clone())),
+        TokenBackend::EncryptedFile => Box::new(EncryptedFileTokenStore::new(dir.clone())),
+        TokenBackend::Keychain => Box::new(KeychainTokenStore),
+        TokenBackend::Sqlite => Box::new(SqliteTokenStore::new(dir.token_database())),
+    }
+}
+
+/// The store picked in settings, behind the vault when it's enabled.
+pub fn token_store(dir: &DataDir) -> Result<Box<dyn TokenStore>, String> {
+    let settings = peek_launcher_settings(&dir.launcher_settings_file())?;
+    Ok(wrap_token_store(
+        dir,
+        token_store_for(dir, settings.token_backend),
+    ))
+}
+
+/// Move every profile's token from one backend to another; returns how many moved.
+/// Vault-sealed tokens are moved as they are. Every token is copied and read
+/// back first, then `switch` makes `to` the configured backend, and only then
+/// are the originals deleted; a failure before that leaves `from` untouched.
+pub fn migrate_tokens(
+    dir: &DataDir,
+    from: TokenBackend,
+    to: TokenBackend,
+    switch: impl FnOnce() -> Result<(), String>,
+) -> Result<usize, String> {
+    let source = token_store_for(dir, from);
+    let target = token_store_for(dir, to);
+    let profiles = load_profiles(&dir.profiles_file())?;
+
+    let token_ids: Vec<String> = profiles
+        .iter()
+        .flat_map(|p| profile_token_ids(&p.id))
+        .collect();
+    let mut copied = Vec::new();
+    if let Err(e) = copy_tokens(&*source, &*target, &token_ids, &mut copied).and_then(|_| switch())
+    {
+        // the old backend is still the configured one, so drop the half-made copy
+        for token_id in &copied {
+            let _ = target.delete(token_id);
+        }
+        return Err(e);
+    }
+
+    for token_id in &copied {
+        if let Err(e) = source.delete(token_id) {
+            eprintln!("Warning: could not remove {token_id} from the old token backend: {e}");
+        }
+    }
+    Ok(copied.len())
+}
+
+// each copy is read back, so a store that silently drops writes is caught here
+fn copy_tokens(
+    source: &dyn TokenStore,
+    target: &dyn TokenStore,
+    token_ids: &[String],
+    copied: &mut Vec<String>,
+) -> Result<(), String> {
+    for token_id in token_ids {
+        let Some(token) = source.load(token_id)? else {
+            continue;
+        };
+        target.save(token_id, token.expose())?;
+        copied.push(token_id.clone());
+        let stored = target.load(token_id)?;
+        if stored.as_ref().map(SecretToken::expose) != Some(token.expose()) {
+            return Err(format!(
+                "Could not move tokens: the new backend didn't keep {token_id} intact."
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Move every token into `to` and make it the configured backend.
+pub fn migrate_token_backend(dir: &DataDir, to: TokenBackend) -> Result<TokenMigration, String> {
+    let file_path = dir.launcher_settings_file();
+    let mut settings = load_launcher_settings(&file_path)?;
+    let from = settings.token_backend;
+    if from == to {
+        return Ok(TokenMigration { from, to, moved: 0 });
+    }
+    ensure_token_backend_supported(to)?;
+
+    settings.token_backend = to;
+    let moved = migrate_tokens(dir, from, to, || {
+        save_launcher_settings_to_file(&file_path, &settings)
+    })?;
+    Ok(TokenMigration { from, to, moved })
+}
+
+/// Move token files left under `tokens/` into the configured store.
+/// Returns how many were moved.
+// covers files from before the backend was switched, or restored from an old copy
+// of the data folder; migrate_tokens only runs at the moment the setting changes
+pub fn migrate_legacy_token_files(dir: &DataDir) -> Result<usize, String> {
+    let settings = peek_launcher_settings(&dir.launcher_settings_file())?;
+    if settings.token_backend == TokenBackend::File || !dir.root().join("tokens").exists() {
+        return Ok(0);
+    }
+
+    let legacy = FileTokenStore::new(dir.clone());
+    let target = token_store_for(dir, settings.token_backend);
+    let mut moved = 0;
+    let profiles = load_profiles(&dir.profiles_file())?;
+    for token_id in profiles.iter().flat_map(|p| profile_token_ids(&p.id)) {
+        let Some(token) = legacy.load(&token_id)? else {
+            continue;
+        };
+        // a token already in the store is newer than a leftover file
+        if !target.has(&token_id) {
+            target.save(&token_id, token.expose())?;
+        }
+        legacy.delete(&token_id)?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+pub fn save_profile_token(dir: &DataDir, profile_id: &str, token: &str) -> Result<(), String> {
+    token_store(dir)?.save(profile_id, token)?;
+    profile_updated(profile_id);
+    Ok(())
+}
+
+pub fn load_profile_token(dir: &DataDir, profile_id: &str) -> Result<SecretToken, String> {
+    token_store(dir)?
+        .load(profile_id)?
+        .ok_or_else(|| "No token saved for this profile. Log in first.".to_string())
+}
+
+/// Delete the profile's token, and its per-channel tokens with it.
+pub fn delete_profile_token(dir: &DataDir, profile_id: &str) -> Result<(), String> {
+    let store = token_store(dir)?;
+    for token_id in profile_token_ids(profile_id) {
+        store.delete(&token_id)?;
+    }
+    Ok(())
+}
+
+pub fn profile_has_token(dir: &DataDir, profile_id: &str) -> bool {
+    token_store(dir)
+        .map(|store| store.has(profile_id))
+        .unwrap_or(false)
+}
+
+// ── Per-channel tokens ──
+
+// the channels a profile can keep a token of their own for, next to its main one
+const TOKEN_CHANNELS: [DiscordChannel; 7] = [
+    DiscordChannel::Stable,
+    DiscordChannel::Ptb,
+    DiscordChannel::Canary,
+    DiscordChannel::Development,
+    DiscordChannel::Vesktop,
+    DiscordChannel::WebCord,
+    DiscordChannel::ArmCord,
+];
+
+/// Where a profile's token for `channel` is kept in the token store, e.g.
+/// `profile-1.canary`; None for Auto and custom clients, which use the main one.
+pub(crate) fn channel_token_id(profile_id: &str, channel: DiscordChannel) -> Option<String> {
+    let slug = match channel {
+        DiscordChannel::Stable => "stable",
+        DiscordChannel::Ptb => "ptb",
+        DiscordChannel::Canary => "canary",
+        DiscordChannel::Development => "development",
+        DiscordChannel::Vesktop => "vesktop",
+        DiscordChannel::WebCord => "webcord",
+        DiscordChannel::ArmCord => "armcord",
+        DiscordChannel::Auto | DiscordChannel::Custom => return None,
+    };
+    Some(format!("{profile_id}.{slug}"))
+}
+
+// the main token's id first, then every channel's
+pub(crate) fn profile_token_ids(profile_id: &str) -> Vec<String> {
+    std::iter::once(profile_id.to_string())
+        .chain(
+            TOKEN_CHANNELS
+                .iter()
+                .filter_map(|channel| channel_token_id(profile_id, *channel)),
+        )
+        .collect()
+}
+
+/// Save a token for this profile on `channel` only; switching into that
+/// channel uses it instead of the main token.
+pub fn save_channel_token(
+    dir: &DataDir,
+    profile_id: &str,
+    channel: DiscordChannel,
+    token: &str,
+) -> Result<(), String> {
+    let token_id = channel_token_id(profile_id, channel)
+        .ok_or_else(|| "Only an installed Discord channel can have its own token.".to_string())?;
+    token_store(dir)?.save(&token_id, token)?;
+    profile_updated(profile_id);
+    Ok(())
+}
+
+/// The channels this profile has a token of their own for.
+pub fn list_channel_tokens(dir: &DataDir, profile_id: &str) -> Result<Vec<DiscordChannel>, String> {
+    find_profile(dir, profile_id)?;
+    let store = token_store(dir)?;
+    Ok(TOKEN_CHANNELS
+        .into_iter()
+        .filter(|channel| {
+            channel_token_id(profile_id, *channel).is_some_and(|token_id| store.has(&token_id))
+        })
+        .collect())
+}
+
+/// Forget the profile's token for `channel`; switching there uses the main one again.
+pub fn delete_channel_token(
+    dir: &DataDir,
+    profile_id: &str,
+    channel: DiscordChannel,
+) -> Result<(), String> {
+    find_profile(dir, profile_id)?;
+    let Some(token_id) = channel_token_id(profile_id, channel) else {
+        return Ok(());
+    };
+    token_store(dir)?.delete(&token_id)?;
+    profile_updated(profile_id);
+    Ok(())
+}
+
+/// The token a switch into `channel` writes: the one saved for that channel,
+/// or the main token when it has none of its own. Auto is whichever channel
+/// its storage turns out to be.
+pub fn load_switch_token(
+    dir: &DataDir,
+    profile_id: &str,
+    channel: DiscordChannel,
+) -> Result<SecretToken, String> {
+    let own =
+        resolve_storage_channel(channel).and_then(|channel| channel_token_id(profile_id, channel));
+    if let Some(token_id) = own {
+        if let Some(token) = token_store(dir)?.load(&token_id)? {
+            return Ok(token);
+        }
+    }
+    load_profile_token(dir, profile_id)
+}
+
+/// Delete every profile's token from every backend, not only the active one,
+/// shred whatever else is left in `tokens/` and the SQLite store, and drop the
+/// encrypted-file key. Keeps going past failures; returns how many profiles
+/// had a token and what went wrong.
+pub fn wipe_all_tokens(dir: &DataDir) -> (usize, Vec<String>) {
+    let mut errors = Vec::new();
+    let profiles = load_profiles(&dir.profiles_file()).unwrap_or_else(|e| {
+        errors.push(e);
+        Vec::new()
+    });
+    let backends = [
+        TokenBackend::File,
+        TokenBackend::EncryptedFile,
+        TokenBackend::Keychain,
+        TokenBackend::Sqlite,
+    ];
+
+    let mut wiped = 0;
+    for profile in &profiles {
+        let mut had_token = false;
+        for backend in backends {
+            // opening the SQLite store would create an empty database
+            if backend == TokenBackend::Sqlite && !dir.token_database().exists() {
+                continue;
+            }
+            let store = token_store_for(dir, backend);
+            for token_id in profile_token_ids(&profile.id) {
+                if !store.has(&token_id) {
+                    continue;
+                }
+                had_token = true;
+                if let Err(e) = store.delete(&token_id) {
+                    errors.push(format!("{}: {e}", profile.nickname));
+                }
+            }
+        }
+        wiped += usize::from(had_token);
+    }
+
+    // tokens of profiles that no longer exist, and the database's own leftovers
+    let tokens_dir = dir.root().join("tokens");
+    if let Ok(entries) = fs::read_dir(&tokens_dir) {
+        for entry in entries.flatten() {
+            if let Err(e) = wipe_token_file(&entry.path()) {
+                errors.push(e);
+            }
+        }
+    }
+    let database = dir.token_database();
+    for suffix in ["", "-journal", "-wal"] {
+        let mut path = database.clone().into_os_string();
+        path.push(suffix);
+        if let Err(e) = wipe_token_file(Path::new(&path)) {
+            errors.push(e);
+        }
+    }
+    if let Err(e) = KeychainTokenStore.delete(FILE_KEY_ACCOUNT) {
+        errors.push(e);
+    }
+    (wiped, errors)
+}
+
+/// Forget the saved token but keep the profile (nickname, notes, ...) around.
+pub fn clear_profile_token(dir: &DataDir, profile_id: &str) -> Result<Profile, String> {
+    let stored = find_profile(dir, profile_id)?;
+    delete_profile_token(dir, profile_id)?;
+    Ok(stored.into_profile(false))
+}
+
+/// The saved token with everything but the first and last few characters
+/// masked, e.g. "MTIz…Xy9w"; safe to show on screen.
+pub fn get_token_preview(dir: &DataDir, profile_id: &str) -> Result<String, String> {
+    find_profile(dir, profile_id)?;
+    let token = load_profile_token(dir, profile_id)?;
+    let chars: Vec<char> = token.expose().chars().collect();
+    // too short to show both ends without giving most of it away
+    if chars.len() <= PREVIEW_CHARS * 3 {
+        return Ok("•".repeat(8));
+    }
+    let head: String = chars[..PREVIEW_CHARS].iter().collect();
+    let tail: String = chars[chars.len() - PREVIEW_CHARS..].iter().collect();
+    Ok(format!("{head}…{tail}"))
+}
+
+/// The full saved token. Refuses unless `confirmed` is set, goes through the
+/// app lock and the biometric gate, and every attempt lands in the audit log.
+pub fn reveal_token(dir: &DataDir, profile_id: &str, confirmed: bool) -> Result<String, String> {
+    let result = load_revealed_token(dir, profile_id, confirmed);
+    record_audit(dir, AuditAction::Reveal, Some(profile_id), None, &result);
+    result
+}
+
+fn load_revealed_token(dir: &DataDir, profile_id: &str, confirmed: bool) -> Result<String, String> {
+    if !confirmed {
+        return Err("Revealing a token has to be confirmed.".to_string());
+    }
+    // deliberately handed out as plain text for the frontend to show
+    Ok(authorize_reveal(dir, profile_id)?.expose().to_string())
+}
+
+/// The checks before a token may leave the app in full (app lock, biometric
+/// gate), then the token itself. Callers record the attempt in the audit log.
+pub fn authorize_reveal(dir: &DataDir, profile_id: &str) -> Result<SecretToken, String> {
+    find_profile(dir, profile_id)?;
+    ensure_app_unlocked(dir)?;
+    require_biometric(dir, BiometricAction::RevealToken)?;
+    load_profile_token(dir, profile_id)
+}
@@ -0,0 +1,101 @@
+use serde::Serialize;
+
+use crate::{
+    api::{fetch_discord_user, snowflake_created_at_ms, DiscordUser},
+    clock::now_ms,
+    profiles::find_profile,
+    tokens::load_profile_token,
+    DataDir,
+};
+
+const DAY_MS: u128 = 24 * 60 * 60 * 1000;
+/// Younger than this counts as a new account.
+pub const NEW_ACCOUNT_DAYS: u128 = 30;
+/// Older than this counts as aged.
+pub const AGED_ACCOUNT_DAYS: u128 = 365;
+
+// private user flag bits that mean Discord is treating the account with suspicion
+const SUSPICIOUS_FLAGS: &[(u64, &str)] = &[
+    (1 << 20, "Marked as a likely spammer"),
+    (1 << 33, "High global rate limit"),
+    (1 << 35, "Disabled for suspicious activity"),
+    (1 << 44, "Quarantined"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountAge {
+    New,
+    Established,
+    Aged,
+}
+
+/// Computed at-a-glance view of how much weight an account carries.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustSummary {
+    pub profile_id: String,
+    pub created_at_ms: Option<u128>,
+    pub age_days: Option<u128>,
+    pub age: Option<AccountAge>,
+    /// `None` when Discord couldn't be asked (no token, locked vault, offline).
+    pub email_verified: Option<bool>,
+    pub mfa_enabled: Option<bool>,
+    pub phone_verified: Option<bool>,
+    pub flagged: bool,
+    pub flag_reasons: Vec<String>,
+    /// Why the live checks are missing, if they are.
+    pub lookup_error: Option<String>,
+}
+
+/// Age from the account id, plus verification and flags from a live lookup when
+/// the profile has a usable token.
+pub fn get_trust_summary(dir: &DataDir, profile_id: &str) -> Result<TrustSummary, String> {
+    let stored = find_profile(dir, profile_id)?;
+
+    let lookup =
+        load_profile_token(dir, profile_id).and_then(|token| fetch_discord_user(token.expose()));
+    let (user, lookup_error) = match lookup {
+        Ok(user) => (Some(user), None),
+        Err(e) => (None, Some(e)),
+    };
+
+    let user_id = user
+        .as_ref()
+        .map(|u| u.id.clone())
+        .or(stored.discord_user_id);
+    let created_at_ms = user_id.as_deref().and_then(snowflake_created_at_ms);
+    let age_days = created_at_ms.map(|created| now_ms().saturating_sub(created) / DAY_MS);
+    let flag_reasons = user.as_ref().map(suspicious_flags).unwrap_or_default();
+
+    Ok(TrustSummary {
+        profile_id: profile_id.to_string(),
+        created_at_ms,
+        age_days,
+        age: age_days.map(classify_age),
+        email_verified: user.as_ref().map(|u| u.verified),
+        mfa_enabled: user.as_ref().map(|u| u.mfa_enabled),
+        phone_verified: user.as_ref().map(|u| u.phone.is_some()),
+        flagged: !flag_reasons.is_empty(),
+        flag_reasons,
+        lookup_error,
+    })
+}
+
+pub fn classify_age(age_days: u128) -> AccountAge {
+    if age_days < NEW_ACCOUNT_DAYS {
+        AccountAge::New
+    } else if age_days < AGED_ACCOUNT_DAYS {
+        AccountAge::Established
+    } else {
+        AccountAge::Aged
+    }
+}
+
+fn suspicious_flags(user: &DiscordUser) -> Vec<String> {
+    SUSPICIOUS_FLAGS
+        .iter()
+        .filter(|(bit, _)| user.flags & bit != 0)
+        .map(|(_, reason)| reason.to_string())
+        .collect()
+}
@@ -0,0 +1,137 @@
+use crate::launch::DiscordInstallation;
+
+/// What [`update_discord`] did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    UpToDate,
+    Updated {
+        from: String,
+        to: String,
+    },
+    /// No updater the app can drive: macOS and Linux builds update themselves
+    /// or come from a package manager, and forks and Store installs have their own.
+    Unsupported,
+}
+
+/// Run Discord's own updater for `installation` and wait for it to finish, so
+/// a freshly launched client doesn't restart itself to apply an update.
+/// Windows only; the Squirrel feed is asked first, so an up-to-date install
+/// costs one request.
+pub fn update_discord(installation: &DiscordInstallation) -> Result<UpdateOutcome, String> {
+    #[cfg(target_os = "windows")]
+    return windows::update(installation);
+
+    #[allow(unreachable_code)]
+    {
+        let _ = installation;
+        Ok(UpdateOutcome::Unsupported)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::{
+        path::Path,
+        process::{Command, Stdio},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::UpdateOutcome;
+    use crate::{
+        http::request,
+        launch::{squirrel_updater, DiscordInstallation},
+        settings::DiscordChannel,
+    };
+
+    // a full host update downloads around 100 MB
+    const UPDATE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+    const UPDATE_POLL: Duration = Duration::from_millis(500);
+
+    pub fn update(installation: &DiscordInstallation) -> Result<UpdateOutcome, String> {
+        let Some(feed) = feed_url(installation.channel) else {
+            return Ok(UpdateOutcome::Unsupported);
+        };
+        let executable = Path::new(&installation.executable_path);
+        let Some((updater, _)) = squirrel_updater(executable) else {
+            return Ok(UpdateOutcome::Unsupported);
+        };
+        let installed = executable
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("app-"))
+            .unwrap_or_default()
+            .to_string();
+
+        let latest = latest_version(&feed)?;
+        if !is_newer(&latest, &installed) {
+            return Ok(UpdateOutcome::UpToDate);
+        }
+
+        let mut child = Command::new(&updater)
+            .args(["--update", &feed])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Could not start Discord's updater: {e}"))?;
+        let started = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if started.elapsed() < UPDATE_TIMEOUT => thread::sleep(UPDATE_POLL),
+                Ok(None) => {
+                    let _ = child.kill();
+                    return Err(format!(
+                        "Discord's updater was still running after {} minutes and was stopped.",
+                        UPDATE_TIMEOUT.as_secs() / 60
+                    ));
+                }
+                Err(e) => return Err(format!("Could not wait for Discord's updater: {e}")),
+            }
+        };
+        if !status.success() {
+            return Err(format!("Discord's updater failed ({status})."));
+        }
+        Ok(UpdateOutcome::Updated {
+            from: installed,
+            to: latest,
+        })
+    }
+
+    // the Squirrel feed behind each official channel; Update.exe adds /RELEASES
+    fn feed_url(channel: DiscordChannel) -> Option<String> {
+        let name = match channel {
+            DiscordChannel::Stable => "stable",
+            DiscordChannel::Ptb => "ptb",
+            DiscordChannel::Canary => "canary",
+            DiscordChannel::Development => "development",
+            _ => return None,
+        };
+        Some(format!("https://discord.com/api/updates/{name}"))
+    }
+
+    // answers with the newest host version, e.g. {"name": "1.0.9163", ...}
+    fn latest_version(feed: &str) -> Result<String, String> {
+        let response = request("GET", &format!("{feed}?platform=win"))?
+            .call()
+            .map_err(|e| format!("Could not check for Discord updates: {e}"))?;
+        let body: serde_json::Value = response
+            .into_json()
+            .map_err(|e| format!("Could not read Discord's update feed: {e}"))?;
+        body.get("name")
+            .and_then(|name| name.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| "Discord's update feed didn't name a version.".to_string())
+    }
+
+    fn is_newer(latest: &str, installed: &str) -> bool {
+        let parts = |version: &str| -> Vec<u64> {
+            version
+                .split('.')
+                .map(|part| part.parse().unwrap_or(0))
+                .collect()
+        };
+        parts(latest) > parts(installed)
+    }
+}
@@ -0,0 +1,95 @@
+use serde_json::Value;
+use std::{fs, path::Path};
+
+use crate::{profiles::load_profiles, DataDir};
+
+/// Schema of `accounts.json` (and of the profiles in a backup) this build writes.
+pub const PROFILES_SCHEMA_VERSION: u32 = 3;
+
+// PROFILE_UPGRADES[n] lifts a profile list from schema n + 1 to n + 2.
+// Released steps are never edited or removed, only appended to, so a file
+// from any older build walks the whole chain and loads.
+const PROFILE_UPGRADES: [fn(&mut Value); PROFILES_SCHEMA_VERSION as usize - 1] =
+    [rename_name_to_nickname, quarantine_invalid_tokens];
+
+/// Bring a profile list written at schema `from` up to [`PROFILES_SCHEMA_VERSION`].
+pub(crate) fn upgrade_profiles(profiles: &mut Value, from: u32) -> Result<(), String> {
+    if from > PROFILES_SCHEMA_VERSION {
+        return Err(format!(
+            "These accounts were saved by a newer version of the app (schema {from}). Update the app to open them."
+        ));
+    }
+    for step in &PROFILE_UPGRADES[from.saturating_sub(1) as usize..] {
+        step(profiles);
+    }
+    Ok(())
+}
+
+/// Split an `accounts.json` payload into its schema version and the profile list.
+/// Schema 1 files are a bare list; later ones wrap it with a `schemaVersion`.
+pub(crate) fn parse_profiles_document(content: &str) -> Result<(u32, Value), String> {
+    let value: Value =
+        serde_json::from_str(content).map_err(|e| format!("Could not parse account file: {e}"))?;
+    match value {
+        Value::Array(_) => Ok((1, value)),
+        Value::Object(mut document) => {
+            let version = document
+                .get("schemaVersion")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| "Could not parse account file: no schema version.".to_string())?;
+            let profiles = document
+                .remove("profiles")
+                .unwrap_or(Value::Array(Vec::new()));
+            Ok((u32::try_from(version).unwrap_or(u32::MAX), profiles))
+        }
+        _ => Err("Could not parse account file: expected a list of accounts.".to_string()),
+    }
+}
+
+/// Copy a file about to be rewritten by an upgrade to `<name>.v<from>.bak`.
+/// The first copy per version wins, so a retried upgrade never clobbers it.
+pub(crate) fn back_up_before_upgrade(file_path: &Path, from: u32) -> Result<(), String> {
+    let Some(name) = file_path.file_name() else {
+        return Ok(());
+    };
+    let backup = file_path.with_file_name(format!("{}.v{from}.bak", name.to_string_lossy()));
+    if backup.exists() {
+        return Ok(());
+    }
+    fs::copy(file_path, &backup).map(drop).map_err(|e| {
+        format!(
+            "Could not back up {} before upgrading it: {e}",
+            name.to_string_lossy()
+        )
+    })
+}
+
+/// Rewrite data files saved by older builds in the current schema.
+/// Loading does this anyway; running it at startup keeps files synced to
+/// other machines from lingering in the old shape.
+pub fn upgrade_data_files(dir: &DataDir) -> Result<(), String> {
+    load_profiles(&dir.profiles_file()).map(drop)
+}
+
+// ── Profile upgrades ──
+
+// 1 -> 2: the display name was stored as `name` before nicknames existed
+fn rename_name_to_nickname(profiles: &mut Value) {
+    for profile in profiles.as_array_mut().into_iter().flatten() {
+        let Some(fields) = profile.as_object_mut() else {
+            continue;
+        };
+        if let Some(name) = fields.remove("name") {
+            fields.entry("nickname").or_insert(name);
+        }
+    }
+}
+
+// 2 -> 3: tokens Discord rejected were marked "invalid" before quarantine existed
+fn quarantine_invalid_tokens(profiles: &mut Value) {
+    for profile in profiles.as_array_mut().into_iter().flatten() {
+        if profile["tokenStatus"] == "invalid" {
+            profile["tokenStatus"] = Value::from("quarantined");
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+use zeroize::Zeroizing;
+
+use crate::{
+    crypto::{open, open_string, random_bytes, seal, SecretKey},
+    profiles::{load_profiles, save_profiles},
+    secret::SecretToken,
+    settings::peek_launcher_settings,
+    tokens::{profile_token_ids, token_store_for, TokenStore},
+    DataDir,
+};
+
+/// Returned by every token read or write while the vault is locked; the
+/// frontend matches on it to prompt for the master password.
+pub const VAULT_LOCKED: &str = "Vault is locked. Unlock it with your master password first.";
+
+// stored tokens carry this prefix once encrypted, so plaintext left over from
+// before the vault was enabled can still be told apart
+const SEALED_PREFIX: &str = "vault:v1:";
+// encrypted with the derived key to check a password without touching any token
+const VERIFIER_PLAINTEXT: &[u8] = b"alt-mngr-vault";
+const SALT_LEN: usize = 16;
+// same name as `DataDir::vault_file`, for callers that only have a file path
+const VAULT_FILE_NAME: &str = "vault.json";
+
+type VaultKey = SecretKey;
+
+// derived keys for unlocked vaults, by data folder; dropped (and zeroed) on lock
+static UNLOCKED: Mutex<Vec<(PathBuf, VaultKey)>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultConfig {
+    salt: String,
+    verifier: String,
+    /// Seal `accounts.json` too, not just the tokens.
+    #[serde(default)]
+    encrypt_profiles: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    /// Nicknames, notes and the rest of `accounts.json` are encrypted as well.
+    pub profiles_encrypted: bool,
+}
+
+/// Wraps the configured backend: tokens are sealed with AES-256-GCM before
+/// they reach it, and every read or write fails with [`VAULT_LOCKED`] while locked.
+pub struct VaultTokenStore {
+    inner: Box<dyn TokenStore>,
+    key: Option<VaultKey>,
+}
+
+impl VaultTokenStore {
+    fn key(&self) -> Result<&VaultKey, String> {
+        self.key.as_ref().ok_or_else(|| VAULT_LOCKED.to_string())
+    }
+}
+
+impl TokenStore for VaultTokenStore {
+    fn save(&self, profile_id: &str, token: &str) -> Result<(), String> {
+        let sealed = seal(self.key()?, token.as_bytes())?;
+        self.inner
+            .save(profile_id, &format!("{SEALED_PREFIX}{sealed}"))
+    }
+
+    fn load(&self, profile_id: &str) -> Result<Option<SecretToken>, String> {
+        let key = self.key()?;
+        let Some(stored) = self.inner.load(profile_id)? else {
+            return Ok(None);
+        };
+        match stored.expose().strip_prefix(SEALED_PREFIX) {
+            Some(sealed) => open_string(key, sealed).map(|token| Some(SecretToken::new(token))),
+            // saved before the vault was enabled and not yet re-encrypted
+            None => Ok(Some(stored)),
+        }
+    }
+
+    fn delete(&self, profile_id: &str) -> Result<(), String> {
+        self.inner.delete(profile_id)
+    }
+
+    // presence doesn't need the key, so the UI can still show which profiles have tokens
+    fn has(&self, profile_id: &str) -> bool {
+        self.inner.has(profile_id)
+    }
+}
+
+pub fn vault_enabled(dir: &DataDir) -> bool {
+    dir.vault_file().exists()
+}
+
+pub fn vault_status(dir: &DataDir) -> VaultStatus {
+    VaultStatus {
+        enabled: vault_enabled(dir),
+        unlocked: unlocked_key(dir).is_some(),
+        profiles_encrypted: load_vault_config(dir).is_ok_and(|config| config.encrypt_profiles),
+    }
+}
+
+/// Fail with [`VAULT_LOCKED`] if the vault is on but locked; a no-op otherwise.
+/// Call before anything that closes Discord so it isn't killed for nothing.
+pub fn ensure_unlocked(dir: &DataDir) -> Result<(), String> {
+    if vault_enabled(dir) && unlocked_key(dir).is_none() {
+        return Err(VAULT_LOCKED.to_string());
+    }
+    Ok(())
+}
+
+/// Put `store` behind the vault when it's enabled.
+pub fn wrap_token_store(dir: &DataDir, store: Box<dyn TokenStore>) -> Box<dyn TokenStore> {
+    if !vault_enabled(dir) {
+        return store;
+    }
+    Box::new(VaultTokenStore {
+        inner: store,
+        key: unlocked_key(dir),
+    })
+}
+
+/// Turn the vault on with `password` and encrypt every saved token.
+/// The vault is left unlocked.
+pub fn enable_vault(dir: &DataDir, password: &str) -> Result<VaultStatus, String> {
+    if vault_enabled(dir) {
+        return Err("The vault is already enabled.".to_string());
+    }
+    check_password_length(password)?;
+
+    let salt = random_bytes::<SALT_LEN>();
+    let key = derive_key(password, &salt)?;
+    let config = VaultConfig {
+        salt: STANDARD.encode(salt),
+        verifier: seal(&key, VERIFIER_PLAINTEXT)?,
+        encrypt_profiles: false,
+    };
+
+    let settings = peek_launcher_settings(&dir.launcher_settings_file())?;
+    let plain = token_store_for(dir, settings.token_backend);
+    let mut tokens = Vec::new();
+    for profile in load_profiles(&dir.profiles_file())? {
+        for token_id in profile_token_ids(&profile.id) {
+            if let Some(token) = plain.load(&token_id)? {
+                tokens.push((token_id, token));
+            }
+        }
+    }
+
+    save_vault_config(dir, &config)?;
+    remember_key(dir, key);
+    let sealed = wrap_token_store(dir, plain);
+    for (profile_id, token) in &tokens {
+        sealed.save(profile_id, token.expose())?;
+    }
+    Ok(vault_status(dir))
+}
+
+/// Decrypt every saved token back into the plain backend and turn the vault off.
+pub fn disable_vault(dir: &DataDir, password: &str) -> Result<VaultStatus, String> {
+    unlock_vault(dir, password)?;
+
+    let settings = peek_launcher_settings(&dir.launcher_settings_file())?;
+    let sealed = wrap_token_store(dir, token_store_for(dir, settings.token_backend));
+    let plain = token_store_for(dir, settings.token_backend);
+    let profiles = load_profiles(&dir.profiles_file())?;
+    for token_id in profiles.iter().flat_map(|p| profile_token_ids(&p.id)) {
+        if let Some(token) = sealed.load(&token_id)? {
+            plain.save(&token_id, token.expose())?;
+        }
+    }
+
+    // plain JSON while the vault is still there: a sealed accounts.json left
+    // without its vault config could never be opened again
+    let mut config = load_vault_config(dir)?;
+    if config.encrypt_profiles {
+        config.encrypt_profiles = false;
+        save_vault_config(dir, &config)?;
+        save_profiles(&dir.profiles_file(), &profiles)?;
+    }
+
+    fs::remove_file(dir.vault_file()).map_err(|e| format!("Could not remove vault file: {e}"))?;
+    forget_key(dir);
+    Ok(vault_status(dir))
+}
+
+/// Encrypt `accounts.json` with the vault key as well, or go back to plain
+/// JSON. Needs the vault to be unlocked; while it's locked the profile list
+/// can't be read at all.
+pub fn set_profile_encryption(dir: &DataDir, enabled: bool) -> Result<VaultStatus, String> {
+    let mut config = load_vault_config(dir)?;
+    ensure_unlocked(dir)?;
+    let profiles = load_profiles(&dir.profiles_file())?;
+    config.encrypt_profiles = enabled;
+    save_vault_config(dir, &config)?;
+    save_profiles(&dir.profiles_file(), &profiles)?;
+    Ok(vault_status(dir))
+}
+
+/// `accounts.json` as it goes to disk: sealed with the vault key when the
+/// vault is set to encrypt it, unchanged otherwise.
+pub(crate) fn seal_profiles_payload(file_path: &Path, payload: String) -> Result<String, String> {
+    let Some(root) = file_path.parent() else {
+        return Ok(payload);
+    };
+    let encrypt =
+        read_vault_config(&root.join(VAULT_FILE_NAME)).is_ok_and(|config| config.encrypt_profiles);
+    if !encrypt {
+        return Ok(payload);
+    }
+    let key = key_for_root(root).ok_or_else(|| VAULT_LOCKED.to_string())?;
+    Ok(format!(
+        "{SEALED_PREFIX}{}",
+        seal(&key, payload.as_bytes())?
+    ))
+}
+
+/// The JSON in `accounts.json`, decrypting it if it was sealed.
+pub(crate) fn open_profiles_payload(file_path: &Path, content: String) -> Result<String, String> {
+    let Some(sealed) = content.trim().strip_prefix(SEALED_PREFIX) else {
+        return Ok(content);
+    };
+    let key = file_path
+        .parent()
+        .and_then(key_for_root)
+        .ok_or_else(|| VAULT_LOCKED.to_string())?;
+    open_string(&key, sealed).map_err(|e| format!("Could not decrypt account file: {e}"))
+}
+
+/// Check `password` and keep the derived key in memory until [`lock_vault`].
+pub fn unlock_vault(dir: &DataDir, password: &str) -> Result<VaultStatus, String> {
+    let config = load_vault_config(dir)?;
+    let salt = STANDARD
+        .decode(&config.salt)
+        .map_err(|e| format!("Could not read vault salt: {e}"))?;
+    let key = derive_key(password, &salt)?;
+    match open(&key, &config.verifier) {
+        Ok(check) if check.as_slice() == VERIFIER_PLAINTEXT => {}
+        _ => return Err("Wrong master password.".to_string()),
+    }
+
+    remember_key(dir, key);
+    Ok(vault_status(dir))
+}
+
+/// Drop the in-memory key; token reads and writes fail until the next unlock.
+pub fn lock_vault(dir: &DataDir) -> VaultStatus {
+    forget_key(dir);
+    vault_status(dir)
+}
+
+fn check_password_length(password: &str) -> Result<(), String> {
+    if password.chars().count() < 8 {
+        return Err("Master password must be at least 8 characters.".to_string());
+    }
+    Ok(())
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<VaultKey, String> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, key.as_mut())
+        .map_err(|e| format!("Could not derive vault key: {e}"))?;
+    Ok(key)
+}
+
+fn load_vault_config(dir: &DataDir) -> Result<VaultConfig, String> {
+    read_vault_config(&dir.vault_file())
+}
+
+fn read_vault_config(path: &Path) -> Result<VaultConfig, String> {
+    if !path.exists() {
+        return Err("The vault is not enabled.".to_string());
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Could not read vault file: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Could not parse vault file: {e}"))
+}
+
+fn save_vault_config(dir: &DataDir, config: &VaultConfig) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Could not serialize vault file: {e}"))?;
+    fs::write(dir.vault_file(), payload).map_err(|e| format!("Could not save vault file: {e}"))
+}
+
+fn unlocked_keys() -> MutexGuard<'static, Vec<(PathBuf, VaultKey)>> {
+    UNLOCKED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn unlocked_key(dir: &DataDir) -> Option<VaultKey> {
+    key_for_root(dir.root())
+}
+
+fn key_for_root(root: &Path) -> Option<VaultKey> {
+    unlocked_keys()
+        .iter()
+        .find(|(unlocked, _)| unlocked == root)
+        .map(|(_, key)| key.clone())
+}
+
+fn remember_key(dir: &DataDir, key: VaultKey) {
+    let mut keys = unlocked_keys();
+    keys.retain(|(root, _)| root != dir.root());
+    keys.push((dir.root().to_path_buf(), key));
+}
+
+fn forget_key(dir: &DataDir) {
+    unlocked_keys().retain(|(root, _)| root != dir.root());
+}
@@ -0,0 +1,115 @@
+/// What gets put in front of Discord's window title, e.g. "[Main] ".
+pub fn window_title_prefix(nickname: &str) -> String {
+    format!("[{nickname}] ")
+}
+
+/// Put `prefix` in front of every visible Discord window title that doesn't
+/// already start with it. Discord rewrites its title whenever the channel
+/// changes, so callers re-apply this while it runs. Returns how many windows
+/// were re-titled; always 0 where other apps' titles can't be changed.
+pub fn tag_discord_windows(prefix: &str) -> usize {
+    #[cfg(target_os = "windows")]
+    {
+        return windows_titles::tag(prefix);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = prefix;
+        0
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_titles {
+    use std::{iter, path::Path};
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, BOOL, HWND, LPARAM},
+        System::Threading::{
+            OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+            PROCESS_QUERY_LIMITED_INFORMATION,
+        },
+        UI::WindowsAndMessaging::{
+            EnumWindows, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+            IsWindowVisible, SetWindowTextW,
+        },
+    };
+
+    const DISCORD_EXECUTABLES: [&str; 4] = [
+        "Discord.exe",
+        "DiscordPTB.exe",
+        "DiscordCanary.exe",
+        "DiscordDevelopment.exe",
+    ];
+
+    struct Search<'a> {
+        prefix: &'a str,
+        tagged: usize,
+    }
+
+    pub fn tag(prefix: &str) -> usize {
+        let mut search = Search { prefix, tagged: 0 };
+        unsafe {
+            EnumWindows(Some(visit), &mut search as *mut Search as LPARAM);
+        }
+        search.tagged
+    }
+
+    unsafe extern "system" fn visit(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = &mut *(lparam as *mut Search);
+        if IsWindowVisible(hwnd) == 0 || !belongs_to_discord(hwnd) {
+            return 1;
+        }
+        let title = window_text(hwnd);
+        if title.is_empty() || title.starts_with(search.prefix) {
+            return 1;
+        }
+        let tagged: Vec<u16> = format!("{}{title}", search.prefix)
+            .encode_utf16()
+            .chain(iter::once(0))
+            .collect();
+        if SetWindowTextW(hwnd, tagged.as_ptr()) != 0 {
+            search.tagged += 1;
+        }
+        1
+    }
+
+    unsafe fn window_text(hwnd: HWND) -> String {
+        let len = GetWindowTextLengthW(hwnd);
+        if len <= 0 {
+            return String::new();
+        }
+        let mut buffer = vec![0u16; len as usize + 1];
+        let copied = GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32);
+        String::from_utf16_lossy(&buffer[..copied.max(0) as usize])
+    }
+
+    unsafe fn belongs_to_discord(hwnd: HWND) -> bool {
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut pid);
+        if pid == 0 {
+            return false;
+        }
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return false;
+        }
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let ok =
+            QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buffer.as_mut_ptr(), &mut len);
+        CloseHandle(process);
+        if ok == 0 {
+            return false;
+        }
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        Path::new(&path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                DISCORD_EXECUTABLES
+                    .iter()
+                    .any(|exe| exe.eq_ignore_ascii_case(name))
+            })
+    }
+}
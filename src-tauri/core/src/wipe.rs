@@ -0,0 +1,173 @@
+use serde::Serialize;
+use std::{
+    env, fs,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    app_lock::lock_app,
+    audit::{record_audit, AuditAction},
+    crypto::random_hex,
+    progress::{report_progress, Operation, ProgressStep},
+    storage::{
+        delete_token_from_dir, discord_storage_dirs, token_keys, STORAGE_LOCKED, TOKEN_KEYS,
+    },
+    storage_backup::remove_discord_storage_backups,
+    switch::with_discord_closed,
+    tokens::wipe_all_tokens,
+    vault::lock_vault,
+    DataDir,
+};
+
+// how long a factory reset nonce stays valid
+const RESET_NONCE_TTL: Duration = Duration::from_secs(120);
+
+// the one outstanding factory reset nonce, for the data folder it was issued for
+static PENDING_RESET: Mutex<Option<(PathBuf, String, Instant)>> = Mutex::new(None);
+
+/// What `panic_wipe` got rid of, and anything it couldn't.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PanicWipeReport {
+    /// Profiles whose saved token was deleted; the profiles themselves stay.
+    pub tokens_wiped: usize,
+    /// Discord channels (Stable, PTB, Canary, Development, ...) signed out.
+    pub channels_cleared: usize,
+    pub errors: Vec<String>,
+}
+
+/// Shred every saved token in every backend, sign every installed Discord
+/// channel out and leave Discord closed. For handing a machine over or a
+/// suspected compromise, so it carries on past individual failures. Works
+/// while the app is locked; `confirmed` has to be set.
+pub fn panic_wipe(dir: &DataDir, confirmed: bool) -> Result<PanicWipeReport, String> {
+    let result = wipe_everything(dir, confirmed);
+    record_audit(dir, AuditAction::Wipe, None, None, &result);
+    result
+}
+
+fn wipe_everything(dir: &DataDir, confirmed: bool) -> Result<PanicWipeReport, String> {
+    if !confirmed {
+        return Err("Wiping every token has to be confirmed.".to_string());
+    }
+    let (tokens_wiped, mut errors) = wipe_all_tokens(dir);
+    // copies of Discord's storage still hold the tokens that were in it
+    if let Err(e) = remove_discord_storage_backups(dir) {
+        errors.push(e);
+    }
+
+    // a broken token-keys.json shouldn't stop the built-in keys from being cleared
+    let keys = token_keys(dir).unwrap_or_else(|e| {
+        errors.push(e);
+        TOKEN_KEYS.iter().map(|key| key.to_vec()).collect()
+    });
+    let storage_dirs = discord_storage_dirs().unwrap_or_else(|e| {
+        errors.push(e);
+        Vec::new()
+    });
+
+    // closes Discord even when there's no storage to clear
+    let cleared = with_discord_closed(dir, Operation::Wipe, || {
+        report_progress(dir, Operation::Wipe, ProgressStep::ClearingToken);
+        let mut cleared = 0;
+        let mut failures = Vec::new();
+        for storage_dir in &storage_dirs {
+            match delete_token_from_dir(storage_dir, &keys, false) {
+                Ok(()) => cleared += 1,
+                Err(e) if e.starts_with(STORAGE_LOCKED) => return Err(e),
+                Err(e) => failures.push(format!("{}: {e}", storage_dir.display())),
+            }
+        }
+        Ok((cleared, failures))
+    });
+    let channels_cleared = match cleared {
+        Ok((cleared, failures)) => {
+            errors.extend(failures);
+            cleared
+        }
+        Err(e) => {
+            errors.push(e);
+            0
+        }
+    };
+
+    Ok(PanicWipeReport {
+        tokens_wiped,
+        channels_cleared,
+        errors,
+    })
+}
+
+// ── Factory reset ──
+
+/// First half of a factory reset: a one-time nonce, valid for two minutes,
+/// that `factory_reset` has to be called with.
+pub fn request_factory_reset(dir: &DataDir) -> Result<String, String> {
+    let nonce = random_hex::<16>();
+    *PENDING_RESET
+        .lock()
+        .map_err(|_| "Factory reset state is poisoned.".to_string())? =
+        Some((dir.root().to_path_buf(), nonce.clone(), Instant::now()));
+    Ok(nonce)
+}
+
+/// Delete everything the app keeps: tokens in every backend (keychain entries
+/// included), `accounts.json`, settings, the vault, the activity log, plugins
+/// and leftover temp copies of Discord's storage. The data folder is left
+/// empty, as on first run. Discord itself isn't touched.
+pub fn factory_reset(dir: &DataDir, nonce: &str) -> Result<String, String> {
+    take_reset_nonce(dir, nonce)?;
+
+    let (tokens_wiped, mut errors) = wipe_all_tokens(dir);
+    lock_vault(dir);
+    let _ = lock_app(dir);
+
+    let entries =
+        fs::read_dir(dir.root()).map_err(|e| format!("Could not read app data directory: {e}"))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        if let Err(e) = removed {
+            errors.push(format!("{}: {e}", path.display()));
+        }
+    }
+    // snapshot copies that a crash left behind in the temp folder
+    if let Ok(entries) = fs::read_dir(env::temp_dir()) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("alt-mngr-") {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(format!("Reset finished with errors: {}", errors.join("; ")));
+    }
+    Ok(format!(
+        "Everything was removed, including {tokens_wiped} saved tokens."
+    ))
+}
+
+// nonces are single use, so a second call needs a fresh request
+fn take_reset_nonce(dir: &DataDir, nonce: &str) -> Result<(), String> {
+    let pending = PENDING_RESET
+        .lock()
+        .map_err(|_| "Factory reset state is poisoned.".to_string())?
+        .take();
+    match pending {
+        Some((root, expected, issued))
+            if root == dir.root() && expected == nonce && issued.elapsed() < RESET_NONCE_TTL =>
+        {
+            Ok(())
+        }
+        _ => {
+            Err("The factory reset confirmation expired or doesn't match. Start over.".to_string())
+        }
+    }
+}
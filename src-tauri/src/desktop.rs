@@ -0,0 +1,1547 @@
+use altmng_core::{
+    app_lock::{self, AppLockStatus},
+    audit::{self, AuditAction, AuditPage, AuditQuery},
+    backup::{self, ConflictResolution, ImportPreview},
+    biometric::{self, BiometricStatus},
+    capabilities::{self, PlatformCapabilities},
+    capture_wizard::{self, CaptureWizardState},
+    clipboard,
+    compare::{self, ProfileComparison},
+    diagnostics::{self, DiagnosedOperation, DiagnosticsReport, StorageDiffReport},
+    dry_run::{self, DryRunReport},
+    health::{self, HealthSummary},
+    hotkeys::{self, HotkeyProblem},
+    launch::{self, detect_installations_for_current_os, is_discord_running, DiscordInstallation},
+    mod_bridge::{self, ModBridgeInfo},
+    notifications::NotificationPreset,
+    operation_history::{self, OperationResult, RetriedOperation},
+    overlap::{self, FriendOverlapReport, GuildOverlapReport},
+    path_repair,
+    permissions::{self, PermissionReport},
+    plugins::{self, PluginEventKind, PluginInfo},
+    presence::RichPresence,
+    presets, profile_events,
+    profiles::{self, Profile, PROFILE_ICONS},
+    progress,
+    protection::{self, SwitchConfirmation},
+    retention, run_as,
+    sandbox::{self, SandboxTool},
+    session_import::{self, SessionImport},
+    sessions::{self, CurrentSession, RunningSession},
+    settings::{
+        self, load_launcher_settings, DiscordChannel, LaunchPreset, LauncherSettings, TokenBackend,
+    },
+    storage::{self, STORAGE_LOCKED},
+    storage_backup::{self, StorageBackup},
+    storage_repair, support,
+    switch::{self, SwitchOutcome},
+    tokens::{self, TokenMigration},
+    trust::{self, TrustSummary},
+    upgrades,
+    vault::{self, VaultStatus},
+    window_tag,
+    wipe::{self, PanicWipeReport},
+    DataDir,
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{data_dir, CommandError};
+
+// how long an armed auto-capture keeps watching for a fresh login
+const AUTO_CAPTURE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const AUTO_CAPTURE_POLL: Duration = Duration::from_secs(3);
+// how often a queued switch looks for Discord having exited
+const QUEUED_SWITCH_POLL: Duration = Duration::from_secs(2);
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const AUTO_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const PRUNE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+// Discord resets its title on every channel change, so the tag is re-applied this often
+const WINDOW_TAG_POLL: Duration = Duration::from_secs(2);
+// give up on tagging if Discord never shows up after a switch
+const WINDOW_TAG_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+// matches `title` in tauri.conf.json
+const APP_TITLE: &str = "Discord Alt Manager";
+// matches `identifier` in tauri.conf.json
+#[cfg(target_os = "windows")]
+const APP_IDENTIFIER: &str = "com.filip.alt-mngr";
+
+// ── Data structures ──
+
+// which profile (if any) is waiting for the next login to be captured
+#[derive(Default)]
+struct AutoCaptureState {
+    armed_profile_id: Mutex<Option<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AutoCaptureEvent {
+    profile_id: String,
+    profile: Option<Profile>,
+    error: Option<String>,
+}
+
+// which profile (if any) gets switched to once the user quits Discord
+#[derive(Default)]
+struct QueuedSwitchState {
+    queued_profile_id: Mutex<Option<String>>,
+}
+
+// which profile's nickname is being kept in Discord's window title
+#[derive(Default)]
+struct WindowTagState {
+    tagged_profile_id: Mutex<Option<String>>,
+}
+
+// when the frontend last invoked a command, for auto-lock
+struct ActivityState {
+    last_command_at: Mutex<Instant>,
+}
+
+impl Default for ActivityState {
+    fn default() -> Self {
+        Self {
+            last_command_at: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultLockedEvent {
+    idle_minutes: u32,
+    vault: VaultStatus,
+    app_lock: AppLockStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueuedSwitchEvent {
+    profile_id: String,
+    outcome: Option<SwitchOutcome>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SwitchFailedEvent {
+    profile_id: String,
+    error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SwitchReminderEvent {
+    profile_id: String,
+    reminder: String,
+}
+
+// ── Tauri commands: Profile CRUD ──
+
+#[tauri::command]
+fn list_profiles(app: AppHandle) -> Result<Vec<Profile>, CommandError> {
+    Ok(profiles::list_profiles(&data_dir(&app)?)?)
+}
+
+#[tauri::command]
+fn add_profile(
+    app: AppHandle,
+    nickname: String,
+    avatar_color: Option<String>,
+    icon: Option<String>,
+) -> Result<Profile, String> {
+    profiles::add_profile(
+        &data_dir(&app)?,
+        &nickname,
+        avatar_color.as_deref(),
+        icon.as_deref(),
+    )
+}
+
+#[tauri::command]
+fn update_profile(
+    app: AppHandle,
+    profile_id: String,
+    nickname: String,
+    avatar_color: String,
+    icon: Option<String>,
+    notes: Option<String>,
+    switch_reminder: Option<String>,
+) -> Result<Profile, String> {
+    profiles::update_profile(
+        &data_dir(&app)?,
+        &profile_id,
+        &nickname,
+        &avatar_color,
+        icon.as_deref(),
+        notes.as_deref(),
+        switch_reminder.as_deref(),
+    )
+}
+
+// pushed to the account's servers every time we switch to it
+#[tauri::command]
+fn set_notification_preset(
+    app: AppHandle,
+    profile_id: String,
+    preset: NotificationPreset,
+) -> Result<Profile, String> {
+    profiles::set_notification_preset(&data_dir(&app)?, &profile_id, preset)
+}
+
+// protected profiles need a confirmation for every switch
+#[tauri::command]
+fn set_profile_protected(
+    app: AppHandle,
+    profile_id: String,
+    protected: bool,
+) -> Result<Profile, String> {
+    profiles::set_profile_protected(&data_dir(&app)?, &profile_id, protected)
+}
+
+// what of this profile may leave the machine in backups and digests
+#[tauri::command]
+fn set_export_policy(
+    app: AppHandle,
+    profile_id: String,
+    never_export: bool,
+    never_sync: bool,
+) -> Result<Profile, String> {
+    profiles::set_export_policy(&data_dir(&app)?, &profile_id, never_export, never_sync)
+}
+
+// None turns the profile's Rich Presence off
+#[tauri::command]
+fn set_rich_presence(
+    app: AppHandle,
+    profile_id: String,
+    presence: Option<RichPresence>,
+) -> Result<Profile, String> {
+    profiles::set_rich_presence(&data_dir(&app)?, &profile_id, presence)
+}
+
+// the password goes straight to Credential Manager
+#[tauri::command]
+fn set_windows_account(
+    app: AppHandle,
+    profile_id: String,
+    account: Option<String>,
+    password: Option<String>,
+) -> Result<Profile, String> {
+    run_as::set_windows_account(
+        &data_dir(&app)?,
+        &profile_id,
+        account.as_deref(),
+        password.as_deref(),
+    )
+}
+
+#[tauri::command]
+fn list_sandbox_tools() -> Vec<SandboxTool> {
+    sandbox::available_sandbox_tools()
+}
+
+#[tauri::command]
+fn set_profile_sandbox(
+    app: AppHandle,
+    profile_id: String,
+    tool: Option<SandboxTool>,
+) -> Result<Profile, String> {
+    sandbox::set_profile_sandbox(&data_dir(&app)?, &profile_id, tool)
+}
+
+// on for throwaway alts, off for the main account
+#[tauri::command]
+fn set_clear_cache_on_switch(
+    app: AppHandle,
+    profile_id: String,
+    enabled: bool,
+) -> Result<Profile, String> {
+    profiles::set_clear_cache_on_switch(&data_dir(&app)?, &profile_id, enabled)
+}
+
+#[tauri::command]
+fn remove_profile(app: AppHandle, profile_id: String) -> Result<(), String> {
+    profiles::remove_profile(&data_dir(&app)?, &profile_id)
+}
+
+// look a profile up the way a human would type it (CLI, deep links, hotkeys)
+#[tauri::command]
+fn resolve_profile(app: AppHandle, selector: String) -> Result<Profile, String> {
+    profiles::resolve_profile(&data_dir(&app)?, &selector)
+}
+
+// check a hotkey before binding it; rejections come with combos to try instead
+#[tauri::command]
+fn validate_hotkey(accelerator: String) -> Result<String, HotkeyProblem> {
+    hotkeys::validate_hotkey(&accelerator)
+}
+
+#[tauri::command]
+fn list_profile_icons() -> Vec<String> {
+    PROFILE_ICONS.iter().map(|icon| icon.to_string()).collect()
+}
+
+// ── Tauri commands: Backup & import ──
+
+#[tauri::command]
+fn export_profile_backup(app: AppHandle, path: String, include_tokens: bool) -> Result<(), String> {
+    backup::export_profile_backup(&data_dir(&app)?, Path::new(&path), include_tokens)
+}
+
+// show what an import would do before anything is written
+#[tauri::command]
+fn preview_profile_import(app: AppHandle, path: String) -> Result<ImportPreview, String> {
+    backup::preview_profile_import(&data_dir(&app)?, Path::new(&path))
+}
+
+// apply an import; every conflict needs an explicit resolution
+#[tauri::command]
+fn apply_profile_import(
+    app: AppHandle,
+    path: String,
+    resolutions: Vec<ConflictResolution>,
+) -> Result<Vec<Profile>, String> {
+    backup::apply_profile_import(&data_dir(&app)?, Path::new(&path), &resolutions)
+}
+
+// ── Tauri commands: Launcher settings ──
+
+#[tauri::command]
+fn get_launcher_settings(app: AppHandle) -> Result<LauncherSettings, String> {
+    // the password hash stays in the backend; saving keeps the stored one
+    load_launcher_settings(&data_dir(&app)?.launcher_settings_file()).map(|settings| {
+        LauncherSettings {
+            app_password_hash: None,
+            ..settings
+        }
+    })
+}
+
+#[tauri::command]
+fn save_launcher_settings(
+    app: AppHandle,
+    settings: LauncherSettings,
+) -> Result<LauncherSettings, String> {
+    settings::save_launcher_settings(&data_dir(&app)?, settings)
+}
+
+// what to enter in the Vencord or BetterDiscord plugin
+#[tauri::command]
+fn get_mod_bridge_info(app: AppHandle) -> Result<ModBridgeInfo, String> {
+    mod_bridge::mod_bridge_info(&data_dir(&app)?)
+}
+
+#[tauri::command]
+fn reset_mod_bridge_key(app: AppHandle) -> Result<ModBridgeInfo, String> {
+    mod_bridge::reset_mod_bridge_key(&data_dir(&app)?)
+}
+
+#[tauri::command]
+fn save_launch_preset(app: AppHandle, preset: LaunchPreset) -> Result<LauncherSettings, String> {
+    presets::save_launch_preset(&data_dir(&app)?, preset)
+}
+
+#[tauri::command]
+fn remove_launch_preset(app: AppHandle, name: String) -> Result<LauncherSettings, String> {
+    presets::remove_launch_preset(&data_dir(&app)?, &name)
+}
+
+// `name: None` goes back to the plain channel / custom path settings
+#[tauri::command]
+fn activate_launch_preset(
+    app: AppHandle,
+    name: Option<String>,
+) -> Result<LauncherSettings, String> {
+    presets::activate_launch_preset(&data_dir(&app)?, name.as_deref())
+}
+
+#[tauri::command]
+fn export_launch_presets(app: AppHandle, path: String) -> Result<usize, String> {
+    presets::export_launch_presets(&data_dir(&app)?, Path::new(&path))
+}
+
+#[tauri::command]
+fn import_launch_presets(
+    app: AppHandle,
+    path: String,
+    overwrite: bool,
+) -> Result<LauncherSettings, String> {
+    presets::import_launch_presets(&data_dir(&app)?, Path::new(&path), overwrite)
+}
+
+#[tauri::command]
+fn detect_discord_installations() -> Vec<DiscordInstallation> {
+    detect_installations_for_current_os()
+}
+
+// every Discord channel that's running and the profile it was launched with
+#[tauri::command]
+fn list_running_sessions(app: AppHandle) -> Result<Vec<RunningSession>, String> {
+    Ok(sessions::list_running_sessions(&data_dir(&app)?))
+}
+
+// who Discord's storage says is logged in, read from a copy, and their profile
+#[tauri::command]
+fn get_current_session(
+    app: AppHandle,
+    channel: Option<DiscordChannel>,
+) -> Result<CurrentSession, String> {
+    sessions::get_current_session(&data_dir(&app)?, channel)
+}
+
+// close one channel instead of every Discord
+#[tauri::command]
+fn terminate_session(app: AppHandle, channel: DiscordChannel) -> Result<(), CommandError> {
+    Ok(sessions::terminate_session(&data_dir(&app)?, channel)?)
+}
+
+// close Discord that runs as administrator, behind the OS's elevation prompt
+#[tauri::command]
+fn terminate_discord_elevated() -> Result<(), String> {
+    launch::terminate_discord_elevated()
+}
+
+// ── Tauri commands: Diagnostics ──
+
+#[tauri::command]
+fn get_diagnostics(app: AppHandle) -> Result<DiagnosticsReport, String> {
+    diagnostics::get_diagnostics(&data_dir(&app)?)
+}
+
+#[tauri::command]
+fn get_platform_capabilities() -> PlatformCapabilities {
+    capabilities::get_platform_capabilities()
+}
+
+// a zip to attach to bug reports; never contains tokens
+#[tauri::command]
+fn export_support_bundle(app: AppHandle, path: String) -> Result<String, String> {
+    support::export_support_bundle(&data_dir(&app)?, Path::new(&path))
+}
+
+// run an operation for real and report, redacted, what it did to Discord's token keys
+#[tauri::command]
+fn diagnose_storage_change(
+    app: AppHandle,
+    operation: DiagnosedOperation,
+    profile_id: Option<String>,
+) -> Result<StorageDiffReport, String> {
+    diagnostics::diagnose_storage_change(&data_dir(&app)?, operation, profile_id.as_deref())
+}
+
+// ── Tauri commands: Token management ──
+
+// close Discord, wipe the stored token, and relaunch so the user
+// lands on the login screen and can enter credentials
+#[tauri::command]
+fn prepare_login(
+    app: AppHandle,
+    ignore_call: bool,
+    force_unlock: bool,
+) -> Result<String, CommandError> {
+    let dir = data_dir(&app)?;
+    let message = switch::prepare_login(&dir, ignore_call, force_unlock)?;
+    thread::spawn(move || run_login_watch(dir));
+    Ok(message)
+}
+
+// the last capture and switch result of every profile
+#[tauri::command]
+fn get_operation_history(app: AppHandle) -> Result<HashMap<String, Vec<OperationResult>>, String> {
+    Ok(operation_history::get_operation_history(&data_dir(&app)?))
+}
+
+// run a profile's last failed capture or switch again
+#[tauri::command]
+fn retry_last_operation(
+    app: AppHandle,
+    profile_id: String,
+) -> Result<RetriedOperation, CommandError> {
+    Ok(operation_history::retry_last_operation(
+        &data_dir(&app)?,
+        &profile_id,
+    )?)
+}
+
+// close Discord, pull the token out of its LevelDB, and stash it for this profile;
+// `account` picks a user from Discord's own account switcher, `channel` a client
+#[tauri::command]
+fn capture_token(
+    app: AppHandle,
+    profile_id: String,
+    account: Option<String>,
+    channel: Option<DiscordChannel>,
+) -> Result<Profile, CommandError> {
+    Ok(switch::capture_token(
+        &data_dir(&app)?,
+        &profile_id,
+        account.as_deref(),
+        channel,
+    )?)
+}
+
+// a profile for every account already logged in to an installed client; first-run onboarding
+#[tauri::command]
+fn import_existing_sessions(app: AppHandle) -> Result<SessionImport, String> {
+    session_import::import_existing_sessions(&data_dir(&app)?)
+}
+
+// add this profile to Discord's own account switcher instead of replacing the
+// logged-in account
+#[tauri::command]
+fn hot_add_profile(
+    app: AppHandle,
+    profile_id: String,
+    ignore_call: bool,
+    force_unlock: bool,
+) -> Result<String, CommandError> {
+    Ok(switch::hot_add_profile(
+        &data_dir(&app)?,
+        &profile_id,
+        ignore_call,
+        force_unlock,
+    )?)
+}
+
+// the channels this profile keeps a token of their own for
+#[tauri::command]
+fn list_channel_tokens(app: AppHandle, profile_id: String) -> Result<Vec<DiscordChannel>, String> {
+    tokens::list_channel_tokens(&data_dir(&app)?, &profile_id)
+}
+
+#[tauri::command]
+fn delete_channel_token(
+    app: AppHandle,
+    profile_id: String,
+    channel: DiscordChannel,
+) -> Result<(), String> {
+    tokens::delete_channel_token(&data_dir(&app)?, &profile_id, channel)
+}
+
+// user ids logged in through Discord's own account switcher, to capture from
+#[tauri::command]
+fn list_discord_logged_in_accounts(
+    app: AppHandle,
+    channel: Option<DiscordChannel>,
+) -> Result<Vec<String>, String> {
+    storage::list_discord_logged_in_accounts(&data_dir(&app)?, channel)
+}
+
+// clear Discord's session, relaunch to the login screen, and capture
+// whatever account logs in next straight into this profile
+#[tauri::command]
+fn relogin_profile(
+    app: AppHandle,
+    profile_id: String,
+    ignore_call: bool,
+    force_unlock: bool,
+) -> Result<String, CommandError> {
+    Ok(start_relogin(&app, &profile_id, ignore_call, force_unlock)?)
+}
+
+#[tauri::command]
+fn cancel_auto_capture(auto_capture: tauri::State<'_, AutoCaptureState>) -> Result<(), String> {
+    *auto_capture
+        .armed_profile_id
+        .lock()
+        .map_err(|_| "Auto-capture state is poisoned.".to_string())? = None;
+    capture_wizard::reset_capture_wizard();
+    Ok(())
+}
+
+// where the login-and-capture flow stands; changes also arrive as "capture-wizard" events
+#[tauri::command]
+fn get_capture_wizard() -> CaptureWizardState {
+    capture_wizard::capture_wizard_state()
+}
+
+// closing the wizard also stops an armed auto-capture
+#[tauri::command]
+fn reset_capture_wizard(
+    auto_capture: tauri::State<'_, AutoCaptureState>,
+) -> Result<CaptureWizardState, String> {
+    cancel_auto_capture(auto_capture)?;
+    Ok(capture_wizard::reset_capture_wizard())
+}
+
+// move every saved token to another backend and make it the configured one
+#[tauri::command]
+fn migrate_token_backend(app: AppHandle, to: TokenBackend) -> Result<TokenMigration, String> {
+    tokens::migrate_token_backend(&data_dir(&app)?, to)
+}
+
+// forget the saved token but keep the profile (nickname, notes, ...) around
+#[tauri::command]
+fn clear_profile_token(app: AppHandle, profile_id: String) -> Result<Profile, String> {
+    tokens::clear_profile_token(&data_dir(&app)?, &profile_id)
+}
+
+// only the first and last few characters, for display
+#[tauri::command]
+fn get_token_preview(app: AppHandle, profile_id: String) -> Result<String, CommandError> {
+    Ok(tokens::get_token_preview(&data_dir(&app)?, &profile_id)?)
+}
+
+// the full token; the frontend has to pass `confirmed` after asking the user
+#[tauri::command]
+fn reveal_token(
+    app: AppHandle,
+    profile_id: String,
+    confirmed: bool,
+) -> Result<String, CommandError> {
+    let dir = data_dir(&app)?;
+    Ok(tokens::reveal_token(&dir, &profile_id, confirmed)?)
+}
+
+// the clipboard is wiped again after `ttl_secs` unless something else was copied
+#[tauri::command]
+fn copy_token_to_clipboard(
+    app: AppHandle,
+    profile_id: String,
+    ttl_secs: u64,
+) -> Result<String, CommandError> {
+    let dir = data_dir(&app)?;
+    Ok(clipboard::copy_token_to_clipboard(
+        &dir,
+        &profile_id,
+        ttl_secs,
+    )?)
+}
+
+// drops any armed auto-capture or queued switch too, so nothing logs back in afterwards
+#[tauri::command]
+fn panic_wipe(app: AppHandle, confirmed: bool) -> Result<PanicWipeReport, String> {
+    if confirmed {
+        if let Ok(mut armed) = app.state::<AutoCaptureState>().armed_profile_id.lock() {
+            *armed = None;
+        }
+        capture_wizard::reset_capture_wizard();
+        if let Ok(mut queued) = app.state::<QueuedSwitchState>().queued_profile_id.lock() {
+            *queued = None;
+        }
+    }
+    wipe::panic_wipe(&data_dir(&app)?, confirmed)
+}
+
+// the nonce `factory_reset` has to be called with; the frontend asks the user in between
+#[tauri::command]
+fn request_factory_reset(app: AppHandle) -> Result<String, String> {
+    wipe::request_factory_reset(&data_dir(&app)?)
+}
+
+#[tauri::command]
+fn factory_reset(app: AppHandle, nonce: String) -> Result<String, String> {
+    let message = wipe::factory_reset(&data_dir(&app)?, &nonce)?;
+    if let Ok(mut armed) = app.state::<AutoCaptureState>().armed_profile_id.lock() {
+        *armed = None;
+    }
+    capture_wizard::reset_capture_wizard();
+    if let Ok(mut queued) = app.state::<QueuedSwitchState>().queued_profile_id.lock() {
+        *queued = None;
+    }
+    if let Ok(mut tagged) = app.state::<WindowTagState>().tagged_profile_id.lock() {
+        *tagged = None;
+    }
+    Ok(message)
+}
+
+// report token files other users could read; with `fix`, lock them down
+#[tauri::command]
+fn check_token_permissions(app: AppHandle, fix: bool) -> Result<PermissionReport, String> {
+    permissions::check_token_permissions(&data_dir(&app)?, fix)
+}
+
+// ── Tauri commands: Storage backups ──
+
+#[tauri::command]
+fn list_discord_storage_backups(app: AppHandle) -> Result<Vec<StorageBackup>, String> {
+    storage_backup::list_discord_storage_backups(&data_dir(&app)?)
+}
+
+// for when a write left Discord unable to start or signed in wrong
+#[tauri::command]
+fn restore_discord_storage_backup(
+    app: AppHandle,
+    backup_id: String,
+) -> Result<String, CommandError> {
+    Ok(storage_backup::restore_discord_storage_backup(
+        &data_dir(&app)?,
+        &backup_id,
+    )?)
+}
+
+// closes Discord; may rebuild its storage from the logs when nothing else works
+#[tauri::command]
+fn repair_discord_storage(app: AppHandle) -> Result<String, CommandError> {
+    Ok(storage_repair::repair_discord_storage(&data_dir(&app)?)?)
+}
+
+// ── Tauri commands: Token key patterns ──
+
+#[tauri::command]
+fn list_token_key_patterns(app: AppHandle) -> Result<Vec<String>, String> {
+    storage::list_token_key_patterns(&data_dir(&app)?)
+}
+
+// teach the app a new LevelDB key without waiting for a release
+#[tauri::command]
+fn add_token_key_pattern(app: AppHandle, pattern: String) -> Result<Vec<String>, String> {
+    storage::add_token_key_pattern(&data_dir(&app)?, &pattern)
+}
+
+#[tauri::command]
+fn remove_token_key_pattern(app: AppHandle, pattern: String) -> Result<Vec<String>, String> {
+    storage::remove_token_key_pattern(&data_dir(&app)?, &pattern)
+}
+
+// ── Tauri commands: Token health ──
+
+// ask Discord about every saved token and remember the answers
+#[tauri::command]
+fn validate_profile_tokens(app: AppHandle) -> Result<Vec<Profile>, String> {
+    health::validate_profile_tokens(&data_dir(&app)?)
+}
+
+#[tauri::command]
+fn send_health_digest(app: AppHandle) -> Result<HealthSummary, String> {
+    health::send_health_digest(&data_dir(&app)?)
+}
+
+// inject this profile's saved token back into Discord's storage and launch it
+#[tauri::command]
+fn switch_to_profile(
+    app: AppHandle,
+    profile_id: String,
+    ignore_call: bool,
+    force_unlock: bool,
+    confirmation: Option<SwitchConfirmation>,
+) -> Result<SwitchOutcome, CommandError> {
+    let result = data_dir(&app).and_then(|dir| {
+        switch::switch_to_profile(
+            &dir,
+            &profile_id,
+            ignore_call,
+            force_unlock,
+            confirmation.as_ref(),
+        )
+    });
+    report_switch(&app, &profile_id, &result);
+    Ok(result?)
+}
+
+// lets one switch into a protected profile through, after the user confirmed it
+#[tauri::command]
+fn request_switch_confirmation(app: AppHandle, profile_id: String) -> Result<String, String> {
+    protection::request_switch_confirmation(&data_dir(&app)?, &profile_id)
+}
+
+// switch once the user closes Discord themselves instead of killing it now
+#[tauri::command]
+fn queue_switch(
+    app: AppHandle,
+    profile_id: String,
+    relaunch: bool,
+    confirmation: Option<SwitchConfirmation>,
+) -> Result<String, CommandError> {
+    let dir = data_dir(&app)?;
+    let nickname = profiles::find_profile(&dir, &profile_id)?.nickname;
+    switch::authorize_switch(&dir, &profile_id, confirmation.as_ref())?;
+
+    *app.state::<QueuedSwitchState>()
+        .queued_profile_id
+        .lock()
+        .map_err(|_| "Queued switch state is poisoned.".to_string())? = Some(profile_id.clone());
+
+    let handle = app.clone();
+    thread::spawn(move || run_queued_switch(handle, dir, profile_id, relaunch));
+
+    Ok(format!(
+        "Will switch to '{nickname}' as soon as Discord is closed."
+    ))
+}
+
+#[tauri::command]
+fn cancel_queued_switch(app: AppHandle) -> Result<(), String> {
+    *app.state::<QueuedSwitchState>()
+        .queued_profile_id
+        .lock()
+        .map_err(|_| "Queued switch state is poisoned.".to_string())? = None;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_queued_switch(app: AppHandle) -> Option<String> {
+    app.state::<QueuedSwitchState>()
+        .queued_profile_id
+        .lock()
+        .ok()
+        .and_then(|queued| queued.clone())
+}
+
+// account age, verification and flags at a glance, for picking which alt to use
+#[tauri::command]
+fn get_trust_summary(app: AppHandle, profile_id: String) -> Result<TrustSummary, String> {
+    trust::get_trust_summary(&data_dir(&app)?, &profile_id)
+}
+
+// rehearse a switch on a copy of Discord's storage; the live session is never touched
+#[tauri::command]
+fn dry_run_switch(app: AppHandle, profile_id: String) -> Result<DryRunReport, String> {
+    dry_run::dry_run_switch(&data_dir(&app)?, &profile_id)
+}
+
+// opt-in: looks up friend lists only when the user asks for a comparison
+#[tauri::command]
+fn get_friend_overlap(
+    app: AppHandle,
+    profile_ids: Vec<String>,
+) -> Result<FriendOverlapReport, String> {
+    overlap::friend_overlap(&data_dir(&app)?, &profile_ids)
+}
+
+// opt-in: looks up server lists only when the user asks for a comparison
+#[tauri::command]
+fn get_guild_overlap(
+    app: AppHandle,
+    profile_ids: Vec<String>,
+) -> Result<GuildOverlapReport, String> {
+    overlap::guild_overlap(&data_dir(&app)?, &profile_ids)
+}
+
+// side by side, for deciding which alt to retire; asks Discord about each one
+#[tauri::command]
+fn compare_profiles(
+    app: AppHandle,
+    profile_ids: Vec<String>,
+) -> Result<Vec<ProfileComparison>, String> {
+    compare::compare_profiles(&data_dir(&app)?, &profile_ids)
+}
+
+// ── Tauri commands: Plugins ──
+
+#[tauri::command]
+fn list_plugins(app: AppHandle) -> Result<Vec<PluginInfo>, String> {
+    plugins::list_plugins(&data_dir(&app)?)
+}
+
+// nothing under plugins/ runs until it's enabled here
+#[tauri::command]
+fn set_plugin_enabled(
+    app: AppHandle,
+    plugin_id: String,
+    enabled: bool,
+) -> Result<Vec<PluginInfo>, CommandError> {
+    Ok(plugins::set_plugin_enabled(
+        &data_dir(&app)?,
+        &plugin_id,
+        enabled,
+    )?)
+}
+
+#[tauri::command]
+fn run_plugin_command(
+    app: AppHandle,
+    plugin_id: String,
+    command_id: String,
+    profile_id: Option<String>,
+) -> Result<String, CommandError> {
+    let dir = data_dir(&app)?;
+    Ok(plugins::run_plugin_command(
+        &dir,
+        &plugin_id,
+        &command_id,
+        profile_id.as_deref(),
+    )?)
+}
+
+// ── Tauri commands: Audit log ──
+
+// newest first; `query` filters by action, profile, failures and time
+#[tauri::command]
+fn get_audit_log(app: AppHandle, query: AuditQuery) -> Result<AuditPage, String> {
+    audit::get_audit_log(&data_dir(&app)?, &query)
+}
+
+// ── Tauri commands: App lock ──
+
+#[tauri::command]
+fn get_app_lock_status(app: AppHandle) -> Result<AppLockStatus, String> {
+    app_lock::app_lock_status(&data_dir(&app)?)
+}
+
+// unlocks capture, switch and token export for the rest of this session
+#[tauri::command]
+fn verify_app_password(app: AppHandle, password: String) -> Result<AppLockStatus, String> {
+    app_lock::verify_app_password(&data_dir(&app)?, &password)
+}
+
+#[tauri::command]
+fn lock_app(app: AppHandle) -> Result<AppLockStatus, String> {
+    app_lock::lock_app(&data_dir(&app)?)
+}
+
+// `new_password: None` removes the password
+#[tauri::command]
+fn set_app_password(
+    app: AppHandle,
+    current_password: Option<String>,
+    new_password: Option<String>,
+) -> Result<AppLockStatus, String> {
+    app_lock::set_app_password(
+        &data_dir(&app)?,
+        current_password.as_deref(),
+        new_password.as_deref(),
+    )
+}
+
+// ── Tauri commands: Biometrics ──
+
+// which commands are gated is part of the launcher settings
+#[tauri::command]
+fn get_biometric_status(app: AppHandle) -> Result<BiometricStatus, String> {
+    biometric::biometric_status(&data_dir(&app)?)
+}
+
+// ── Tauri commands: Vault ──
+
+#[tauri::command]
+fn get_vault_status(app: AppHandle) -> Result<VaultStatus, String> {
+    Ok(vault::vault_status(&data_dir(&app)?))
+}
+
+// encrypt every saved token with a key derived from the master password
+#[tauri::command]
+fn enable_vault(app: AppHandle, password: String) -> Result<VaultStatus, String> {
+    vault::enable_vault(&data_dir(&app)?, &password)
+}
+
+#[tauri::command]
+fn disable_vault(app: AppHandle, password: String) -> Result<VaultStatus, String> {
+    vault::disable_vault(&data_dir(&app)?, &password)
+}
+
+#[tauri::command]
+fn unlock_vault(app: AppHandle, password: String) -> Result<VaultStatus, String> {
+    vault::unlock_vault(&data_dir(&app)?, &password)
+}
+
+// also keep nicknames and notes in accounts.json encrypted
+#[tauri::command]
+fn set_profile_encryption(app: AppHandle, enabled: bool) -> Result<VaultStatus, String> {
+    vault::set_profile_encryption(&data_dir(&app)?, enabled)
+}
+
+#[tauri::command]
+fn lock_vault(app: AppHandle) -> Result<VaultStatus, String> {
+    Ok(vault::lock_vault(&data_dir(&app)?))
+}
+
+// ── Helpers: auto-capture ──
+
+// shared by the relogin command and the "Re-login" toast button
+fn start_relogin(
+    app: &AppHandle,
+    profile_id: &str,
+    ignore_call: bool,
+    force_unlock: bool,
+) -> Result<String, String> {
+    let dir = data_dir(app)?;
+    let nickname = profiles::find_profile(&dir, profile_id)?.nickname;
+    // the capture at the end would fail anyway; don't log the user out for nothing
+    app_lock::ensure_app_unlocked(&dir)?;
+    vault::ensure_unlocked(&dir)?;
+
+    switch::prepare_login(&dir, ignore_call, force_unlock)?;
+    capture_wizard::expect_capture_for(profile_id);
+
+    *app.state::<AutoCaptureState>()
+        .armed_profile_id
+        .lock()
+        .map_err(|_| "Auto-capture state is poisoned.".to_string())? = Some(profile_id.to_string());
+
+    let handle = app.clone();
+    let armed_id = profile_id.to_string();
+    thread::spawn(move || run_auto_capture(handle, dir, armed_id));
+
+    Ok(format!(
+        "Discord launched. Log in as '{nickname}' and the token will be captured automatically."
+    ))
+}
+
+fn auto_capture_is_armed_for(app: &AppHandle, profile_id: &str) -> bool {
+    app.state::<AutoCaptureState>()
+        .armed_profile_id
+        .lock()
+        .map(|armed| armed.as_deref() == Some(profile_id))
+        .unwrap_or(false)
+}
+
+// poll a copy of Discord's storage (the live DB stays locked while it runs)
+// until a token shows up, the timeout hits, or the capture gets disarmed
+fn run_auto_capture(app: AppHandle, dir: DataDir, profile_id: String) {
+    let started = Instant::now();
+    let outcome = loop {
+        thread::sleep(AUTO_CAPTURE_POLL);
+        if !auto_capture_is_armed_for(&app, &profile_id) {
+            return;
+        }
+        if started.elapsed() > AUTO_CAPTURE_TIMEOUT {
+            capture_wizard::reset_capture_wizard();
+            break Err("Timed out waiting for a login.".to_string());
+        }
+        if let Ok(token) = capture_wizard::detect_login(&dir) {
+            break switch::store_captured_token(&dir, &profile_id, token.expose());
+        }
+    };
+
+    audit::record_audit(
+        &dir,
+        AuditAction::Capture,
+        Some(&profile_id),
+        Some("automatic"),
+        &outcome,
+    );
+    plugins::dispatch_plugin_event(&dir, PluginEventKind::Capture, Some(&profile_id), &outcome);
+
+    if let Ok(mut armed) = app.state::<AutoCaptureState>().armed_profile_id.lock() {
+        if armed.as_deref() == Some(profile_id.as_str()) {
+            *armed = None;
+        }
+    }
+
+    let (profile, error) = match outcome {
+        Ok(stored) => (Some(stored.into_profile(true)), None),
+        Err(e) => (None, Some(e)),
+    };
+    let _ = app.emit(
+        "auto-capture",
+        AutoCaptureEvent {
+            profile_id,
+            profile,
+            error,
+        },
+    );
+}
+
+// move the wizard on to "token detected" once the user has logged in by hand;
+// capturing stays a separate click
+fn run_login_watch(dir: DataDir) {
+    let started = Instant::now();
+    while started.elapsed() < AUTO_CAPTURE_TIMEOUT {
+        thread::sleep(AUTO_CAPTURE_POLL);
+        let waiting = matches!(
+            capture_wizard::capture_wizard_state(),
+            CaptureWizardState::AwaitingLogin {
+                profile_id: None,
+                ..
+            }
+        );
+        if !waiting || capture_wizard::detect_login(&dir).is_ok() {
+            return;
+        }
+    }
+}
+
+// ── Helpers: queued switch ──
+
+fn switch_is_queued_for(app: &AppHandle, profile_id: &str) -> bool {
+    app.state::<QueuedSwitchState>()
+        .queued_profile_id
+        .lock()
+        .map(|queued| queued.as_deref() == Some(profile_id))
+        .unwrap_or(false)
+}
+
+// wait for Discord to go away on its own, however long that takes,
+// unless the switch gets cancelled or replaced by another one
+fn run_queued_switch(app: AppHandle, dir: DataDir, profile_id: String, relaunch: bool) {
+    let result = loop {
+        while is_discord_running() {
+            thread::sleep(QUEUED_SWITCH_POLL);
+            if !switch_is_queued_for(&app, &profile_id) {
+                return;
+            }
+        }
+        if !switch_is_queued_for(&app, &profile_id) {
+            return;
+        }
+
+        match switch::switch_after_discord_exit(&dir, &profile_id, relaunch) {
+            // Discord came straight back (an update restart); keep waiting
+            Err(e) if e.starts_with(STORAGE_LOCKED) => continue,
+            result => break result,
+        }
+    };
+
+    if let Ok(mut queued) = app.state::<QueuedSwitchState>().queued_profile_id.lock() {
+        if queued.as_deref() == Some(profile_id.as_str()) {
+            *queued = None;
+        }
+    }
+
+    report_switch(&app, &profile_id, &result);
+    let (outcome, error) = match result {
+        Ok(outcome) => (Some(outcome), None),
+        Err(e) => (None, Some(e)),
+    };
+    let _ = app.emit(
+        "queued-switch",
+        QueuedSwitchEvent {
+            profile_id,
+            outcome,
+            error,
+        },
+    );
+}
+
+// ── Helpers: health digest ──
+
+// background loop: wakes up every so often and sends the digest when it's due
+fn run_digest_scheduler(app: AppHandle) {
+    loop {
+        thread::sleep(DIGEST_CHECK_INTERVAL);
+        if let Err(e) = data_dir(&app).and_then(|dir| health::send_digest_if_due(&dir)) {
+            eprintln!("Warning: health digest failed: {e}");
+        }
+    }
+}
+
+// ── Helpers: retention ──
+
+// background loop: prunes once at startup, then every few hours
+fn run_history_pruner(app: AppHandle) {
+    loop {
+        if let Err(e) = data_dir(&app).and_then(|dir| retention::prune_history(&dir)) {
+            eprintln!("Warning: could not prune history: {e}");
+        }
+        thread::sleep(PRUNE_INTERVAL);
+    }
+}
+
+// ── Helpers: auto-lock ──
+
+fn record_activity(app: &AppHandle) {
+    if let Ok(mut last) = app.state::<ActivityState>().last_command_at.lock() {
+        *last = Instant::now();
+    }
+}
+
+fn idle_time(app: &AppHandle) -> Duration {
+    app.state::<ActivityState>()
+        .last_command_at
+        .lock()
+        .map(|last| last.elapsed())
+        .unwrap_or_default()
+}
+
+// background loop: locks the vault and app once nothing has been invoked
+// for the configured number of minutes
+fn run_auto_lock_watcher(app: AppHandle) {
+    loop {
+        thread::sleep(AUTO_LOCK_CHECK_INTERVAL);
+        let Ok(dir) = data_dir(&app) else {
+            continue;
+        };
+        let Ok(settings) = load_launcher_settings(&dir.launcher_settings_file()) else {
+            continue;
+        };
+        let Some(idle_minutes) = settings.auto_lock_minutes else {
+            continue;
+        };
+        if idle_time(&app) < Duration::from_secs(u64::from(idle_minutes) * 60) {
+            continue;
+        }
+
+        // only lock (and tell the UI) when there's something unlocked
+        let vault = vault::vault_status(&dir);
+        let Ok(app_lock) = app_lock::app_lock_status(&dir) else {
+            continue;
+        };
+        let vault_open = vault.enabled && vault.unlocked;
+        let app_open = app_lock.enabled && app_lock.unlocked;
+        if !vault_open && !app_open {
+            continue;
+        }
+
+        let vault = vault::lock_vault(&dir);
+        let Ok(app_lock) = app_lock::lock_app(&dir) else {
+            continue;
+        };
+        let _ = app.emit(
+            "vault-locked",
+            VaultLockedEvent {
+                idle_minutes,
+                vault,
+                app_lock,
+            },
+        );
+    }
+}
+
+// ── Helpers: window title tag ──
+
+// show which profile is active in our own title and, where the OS allows it,
+// in Discord's; every new switch replaces the previous tagger
+fn tag_active_profile(app: &AppHandle, profile_id: &str) {
+    let Ok(dir) = data_dir(app) else {
+        return;
+    };
+    let enabled = load_launcher_settings(&dir.launcher_settings_file())
+        .map(|settings| settings.tag_discord_window)
+        .unwrap_or(false);
+    let nickname = profiles::find_profile(&dir, profile_id)
+        .ok()
+        .map(|profile| profile.nickname)
+        .filter(|_| enabled);
+
+    let title = match &nickname {
+        Some(nickname) => format!("{APP_TITLE} — {nickname}"),
+        None => APP_TITLE.to_string(),
+    };
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_title(&title);
+    }
+
+    // stops any tagger still running for an earlier switch
+    if let Ok(mut tagged) = app.state::<WindowTagState>().tagged_profile_id.lock() {
+        *tagged = nickname.as_ref().map(|_| profile_id.to_string());
+    }
+    let Some(nickname) = nickname else {
+        return;
+    };
+
+    let handle = app.clone();
+    let profile_id = profile_id.to_string();
+    thread::spawn(move || run_window_tagger(handle, profile_id, nickname));
+}
+
+fn window_tag_is_for(app: &AppHandle, profile_id: &str) -> bool {
+    app.state::<WindowTagState>()
+        .tagged_profile_id
+        .lock()
+        .map(|tagged| tagged.as_deref() == Some(profile_id))
+        .unwrap_or(false)
+}
+
+// keep the prefix on Discord's title until it exits or another switch happens
+fn run_window_tagger(app: AppHandle, profile_id: String, nickname: String) {
+    let prefix = window_tag::window_title_prefix(&nickname);
+    let started = Instant::now();
+    let mut seen_running = false;
+    while window_tag_is_for(&app, &profile_id) {
+        thread::sleep(WINDOW_TAG_POLL);
+        if is_discord_running() {
+            seen_running = true;
+            window_tag::tag_discord_windows(&prefix);
+        } else if seen_running || started.elapsed() > WINDOW_TAG_STARTUP_TIMEOUT {
+            break;
+        }
+    }
+}
+
+// ── Helpers: notifications ──
+
+// what every finished switch, direct or queued, reports back
+fn report_switch(app: &AppHandle, profile_id: &str, result: &Result<SwitchOutcome, String>) {
+    match result {
+        Ok(outcome) => {
+            tag_active_profile(app, profile_id);
+            if let Some(reminder) = &outcome.reminder {
+                notify_switch_reminder(app, profile_id, reminder);
+            }
+        }
+        Err(e) => notify_switch_failed(app, profile_id, e),
+    }
+}
+
+fn notify_switch_failed(app: &AppHandle, profile_id: &str, error: &str) {
+    let _ = app.emit(
+        "switch-failed",
+        SwitchFailedEvent {
+            profile_id: profile_id.to_string(),
+            error: error.to_string(),
+        },
+    );
+
+    #[cfg(target_os = "windows")]
+    show_switch_failed_toast(app, profile_id, error);
+}
+
+// the profile's "notes on switch", surfaced even if the window isn't focused
+fn notify_switch_reminder(app: &AppHandle, profile_id: &str, reminder: &str) {
+    let _ = app.emit(
+        "switch-reminder",
+        SwitchReminderEvent {
+            profile_id: profile_id.to_string(),
+            reminder: reminder.to_string(),
+        },
+    );
+
+    #[cfg(target_os = "windows")]
+    show_switch_reminder_toast(reminder);
+}
+
+#[cfg(target_os = "windows")]
+fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+// toast with buttons that route straight back into the backend
+#[cfg(target_os = "windows")]
+fn show_switch_failed_toast(app: &AppHandle, profile_id: &str, error: &str) {
+    use tauri_winrt_notification::Toast;
+
+    let handle = app.clone();
+    let target_id = profile_id.to_string();
+    let result = Toast::new(toast_app_id())
+        .title("Account switch failed")
+        .text1(error)
+        .add_button("Re-login", "relogin")
+        .add_button("Open diagnostics", "diagnostics")
+        .on_activated(move |action| {
+            let handle = handle.clone();
+            let target_id = target_id.clone();
+            match action.as_deref() {
+                Some("relogin") => {
+                    // the toast callback runs on a WinRT thread, don't block it
+                    thread::spawn(move || {
+                        if let Err(e) = start_relogin(&handle, &target_id, false, false) {
+                            notify_switch_failed(&handle, &target_id, &e);
+                        }
+                    });
+                }
+                Some("diagnostics") => {
+                    focus_main_window(&handle);
+                    let _ = handle.emit("open-diagnostics", target_id);
+                }
+                _ => focus_main_window(&handle),
+            }
+            Ok(())
+        })
+        .show();
+
+    if let Err(e) = result {
+        eprintln!("Warning: could not show notification: {e}");
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn show_switch_reminder_toast(reminder: &str) {
+    use tauri_winrt_notification::Toast;
+
+    let result = Toast::new(toast_app_id())
+        .title("Reminder for this account")
+        .text1(reminder)
+        .show();
+
+    if let Err(e) = result {
+        eprintln!("Warning: could not show notification: {e}");
+    }
+}
+
+// unpackaged dev builds have no registered AppUserModelID
+#[cfg(target_os = "windows")]
+fn toast_app_id() -> &'static str {
+    if cfg!(debug_assertions) {
+        tauri_winrt_notification::Toast::POWERSHELL_APP_ID
+    } else {
+        APP_IDENTIFIER
+    }
+}
+
+// ── Entry point ──
+
+pub(crate) fn run() {
+    let commands = tauri::generate_handler![
+        list_profiles,
+        add_profile,
+        update_profile,
+        set_notification_preset,
+        set_clear_cache_on_switch,
+        set_windows_account,
+        list_sandbox_tools,
+        set_profile_sandbox,
+        set_rich_presence,
+        set_export_policy,
+        set_profile_protected,
+        request_switch_confirmation,
+        remove_profile,
+        resolve_profile,
+        list_profile_icons,
+        validate_hotkey,
+        export_profile_backup,
+        preview_profile_import,
+        apply_profile_import,
+        get_launcher_settings,
+        save_launcher_settings,
+        get_mod_bridge_info,
+        reset_mod_bridge_key,
+        save_launch_preset,
+        remove_launch_preset,
+        activate_launch_preset,
+        export_launch_presets,
+        import_launch_presets,
+        detect_discord_installations,
+        list_running_sessions,
+        get_current_session,
+        terminate_session,
+        terminate_discord_elevated,
+        get_diagnostics,
+        get_platform_capabilities,
+        export_support_bundle,
+        diagnose_storage_change,
+        prepare_login,
+        capture_token,
+        list_discord_logged_in_accounts,
+        list_channel_tokens,
+        delete_channel_token,
+        hot_add_profile,
+        import_existing_sessions,
+        get_operation_history,
+        retry_last_operation,
+        clear_profile_token,
+        get_token_preview,
+        reveal_token,
+        copy_token_to_clipboard,
+        panic_wipe,
+        request_factory_reset,
+        factory_reset,
+        check_token_permissions,
+        migrate_token_backend,
+        relogin_profile,
+        cancel_auto_capture,
+        get_capture_wizard,
+        reset_capture_wizard,
+        list_discord_storage_backups,
+        restore_discord_storage_backup,
+        repair_discord_storage,
+        list_token_key_patterns,
+        add_token_key_pattern,
+        remove_token_key_pattern,
+        validate_profile_tokens,
+        send_health_digest,
+        get_trust_summary,
+        get_friend_overlap,
+        get_guild_overlap,
+        compare_profiles,
+        switch_to_profile,
+        queue_switch,
+        cancel_queued_switch,
+        get_queued_switch,
+        dry_run_switch,
+        get_audit_log,
+        list_plugins,
+        set_plugin_enabled,
+        run_plugin_command,
+        get_biometric_status,
+        get_app_lock_status,
+        verify_app_password,
+        lock_app,
+        set_app_password,
+        get_vault_status,
+        enable_vault,
+        disable_vault,
+        unlock_vault,
+        set_profile_encryption,
+        lock_vault,
+    ];
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .manage(AutoCaptureState::default())
+        .manage(QueuedSwitchState::default())
+        .manage(ActivityState::default())
+        .manage(WindowTagState::default())
+        .setup(|app| {
+            let migrated =
+                data_dir(app.handle()).and_then(|dir| tokens::migrate_legacy_token_files(&dir));
+            match migrated {
+                Ok(0) => {}
+                Ok(count) => eprintln!("Moved {count} token file(s) into the token store."),
+                Err(e) => eprintln!("Warning: could not migrate token files: {e}"),
+            }
+
+            // offline mode and custom clients apply process-wide from the first command on
+            if let Err(e) = data_dir(app.handle())
+                .and_then(|dir| load_launcher_settings(&dir.launcher_settings_file()))
+            {
+                eprintln!("Warning: could not load launcher settings: {e}");
+            }
+
+            // an encrypted account file waits for the vault; loading it upgrades it then
+            if let Err(e) =
+                data_dir(app.handle()).and_then(|dir| upgrades::upgrade_data_files(&dir))
+            {
+                eprintln!("Warning: could not upgrade data files: {e}");
+            }
+
+            let bound =
+                data_dir(app.handle()).and_then(|dir| tokens::bind_token_files_to_machine(&dir));
+            match bound {
+                Ok(0) => {}
+                Ok(count) => eprintln!("Bound {count} token file(s) to this machine."),
+                Err(e) => eprintln!("Warning: could not bind token files to this machine: {e}"),
+            }
+
+            // screen readers announce these; the frontend puts them in a live region
+            let handle = app.handle().clone();
+            progress::set_progress_listener(move |event| {
+                let _ = handle.emit("operation-progress", event);
+            });
+
+            // the frontend patches its profile list from these instead of reloading it
+            let handle = app.handle().clone();
+            profile_events::set_profiles_listener(move |delta| {
+                let _ = handle.emit("profiles-changed", delta);
+            });
+
+            // a Discord update removed the folder a custom path pointed into
+            let handle = app.handle().clone();
+            path_repair::set_path_repair_listener(move |repair| {
+                let _ = handle.emit("custom-path-repaired", repair);
+            });
+
+            let handle = app.handle().clone();
+            capture_wizard::set_capture_wizard_listener(move |state| {
+                let _ = handle.emit("capture-wizard", state);
+            });
+
+            let handle = app.handle().clone();
+            thread::spawn(move || run_digest_scheduler(handle));
+
+            // token pushes from a client-mod plugin, while the setting is on
+            match data_dir(app.handle()) {
+                Ok(dir) => {
+                    thread::spawn(move || mod_bridge::serve_mod_bridge(dir));
+                }
+                Err(e) => eprintln!("Warning: could not start the mod bridge: {e}"),
+            }
+
+            let handle = app.handle().clone();
+            thread::spawn(move || run_auto_lock_watcher(handle));
+
+            let handle = app.handle().clone();
+            thread::spawn(move || run_history_pruner(handle));
+            Ok(())
+        })
+        // every command counts as user activity for auto-lock
+        .invoke_handler(move |invoke| {
+            record_activity(invoke.message.webview_ref().app_handle());
+            commands(invoke)
+        })
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
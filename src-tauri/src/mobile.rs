@@ -0,0 +1,108 @@
+use altmng_core::{
+    app_lock::{self, AppLockStatus},
+    backup::{self, ConflictResolution, ImportPreview},
+    capabilities::{self, PlatformCapabilities},
+    profiles::{self, Profile},
+    tokens,
+    vault::{self, VaultStatus},
+};
+use tauri::AppHandle;
+
+use crate::{data_dir, CommandError};
+
+// ── Tauri commands: Profiles ──
+
+#[tauri::command]
+fn list_profiles(app: AppHandle) -> Result<Vec<Profile>, CommandError> {
+    Ok(profiles::list_profiles(&data_dir(&app)?)?)
+}
+
+// only the first and last few characters, for display
+#[tauri::command]
+fn get_token_preview(app: AppHandle, profile_id: String) -> Result<String, CommandError> {
+    Ok(tokens::get_token_preview(&data_dir(&app)?, &profile_id)?)
+}
+
+// the full token for the frontend to put on the clipboard; the OS clipboard
+// tools the desktop build uses don't exist on phones
+#[tauri::command]
+fn reveal_token(
+    app: AppHandle,
+    profile_id: String,
+    confirmed: bool,
+) -> Result<String, CommandError> {
+    let dir = data_dir(&app)?;
+    Ok(tokens::reveal_token(&dir, &profile_id, confirmed)?)
+}
+
+#[tauri::command]
+fn get_platform_capabilities() -> PlatformCapabilities {
+    capabilities::get_platform_capabilities()
+}
+
+// ── Tauri commands: Sync from desktop ──
+
+// a picked file on a phone has no path the app can open, so the export
+// arrives as text
+#[tauri::command]
+fn preview_desktop_export(app: AppHandle, contents: String) -> Result<ImportPreview, String> {
+    backup::preview_profile_import_contents(&data_dir(&app)?, &contents)
+}
+
+#[tauri::command]
+fn apply_desktop_export(
+    app: AppHandle,
+    contents: String,
+    resolutions: Vec<ConflictResolution>,
+) -> Result<Vec<Profile>, String> {
+    backup::apply_profile_import_contents(&data_dir(&app)?, &contents, &resolutions)
+}
+
+// ── Tauri commands: Locks ──
+
+#[tauri::command]
+fn get_app_lock_status(app: AppHandle) -> Result<AppLockStatus, String> {
+    app_lock::app_lock_status(&data_dir(&app)?)
+}
+
+#[tauri::command]
+fn verify_app_password(app: AppHandle, password: String) -> Result<AppLockStatus, String> {
+    app_lock::verify_app_password(&data_dir(&app)?, &password)
+}
+
+#[tauri::command]
+fn get_vault_status(app: AppHandle) -> Result<VaultStatus, String> {
+    Ok(vault::vault_status(&data_dir(&app)?))
+}
+
+#[tauri::command]
+fn unlock_vault(app: AppHandle, password: String) -> Result<VaultStatus, String> {
+    vault::unlock_vault(&data_dir(&app)?, &password)
+}
+
+#[tauri::command]
+fn lock_vault(app: AppHandle) -> Result<VaultStatus, String> {
+    Ok(vault::lock_vault(&data_dir(&app)?))
+}
+
+// ── Entry point ──
+
+pub(crate) fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .invoke_handler(tauri::generate_handler![
+            list_profiles,
+            get_token_preview,
+            reveal_token,
+            get_platform_capabilities,
+            preview_desktop_export,
+            apply_desktop_export,
+            get_app_lock_status,
+            verify_app_password,
+            get_vault_status,
+            unlock_vault,
+            lock_vault,
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
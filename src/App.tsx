@@ -1,29 +1,440 @@
 import { FormEvent, useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import "./App.css";
 
-type DiscordChannel = "auto" | "stable" | "ptb" | "canary";
+type DiscordChannel =
+  | "auto"
+  | "stable"
+  | "ptb"
+  | "canary"
+  | "development"
+  | "vesktop"
+  | "webCord"
+  | "armCord"
+  | "custom";
 
 type Profile = {
   id: string;
   nickname: string;
   avatarColor: string;
+  icon: ProfileIcon | null;
   createdAtMs: number;
   hasToken: boolean;
+  switchReminder: string | null;
+  notificationPreset: NotificationPreset;
+  clearCacheOnSwitch: boolean;
+  protected: boolean;
+  tokenStatus: "unknown" | "valid" | "quarantined";
+  richPresence: RichPresence | null;
+  neverExport: boolean;
+  neverSync: boolean;
+  windowsAccount: string | null;
+  sandbox: SandboxTool | null;
+};
+
+type NotificationPreset = "unchanged" | "mentionsOnly" | "muteAll";
+
+type ProfileIcon =
+  | "gaming"
+  | "work"
+  | "test-tube"
+  | "ghost"
+  | "star"
+  | "shield"
+  | "code"
+  | "music"
+  | "heart"
+  | "bot";
+
+type SessionImport = {
+  imported: Profile[];
+  alreadySaved: number;
+  skipped: string[];
+};
+
+type SwitchOutcome = {
+  message: string;
+  reminder: string | null;
+};
+
+type TokenBackend = "file" | "encryptedFile" | "keychain" | "sqlite";
+
+type WriteStrategy = "put" | "deleteThenPut" | "allKeys";
+
+type CaptureMode = "closeDiscord" | "snapshot" | "devTools";
+
+type LaunchPreset = {
+  name: string;
+  preferredChannel: DiscordChannel;
+  customExecutablePath: string | null;
+  customClient: string | null;
+};
+
+type CustomClient = {
+  name: string;
+  executablePath: string;
+  storageDir: string;
+  processName: string;
+};
+
+const EMPTY_CLIENT: CustomClient = {
+  name: "",
+  executablePath: "",
+  storageDir: "",
+  processName: "",
+};
+
+type BiometricGate = {
+  switchProfile: boolean;
+  revealToken: boolean;
+};
+
+type BiometricStatus = {
+  available: boolean;
+  gate: BiometricGate;
+};
+
+type PlatformCapabilities = {
+  os: string;
+  companion: boolean;
+  tokenBackends: TokenBackend[];
+  keychain: boolean;
+  machineBoundFiles: boolean;
+  discordTokenDecryption: boolean;
+  biometric: boolean;
+  callDetection: boolean;
+  windowTagging: boolean;
+  squirrelUpdater: boolean;
+  storeBuild: boolean;
+  spotlightDetection: boolean;
+  linuxInstallPaths: boolean;
+  tray: boolean;
+  globalHotkeys: boolean;
+  sandboxedLaunch: boolean;
+};
+
+type ModBridgeInfo = {
+  endpoint: string;
+  key: string;
 };
 
 type LauncherSettings = {
   preferredChannel: DiscordChannel;
   customExecutablePath: string | null;
+  tokenBackend: TokenBackend;
+  writeStrategy: WriteStrategy;
+  captureMode: CaptureMode;
+  modBridge: boolean;
+  biometric: BiometricGate;
+  autoLockMinutes: number | null;
+  tagDiscordWindow: boolean;
+  offlineMode: boolean;
+  retention: {
+    switchHistoryDays: number | null;
+    auditLogDays: number | null;
+  };
+  presets: LaunchPreset[];
+  activePreset: string | null;
+  customClients: CustomClient[];
+  customClient: string | null;
+  installSearchRoots: string[];
+  launchThroughUpdater: boolean;
+  updateBeforeSwitch: boolean;
+};
+
+type PluginCommand = {
+  id: string;
+  title: string;
+  needsProfile: boolean;
+};
+
+type RichPresence = {
+  clientId: string;
+  details: string | null;
+  state: string | null;
+  largeImage: string | null;
+  largeText: string | null;
+};
+
+type CaptureWizardState =
+  | { state: "idle" }
+  | { state: "awaitingLogin"; profileId: string | null; sinceMs: number }
+  | { state: "tokenDetected"; profileId: string | null; detectedAtMs: number }
+  | { state: "captured"; profileId: string; capturedAtMs: number };
+
+type SwitchConfirmation =
+  | { kind: "token"; value: string }
+  | { kind: "vaultPassword"; value: string };
+
+type StorageBackup = {
+  id: string;
+  storageDir: string;
+  createdAtMs: number;
+  channel: DiscordChannel | null;
+};
+
+type CurrentSession = {
+  channel: DiscordChannel | null;
+  loggedIn: boolean;
+  userId: string | null;
+  username: string | null;
+  tokenRejected: boolean;
+  profileId: string | null;
+  nickname: string | null;
+};
+
+type RunningSession = {
+  channel: DiscordChannel;
+  pids: number[];
+  profileId: string | null;
+  nickname: string | null;
+  launchedAtMs: number | null;
+};
+
+const TOKEN_BACKENDS: TokenBackend[] = [
+  "file",
+  "encryptedFile",
+  "keychain",
+  "sqlite",
+];
+
+const TOKEN_BACKEND_NAMES: Record<TokenBackend, string> = {
+  file: "Files (bound to this computer)",
+  encryptedFile: "Encrypted files",
+  keychain: "System keychain",
+  sqlite: "SQLite database",
+};
+
+const SANDBOX_NAMES: Record<SandboxTool, string> = {
+  sandboxie: "Sandboxie-Plus",
+  firejail: "firejail",
+  bubblewrap: "bubblewrap",
+};
+
+const MOD_NAMES: Record<ClientMod, string> = {
+  betterDiscord: "BetterDiscord",
+  vencord: "Vencord",
+  openAsar: "OpenAsar",
+};
+
+const CHANNEL_NAMES: Record<DiscordChannel, string> = {
+  auto: "Discord",
+  stable: "Discord",
+  ptb: "Discord PTB",
+  canary: "Discord Canary",
+  development: "Discord Development",
+  vesktop: "Vesktop",
+  webCord: "WebCord",
+  armCord: "ArmCord",
+  custom: "Custom client",
+};
+
+type PluginInfo = {
+  id: string;
+  name: string;
+  description: string;
+  enabled: boolean;
+  events: ("switch" | "capture")[];
+  commands: PluginCommand[];
+  error: string | null;
+};
+
+type PermissionReport = {
+  checked: number;
+  lax: { path: string; problem: string; fixed: boolean }[];
+};
+
+type PanicWipeReport = {
+  tokensWiped: number;
+  channelsCleared: number;
+  errors: string[];
+};
+
+type VaultLockedEvent = {
+  idleMinutes: number;
+  vault: VaultStatus;
+  appLock: AppLockStatus;
+};
+
+type SharedItem = {
+  id: string;
+  name: string;
+  profileIds: string[];
+};
+
+type ProfileLookup = {
+  profileId: string;
+  count: number | null;
+  error: string | null;
+};
+
+type FriendOverlapReport = {
+  profiles: ProfileLookup[];
+  shared: SharedItem[];
+  directLinks: { profileId: string; friendProfileId: string }[];
+};
+
+type GuildOverlapReport = {
+  profiles: ProfileLookup[];
+  shared: SharedItem[];
+};
+
+type ProfileComparison = {
+  profileId: string;
+  nickname: string;
+  accountCreatedAtMs: number | null;
+  nitro: "none" | "classic" | "nitro" | "basic" | null;
+  guildCount: number | null;
+  tokenStatus: Profile["tokenStatus"];
+  tokenCheckedAtMs: number | null;
+  lastUsedMs: number | null;
+  error: string | null;
+};
+
+const NITRO_NAMES: Record<NonNullable<ProfileComparison["nitro"]>, string> = {
+  none: "None",
+  classic: "Nitro Classic",
+  nitro: "Nitro",
+  basic: "Nitro Basic",
+};
+
+type AuditEntry = {
+  atMs: number;
+  action: "capture" | "switch" | "export" | "reveal" | "wipe" | "restore";
+  profileId: string | null;
+  succeeded: boolean;
+  detail: string | null;
+  error: string | null;
+};
+
+type AuditPage = {
+  entries: AuditEntry[];
+  total: number;
+  offset: number;
+};
+
+const AUDIT_PAGE_SIZE = 50;
+// how long a copied token stays on the clipboard
+const CLIPBOARD_TTL_SECS = 30;
+
+type QueuedSwitchEvent = {
+  profileId: string;
+  outcome: SwitchOutcome | null;
+  error: string | null;
+};
+
+// one step of a capture or switch, with a sentence for screen readers
+type ProgressEvent = {
+  operation: "login" | "capture" | "switch" | "wipe" | "restore" | "repair";
+  step: { kind: string; attempt?: number; label?: string };
+  narration: string;
+};
+
+type OperationResult = {
+  operation: ProgressEvent["operation"];
+  succeeded: boolean;
+  finishedAtMs: number;
+  durationMs: number;
+  failedStep: ProgressEvent["step"] | null;
+  error: string | null;
+};
+
+type RetriedOperation =
+  | { operation: "capture"; profile: Profile }
+  | { operation: "switch"; outcome: SwitchOutcome };
+
+// where a capture or switch was when it failed
+const STEP_NAMES: Record<string, string> = {
+  closingDiscord: "closing Discord",
+  retryingAfterRestart: "closing Discord again",
+  clearingToken: "signing Discord out",
+  readingToken: "reading the token",
+  savingToken: "saving the token",
+  backingUpStorage: "backing up Discord's storage",
+  writingToken: "writing the token",
+  verifyingToken: "checking the written token",
+  clearingCache: "clearing the cache",
+  updatingDiscord: "updating Discord",
+  launchingDiscord: "launching Discord",
+  applyingNotificationPreset: "applying notification settings",
+};
+
+type TrustSummary = {
+  profileId: string;
+  ageDays: number | null;
+  age: "new" | "established" | "aged" | null;
+  emailVerified: boolean | null;
+  mfaEnabled: boolean | null;
+  phoneVerified: boolean | null;
+  flagged: boolean;
+  flagReasons: string[];
+  lookupError: string | null;
+};
+
+type AppLockStatus = {
+  enabled: boolean;
+  unlocked: boolean;
+};
+
+type VaultStatus = {
+  enabled: boolean;
+  unlocked: boolean;
+  profilesEncrypted: boolean;
+};
+
+// capture/switch/relogin/prepare-login reject with this instead of a plain string
+type CommandError = {
+  kind:
+    | "vaultLocked"
+    | "appLocked"
+    | "inCall"
+    | "protected"
+    | "quarantined"
+    | "preflight"
+    | "needsElevation"
+    | "discordRunning"
+    | "storageDamaged"
+    | "failed";
+  message: string;
+};
+
+// checks the user chose to skip after the backend warned about them
+type Overrides = {
+  ignoreCall: boolean;
+  forceUnlock: boolean;
+};
+
+const NO_OVERRIDES: Overrides = { ignoreCall: false, forceUnlock: false };
+
+type ProfilesDelta = {
+  added: string[];
+  updated: string[];
+  removed: string[];
 };
 
 type DiscordInstallation = {
   channel: DiscordChannel;
   label: string;
   executablePath: string;
+  appUserModelId: string | null;
+  launchUri: string | null;
+  version: string | null;
+  arch: string | null;
+  mods: ClientMod[];
+};
+
+type ClientMod = "betterDiscord" | "vencord" | "openAsar";
+
+type SandboxTool = "sandboxie" | "firejail" | "bubblewrap";
+
+type PathRepair = {
+  preset: string | null;
+  oldPath: string;
+  newPath: string;
 };
 
-type View = "empty" | "adding" | "profile";
+type View = "empty" | "adding" | "profile" | "audit" | "compare";
 
 const PALETTE = [
   "#4361ee",
@@ -44,6 +455,50 @@ function formatDate(ms: number) {
   });
 }
 
+// outline paths on a 24x24 grid, drawn with the avatar's text color
+const PROFILE_ICONS: Record<ProfileIcon, string> = {
+  gaming:
+    "M6 11h4M8 9v4M15 12h.01M18 10h.01M17.32 5H6.68a4 4 0 0 0-3.98 3.59L2 15a3 3 0 0 0 5.12 2.12L9 15h6l1.88 2.12A3 3 0 0 0 22 15l-.7-6.41A4 4 0 0 0 17.32 5z",
+  work: "M16 20V4a2 2 0 0 0-2-2h-4a2 2 0 0 0-2 2v16M4 6h16a2 2 0 0 1 2 2v10a2 2 0 0 1-2 2H4a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2z",
+  "test-tube": "M14.5 2v17.5a2.5 2.5 0 0 1-5 0V2M8.5 2h7M14.5 16h-5",
+  ghost:
+    "M9 10h.01M15 10h.01M12 2a8 8 0 0 0-8 8v12l3-3 2.5 2.5L12 19l2.5 2.5L17 19l3 3V10a8 8 0 0 0-8-8z",
+  star: "M12 2l3.09 6.26L22 9.27l-5 4.87 1.18 6.88L12 17.77l-6.18 3.25L7 14.14 2 9.27l6.91-1.01L12 2z",
+  shield: "M12 22s8-4 8-10V5l-8-3-8 3v7c0 6 8 10 8 10z",
+  code: "M16 18l6-6-6-6M8 6l-6 6 6 6",
+  music:
+    "M9 18V5l12-2v13M9 18a3 3 0 1 1-6 0 3 3 0 0 1 6 0zM21 16a3 3 0 1 1-6 0 3 3 0 0 1 6 0z",
+  heart:
+    "M20.84 4.61a5.5 5.5 0 0 0-7.78 0L12 5.67l-1.06-1.06a5.5 5.5 0 0 0-7.78 7.78l1.06 1.06L12 21.23l7.78-7.78 1.06-1.06a5.5 5.5 0 0 0 0-7.78z",
+  bot: "M12 8V4H8M6 8h12a2 2 0 0 1 2 2v8a2 2 0 0 1-2 2H6a2 2 0 0 1-2-2v-8a2 2 0 0 1 2-2zM2 14h2M20 14h2M15 13v2M9 13v2",
+};
+
+function ProfileIconSvg({ icon }: { icon: ProfileIcon }) {
+  return (
+    <svg
+      className="profile-icon"
+      viewBox="0 0 24 24"
+      fill="none"
+      stroke="currentColor"
+      strokeWidth={2}
+      strokeLinecap="round"
+      strokeLinejoin="round"
+      aria-label={icon}
+    >
+      <path d={PROFILE_ICONS[icon]} />
+    </svg>
+  );
+}
+
+// the picked icon, or the initials when there is none
+function avatarContent(profile: Profile) {
+  return profile.icon ? (
+    <ProfileIconSvg icon={profile.icon} />
+  ) : (
+    initialsOf(profile.nickname)
+  );
+}
+
 function initialsOf(name: string) {
   const words = name.trim().split(/\s+/).filter(Boolean);
   if (words.length === 0) return "?";
@@ -51,10 +506,29 @@ function initialsOf(name: string) {
   return `${words[0][0]}${words[1][0]}`.toUpperCase();
 }
 
+function errorMessage(err: unknown) {
+  if (typeof err === "object" && err !== null && "message" in err) {
+    return String((err as CommandError).message);
+  }
+  return String(err);
+}
+
+function errorKind(err: unknown) {
+  return typeof err === "object" && err !== null
+    ? (err as CommandError).kind
+    : null;
+}
+
 function App() {
   const [profiles, setProfiles] = useState<Profile[]>([]);
   const [installations, setInstallations] = useState<DiscordInstallation[]>([]);
-  const [, setSettings] = useState<LauncherSettings | null>(null);
+  // channels the selected profile keeps a token of their own for
+  const [channelTokens, setChannelTokens] = useState<DiscordChannel[]>([]);
+  // the client to capture from; null for the one launches use
+  const [captureChannel, setCaptureChannel] = useState<DiscordChannel | null>(
+    null,
+  );
+  const [settings, setSettings] = useState<LauncherSettings | null>(null);
 
   const [selectedId, setSelectedId] = useState<string | null>(null);
   const [view, setView] = useState<View>("empty");
@@ -65,17 +539,96 @@ function App() {
   const [editing, setEditing] = useState(false);
   const [editNickname, setEditNickname] = useState("");
   const [editColor, setEditColor] = useState(PALETTE[0]);
+  const [editIcon, setEditIcon] = useState<ProfileIcon | null>(null);
+  const [editReminder, setEditReminder] = useState("");
 
   const [settingsChannel, setSettingsChannel] =
     useState<DiscordChannel>("auto");
   const [settingsCustomPath, setSettingsCustomPath] = useState("");
+  const [settingsClients, setSettingsClients] = useState<CustomClient[]>([]);
+  const [settingsCustomClient, setSettingsCustomClient] = useState("");
+  const [clientDraft, setClientDraft] = useState<CustomClient>(EMPTY_CLIENT);
+  const [settingsSearchRoots, setSettingsSearchRoots] = useState("");
+  const [presetName, setPresetName] = useState("");
+  const [settingsBiometric, setSettingsBiometric] = useState<BiometricGate>({
+    switchProfile: false,
+    revealToken: false,
+  });
+  const [biometricAvailable, setBiometricAvailable] = useState(false);
+  const [capabilities, setCapabilities] = useState<PlatformCapabilities | null>(
+    null,
+  );
+  const [sandboxTools, setSandboxTools] = useState<SandboxTool[]>([]);
+  const [windowsAccountDraft, setWindowsAccountDraft] = useState("");
+  const [windowsPasswordDraft, setWindowsPasswordDraft] = useState("");
+  const [settingsAutoLock, setSettingsAutoLock] = useState("");
+  const [settingsTagWindow, setSettingsTagWindow] = useState(false);
+  const [settingsThroughUpdater, setSettingsThroughUpdater] = useState(true);
+  const [settingsUpdateFirst, setSettingsUpdateFirst] = useState(false);
+  const [settingsOffline, setSettingsOffline] = useState(false);
+  const [settingsModBridge, setSettingsModBridge] = useState(false);
+  const [modBridgeInfo, setModBridgeInfo] = useState<ModBridgeInfo | null>(
+    null,
+  );
+  const [settingsSwitchDays, setSettingsSwitchDays] = useState("");
+  const [settingsAuditDays, setSettingsAuditDays] = useState("");
+  const [settingsTokenBackend, setSettingsTokenBackend] =
+    useState<TokenBackend>("file");
+  const [settingsWriteStrategy, setSettingsWriteStrategy] =
+    useState<WriteStrategy>("put");
+  const [settingsCaptureMode, setSettingsCaptureMode] =
+    useState<CaptureMode>("closeDiscord");
+
+  const [trust, setTrust] = useState<TrustSummary | null>(null);
+  const [shownToken, setShownToken] = useState<{
+    profileId: string;
+    text: string;
+    revealed: boolean;
+  } | null>(null);
+
+  const [appLock, setAppLock] = useState<AppLockStatus | null>(null);
+  const [appPasswordInput, setAppPasswordInput] = useState("");
+  const [newAppPassword, setNewAppPassword] = useState("");
+
+  const [vault, setVault] = useState<VaultStatus | null>(null);
+  // accounts.json is encrypted and the vault is locked, so there's nothing to list
+  const [profilesLocked, setProfilesLocked] = useState(false);
+  const [plugins, setPlugins] = useState<PluginInfo[]>([]);
+  const [sessions, setSessions] = useState<RunningSession[] | null>(null);
+  const [currentSession, setCurrentSession] = useState<CurrentSession | null>(
+    null,
+  );
+  const [storageBackups, setStorageBackups] = useState<
+    StorageBackup[] | null
+  >(null);
+  const [vaultPassword, setVaultPassword] = useState("");
 
   const [busy, setBusy] = useState(false);
+  const [queuedSwitchId, setQueuedSwitchId] = useState<string | null>(null);
+  const [auditEntries, setAuditEntries] = useState<AuditEntry[]>([]);
+  const [auditTotal, setAuditTotal] = useState(0);
+  const [auditFailuresOnly, setAuditFailuresOnly] = useState(false);
+  const [compareIds, setCompareIds] = useState<string[]>([]);
+  const [friendOverlap, setFriendOverlap] =
+    useState<FriendOverlapReport | null>(null);
+  const [guildOverlap, setGuildOverlap] = useState<GuildOverlapReport | null>(
+    null,
+  );
+  const [comparison, setComparison] = useState<ProfileComparison[] | null>(
+    null,
+  );
+  const [operationHistory, setOperationHistory] = useState<
+    Record<string, OperationResult[]>
+  >({});
   const [loading, setLoading] = useState(true);
   const [status, setStatus] = useState("");
+  const [narration, setNarration] = useState("");
   const [error, setError] = useState("");
   const [confirmRemove, setConfirmRemove] = useState<Profile | null>(null);
   const [waitingForLogin, setWaitingForLogin] = useState<string | null>(null);
+  const [captureWizard, setCaptureWizard] = useState<CaptureWizardState>({
+    state: "idle",
+  });
 
   const sortedProfiles = useMemo(
     () => profiles.slice().sort((a, b) => b.createdAtMs - a.createdAtMs),
@@ -86,6 +639,10 @@ function App() {
     () => profiles.find((p) => p.id === selectedId) ?? null,
     [profiles, selectedId],
   );
+  // newest first, so this is whichever of capture or switch ran last
+  const lastOperation = selectedId
+    ? (operationHistory[selectedId]?.[0] ?? null)
+    : null;
 
   function showStatus(msg: string) {
     setStatus(msg);
@@ -99,19 +656,84 @@ function App() {
     setTimeout(() => setError(""), 6000);
   }
 
+  async function listProfiles() {
+    try {
+      const loaded = await invoke<Profile[]>("list_profiles");
+      setProfilesLocked(false);
+      return loaded;
+    } catch (err) {
+      if (errorKind(err) !== "vaultLocked") throw err;
+      setProfilesLocked(true);
+      return [];
+    }
+  }
+
+  // the account list comes and goes with the vault when it's encrypted too
+  async function syncProfilesWithVault(next: VaultStatus) {
+    if (!next.profilesEncrypted) return;
+    if (next.unlocked) {
+      setProfiles(await listProfiles());
+    } else {
+      setProfiles([]);
+      setProfilesLocked(true);
+      setSelectedId(null);
+      setView("empty");
+    }
+  }
+
   async function loadData() {
     try {
-      const [loadedProfiles, loadedSettings, loadedInstallations] =
-        await Promise.all([
-          invoke<Profile[]>("list_profiles"),
-          invoke<LauncherSettings>("get_launcher_settings"),
-          invoke<DiscordInstallation[]>("detect_discord_installations"),
-        ]);
+      const [
+        loadedProfiles,
+        loadedSettings,
+        loadedInstallations,
+        loadedVault,
+        loadedAppLock,
+        loadedBiometric,
+        loadedPlugins,
+        loadedCapabilities,
+      ] = await Promise.all([
+        listProfiles(),
+        invoke<LauncherSettings>("get_launcher_settings"),
+        invoke<DiscordInstallation[]>("detect_discord_installations"),
+        invoke<VaultStatus>("get_vault_status"),
+        invoke<AppLockStatus>("get_app_lock_status"),
+        invoke<BiometricStatus>("get_biometric_status"),
+        invoke<PluginInfo[]>("list_plugins"),
+        invoke<PlatformCapabilities>("get_platform_capabilities"),
+      ]);
+      setPlugins(loadedPlugins);
+      setCapabilities(loadedCapabilities);
+      if (loadedCapabilities.sandboxedLaunch) {
+        setSandboxTools(await invoke<SandboxTool[]>("list_sandbox_tools"));
+      }
+      setBiometricAvailable(loadedBiometric.available);
+      setAppLock(loadedAppLock);
       setProfiles(loadedProfiles);
+      setVault(loadedVault);
       setSettings(loadedSettings);
       setInstallations(loadedInstallations);
       setSettingsChannel(loadedSettings.preferredChannel);
       setSettingsCustomPath(loadedSettings.customExecutablePath ?? "");
+      setSettingsClients(loadedSettings.customClients);
+      setSettingsCustomClient(loadedSettings.customClient ?? "");
+      setSettingsSearchRoots(loadedSettings.installSearchRoots.join("; "));
+      setSettingsThroughUpdater(loadedSettings.launchThroughUpdater);
+      setSettingsUpdateFirst(loadedSettings.updateBeforeSwitch);
+      setSettingsTokenBackend(loadedSettings.tokenBackend);
+      setSettingsWriteStrategy(loadedSettings.writeStrategy);
+      setSettingsCaptureMode(loadedSettings.captureMode);
+      setSettingsBiometric(loadedSettings.biometric);
+      setSettingsAutoLock(loadedSettings.autoLockMinutes?.toString() ?? "");
+      setSettingsTagWindow(loadedSettings.tagDiscordWindow);
+      setSettingsOffline(loadedSettings.offlineMode);
+      setSettingsModBridge(loadedSettings.modBridge);
+      setSettingsSwitchDays(
+        loadedSettings.retention.switchHistoryDays?.toString() ?? "",
+      );
+      setSettingsAuditDays(
+        loadedSettings.retention.auditLogDays?.toString() ?? "",
+      );
     } catch (err) {
       showError(String(err));
     } finally {
@@ -121,6 +743,110 @@ function App() {
 
   useEffect(() => {
     loadData();
+    loadOperationHistory();
+    loadCurrentSession();
+    invoke<string | null>("get_queued_switch").then(setQueuedSwitchId);
+  }, []);
+
+  useEffect(() => {
+    loadChannelTokens(selectedId);
+  }, [selectedId]);
+
+  useEffect(() => {
+    const unlisten = listen<QueuedSwitchEvent>("queued-switch", (event) => {
+      setQueuedSwitchId(null);
+      loadOperationHistory();
+      const { outcome, error } = event.payload;
+      if (outcome) {
+        showStatus(
+          outcome.reminder
+            ? `${outcome.message} Reminder: ${outcome.reminder}`
+            : outcome.message,
+        );
+      } else if (error) {
+        showError(error);
+      }
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  useEffect(() => {
+    invoke<CaptureWizardState>("get_capture_wizard").then(setCaptureWizard);
+    const unlisten = listen<CaptureWizardState>("capture-wizard", (event) => {
+      setCaptureWizard(event.payload);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  // background work (health checks, auto-capture, imports) changes profiles too;
+  // patch just the ones that changed instead of reloading the list
+  useEffect(() => {
+    const unlisten = listen<ProfilesDelta>("profiles-changed", async (event) => {
+      const { added, updated, removed } = event.payload;
+      const changed = await Promise.all(
+        [...added, ...updated].map((id) =>
+          invoke<Profile>("resolve_profile", { selector: id }).catch(() => null),
+        ),
+      );
+      setProfiles((prev) => {
+        let next = prev.filter((p) => !removed.includes(p.id));
+        for (const profile of changed) {
+          if (!profile) continue;
+          next = next.some((p) => p.id === profile.id)
+            ? next.map((p) => (p.id === profile.id ? profile : p))
+            : [...next, profile];
+        }
+        return next;
+      });
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlisten = listen<ProgressEvent>("operation-progress", (event) => {
+      setNarration(event.payload.narration);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  // Discord updated itself out of the folder a custom path pointed into
+  useEffect(() => {
+    const unlisten = listen<PathRepair>("custom-path-repaired", (event) => {
+      const { preset, newPath } = event.payload;
+      invoke<LauncherSettings>("get_launcher_settings").then((loaded) => {
+        setSettings(loaded);
+        if (!preset) setSettingsCustomPath(loaded.customExecutablePath ?? "");
+      });
+      showStatus(
+        preset
+          ? `Discord was updated; preset "${preset}" now launches ${newPath}.`
+          : `Discord was updated; now launching ${newPath}.`,
+      );
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  // the backend locked everything after the idle timeout
+  useEffect(() => {
+    const unlisten = listen<VaultLockedEvent>("vault-locked", (event) => {
+      setVault(event.payload.vault);
+      setAppLock(event.payload.appLock);
+      syncProfilesWithVault(event.payload.vault);
+      showStatus(`Locked after ${event.payload.idleMinutes} idle minutes.`);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
   }, []);
 
   function selectProfile(profile: Profile) {
@@ -128,6 +854,8 @@ function App() {
     setView("profile");
     setEditing(false);
     setWaitingForLogin(null);
+    setTrust(null);
+    setShownToken(null);
   }
 
   function startAdding() {
@@ -155,6 +883,29 @@ function App() {
     }
   }
 
+  // a profile for every account already logged in to Discord on this machine
+  async function importExistingSessions() {
+    try {
+      setBusy(true);
+      const result = await invoke<SessionImport>("import_existing_sessions");
+      setProfiles((prev) => [...prev, ...result.imported]);
+      const parts = [
+        `Imported ${result.imported.length} account${result.imported.length !== 1 ? "s" : ""}.`,
+      ];
+      if (result.alreadySaved > 0) {
+        parts.push(`${result.alreadySaved} already had a profile.`);
+      }
+      if (result.skipped.length > 0) {
+        parts.push(`Skipped: ${result.skipped.join(" ")}`);
+      }
+      showStatus(parts.join(" "));
+    } catch (err) {
+      showError(errorMessage(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
   async function removeProfile(profile: Profile) {
     try {
       await invoke("remove_profile", { profileId: profile.id });
@@ -175,6 +926,8 @@ function App() {
     setEditing(true);
     setEditNickname(profile.nickname);
     setEditColor(profile.avatarColor);
+    setEditIcon(profile.icon);
+    setEditReminder(profile.switchReminder ?? "");
   }
 
   async function saveEdit() {
@@ -184,6 +937,9 @@ function App() {
         profileId: selectedId,
         nickname: editNickname,
         avatarColor: editColor,
+        // an empty string clears the icon
+        icon: editIcon ?? "",
+        switchReminder: editReminder,
       });
       setProfiles((prev) =>
         prev.map((p) => (p.id === updated.id ? updated : p)),
@@ -196,93 +952,1017 @@ function App() {
   }
 
   // wipe the current token and open Discord so the user sees the login screen
-  async function prepareLogin(profileId: string) {
+  async function prepareLogin(
+    profileId: string,
+    overrides: Overrides = NO_OVERRIDES,
+  ) {
     try {
       setBusy(true);
-      await invoke<string>("prepare_login");
+      const message = await invoke<string>("prepare_login", { ...overrides });
       setWaitingForLogin(profileId);
-      showStatus("Discord launched. Log in with your account credentials.");
+      // names the profile the previous session was saved to, if any
+      showStatus(message);
     } catch (err) {
-      showError(String(err));
+      const overridden = confirmOverrideAfterWarning(err, overrides);
+      if (overridden) {
+        return await prepareLogin(profileId, overridden);
+      }
+      if (await closeElevatedAfterWarning(err)) {
+        return await prepareLogin(profileId, overrides);
+      }
+      showError(errorMessage(err));
     } finally {
       setBusy(false);
     }
   }
 
+  // with several accounts in Discord's own switcher, ask which one to capture;
+  // undefined means the user backed out
+  async function pickLoggedInAccount(): Promise<string | null | undefined> {
+    const accounts = await invoke<string[]>("list_discord_logged_in_accounts", {
+      channel: captureChannel,
+    }).catch(() => [] as string[]);
+    if (accounts.length < 2) {
+      return null;
+    }
+    const choice = window.prompt(
+      `Discord has ${accounts.length} accounts logged in. Which one should be captured?\n\n${accounts
+        .map((id, i) => `${i + 1}. User ${id}`)
+        .join("\n")}\n\nLeave empty for the active account.`,
+      "",
+    );
+    if (choice === null) {
+      return undefined;
+    }
+    if (choice.trim() === "") {
+      return null;
+    }
+    const picked = accounts[Number(choice.trim()) - 1];
+    if (!picked) {
+      showError("That isn't one of the listed accounts.");
+    }
+    return picked;
+  }
+
   // close Discord, grab the token from its storage, and save it to this profile
-  async function captureToken(profileId: string) {
+  async function captureToken(profileId: string, account?: string | null) {
     try {
       setBusy(true);
+      if (account === undefined) {
+        account = await pickLoggedInAccount();
+        if (account === undefined) {
+          return;
+        }
+      }
       const updated = await invoke<Profile>("capture_token", {
         profileId,
+        account,
+        channel: captureChannel,
       });
       setProfiles((prev) =>
         prev.map((p) => (p.id === updated.id ? updated : p)),
       );
       setWaitingForLogin(null);
-      showStatus(`Token captured for "${updated.nickname}".`);
+      loadChannelTokens(updated.id);
+      loadCurrentSession();
+      showStatus(
+        captureChannel
+          ? `${CHANNEL_NAMES[captureChannel]} token captured for "${updated.nickname}".`
+          : `Token captured for "${updated.nickname}".`,
+      );
     } catch (err) {
-      showError(String(err));
+      if (await closeElevatedAfterWarning(err)) {
+        return await captureToken(profileId, account);
+      }
+      if (await repairAfterDamage(err)) {
+        return await captureToken(profileId, account);
+      }
+      noteLockError(err);
+      showError(errorMessage(err));
     } finally {
       setBusy(false);
+      loadOperationHistory();
     }
   }
 
   // inject this profile's saved token into Discord and launch it
-  async function switchToProfile(profile: Profile) {
+  async function switchToProfile(
+    profile: Profile,
+    overrides: Overrides = NO_OVERRIDES,
+    confirmation: SwitchConfirmation | null = null,
+  ) {
     try {
       setBusy(true);
-      const message = await invoke<string>("switch_to_profile", {
+      const outcome = await invoke<SwitchOutcome>("switch_to_profile", {
         profileId: profile.id,
+        ...overrides,
+        confirmation,
       });
-      showStatus(message);
+      showStatus(
+        outcome.reminder
+          ? `${outcome.message} Reminder: ${outcome.reminder}`
+          : outcome.message,
+      );
+      loadCurrentSession();
     } catch (err) {
-      showError(String(err));
+      const overridden = confirmOverrideAfterWarning(err, overrides);
+      if (overridden) {
+        return await switchToProfile(profile, overridden, confirmation);
+      }
+      const confirmed = await confirmProtectedSwitch(err, profile);
+      if (confirmed) {
+        return await switchToProfile(profile, overrides, confirmed);
+      }
+      if (offerReloginForQuarantine(err)) {
+        return await prepareLogin(profile.id);
+      }
+      if (await closeElevatedAfterWarning(err)) {
+        return await switchToProfile(profile, overrides, confirmation);
+      }
+      if (await repairAfterDamage(err)) {
+        return await switchToProfile(profile, overrides, confirmation);
+      }
+      noteLockError(err);
+      showError(errorMessage(err));
     } finally {
       setBusy(false);
+      loadOperationHistory();
     }
   }
 
-  async function saveLauncherSettings() {
+  // add the profile to Discord's own account switcher, keeping the current login
+  async function hotAddProfile(
+    profile: Profile,
+    overrides: Overrides = NO_OVERRIDES,
+  ) {
     try {
-      const saved = await invoke<LauncherSettings>("save_launcher_settings", {
-        settings: {
-          preferredChannel: settingsChannel,
-          customExecutablePath: settingsCustomPath.trim() || null,
-        },
-      });
-      const refreshed = await invoke<DiscordInstallation[]>(
-        "detect_discord_installations",
+      setBusy(true);
+      showStatus(
+        await invoke<string>("hot_add_profile", {
+          profileId: profile.id,
+          ...overrides,
+        }),
+      );
+    } catch (err) {
+      const overridden = confirmOverrideAfterWarning(err, overrides);
+      if (overridden) {
+        return await hotAddProfile(profile, overridden);
+      }
+      if (await closeElevatedAfterWarning(err)) {
+        return await hotAddProfile(profile, overrides);
+      }
+      if (await repairAfterDamage(err)) {
+        return await hotAddProfile(profile, overrides);
+      }
+      noteLockError(err);
+      showError(errorMessage(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function loadOperationHistory() {
+    try {
+      setOperationHistory(
+        await invoke<Record<string, OperationResult[]>>("get_operation_history"),
       );
-      setSettings(saved);
-      setInstallations(refreshed);
-      showStatus("Settings saved.");
     } catch (err) {
       showError(String(err));
     }
   }
 
-  if (loading) {
+  // run the last failed capture or switch again
+  async function retryLastOperation(profile: Profile) {
+    try {
+      setBusy(true);
+      const retried = await invoke<RetriedOperation>("retry_last_operation", {
+        profileId: profile.id,
+      });
+      if (retried.operation === "capture") {
+        setProfiles((prev) =>
+          prev.map((p) => (p.id === retried.profile.id ? retried.profile : p)),
+        );
+        setWaitingForLogin(null);
+        showStatus(`Token captured for "${retried.profile.nickname}".`);
+      } else {
+        showStatus(
+          retried.outcome.reminder
+            ? `${retried.outcome.message} Reminder: ${retried.outcome.reminder}`
+            : retried.outcome.message,
+        );
+      }
+    } catch (err) {
+      // only switches are refused for these
+      const kind = errorKind(err);
+      if (kind === "inCall" || kind === "protected") {
+        return await switchToProfile(profile);
+      }
+      if (await closeElevatedAfterWarning(err)) {
+        return await retryLastOperation(profile);
+      }
+      noteLockError(err);
+      showError(errorMessage(err));
+    } finally {
+      setBusy(false);
+      loadOperationHistory();
+    }
+  }
+
+  // a quarantined token would only land Discord on its login screen
+  function offerReloginForQuarantine(err: unknown): boolean {
     return (
-      <main className="app-shell">
-        <div className="sidebar" />
-        <div className="main">
-          <div className="loading-state">loading...</div>
-        </div>
-      </main>
+      errorKind(err) === "quarantined" &&
+      window.confirm(`${errorMessage(err)}\n\nLog in now?`)
     );
   }
 
-  return (
-    <main className="app-shell">
-      {/* ── Sidebar ── */}
-      <nav className="sidebar">
-        <div className="sidebar-header">
-          <h1>Accounts</h1>
-        </div>
+  // switching into or out of a protected profile needs an explicit yes,
+  // traded for a one-time token
+  async function confirmProtectedSwitch(
+    err: unknown,
+    profile: Profile,
+  ): Promise<SwitchConfirmation | null> {
+    if (
+      errorKind(err) !== "protected" ||
+      !window.confirm(
+        `${errorMessage(err)}\n\nSwitch to "${profile.nickname}" anyway?`,
+      )
+    ) {
+      return null;
+    }
+    const value = await invoke<string>("request_switch_confirmation", {
+      profileId: profile.id,
+    });
+    return { kind: "token", value };
+  }
 
-        <ul className="sidebar-list">
-          {sortedProfiles.map((profile) => (
+  // the form is uncontrolled; an empty application id turns the presence off
+  async function saveRichPresence(event: FormEvent<HTMLFormElement>) {
+    event.preventDefault();
+    if (!selectedId) return;
+    const form = new FormData(event.currentTarget);
+    const field = (name: string) => String(form.get(name) ?? "").trim() || null;
+    const clientId = field("clientId");
+    try {
+      const updated = await invoke<Profile>("set_rich_presence", {
+        profileId: selectedId,
+        presence: clientId && {
+          clientId,
+          details: field("details"),
+          state: field("state"),
+          largeImage: field("largeImage"),
+          largeText: field("largeText"),
+        },
+      });
+      setProfiles((prev) =>
+        prev.map((p) => (p.id === updated.id ? updated : p)),
+      );
+      showStatus(
+        updated.richPresence ? "Rich Presence saved." : "Rich Presence turned off.",
+      );
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  async function saveExportPolicy(
+    profile: Profile,
+    neverExport: boolean,
+    neverSync: boolean,
+  ) {
+    try {
+      const updated = await invoke<Profile>("set_export_policy", {
+        profileId: profile.id,
+        neverExport,
+        neverSync,
+      });
+      setProfiles((prev) =>
+        prev.map((p) => (p.id === updated.id ? updated : p)),
+      );
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  async function saveProfileProtected(profileId: string, isProtected: boolean) {
+    try {
+      const updated = await invoke<Profile>("set_profile_protected", {
+        profileId,
+        protected: isProtected,
+      });
+      setProfiles((prev) =>
+        prev.map((p) => (p.id === updated.id ? updated : p)),
+      );
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  // the backend won't close Discord mid-call, or open its storage while
+  // something still holds it, unless told to go ahead; each is its own
+  // override, so closing Discord during a call never takes its LOCK away
+  function confirmOverrideAfterWarning(
+    err: unknown,
+    overrides: Overrides,
+  ): Overrides | null {
+    const kind = errorKind(err);
+    if (
+      kind === "inCall" &&
+      window.confirm(`${errorMessage(err)} Close Discord anyway?`)
+    ) {
+      return { ...overrides, ignoreCall: true };
+    }
+    if (
+      kind === "discordRunning" &&
+      window.confirm(
+        `${errorMessage(err)}\n\nWrite to its storage anyway? This can corrupt it; a backup is taken first.`,
+      )
+    ) {
+      return { ...overrides, forceUnlock: true };
+    }
+    return null;
+  }
+
+  // an elevated Discord can only be closed behind the OS's admin prompt
+  async function closeElevatedAfterWarning(err: unknown): Promise<boolean> {
+    if (
+      errorKind(err) !== "needsElevation" ||
+      !window.confirm(
+        `${errorMessage(err)}\n\nClose Discord with administrator rights and try again?`,
+      )
+    ) {
+      return false;
+    }
+    try {
+      await invoke("terminate_discord_elevated");
+      return true;
+    } catch (elevationErr) {
+      showError(String(elevationErr));
+      return false;
+    }
+  }
+
+  // a database that won't open can often be pointed at its manifest again
+  async function repairAfterDamage(err: unknown): Promise<boolean> {
+    if (
+      errorKind(err) !== "storageDamaged" ||
+      !window.confirm(`${errorMessage(err)}\n\nRepair Discord's storage now?`)
+    ) {
+      return false;
+    }
+    return await repairStorage();
+  }
+
+  async function repairStorage(): Promise<boolean> {
+    try {
+      showStatus(await invoke<string>("repair_discord_storage"));
+      return true;
+    } catch (err) {
+      noteLockError(err);
+      showError(errorMessage(err));
+      return false;
+    }
+  }
+
+  // wait for the user to quit Discord, then switch and start it again
+  async function queueSwitch(
+    profile: Profile,
+    confirmation: SwitchConfirmation | null = null,
+  ) {
+    try {
+      const message = await invoke<string>("queue_switch", {
+        profileId: profile.id,
+        relaunch: true,
+        confirmation,
+      });
+      setQueuedSwitchId(profile.id);
+      showStatus(message);
+    } catch (err) {
+      const confirmed = await confirmProtectedSwitch(err, profile);
+      if (confirmed) {
+        return await queueSwitch(profile, confirmed);
+      }
+      if (offerReloginForQuarantine(err)) {
+        return await prepareLogin(profile.id);
+      }
+      noteLockError(err);
+      showError(errorMessage(err));
+    }
+  }
+
+  async function cancelQueuedSwitch() {
+    try {
+      await invoke("cancel_queued_switch");
+      setQueuedSwitchId(null);
+      showStatus("Queued switch cancelled.");
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  // account age, verification and flags for the selected profile
+  async function checkTrust(profileId: string) {
+    try {
+      setBusy(true);
+      setTrust(await invoke<TrustSummary>("get_trust_summary", { profileId }));
+    } catch (err) {
+      showError(String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  // pushed to the account's servers on every switch
+  async function saveNotificationPreset(
+    profileId: string,
+    preset: NotificationPreset,
+  ) {
+    try {
+      const updated = await invoke<Profile>("set_notification_preset", {
+        profileId,
+        preset,
+      });
+      setProfiles((prev) =>
+        prev.map((p) => (p.id === updated.id ? updated : p)),
+      );
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  async function saveClearCacheOnSwitch(profileId: string, enabled: boolean) {
+    try {
+      const updated = await invoke<Profile>("set_clear_cache_on_switch", {
+        profileId,
+        enabled,
+      });
+      setProfiles((prev) =>
+        prev.map((p) => (p.id === updated.id ? updated : p)),
+      );
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  async function saveSandbox(profileId: string, tool: SandboxTool | null) {
+    try {
+      const updated = await invoke<Profile>("set_profile_sandbox", {
+        profileId,
+        tool,
+      });
+      setProfiles((prev) =>
+        prev.map((p) => (p.id === updated.id ? updated : p)),
+      );
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  async function saveWindowsAccount(profileId: string, clear: boolean) {
+    try {
+      const updated = await invoke<Profile>("set_windows_account", {
+        profileId,
+        account: clear ? null : windowsAccountDraft,
+        password: clear ? null : windowsPasswordDraft,
+      });
+      setProfiles((prev) =>
+        prev.map((p) => (p.id === updated.id ? updated : p)),
+      );
+      setWindowsAccountDraft("");
+      setWindowsPasswordDraft("");
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  async function setPluginEnabled(plugin: PluginInfo, enabled: boolean) {
+    if (
+      enabled &&
+      !window.confirm(
+        `Enable "${plugin.name}"? It will run programs from its plugin folder on this computer.`,
+      )
+    ) {
+      return;
+    }
+    try {
+      setPlugins(
+        await invoke<PluginInfo[]>("set_plugin_enabled", {
+          pluginId: plugin.id,
+          enabled,
+        }),
+      );
+    } catch (err) {
+      noteLockError(err);
+      showError(errorMessage(err));
+    }
+  }
+
+  async function runPluginCommand(
+    plugin: PluginInfo,
+    command: PluginCommand,
+    profileId: string | null,
+  ) {
+    try {
+      setBusy(true);
+      showStatus(
+        await invoke<string>("run_plugin_command", {
+          pluginId: plugin.id,
+          commandId: command.id,
+          profileId,
+        }),
+      );
+    } catch (err) {
+      noteLockError(err);
+      showError(errorMessage(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  // report token files other users could read and offer to lock them down
+  async function checkTokenPermissions() {
+    try {
+      const report = await invoke<PermissionReport>("check_token_permissions", {
+        fix: false,
+      });
+      if (report.lax.length === 0) {
+        showStatus(`Checked ${report.checked} token files; all private.`);
+        return;
+      }
+      const details = report.lax
+        .map((item) => `${item.path}: ${item.problem}`)
+        .join("\n");
+      if (!window.confirm(`Other users may be able to read:\n${details}\n\nFix?`)) {
+        return;
+      }
+      const fixed = await invoke<PermissionReport>("check_token_permissions", {
+        fix: true,
+      });
+      const failed = fixed.lax.filter((item) => !item.fixed).length;
+      if (failed > 0) {
+        showError(`Could not fix ${failed} of ${fixed.lax.length} token files.`);
+      } else {
+        showStatus(`Fixed ${fixed.lax.length} token files.`);
+      }
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  // for handing the machine over: every saved token and Discord session goes
+  async function panicWipe() {
+    if (
+      !window.confirm(
+        "Delete every saved token, sign Discord out on all channels and close it? Accounts stay in the list but will have to be captured again.",
+      )
+    ) {
+      return;
+    }
+    try {
+      const report = await invoke<PanicWipeReport>("panic_wipe", {
+        confirmed: true,
+      });
+      setProfiles((prev) => prev.map((p) => ({ ...p, hasToken: false })));
+      setQueuedSwitchId(null);
+      setShownToken(null);
+      const summary = `Wiped ${report.tokensWiped} tokens and signed out ${report.channelsCleared} Discord channels.`;
+      if (report.errors.length > 0) {
+        showError(`${summary} Some things failed:\n${report.errors.join("\n")}`);
+      } else {
+        showStatus(summary);
+      }
+    } catch (err) {
+      showError(errorMessage(err));
+    }
+  }
+
+  async function loadSessions() {
+    try {
+      setSessions(await invoke<RunningSession[]>("list_running_sessions"));
+    } catch (err) {
+      showError(errorMessage(err));
+    }
+  }
+
+  // best effort: Discord may not be installed or started yet
+  async function loadCurrentSession() {
+    setCurrentSession(
+      await invoke<CurrentSession>("get_current_session").catch(() => null),
+    );
+  }
+
+  function describeCurrentSession(session: CurrentSession): string {
+    if (!session.loggedIn) {
+      return "Discord is logged out";
+    }
+    const who =
+      session.nickname ??
+      session.username ??
+      (session.userId ? `user ${session.userId}` : "an unknown account");
+    const saved = session.profileId ? "" : " (not saved)";
+    const rejected = session.tokenRejected ? ", but its token was rejected" : "";
+    return `Logged in as ${who}${saved}${rejected}`;
+  }
+
+  async function terminateSession(session: RunningSession) {
+    try {
+      await invoke("terminate_session", { channel: session.channel });
+      showStatus(`Closed ${CHANNEL_NAMES[session.channel]}.`);
+      await loadSessions();
+    } catch (err) {
+      showError(errorMessage(err));
+    }
+  }
+
+  async function loadStorageBackups() {
+    try {
+      setStorageBackups(
+        await invoke<StorageBackup[]>("list_discord_storage_backups"),
+      );
+    } catch (err) {
+      showError(errorMessage(err));
+    }
+  }
+
+  async function restoreStorageBackup(backup: StorageBackup) {
+    if (
+      !window.confirm(
+        `Close Discord and put its storage back the way it was on ${new Date(backup.createdAtMs).toLocaleString()}?`,
+      )
+    ) {
+      return;
+    }
+    try {
+      showStatus(
+        await invoke<string>("restore_discord_storage_backup", {
+          backupId: backup.id,
+        }),
+      );
+      await loadStorageBackups();
+    } catch (err) {
+      noteLockError(err);
+      showError(errorMessage(err));
+    }
+  }
+
+  // back to a first-run state; the backend wants a fresh nonce for every attempt
+  async function factoryReset() {
+    try {
+      const nonce = await invoke<string>("request_factory_reset");
+      if (
+        !window.confirm(
+          "Remove every account, saved token, setting, plugin and the activity log? This can't be undone.",
+        )
+      ) {
+        return;
+      }
+      await invoke<string>("factory_reset", { nonce });
+      window.location.reload();
+    } catch (err) {
+      showError(errorMessage(err));
+    }
+  }
+
+  async function loadChannelTokens(profileId: string | null) {
+    if (!profileId) {
+      setChannelTokens([]);
+      return;
+    }
+    setChannelTokens(
+      await invoke<DiscordChannel[]>("list_channel_tokens", {
+        profileId,
+      }).catch(() => []),
+    );
+  }
+
+  // switching into that channel goes back to the profile's main token
+  async function deleteChannelToken(profileId: string, channel: DiscordChannel) {
+    try {
+      await invoke("delete_channel_token", { profileId, channel });
+      await loadChannelTokens(profileId);
+      showStatus(`Forgot the ${CHANNEL_NAMES[channel]} token.`);
+    } catch (err) {
+      showError(errorMessage(err));
+    }
+  }
+
+  // masked by default; the full token only after an explicit confirmation
+  async function showTokenPreview(profileId: string) {
+    try {
+      const text = await invoke<string>("get_token_preview", { profileId });
+      setShownToken({ profileId, text, revealed: false });
+    } catch (err) {
+      noteLockError(err);
+      showError(errorMessage(err));
+    }
+  }
+
+  async function revealToken(profileId: string) {
+    if (
+      !window.confirm(
+        "Show the full token? Anyone who sees it can log in as this account.",
+      )
+    ) {
+      return;
+    }
+    try {
+      const text = await invoke<string>("reveal_token", {
+        profileId,
+        confirmed: true,
+      });
+      setShownToken({ profileId, text, revealed: true });
+    } catch (err) {
+      noteLockError(err);
+      showError(errorMessage(err));
+    }
+  }
+
+  async function copyToken(profileId: string) {
+    try {
+      showStatus(
+        await invoke<string>("copy_token_to_clipboard", {
+          profileId,
+          ttlSecs: CLIPBOARD_TTL_SECS,
+        }),
+      );
+    } catch (err) {
+      noteLockError(err);
+      showError(errorMessage(err));
+    }
+  }
+
+  // the backend refuses token operations while locked; mirror that in the UI
+  function noteLockError(err: unknown) {
+    const kind = errorKind(err);
+    if (kind === "vaultLocked") setVault((v) => v && { ...v, unlocked: false });
+    if (kind === "appLocked") setAppLock((l) => l && { ...l, unlocked: false });
+  }
+
+  async function unlockApp(event: FormEvent<HTMLFormElement>) {
+    event.preventDefault();
+    try {
+      setAppLock(
+        await invoke<AppLockStatus>("verify_app_password", {
+          password: appPasswordInput,
+        }),
+      );
+      setAppPasswordInput("");
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  // set or change the app password; an empty new password removes it
+  async function saveAppPassword() {
+    try {
+      const next = await invoke<AppLockStatus>("set_app_password", {
+        currentPassword: appPasswordInput || null,
+        newPassword: newAppPassword || null,
+      });
+      setAppLock(next);
+      setAppPasswordInput("");
+      setNewAppPassword("");
+      showStatus(next.enabled ? "App password saved." : "App password removed.");
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  // enable / disable / unlock / lock the master-password vault
+  async function runVaultCommand(
+    command: "enable_vault" | "disable_vault" | "unlock_vault" | "lock_vault",
+  ) {
+    try {
+      const next = await invoke<VaultStatus>(
+        command,
+        command === "lock_vault" ? {} : { password: vaultPassword },
+      );
+      setVault(next);
+      setVaultPassword("");
+      await syncProfilesWithVault(next);
+      showStatus(
+        !next.enabled
+          ? "Vault disabled."
+          : next.unlocked
+            ? "Vault unlocked."
+            : "Vault locked.",
+      );
+    } catch (err) {
+      showError(errorMessage(err));
+    }
+  }
+
+  async function setProfileEncryption(enabled: boolean) {
+    try {
+      setVault(await invoke<VaultStatus>("set_profile_encryption", { enabled }));
+      showStatus(
+        enabled ? "Account list encrypted." : "Account list stored as plain JSON.",
+      );
+    } catch (err) {
+      noteLockError(err);
+      showError(errorMessage(err));
+    }
+  }
+
+  async function activatePreset(name: string | null) {
+    try {
+      const saved = await invoke<LauncherSettings>("activate_launch_preset", {
+        name,
+      });
+      setSettings(saved);
+      showStatus(name ? `Using preset "${name}".` : "Using plain settings.");
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  async function saveCurrentAsPreset() {
+    try {
+      const saved = await invoke<LauncherSettings>("save_launch_preset", {
+        preset: {
+          name: presetName.trim(),
+          preferredChannel: settingsChannel,
+          customExecutablePath: settingsCustomPath.trim() || null,
+          customClient: settingsCustomClient || null,
+        },
+      });
+      setSettings(saved);
+      setPresetName("");
+      showStatus("Preset saved.");
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  async function saveLauncherSettings() {
+    try {
+      const saved = await invoke<LauncherSettings>("save_launcher_settings", {
+        // keep fields this form doesn't show (digest, locale, ...) as they were
+        settings: {
+          ...settings,
+          preferredChannel: settingsChannel,
+          customExecutablePath: settingsCustomPath.trim() || null,
+          customClients: settingsClients,
+          customClient: settingsCustomClient || null,
+          installSearchRoots: settingsSearchRoots
+            .split(";")
+            .map((root) => root.trim())
+            .filter(Boolean),
+          launchThroughUpdater: settingsThroughUpdater,
+          updateBeforeSwitch: settingsUpdateFirst,
+          tokenBackend: settingsTokenBackend,
+          writeStrategy: settingsWriteStrategy,
+          captureMode: settingsCaptureMode,
+          biometric: settingsBiometric,
+          tagDiscordWindow: settingsTagWindow,
+          offlineMode: settingsOffline,
+          modBridge: settingsModBridge,
+          autoLockMinutes: settingsAutoLock.trim()
+            ? Number(settingsAutoLock)
+            : null,
+          retention: {
+            switchHistoryDays: settingsSwitchDays.trim()
+              ? Number(settingsSwitchDays)
+              : null,
+            auditLogDays: settingsAuditDays.trim()
+              ? Number(settingsAuditDays)
+              : null,
+          },
+        },
+      });
+      const refreshed = await invoke<DiscordInstallation[]>(
+        "detect_discord_installations",
+      );
+      setSettings(saved);
+      setInstallations(refreshed);
+      showStatus("Settings saved.");
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  async function showModBridgeInfo(reset: boolean) {
+    try {
+      setModBridgeInfo(
+        await invoke<ModBridgeInfo>(
+          reset ? "reset_mod_bridge_key" : "get_mod_bridge_info",
+        ),
+      );
+      if (reset) showStatus("New mod bridge key created.");
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  // `offset` 0 starts over, anything else appends the next page
+  async function loadAuditLog(offset: number, failuresOnly: boolean) {
+    try {
+      const page = await invoke<AuditPage>("get_audit_log", {
+        query: { offset, limit: AUDIT_PAGE_SIZE, failuresOnly },
+      });
+      setAuditEntries((prev) =>
+        offset === 0 ? page.entries : [...prev, ...page.entries],
+      );
+      setAuditTotal(page.total);
+    } catch (err) {
+      showError(String(err));
+    }
+  }
+
+  function openCompare() {
+    setView("compare");
+    setSelectedId(null);
+    setEditing(false);
+    setFriendOverlap(null);
+    setGuildOverlap(null);
+    setComparison(null);
+  }
+
+  function toggleCompareId(profileId: string) {
+    setCompareIds((prev) =>
+      prev.includes(profileId)
+        ? prev.filter((id) => id !== profileId)
+        : [...prev, profileId],
+    );
+  }
+
+  function nicknameOf(profileId: string) {
+    return profiles.find((p) => p.id === profileId)?.nickname ?? profileId;
+  }
+
+  async function compareFriends() {
+    try {
+      setBusy(true);
+      setFriendOverlap(
+        await invoke<FriendOverlapReport>("get_friend_overlap", {
+          profileIds: compareIds,
+        }),
+      );
+    } catch (err) {
+      showError(String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function compareGuilds() {
+    try {
+      setBusy(true);
+      setGuildOverlap(
+        await invoke<GuildOverlapReport>("get_guild_overlap", {
+          profileIds: compareIds,
+        }),
+      );
+    } catch (err) {
+      showError(String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function compareDetails() {
+    try {
+      setBusy(true);
+      setComparison(
+        await invoke<ProfileComparison[]>("compare_profiles", {
+          profileIds: compareIds,
+        }),
+      );
+    } catch (err) {
+      showError(String(err));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  function openAuditLog() {
+    setView("audit");
+    setSelectedId(null);
+    setEditing(false);
+    loadAuditLog(0, auditFailuresOnly);
+  }
+
+  if (loading) {
+    return (
+      <main className="app-shell">
+        <div className="sidebar" />
+        <div className="main">
+          <div className="loading-state">loading...</div>
+        </div>
+      </main>
+    );
+  }
+
+  return (
+    <main className="app-shell">
+      {/* ── Sidebar ── */}
+      <nav className="sidebar">
+        <div className="sidebar-header">
+          <h1>Accounts</h1>
+        </div>
+
+        <ul className="sidebar-list">
+          {sortedProfiles.map((profile) => (
             <li key={profile.id}>
               <button
                 className={`sidebar-item${selectedId === profile.id && view === "profile" ? " active" : ""}`}
@@ -292,7 +1972,7 @@ function App() {
                   className="avatar"
                   style={{ backgroundColor: profile.avatarColor }}
                 >
-                  {initialsOf(profile.nickname)}
+                  {avatarContent(profile)}
                 </div>
                 <div className="sidebar-item-info">
                   <span className="profile-name">{profile.nickname}</span>
@@ -305,10 +1985,34 @@ function App() {
           ))}
         </ul>
 
+        {currentSession && (
+          <button
+            className="hint"
+            onClick={loadCurrentSession}
+            title="Read from Discord's storage; click to check again"
+          >
+            {describeCurrentSession(currentSession)}
+          </button>
+        )}
+
         <div className="sidebar-footer">
           <button className="add-btn" onClick={startAdding}>
             <span className="plus">+</span> Add Account
           </button>
+          <button
+            className="add-btn"
+            onClick={importExistingSessions}
+            disabled={busy}
+            title="Create a profile for every account logged in to Discord on this computer"
+          >
+            Import Logged-In Accounts
+          </button>
+          <button className="add-btn" onClick={openCompare}>
+            Compare Accounts
+          </button>
+          <button className="add-btn" onClick={openAuditLog}>
+            Activity Log
+          </button>
         </div>
       </nav>
 
@@ -318,15 +2022,245 @@ function App() {
           <div className="empty-state">
             <div className="empty-icon">~</div>
             <p>
-              {profiles.length === 0
-                ? "No accounts yet"
-                : "Select an account"}
+              {profilesLocked
+                ? "Accounts are locked"
+                : profiles.length === 0
+                  ? "No accounts yet"
+                  : "Select an account"}
+            </p>
+            {profilesLocked ? (
+              <div className="form-actions">
+                <input
+                  className="field-input"
+                  type="password"
+                  value={vaultPassword}
+                  onChange={(e) => setVaultPassword(e.currentTarget.value)}
+                  placeholder="master password"
+                />
+                <button
+                  className="btn btn-secondary btn-sm"
+                  disabled={!vaultPassword}
+                  onClick={() => runVaultCommand("unlock_vault")}
+                >
+                  Unlock
+                </button>
+              </div>
+            ) : (
+              <span className="hint">
+                {profiles.length === 0
+                  ? "click + add account, or import the accounts already logged in to Discord"
+                  : `${profiles.length} account${profiles.length !== 1 ? "s" : ""} saved`}
+              </span>
+            )}
+          </div>
+        )}
+
+        {view === "compare" && (
+          <div className="add-form">
+            <h2>Compare Accounts</h2>
+            <p className="form-desc">
+              Looks up the selected accounts' friends, servers or details on
+              Discord and shows them side by side. Nothing is saved.
+            </p>
+
+            {profiles
+              .filter((p) => p.hasToken)
+              .map((profile) => (
+                <label key={profile.id} className="checkbox-label">
+                  <input
+                    type="checkbox"
+                    checked={compareIds.includes(profile.id)}
+                    onChange={() => toggleCompareId(profile.id)}
+                  />
+                  {profile.nickname}
+                </label>
+              ))}
+
+            <div className="form-actions">
+              <button
+                className="btn btn-primary"
+                onClick={compareFriends}
+                disabled={busy || compareIds.length < 2}
+              >
+                Compare Friends
+              </button>
+              <button
+                className="btn btn-secondary"
+                onClick={compareGuilds}
+                disabled={busy || compareIds.length < 2}
+              >
+                Compare Servers
+              </button>
+              <button
+                className="btn btn-secondary"
+                onClick={compareDetails}
+                disabled={busy || compareIds.length < 2}
+              >
+                Compare Details
+              </button>
+            </div>
+
+            {comparison && (
+              <table className="compare-table">
+                <thead>
+                  <tr>
+                    <th />
+                    {comparison.map((column) => (
+                      <th key={column.profileId}>{column.nickname}</th>
+                    ))}
+                  </tr>
+                </thead>
+                <tbody>
+                  {(
+                    [
+                      [
+                        "Created",
+                        (c) =>
+                          c.accountCreatedAtMs
+                            ? formatDate(c.accountCreatedAtMs)
+                            : "—",
+                      ],
+                      ["Nitro", (c) => (c.nitro ? NITRO_NAMES[c.nitro] : "—")],
+                      ["Servers", (c) => c.guildCount?.toString() ?? "—"],
+                      ["Token", (c) => c.tokenStatus],
+                      [
+                        "Last used",
+                        (c) => (c.lastUsedMs ? formatDate(c.lastUsedMs) : "never"),
+                      ],
+                      ["Lookup", (c) => c.error ?? "ok"],
+                    ] as [string, (c: ProfileComparison) => string][]
+                  ).map(([label, value]) => (
+                    <tr key={label}>
+                      <th>{label}</th>
+                      {comparison.map((column) => (
+                        <td key={column.profileId}>{value(column)}</td>
+                      ))}
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            )}
+
+            {friendOverlap && (
+              <>
+                {friendOverlap.profiles
+                  .filter((lookup) => lookup.error)
+                  .map((lookup) => (
+                    <p key={lookup.profileId} className="hint">
+                      {nicknameOf(lookup.profileId)}: {lookup.error}
+                    </p>
+                  ))}
+                {friendOverlap.directLinks.map((link) => (
+                  <p
+                    key={`${link.profileId}-${link.friendProfileId}`}
+                    className="audit-failed"
+                  >
+                    {nicknameOf(link.profileId)} is friends with{" "}
+                    {nicknameOf(link.friendProfileId)}
+                  </p>
+                ))}
+                {friendOverlap.shared.length === 0 ? (
+                  <p className="hint">No shared friends.</p>
+                ) : (
+                  <ul className="audit-list">
+                    {friendOverlap.shared.map((item) => (
+                      <li key={item.id}>
+                        <span>{item.name}</span>
+                        <span>
+                          {item.profileIds.map(nicknameOf).join(", ")}
+                        </span>
+                      </li>
+                    ))}
+                  </ul>
+                )}
+              </>
+            )}
+
+            {guildOverlap && (
+              <>
+                {guildOverlap.profiles
+                  .filter((lookup) => lookup.error)
+                  .map((lookup) => (
+                    <p key={lookup.profileId} className="hint">
+                      {nicknameOf(lookup.profileId)}: {lookup.error}
+                    </p>
+                  ))}
+                {guildOverlap.shared.length === 0 ? (
+                  <p className="hint">No shared servers.</p>
+                ) : (
+                  <ul className="audit-list">
+                    {guildOverlap.shared.map((item) => (
+                      <li key={item.id} className="audit-failed">
+                        <span>{item.name}</span>
+                        <span>
+                          {item.profileIds.map(nicknameOf).join(", ")}
+                        </span>
+                      </li>
+                    ))}
+                  </ul>
+                )}
+              </>
+            )}
+          </div>
+        )}
+
+        {view === "audit" && (
+          <div className="add-form">
+            <h2>Activity Log</h2>
+            <p className="form-desc">
+              Every token capture, switch, export and reveal this app has
+              done, newest first.
             </p>
-            <span className="hint">
-              {profiles.length === 0
-                ? "click + add account to get started"
-                : `${profiles.length} account${profiles.length !== 1 ? "s" : ""} saved`}
-            </span>
+
+            <label className="checkbox-label">
+              <input
+                type="checkbox"
+                checked={auditFailuresOnly}
+                onChange={(e) => {
+                  const failuresOnly = e.currentTarget.checked;
+                  setAuditFailuresOnly(failuresOnly);
+                  loadAuditLog(0, failuresOnly);
+                }}
+              />
+              Failures only
+            </label>
+
+            {auditEntries.length === 0 ? (
+              <p className="hint">Nothing recorded yet.</p>
+            ) : (
+              <ul className="audit-list">
+                {auditEntries.map((entry, i) => (
+                  <li
+                    key={`${entry.atMs}-${i}`}
+                    className={entry.succeeded ? "" : "audit-failed"}
+                  >
+                    <span>{new Date(entry.atMs).toLocaleString()}</span>
+                    <span>
+                      {entry.action}
+                      {entry.detail ? ` (${entry.detail})` : ""}
+                    </span>
+                    <span>
+                      {profiles.find((p) => p.id === entry.profileId)
+                        ?.nickname ??
+                        entry.profileId ??
+                        "—"}
+                    </span>
+                    <span>{entry.succeeded ? "ok" : entry.error}</span>
+                  </li>
+                ))}
+              </ul>
+            )}
+
+            {auditEntries.length < auditTotal && (
+              <button
+                className="btn btn-secondary btn-sm"
+                onClick={() =>
+                  loadAuditLog(auditEntries.length, auditFailuresOnly)
+                }
+              >
+                Load More
+              </button>
+            )}
           </div>
         )}
 
@@ -398,7 +2332,7 @@ function App() {
                 className="avatar-large"
                 style={{ backgroundColor: selectedProfile.avatarColor }}
               >
-                {initialsOf(selectedProfile.nickname)}
+                {avatarContent(selectedProfile)}
               </div>
               <div className="profile-info">
                 <h2>{selectedProfile.nickname}</h2>
@@ -414,14 +2348,369 @@ function App() {
                 ? "Token captured"
                 : "No token — log in to capture credentials"}
             </div>
+            {lastOperation && (
+              <div
+                className={`last-operation ${lastOperation.succeeded ? "succeeded" : "failed"}`}
+              >
+                <span>
+                  {lastOperation.succeeded
+                    ? `Last ${lastOperation.operation} worked in ${(lastOperation.durationMs / 1000).toFixed(1)}s`
+                    : `Last ${lastOperation.operation} failed${
+                        lastOperation.failedStep
+                          ? ` while ${STEP_NAMES[lastOperation.failedStep.kind] ?? lastOperation.failedStep.kind}`
+                          : ""
+                      }: ${lastOperation.error}`}{" "}
+                  ({formatDate(lastOperation.finishedAtMs)})
+                </span>
+                {!lastOperation.succeeded && (
+                  <button
+                    className="btn btn-secondary btn-sm"
+                    onClick={() => retryLastOperation(selectedProfile)}
+                    disabled={busy}
+                  >
+                    Retry
+                  </button>
+                )}
+              </div>
+            )}
+            {selectedProfile.hasToken && (
+              <div className="token-preview">
+                {shownToken?.profileId === selectedProfile.id ? (
+                  <>
+                    <code>{shownToken.text}</code>
+                    {shownToken.revealed ? (
+                      <button
+                        className="btn btn-secondary btn-sm"
+                        onClick={() => setShownToken(null)}
+                      >
+                        Hide
+                      </button>
+                    ) : (
+                      <button
+                        className="btn btn-secondary btn-sm"
+                        onClick={() => revealToken(selectedProfile.id)}
+                      >
+                        Reveal
+                      </button>
+                    )}
+                  </>
+                ) : (
+                  <button
+                    className="btn btn-secondary btn-sm"
+                    onClick={() => showTokenPreview(selectedProfile.id)}
+                  >
+                    Show Token
+                  </button>
+                )}
+                <button
+                  className="btn btn-secondary btn-sm"
+                  onClick={() => copyToken(selectedProfile.id)}
+                  title={`Cleared from the clipboard after ${CLIPBOARD_TTL_SECS} seconds`}
+                >
+                  Copy
+                </button>
+              </div>
+            )}
+            {channelTokens.length > 0 && (
+              <div className="settings-row">
+                <label>Own tokens for</label>
+                {channelTokens.map((channel) => (
+                  <span key={channel}>
+                    {CHANNEL_NAMES[channel]}{" "}
+                    <button
+                      className="btn btn-secondary btn-sm"
+                      onClick={() =>
+                        deleteChannelToken(selectedProfile.id, channel)
+                      }
+                      disabled={busy}
+                    >
+                      Forget
+                    </button>
+                  </span>
+                ))}
+              </div>
+            )}
+
+            <div className="settings-row">
+              <label>Notifications on switch</label>
+              <select
+                value={selectedProfile.notificationPreset}
+                onChange={(e) =>
+                  saveNotificationPreset(
+                    selectedProfile.id,
+                    e.target.value as NotificationPreset,
+                  )
+                }
+              >
+                <option value="unchanged">Leave as they are</option>
+                <option value="mentionsOnly">Mentions only</option>
+                <option value="muteAll">Mute every server</option>
+              </select>
+            </div>
+
+            <div className="settings-row">
+              <label>Protected</label>
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked={selectedProfile.protected}
+                  onChange={(e) =>
+                    saveProfileProtected(
+                      selectedProfile.id,
+                      e.currentTarget.checked,
+                    )
+                  }
+                />
+                Ask before switching to or away from this account
+              </label>
+            </div>
+
+            <div className="settings-row">
+              <label>Sharing</label>
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked={selectedProfile.neverExport}
+                  onChange={(e) =>
+                    saveExportPolicy(
+                      selectedProfile,
+                      e.currentTarget.checked,
+                      selectedProfile.neverSync,
+                    )
+                  }
+                />
+                Leave out of backups and health digests
+              </label>
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked={selectedProfile.neverSync}
+                  onChange={(e) =>
+                    saveExportPolicy(
+                      selectedProfile,
+                      selectedProfile.neverExport,
+                      e.currentTarget.checked,
+                    )
+                  }
+                />
+                Never put its token in a backup
+              </label>
+            </div>
+
+            <form
+              key={selectedProfile.id}
+              className="settings-row"
+              onSubmit={saveRichPresence}
+            >
+              <label>Rich Presence</label>
+              <input
+                className="field-input"
+                name="clientId"
+                placeholder="Application id"
+                defaultValue={selectedProfile.richPresence?.clientId ?? ""}
+              />
+              <input
+                className="field-input"
+                name="details"
+                placeholder="Details, e.g. Streaming on the alt"
+                defaultValue={selectedProfile.richPresence?.details ?? ""}
+              />
+              <input
+                className="field-input"
+                name="state"
+                placeholder="State"
+                defaultValue={selectedProfile.richPresence?.state ?? ""}
+              />
+              <input
+                className="field-input"
+                name="largeImage"
+                placeholder="Image key or URL"
+                defaultValue={selectedProfile.richPresence?.largeImage ?? ""}
+              />
+              <input
+                className="field-input"
+                name="largeText"
+                placeholder="Image text"
+                defaultValue={selectedProfile.richPresence?.largeText ?? ""}
+              />
+              <button type="submit" className="btn btn-secondary btn-sm">
+                Save
+              </button>
+            </form>
+            <p className="hint">
+              Shown on this account after every switch. Create an application in
+              the Discord Developer Portal; its name becomes the title. Leave the
+              id empty to turn it off.
+            </p>
+
+            <div className="settings-row">
+              <label>Cache on switch</label>
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked={selectedProfile.clearCacheOnSwitch}
+                  onChange={(e) =>
+                    saveClearCacheOnSwitch(
+                      selectedProfile.id,
+                      e.currentTarget.checked,
+                    )
+                  }
+                />
+                Clear Discord's cache and session storage
+              </label>
+            </div>
+
+            {capabilities?.os === "windows" && (
+              <>
+                <form
+                  className="edit-row"
+                  onSubmit={(e) => {
+                    e.preventDefault();
+                    saveWindowsAccount(selectedProfile.id, false);
+                  }}
+                >
+                  <input
+                    className="field-input"
+                    value={windowsAccountDraft}
+                    onChange={(e) =>
+                      setWindowsAccountDraft(e.currentTarget.value)
+                    }
+                    placeholder={
+                      selectedProfile.windowsAccount ?? "Windows user, e.g. alt1"
+                    }
+                  />
+                  <input
+                    className="field-input"
+                    type="password"
+                    value={windowsPasswordDraft}
+                    onChange={(e) =>
+                      setWindowsPasswordDraft(e.currentTarget.value)
+                    }
+                    placeholder="Windows password"
+                  />
+                  <button type="submit" className="btn btn-secondary btn-sm">
+                    Save
+                  </button>
+                  {selectedProfile.windowsAccount && (
+                    <button
+                      type="button"
+                      className="btn btn-secondary btn-sm"
+                      onClick={() => saveWindowsAccount(selectedProfile.id, true)}
+                    >
+                      Use my account
+                    </button>
+                  )}
+                </form>
+                <p className="hint">
+                  Start this account's Discord as another Windows user. It stays
+                  logged in there and runs next to your own Discord; the password
+                  is kept in Credential Manager.
+                </p>
+              </>
+            )}
+
+            {capabilities?.sandboxedLaunch && (
+              <>
+                <div className="settings-row">
+                  <label>Sandbox</label>
+                  <select
+                    className="field-select"
+                    value={selectedProfile.sandbox ?? ""}
+                    onChange={(e) =>
+                      saveSandbox(
+                        selectedProfile.id,
+                        (e.currentTarget.value || null) as SandboxTool | null,
+                      )
+                    }
+                  >
+                    <option value="">None</option>
+                    {sandboxTools.map((tool) => (
+                      <option key={tool} value={tool}>
+                        {SANDBOX_NAMES[tool]}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+                <p className="hint">
+                  Start this account's Discord in a sandbox of its own. It stays
+                  logged in there and runs next to your normal Discord.
+                </p>
+              </>
+            )}
+
+            {/* Trust summary */}
+            {trust?.profileId === selectedProfile.id && (
+              <div className="install-chips">
+                {trust.age && (
+                  <span className="chip">
+                    {trust.age} ({trust.ageDays} days)
+                  </span>
+                )}
+                {trust.emailVerified !== null && (
+                  <span className="chip">
+                    {trust.emailVerified ? "email verified" : "email unverified"}
+                  </span>
+                )}
+                {trust.phoneVerified && <span className="chip">phone</span>}
+                {trust.mfaEnabled && <span className="chip">2FA</span>}
+                {trust.flagReasons.map((reason) => (
+                  <span key={reason} className="chip">
+                    {reason}
+                  </span>
+                ))}
+                {trust.lookupError && (
+                  <span className="chip" title={trust.lookupError}>
+                    live check unavailable
+                  </span>
+                )}
+              </div>
+            )}
+
+            {selectedProfile.tokenStatus === "quarantined" && (
+              <p className="hint">
+                Discord rejected this account's saved token. Switching is
+                blocked until you log in again and capture a new one.
+              </p>
+            )}
 
             {/* Waiting for login message */}
             {waitingForLogin === selectedProfile.id && (
               <div className="login-instructions">
-                <p>
-                  Discord is open. Log in with this account's credentials, then
-                  come back here and click <strong>Capture Token</strong>.
-                </p>
+                {captureWizard.state === "tokenDetected" ? (
+                  <p>
+                    Login detected. Click <strong>Capture Token</strong> to save
+                    it to this account.
+                  </p>
+                ) : (
+                  <p>
+                    Discord is open. Log in with this account's credentials,
+                    then come back here and click <strong>Capture Token</strong>.
+                  </p>
+                )}
+                {new Set(installations.map((inst) => inst.channel)).size >
+                  1 && (
+                  <select
+                    className="field-input"
+                    style={{ marginRight: 8 }}
+                    value={captureChannel ?? ""}
+                    onChange={(e) =>
+                      setCaptureChannel(
+                        (e.currentTarget.value || null) as DiscordChannel | null,
+                      )
+                    }
+                    disabled={busy}
+                    title="Which Discord to read the token from; a chosen channel keeps it as that channel's own token"
+                  >
+                    <option value="">The one the app launches</option>
+                    {[...new Set(installations.map((inst) => inst.channel))].map(
+                      (channel) => (
+                        <option key={channel} value={channel}>
+                          {CHANNEL_NAMES[channel]}
+                        </option>
+                      ),
+                    )}
+                  </select>
+                )}
                 <button
                   className="btn btn-primary"
                   onClick={() => captureToken(selectedProfile.id)}
@@ -432,7 +2721,10 @@ function App() {
                 <button
                   className="btn btn-secondary"
                   style={{ marginLeft: 8 }}
-                  onClick={() => setWaitingForLogin(null)}
+                  onClick={() => {
+                    setWaitingForLogin(null);
+                    invoke("reset_capture_wizard").catch(() => {});
+                  }}
                   disabled={busy}
                 >
                   Cancel
@@ -457,9 +2749,36 @@ function App() {
                     onClick={() => prepareLogin(selectedProfile.id)}
                     disabled={busy}
                   >
-                    {busy ? "Launching..." : "Log In"}
+                    {busy ? "Launching..." : "Log In"}
+                  </button>
+                )}
+                {selectedProfile.hasToken && (
+                  <button
+                    className="btn btn-secondary"
+                    onClick={() => hotAddProfile(selectedProfile)}
+                    disabled={busy}
+                    title="Add this account to Discord's own account switcher without logging out the current one"
+                  >
+                    Add to Discord's Switcher
                   </button>
                 )}
+                {selectedProfile.hasToken &&
+                  (queuedSwitchId === selectedProfile.id ? (
+                    <button
+                      className="btn btn-secondary"
+                      onClick={cancelQueuedSwitch}
+                    >
+                      Cancel Queued Switch
+                    </button>
+                  ) : (
+                    <button
+                      className="btn btn-secondary"
+                      onClick={() => queueSwitch(selectedProfile)}
+                      disabled={busy}
+                    >
+                      Switch When Discord Closes
+                    </button>
+                  ))}
                 {selectedProfile.hasToken && (
                   <button
                     className="btn btn-secondary"
@@ -470,6 +2789,36 @@ function App() {
                     Re-login
                   </button>
                 )}
+                <button
+                  className="btn btn-secondary"
+                  onClick={() => checkTrust(selectedProfile.id)}
+                  disabled={busy}
+                >
+                  Trust
+                </button>
+                {plugins
+                  .filter((plugin) => plugin.enabled)
+                  .flatMap((plugin) =>
+                    plugin.commands
+                      .filter((command) => command.needsProfile)
+                      .map((command) => (
+                        <button
+                          key={`${plugin.id}/${command.id}`}
+                          className="btn btn-secondary"
+                          onClick={() =>
+                            runPluginCommand(
+                              plugin,
+                              command,
+                              selectedProfile.id,
+                            )
+                          }
+                          disabled={busy}
+                          title={plugin.name}
+                        >
+                          {command.title}
+                        </button>
+                      )),
+                  )}
                 <button
                   className="btn btn-secondary"
                   onClick={() => startEditing(selectedProfile)}
@@ -516,6 +2865,35 @@ function App() {
                       onChange={(e) => setEditColor(e.currentTarget.value)}
                     />
                   </div>
+                  <div className="icon-row">
+                    <button
+                      type="button"
+                      className={`icon-swatch${editIcon === null ? " selected" : ""}`}
+                      onClick={() => setEditIcon(null)}
+                      title="Initials"
+                    >
+                      {initialsOf(editNickname)}
+                    </button>
+                    {(Object.keys(PROFILE_ICONS) as ProfileIcon[]).map((icon) => (
+                      <button
+                        key={icon}
+                        type="button"
+                        className={`icon-swatch${editIcon === icon ? " selected" : ""}`}
+                        onClick={() => setEditIcon(icon)}
+                        title={icon}
+                      >
+                        <ProfileIconSvg icon={icon} />
+                      </button>
+                    ))}
+                  </div>
+                  <input
+                    className="field-input"
+                    type="text"
+                    value={editReminder}
+                    onChange={(e) => setEditReminder(e.currentTarget.value)}
+                    maxLength={280}
+                    placeholder="Reminder shown on switch (optional)"
+                  />
                 </div>
                 <div className="profile-actions">
                   <button className="btn btn-primary btn-sm" onClick={saveEdit}>
@@ -539,13 +2917,41 @@ function App() {
             {installations.length > 0 && (
               <div className="install-chips">
                 {installations.map((inst) => (
-                  <span key={inst.channel} className="chip">
+                  <span
+                    key={inst.label}
+                    className="chip"
+                    title={inst.executablePath}
+                  >
                     {inst.label}
+                    {inst.version ? ` ${inst.version}` : ""}
+                    {inst.arch ? ` (${inst.arch})` : ""}
+                    {inst.mods.length > 0 &&
+                      ` + ${inst.mods.map((m) => MOD_NAMES[m]).join(", ")}`}
                   </span>
                 ))}
               </div>
             )}
 
+            {settings && settings.presets.length > 0 && (
+              <div className="settings-row">
+                <label>Preset</label>
+                <select
+                  className="field-select"
+                  value={settings.activePreset ?? ""}
+                  onChange={(e) =>
+                    activatePreset(e.currentTarget.value || null)
+                  }
+                >
+                  <option value="">None</option>
+                  {settings.presets.map((preset) => (
+                    <option key={preset.name} value={preset.name}>
+                      {preset.name}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            )}
+
             <div className="settings-row">
               <label>Channel</label>
               <select
@@ -559,6 +2965,10 @@ function App() {
                 <option value="stable">Stable</option>
                 <option value="ptb">PTB</option>
                 <option value="canary">Canary</option>
+                <option value="development">Development</option>
+                <option value="vesktop">Vesktop</option>
+                <option value="webCord">WebCord</option>
+                <option value="armCord">ArmCord</option>
               </select>
             </div>
 
@@ -573,6 +2983,419 @@ function App() {
               />
             </div>
 
+            <div className="settings-row">
+              <label>Search in</label>
+              <input
+                className="field-input"
+                type="text"
+                value={settingsSearchRoots}
+                onChange={(e) => setSettingsSearchRoots(e.currentTarget.value)}
+                placeholder="folders for moved installs, separated by ;"
+              />
+            </div>
+
+            {capabilities?.squirrelUpdater && (
+              <>
+                <div className="settings-row">
+                  <label>Updater</label>
+                  <label className="checkbox-label">
+                    <input
+                      type="checkbox"
+                      checked={settingsThroughUpdater}
+                      onChange={(e) =>
+                        setSettingsThroughUpdater(e.currentTarget.checked)
+                      }
+                    />
+                    Start Discord through Update.exe (Windows)
+                  </label>
+                </div>
+
+                <div className="settings-row">
+                  <label>Before switching</label>
+                  <label className="checkbox-label">
+                    <input
+                      type="checkbox"
+                      checked={settingsUpdateFirst}
+                      onChange={(e) =>
+                        setSettingsUpdateFirst(e.currentTarget.checked)
+                      }
+                    />
+                    Install Discord updates first (Windows)
+                  </label>
+                </div>
+              </>
+            )}
+
+            <div className="settings-row">
+              <label>Client</label>
+              <select
+                className="field-select"
+                value={settingsCustomClient}
+                onChange={(e) => setSettingsCustomClient(e.currentTarget.value)}
+              >
+                <option value="">Channel above</option>
+                {settingsClients.map((client) => (
+                  <option key={client.name} value={client.name}>
+                    {client.name}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            {settingsClients.map((client) => (
+              <div className="settings-row" key={client.name}>
+                <span className="hint">
+                  {client.name}: {client.executablePath} ({client.processName})
+                </span>
+                <button
+                  className="btn btn-secondary btn-sm"
+                  onClick={() => {
+                    setSettingsClients((prev) =>
+                      prev.filter((c) => c.name !== client.name),
+                    );
+                    if (settingsCustomClient === client.name) {
+                      setSettingsCustomClient("");
+                    }
+                  }}
+                >
+                  Remove
+                </button>
+              </div>
+            ))}
+
+            <div className="settings-row">
+              <label>Add client</label>
+              {(
+                [
+                  ["name", "name, e.g. Lightcord"],
+                  ["executablePath", "executable path"],
+                  ["storageDir", "Local Storage/leveldb folder"],
+                  ["processName", "process name"],
+                ] as const
+              ).map(([field, placeholder]) => (
+                <input
+                  key={field}
+                  className="field-input"
+                  type="text"
+                  value={clientDraft[field]}
+                  onChange={(e) => {
+                    const value = e.currentTarget.value;
+                    setClientDraft((prev) => ({ ...prev, [field]: value }));
+                  }}
+                  placeholder={placeholder}
+                />
+              ))}
+              <button
+                className="btn btn-secondary btn-sm"
+                disabled={!clientDraft.name.trim()}
+                onClick={() => {
+                  setSettingsClients((prev) => [...prev, clientDraft]);
+                  setClientDraft(EMPTY_CLIENT);
+                }}
+              >
+                Add
+              </button>
+            </div>
+
+            <div className="settings-row">
+              <label>Save as</label>
+              <input
+                className="field-input"
+                type="text"
+                value={presetName}
+                onChange={(e) => setPresetName(e.currentTarget.value)}
+                placeholder="preset name, e.g. work laptop"
+              />
+              <button
+                className="btn btn-secondary btn-sm"
+                disabled={!presetName.trim()}
+                onClick={saveCurrentAsPreset}
+              >
+                Save
+              </button>
+            </div>
+
+            <div className="settings-row">
+              <label>Token write</label>
+              <select
+                className="field-select"
+                value={settingsWriteStrategy}
+                onChange={(e) =>
+                  setSettingsWriteStrategy(
+                    e.currentTarget.value as WriteStrategy,
+                  )
+                }
+              >
+                <option value="put">Overwrite</option>
+                <option value="deleteThenPut">Delete, then write</option>
+                <option value="allKeys">Write every key variant</option>
+              </select>
+            </div>
+
+            <div className="settings-row">
+              <label>Capture</label>
+              <select
+                className="field-select"
+                value={settingsCaptureMode}
+                onChange={(e) =>
+                  setSettingsCaptureMode(e.currentTarget.value as CaptureMode)
+                }
+              >
+                <option value="closeDiscord">Close Discord first</option>
+                <option value="snapshot">Read a copy, keep Discord open</option>
+                <option value="devTools">Ask Discord through DevTools</option>
+              </select>
+            </div>
+            <p className="hint">
+              Reading a copy doesn't interrupt calls, but a login from the last
+              few seconds may not be in it yet. DevTools restarts Discord once
+              with a debugging port that other programs on this computer can
+              use too, until Discord is restarted.
+            </p>
+
+            <div className="settings-row">
+              <label>Token storage</label>
+              <select
+                className="field-select"
+                value={settingsTokenBackend}
+                onChange={(e) =>
+                  setSettingsTokenBackend(e.currentTarget.value as TokenBackend)
+                }
+              >
+                {(capabilities?.tokenBackends ?? TOKEN_BACKENDS).map(
+                  (backend) => (
+                    <option key={backend} value={backend}>
+                      {backend === "file" && capabilities?.machineBoundFiles === false
+                        ? "Files (not encrypted)"
+                        : TOKEN_BACKEND_NAMES[backend]}
+                    </option>
+                  ),
+                )}
+              </select>
+              <button
+                className="btn btn-secondary btn-sm"
+                onClick={checkTokenPermissions}
+              >
+                Check Permissions
+              </button>
+            </div>
+
+            <div className="settings-row">
+              <label>Emergency</label>
+              <button className="btn btn-danger btn-sm" onClick={panicWipe}>
+                Wipe All Tokens
+              </button>
+              <button className="btn btn-danger btn-sm" onClick={factoryReset}>
+                Factory Reset
+              </button>
+            </div>
+
+            <div className="settings-row">
+              <label>Storage backups</label>
+              <button
+                className="btn btn-secondary btn-sm"
+                onClick={loadStorageBackups}
+              >
+                {storageBackups ? "Refresh" : "Show"}
+              </button>
+              <button
+                className="btn btn-secondary btn-sm"
+                onClick={async () => {
+                  if (await repairStorage()) await loadStorageBackups();
+                }}
+              >
+                Repair
+              </button>
+            </div>
+            {storageBackups &&
+              (storageBackups.length === 0 ? (
+                <span className="hint">
+                  Discord's storage is backed up before each switch or login.
+                  Nothing has been backed up yet.
+                </span>
+              ) : (
+                <ul className="audit-list">
+                  {storageBackups.map((backup) => (
+                    <li key={backup.id}>
+                      {backup.channel
+                        ? CHANNEL_NAMES[backup.channel]
+                        : backup.storageDir}
+                      , {new Date(backup.createdAtMs).toLocaleString()}{" "}
+                      <button
+                        className="btn btn-secondary btn-sm"
+                        onClick={() => restoreStorageBackup(backup)}
+                      >
+                        Restore
+                      </button>
+                    </li>
+                  ))}
+                </ul>
+              ))}
+
+            <div className="settings-row">
+              <label>Running Discord</label>
+              <button className="btn btn-secondary btn-sm" onClick={loadSessions}>
+                {sessions ? "Refresh" : "Show"}
+              </button>
+            </div>
+            {sessions &&
+              (sessions.length === 0 ? (
+                <span className="hint">No Discord channel is running.</span>
+              ) : (
+                <ul className="audit-list">
+                  {sessions.map((session) => (
+                    <li key={session.channel}>
+                      {CHANNEL_NAMES[session.channel]}
+                      {session.nickname ? ` as ${session.nickname}` : ""} (
+                      {session.pids.length} processes){" "}
+                      <button
+                        className="btn btn-secondary btn-sm"
+                        onClick={() => terminateSession(session)}
+                      >
+                        Close
+                      </button>
+                    </li>
+                  ))}
+                </ul>
+              ))}
+
+            {capabilities?.windowTagging && (
+              <div className="settings-row">
+                <label>Window title</label>
+                <label className="checkbox-label">
+                  <input
+                    type="checkbox"
+                    checked={settingsTagWindow}
+                    onChange={(e) =>
+                      setSettingsTagWindow(e.currentTarget.checked)
+                    }
+                  />
+                  Show the active account's name
+                </label>
+              </div>
+            )}
+
+            <div className="settings-row">
+              <label>Offline mode</label>
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked={settingsOffline}
+                  onChange={(e) => setSettingsOffline(e.currentTarget.checked)}
+                />
+                Never contact Discord or any other server
+              </label>
+            </div>
+
+            <div className="settings-row">
+              <label>Mod bridge</label>
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked={settingsModBridge}
+                  onChange={(e) =>
+                    setSettingsModBridge(e.currentTarget.checked)
+                  }
+                />
+                Accept tokens from a Vencord or BetterDiscord plugin
+              </label>
+            </div>
+            {settingsModBridge && (
+              <div className="settings-row">
+                {modBridgeInfo ? (
+                  <>
+                    <code>{modBridgeInfo.endpoint}</code>
+                    <code>{modBridgeInfo.key}</code>
+                    <button
+                      className="btn btn-secondary btn-sm"
+                      onClick={() => showModBridgeInfo(true)}
+                    >
+                      New key
+                    </button>
+                  </>
+                ) : (
+                  <button
+                    className="btn btn-secondary btn-sm"
+                    onClick={() => showModBridgeInfo(false)}
+                  >
+                    Show endpoint and key
+                  </button>
+                )}
+              </div>
+            )}
+            <p className="hint">
+              The plugin sends one JSON line with the key and token, plus a
+              profileId if the account isn't linked to a profile yet.
+            </p>
+
+            <div className="settings-row">
+              <label>Auto-lock</label>
+              <input
+                className="field-input"
+                type="number"
+                min={1}
+                value={settingsAutoLock}
+                onChange={(e) => setSettingsAutoLock(e.currentTarget.value)}
+                placeholder="idle minutes, blank for never"
+              />
+            </div>
+
+            <div className="settings-row">
+              <label>Keep switch history</label>
+              <input
+                className="field-input"
+                type="number"
+                min={1}
+                value={settingsSwitchDays}
+                onChange={(e) => setSettingsSwitchDays(e.currentTarget.value)}
+                placeholder="days, blank for forever"
+              />
+            </div>
+
+            <div className="settings-row">
+              <label>Keep other activity</label>
+              <input
+                className="field-input"
+                type="number"
+                min={1}
+                value={settingsAuditDays}
+                onChange={(e) => setSettingsAuditDays(e.currentTarget.value)}
+                placeholder="days, blank for forever"
+              />
+            </div>
+
+            {biometricAvailable && (
+              <div className="settings-row">
+                <label>Windows Hello / Touch ID</label>
+                <label className="checkbox-label">
+                  <input
+                    type="checkbox"
+                    checked={settingsBiometric.switchProfile}
+                    onChange={(e) =>
+                      setSettingsBiometric({
+                        ...settingsBiometric,
+                        switchProfile: e.currentTarget.checked,
+                      })
+                    }
+                  />
+                  Switching
+                </label>
+                <label className="checkbox-label">
+                  <input
+                    type="checkbox"
+                    checked={settingsBiometric.revealToken}
+                    onChange={(e) =>
+                      setSettingsBiometric({
+                        ...settingsBiometric,
+                        revealToken: e.currentTarget.checked,
+                      })
+                    }
+                  />
+                  Revealing tokens
+                </label>
+              </div>
+            )}
+
             <div style={{ marginTop: 8 }}>
               <button
                 className="btn btn-secondary btn-sm"
@@ -581,13 +3404,193 @@ function App() {
                 Save Settings
               </button>
             </div>
+
+            <div className="divider" />
+
+            {/* App lock */}
+            <div className="section-label">App password</div>
+            {appLock?.enabled && (
+              <div className="settings-row">
+                <label>Current</label>
+                <input
+                  className="field-input"
+                  type="password"
+                  value={appPasswordInput}
+                  onChange={(e) => setAppPasswordInput(e.currentTarget.value)}
+                />
+              </div>
+            )}
+            <div className="settings-row">
+              <label>New</label>
+              <input
+                className="field-input"
+                type="password"
+                value={newAppPassword}
+                onChange={(e) => setNewAppPassword(e.currentTarget.value)}
+                placeholder={appLock?.enabled ? "empty removes it" : "optional"}
+              />
+            </div>
+            <div className="form-actions">
+              <button
+                className="btn btn-secondary btn-sm"
+                onClick={saveAppPassword}
+              >
+                Save Password
+              </button>
+              {appLock?.enabled && (
+                <button
+                  className="btn btn-secondary btn-sm"
+                  onClick={async () =>
+                    setAppLock(await invoke<AppLockStatus>("lock_app"))
+                  }
+                >
+                  Lock Now
+                </button>
+              )}
+            </div>
+
+            <div className="divider" />
+
+            {/* Vault */}
+            <div className="section-label">
+              Vault{" "}
+              {vault?.enabled && (vault.unlocked ? "(unlocked)" : "(locked)")}
+            </div>
+
+            {vault?.enabled && vault.unlocked ? (
+              <>
+                <div className="settings-row">
+                  <label>Account list</label>
+                  <label className="checkbox-label">
+                    <input
+                      type="checkbox"
+                      checked={vault.profilesEncrypted}
+                      onChange={(e) =>
+                        setProfileEncryption(e.currentTarget.checked)
+                      }
+                    />
+                    Encrypt nicknames and notes too
+                  </label>
+                </div>
+                <div className="form-actions">
+                  <button
+                    className="btn btn-secondary btn-sm"
+                    onClick={() => runVaultCommand("lock_vault")}
+                  >
+                    Lock
+                  </button>
+                </div>
+              </>
+            ) : (
+              <>
+                <div className="settings-row">
+                  <label>Master password</label>
+                  <input
+                    className="field-input"
+                    type="password"
+                    value={vaultPassword}
+                    onChange={(e) => setVaultPassword(e.currentTarget.value)}
+                    placeholder={vault?.enabled ? "" : "at least 8 characters"}
+                  />
+                </div>
+                <div className="form-actions">
+                  <button
+                    className="btn btn-secondary btn-sm"
+                    disabled={!vaultPassword}
+                    onClick={() =>
+                      runVaultCommand(
+                        vault?.enabled ? "unlock_vault" : "enable_vault",
+                      )
+                    }
+                  >
+                    {vault?.enabled ? "Unlock" : "Encrypt tokens"}
+                  </button>
+                  {vault?.enabled && (
+                    <button
+                      className="btn btn-secondary btn-sm"
+                      disabled={!vaultPassword}
+                      onClick={() => runVaultCommand("disable_vault")}
+                    >
+                      Turn off
+                    </button>
+                  )}
+                </div>
+              </>
+            )}
+
+            {plugins.length > 0 && (
+              <>
+                <div className="divider" />
+                <div className="section-label">Plugins</div>
+                {plugins.map((plugin) => (
+                  <div key={plugin.id}>
+                    <label className="checkbox-label" title={plugin.description}>
+                      <input
+                        type="checkbox"
+                        checked={plugin.enabled}
+                        disabled={plugin.error !== null && !plugin.enabled}
+                        onChange={(e) =>
+                          setPluginEnabled(plugin, e.currentTarget.checked)
+                        }
+                      />
+                      {plugin.name}
+                    </label>
+                    {plugin.error && <p className="hint">{plugin.error}</p>}
+                    {plugin.enabled &&
+                      plugin.commands.some((c) => !c.needsProfile) && (
+                        <div className="form-actions">
+                          {plugin.commands
+                            .filter((command) => !command.needsProfile)
+                            .map((command) => (
+                              <button
+                                key={command.id}
+                                className="btn btn-secondary btn-sm"
+                                onClick={() =>
+                                  runPluginCommand(plugin, command, null)
+                                }
+                                disabled={busy}
+                              >
+                                {command.title}
+                              </button>
+                            ))}
+                        </div>
+                      )}
+                  </div>
+                ))}
+              </>
+            )}
           </div>
         )}
       </div>
 
+      {/* ── Lock Screen ── */}
+      {appLock?.enabled && !appLock.unlocked && (
+        <div className="confirm-overlay">
+          <form className="confirm-box" onSubmit={unlockApp}>
+            <h3>Locked</h3>
+            <p>Enter the app password to switch or capture accounts.</p>
+            <input
+              className="field-input"
+              type="password"
+              value={appPasswordInput}
+              onChange={(e) => setAppPasswordInput(e.currentTarget.value)}
+              autoFocus
+            />
+            <div className="form-actions">
+              <button className="btn btn-primary btn-sm" type="submit">
+                Unlock
+              </button>
+            </div>
+          </form>
+        </div>
+      )}
+
       {/* ── Toasts ── */}
       {status && <div className="toast success">{status}</div>}
       {error && <div className="toast error">{error}</div>}
+      <div className="visually-hidden" role="status" aria-live="polite">
+        {narration}
+      </div>
 
       {/* ── Confirm Remove Dialog ── */}
       {confirmRemove && (
@@ -0,0 +1,280 @@
+import { ChangeEvent, FormEvent, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import "./App.css";
+
+// the phone build: view and copy saved accounts, sync them from a desktop
+// export; nothing here touches Discord
+
+type Profile = {
+  id: string;
+  nickname: string;
+  avatarColor: string;
+  hasToken: boolean;
+  tokenStatus: "unknown" | "valid" | "quarantined";
+};
+
+type LockStatus = {
+  enabled: boolean;
+  unlocked: boolean;
+};
+
+type ImportConflict = {
+  incoming: Profile;
+  local: Profile;
+};
+
+type ImportPreview = {
+  newProfiles: Profile[];
+  conflicts: ImportConflict[];
+};
+
+type ConflictAction = "keepLocal" | "keepRemote" | "merge";
+
+type CommandError = { kind: string; message: string };
+
+// how long a copied token stays on the clipboard
+const CLIPBOARD_TTL_MS = 30_000;
+
+function errorMessage(err: unknown) {
+  if (typeof err === "object" && err !== null && "message" in err) {
+    return String((err as CommandError).message);
+  }
+  return String(err);
+}
+
+function Companion() {
+  const [profiles, setProfiles] = useState<Profile[]>([]);
+  const [previews, setPreviews] = useState<Record<string, string>>({});
+  const [appLock, setAppLock] = useState<LockStatus | null>(null);
+  const [vault, setVault] = useState<LockStatus | null>(null);
+  const [password, setPassword] = useState("");
+  const [exportText, setExportText] = useState<string | null>(null);
+  const [importPreview, setImportPreview] = useState<ImportPreview | null>(
+    null,
+  );
+  const [resolutions, setResolutions] = useState<
+    Record<string, ConflictAction>
+  >({});
+  const [message, setMessage] = useState<string | null>(null);
+
+  const locked =
+    (appLock?.enabled && !appLock.unlocked) ||
+    (vault?.enabled && !vault.unlocked);
+
+  async function loadData() {
+    try {
+      const [loadedAppLock, loadedVault] = await Promise.all([
+        invoke<LockStatus>("get_app_lock_status"),
+        invoke<LockStatus>("get_vault_status"),
+      ]);
+      setAppLock(loadedAppLock);
+      setVault(loadedVault);
+      if (
+        (loadedAppLock.enabled && !loadedAppLock.unlocked) ||
+        (loadedVault.enabled && !loadedVault.unlocked)
+      ) {
+        return;
+      }
+      const loaded = await invoke<Profile[]>("list_profiles");
+      setProfiles(loaded);
+      const masked = await Promise.all(
+        loaded
+          .filter((p) => p.hasToken)
+          .map(async (p) => {
+            const preview = await invoke<string>("get_token_preview", {
+              profileId: p.id,
+            }).catch(() => "");
+            return [p.id, preview] as const;
+          }),
+      );
+      setPreviews(Object.fromEntries(masked));
+    } catch (err) {
+      setMessage(errorMessage(err));
+    }
+  }
+
+  useEffect(() => {
+    loadData();
+  }, []);
+
+  async function unlock(e: FormEvent) {
+    e.preventDefault();
+    try {
+      if (appLock?.enabled && !appLock.unlocked) {
+        setAppLock(
+          await invoke<LockStatus>("verify_app_password", { password }),
+        );
+      }
+      if (vault?.enabled && !vault.unlocked) {
+        setVault(await invoke<LockStatus>("unlock_vault", { password }));
+      }
+      setPassword("");
+      setMessage(null);
+      await loadData();
+    } catch (err) {
+      setMessage(errorMessage(err));
+    }
+  }
+
+  async function copyToken(profile: Profile) {
+    if (!confirm(`Copy ${profile.nickname}'s token to the clipboard?`)) {
+      return;
+    }
+    try {
+      const token = await invoke<string>("reveal_token", {
+        profileId: profile.id,
+        confirmed: true,
+      });
+      await navigator.clipboard.writeText(token);
+      setMessage(
+        `Copied. The clipboard is cleared in ${CLIPBOARD_TTL_MS / 1000} seconds.`,
+      );
+      // best effort: only works while the app is still in the foreground
+      setTimeout(() => {
+        navigator.clipboard.writeText("").catch(() => {});
+      }, CLIPBOARD_TTL_MS);
+    } catch (err) {
+      setMessage(errorMessage(err));
+    }
+  }
+
+  async function pickExport(e: ChangeEvent<HTMLInputElement>) {
+    const file = e.currentTarget.files?.[0];
+    e.currentTarget.value = "";
+    if (!file) return;
+    try {
+      const contents = await file.text();
+      const preview = await invoke<ImportPreview>("preview_desktop_export", {
+        contents,
+      });
+      setExportText(contents);
+      setImportPreview(preview);
+      setResolutions(
+        Object.fromEntries(
+          preview.conflicts.map((c) => [c.incoming.id, "merge" as const]),
+        ),
+      );
+    } catch (err) {
+      setMessage(errorMessage(err));
+    }
+  }
+
+  async function applyExport() {
+    if (exportText === null) return;
+    try {
+      const imported = await invoke<Profile[]>("apply_desktop_export", {
+        contents: exportText,
+        resolutions: Object.entries(resolutions).map(
+          ([incomingId, action]) => ({ incomingId, action }),
+        ),
+      });
+      setMessage(`Synced ${imported.length} accounts from the desktop export.`);
+      setExportText(null);
+      setImportPreview(null);
+      await loadData();
+    } catch (err) {
+      setMessage(errorMessage(err));
+    }
+  }
+
+  if (locked) {
+    return (
+      <main className="main">
+        <form className="add-form" onSubmit={unlock}>
+          <div className="section-label">Locked</div>
+          <input
+            className="field-input"
+            type="password"
+            value={password}
+            onChange={(e) => setPassword(e.currentTarget.value)}
+            placeholder="Password"
+            autoFocus
+          />
+          <div className="form-actions">
+            <button className="btn btn-primary" type="submit">
+              Unlock
+            </button>
+          </div>
+          {message && <span className="hint">{message}</span>}
+        </form>
+      </main>
+    );
+  }
+
+  return (
+    <main className="main">
+      <div className="section-label">Accounts</div>
+      {profiles.length === 0 ? (
+        <span className="hint">
+          No accounts yet. Export a backup with tokens on your computer and
+          sync it below.
+        </span>
+      ) : (
+        <ul className="audit-list">
+          {profiles.map((profile) => (
+            <li key={profile.id}>
+              <strong style={{ color: profile.avatarColor }}>
+                {profile.nickname}
+              </strong>{" "}
+              {profile.hasToken ? (
+                <>
+                  <code className="token-preview">{previews[profile.id]}</code>{" "}
+                  <button
+                    className="btn btn-secondary btn-sm"
+                    onClick={() => copyToken(profile)}
+                  >
+                    Copy
+                  </button>
+                </>
+              ) : (
+                <span className="hint">no token</span>
+              )}
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <div className="divider" />
+
+      <div className="section-label">Sync from desktop</div>
+      <input type="file" accept=".json,application/json" onChange={pickExport} />
+      {importPreview && (
+        <>
+          <span className="hint">
+            {importPreview.newProfiles.length} new,{" "}
+            {importPreview.conflicts.length} already here
+          </span>
+          {importPreview.conflicts.map((conflict) => (
+            <div className="settings-row" key={conflict.incoming.id}>
+              <label>{conflict.incoming.nickname}</label>
+              <select
+                className="field-select"
+                value={resolutions[conflict.incoming.id]}
+                onChange={(e) =>
+                  setResolutions({
+                    ...resolutions,
+                    [conflict.incoming.id]: e.currentTarget
+                      .value as ConflictAction,
+                  })
+                }
+              >
+                <option value="merge">Merge</option>
+                <option value="keepLocal">Keep this phone's</option>
+                <option value="keepRemote">Take the export's</option>
+              </select>
+            </div>
+          ))}
+          <div className="form-actions">
+            <button className="btn btn-primary" onClick={applyExport}>
+              Sync
+            </button>
+          </div>
+        </>
+      )}
+
+      {message && <span className="hint">{message}</span>}
+    </main>
+  );
+}
+
+export default Companion;
@@ -1,9 +1,17 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { invoke } from "@tauri-apps/api/core";
 import App from "./App";
+import Companion from "./Companion";
 
-ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-  <React.StrictMode>
-    <App />
-  </React.StrictMode>,
-);
+type PlatformCapabilities = { companion: boolean };
+
+// phones get the companion; the desktop build answers false
+invoke<PlatformCapabilities>("get_platform_capabilities")
+  .then((capabilities) => capabilities.companion)
+  .catch(() => false)
+  .then((companion) => {
+    ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
+      <React.StrictMode>{companion ? <Companion /> : <App />}</React.StrictMode>,
+    );
+  });